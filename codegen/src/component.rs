@@ -102,40 +102,28 @@ impl ComponentMeta {
         let ident = &self.ident;
         let vis = &self.vis;
 
-        if self.props_meta.fields.is_empty()
-            && self.state_meta.fields.is_empty()
-            && self.events_meta.events.is_empty()
-        {
-            quote! {
-                #(#attrs)*
-                #vis struct #ident;
-            }
-        } else {
-            let state_fields = self.state_meta.to_struct_fields();
-            let props_fields = self.props_meta.to_struct_fields();
-            let status_field = self.create_status_field();
-            let events_field = self.create_events_field();
+        let state_fields = self.state_meta.to_struct_fields();
+        let props_fields = self.props_meta.to_struct_fields();
+        let status_field = self.create_status_field();
+        let events_field = self.create_events_field();
 
-            quote! {
-                #(#attrs)*
-                #vis struct #ident {
-                    #(#state_fields ,)*
-                    #(#props_fields ,)*
-                    #status_field
-                    #events_field
-                }
+        quote! {
+            #(#attrs)*
+            #vis struct #ident {
+                #(#state_fields ,)*
+                #(#props_fields ,)*
+                #status_field
+                #events_field
             }
         }
     }
 
-    fn create_status_field(&self) -> Option<TokenStream> {
-        if self.props_meta.fields.is_empty() && self.state_meta.fields.is_empty() {
-            None
-        } else {
-            let status_ty = self.get_status_type();
-            Some(quote! {
-                __status__: #status_ty,
-            })
+    /// Every component has a status, even without any props or state, so
+    /// that it has a scope and can be rerendered.
+    fn create_status_field(&self) -> TokenStream {
+        let status_ty = self.get_status_type();
+        quote! {
+            __status__: #status_ty,
         }
     }
 
@@ -184,21 +172,17 @@ impl ComponentMeta {
         )
     }
 
-    fn create_status_wrapper_struct(&self) -> Option<TokenStream> {
-        if self.props_meta.fields.is_empty() && self.state_meta.fields.is_empty() {
-            None
-        } else {
-            let ident = self.get_status_type();
-            let vis = &self.vis;
-            let state_ty = self.get_state_type();
-            let status_set_state = self.impl_set_state_trait_for_status_wrapper();
+    fn create_status_wrapper_struct(&self) -> TokenStream {
+        let ident = self.get_status_type();
+        let vis = &self.vis;
+        let state_ty = self.get_state_type();
+        let status_set_state = self.impl_set_state_trait_for_status_wrapper();
 
-            Some(quote! {
-                #[derive(Clone)]
-                #vis struct #ident(std::rc::Rc<std::cell::RefCell<ruukh::component::Status<#state_ty>>>);
+        quote! {
+            #[derive(Clone)]
+            #vis struct #ident(std::rc::Rc<std::cell::RefCell<ruukh::component::Status<#state_ty>>>);
 
-                #status_set_state
-            })
+            #status_set_state
         }
     }
 
@@ -230,8 +214,10 @@ impl ComponentMeta {
         let events_type = &self.get_events_type();
         let init_body = self.impl_fn_init_body();
         let update_body = self.impl_fn_update_body();
+        let is_props_same_body = self.impl_fn_is_props_same_body();
         let refresh_state_body = self.impl_fn_refresh_state_body();
         let status_body = self.impl_fn_status_body();
+        let type_name = ident.to_string();
 
         quote! {
             impl Component for #ident {
@@ -239,6 +225,10 @@ impl ComponentMeta {
                 type State = #state_type;
                 type Events = #events_type;
 
+                fn type_name() -> &'static str {
+                    #type_name
+                }
+
                 fn init(
                     __props__: Self::Props,
                     __events__: Self::Events,
@@ -255,6 +245,10 @@ impl ComponentMeta {
                     #update_body
                 }
 
+                fn is_props_same(&self, __props__: &Self::Props) -> bool {
+                    #is_props_same_body
+                }
+
                 fn refresh_state(&mut self) -> bool {
                     #refresh_state_body
                 }
@@ -364,17 +358,12 @@ impl ComponentMeta {
             })
         };
 
-        let status_assignment =
-            if self.props_meta.fields.is_empty() && self.state_meta.fields.is_empty() {
-                None
-            } else {
-                let status_ty = self.get_status_type();
-                Some(quote! {
-                    __status__: #status_ty(
-                                    std::rc::Rc::new(
-                                        std::cell::RefCell::new(__status__))),
-                })
-            };
+        let status_ty = self.get_status_type();
+        let status_assignment = quote! {
+            __status__: #status_ty(
+                            std::rc::Rc::new(
+                                std::cell::RefCell::new(__status__))),
+        };
 
         let ident = &self.ident;
         let state_field_idents = &self.state_meta.to_field_idents();
@@ -394,12 +383,8 @@ impl ComponentMeta {
     }
 
     fn impl_fn_status_body(&self) -> TokenStream {
-        if self.props_meta.fields.is_empty() && self.state_meta.fields.is_empty() {
-            quote!(None)
-        } else {
-            quote! {
-                Some(&self.__status__.0)
-            }
+        quote! {
+            Some(&self.__status__.0)
         }
     }
 
@@ -432,6 +417,22 @@ impl ComponentMeta {
         }
     }
 
+    fn impl_fn_is_props_same_body(&self) -> TokenStream {
+        if self.events_meta.has_render_events() {
+            // What the parent renders with the render props may have changed.
+            quote! { false }
+        } else if self.props_meta.fields.is_empty() {
+            quote! { true }
+        } else {
+            let idents = &self.props_meta.to_field_idents();
+            let idents2 = idents;
+
+            quote! {
+                #(self.#idents == __props__.#idents2) && *
+            }
+        }
+    }
+
     fn impl_fn_update_body(&self) -> TokenStream {
         let events_assignment = if self.events_meta.events.is_empty() {
             None
@@ -441,10 +442,19 @@ impl ComponentMeta {
                 self.__events__ = __events__;
             })
         };
+        let render_dirty = if self.events_meta.has_render_events() {
+            Some(quote! {
+                // The render props are rendered again along with the parent.
+                self.__status__.0.borrow_mut().set_render_dirty(true);
+            })
+        } else {
+            None
+        };
 
         if self.props_meta.fields.is_empty() {
             quote! {
                 #events_assignment
+                #render_dirty
 
                 None
             }
@@ -454,6 +464,7 @@ impl ComponentMeta {
 
             quote! {
                 #events_assignment
+                #render_dirty
 
                 use std::mem;
                 #(
@@ -41,6 +41,10 @@ impl EventsMeta {
             .collect();
         let mut event_metas: Vec<EventMeta> = event_metas?.into_iter().flatten().collect();
         event_metas.sort_by(|l, r| l.ident.cmp(&r.ident));
+        let component_ident = &component.ident;
+        for event in event_metas.iter_mut().filter(|event| event.is_render) {
+            event.return_type = syn::parse_quote!(-> ruukh::Markup<#component_ident>);
+        }
 
         Ok(EventsMeta {
             ident: Ident::new(
@@ -62,6 +66,11 @@ impl EventsMeta {
         attrs.into_iter().partition(|attr| attr.path == events)
     }
 
+    /// Whether any of the events is a render prop.
+    pub fn has_render_events(&self) -> bool {
+        self.events.iter().any(|event| event.is_render)
+    }
+
     fn expand_events_with<F>(&self, map_fn: F) -> Vec<TokenStream>
     where
         F: Fn(&EventMeta) -> TokenStream,
@@ -254,6 +263,9 @@ pub struct EventMeta {
     pub return_type: ReturnType,
     /// Whether the event is optional.
     pub is_optional: bool,
+    /// Whether the event is a render prop, which returns the `Markup` of the
+    /// parent to be rendered in its context.
+    pub is_render: bool,
 }
 
 impl EventMeta {
@@ -287,14 +299,33 @@ impl EventMeta {
             false
         };
 
+        let is_render = Self::is_bare_markup(&event.return_type);
         Ok(EventMeta {
             ident: event.ident,
             arguments: arguments?,
             return_type: event.return_type,
             is_optional,
+            is_render,
         })
     }
 
+    /// Whether the return type is `Markup` without its render context, which
+    /// declares a render prop.
+    fn is_bare_markup(return_type: &ReturnType) -> bool {
+        match return_type {
+            ReturnType::Type(_, ty) => match **ty {
+                Type::Path(ref path) => {
+                    path.qself.is_none()
+                        && path.path.segments.len() == 1
+                        && path.path.segments[0].ident == "Markup"
+                        && path.path.segments[0].arguments.is_empty()
+                }
+                _ => false,
+            },
+            ReturnType::Default => false,
+        }
+    }
+
     fn is_first_arg_self_ref((index, arg): &(usize, FnArg)) -> bool {
         match arg {
             FnArg::SelfRef(_) if *index == 0 => true,
@@ -336,6 +367,11 @@ impl EventMeta {
 
     fn prop_fn_type(&self) -> TokenStream {
         let arg_types = self.arg_types();
+        if self.is_render {
+            return quote! {
+                Fn(&RCTX, #(#arg_types),*) -> ruukh::Markup<RCTX>
+            };
+        }
         let ret_type = &self.return_type;
         quote! {
             Fn(&RCTX, #(#arg_types),*) #ret_type
@@ -393,26 +429,60 @@ impl EventMeta {
     fn impl_event_conversion_from_event_prop(&self) -> TokenStream {
         let ident = &self.ident;
         let event_arg_idents = &self.to_arg_idents();
+        let event_arg_fields = &self.to_arg_fields();
+        // The markup of a render prop is rendered in the context of the parent.
+        let rendered = if self.is_render {
+            quote! {
+                let __output__ = ruukh::Markup::from(
+                    ruukh::vdom::vcomponent::VComponent::rendered_in(__output__, &__rctx__)
+                );
+            }
+        } else {
+            quote!()
+        };
 
         let converter = if self.is_optional {
             quote! {
-                if let Some(ref #ident) = #ident {
-                    Some((#ident)(&*__rctx__.borrow(), #(#event_arg_idents),*))
+                if let (Some(#ident), Some(__rctx__)) = (&#ident, __rctx__.upgrade()) {
+                    let __output__ = (#ident)(&*__rctx__.borrow(), #(#event_arg_idents),*);
+                    #rendered
+                    Some(__output__)
                 } else {
                     None
                 }
             }
         } else {
+            // Nothing is handled once the component is destroyed. There is no
+            // value to return for an event which returns one, though.
+            let destroyed = if self.is_render {
+                quote!(return ruukh::vdom::VNode::None)
+            } else {
+                match self.return_type {
+                    ReturnType::Default => quote!(return),
+                    ReturnType::Type(..) => {
+                        quote!(panic!("The component handling the event is already destroyed"))
+                    }
+                }
+            };
             quote! {
-                (#ident)(&*__rctx__.borrow(), #(#event_arg_idents),*)
+                let __rctx__ = match __rctx__.upgrade() {
+                    Some(__rctx__) => __rctx__,
+                    None => #destroyed,
+                };
+                let __output__ = (#ident)(&*__rctx__.borrow(), #(#event_arg_idents),*);
+                #rendered
+                __output__
             }
         };
 
         quote! {
             let #ident = {
-                let __rctx__ = __render_ctx__.clone();
+                // Held weakly, so that the component does not keep the one
+                // which renders it alive.
+                let __rctx__ = std::rc::Rc::downgrade(&__render_ctx__);
                 let #ident = __rctx_events__.#ident;
-                Box::new(move |#(#event_arg_idents),*| {
+                // Typed, so that the arguments by reference take any lifetime.
+                Box::new(move |#(#event_arg_fields),*| {
                     #converter
                 })
             };
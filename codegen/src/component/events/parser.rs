@@ -2,7 +2,7 @@ use syn::{
     parenthesized,
     parse::{Error, Parse, ParseStream, Result as ParseResult},
     spanned::Spanned,
-    Attribute, FnArg, Ident, ReturnType, Token,
+    Attribute, FnArg, Ident, ReturnType, Token, Type,
 };
 
 /// The argument syntax for the `#[events]` attribute.
@@ -13,6 +13,12 @@ use syn::{
 ///     fn event_name(&self, arg: type, ...) -> type;
 ///     fn event_name(&self, arg: type, ...) -> type;
 /// )```
+///
+/// or the messages emitted by the component, instead.
+/// ```ignore,compile_fail
+/// (
+///     emits = MessageType;
+/// )```
 pub struct EventDeclarations {
     pub events: Vec<EventDeclaration>,
 }
@@ -24,7 +30,11 @@ impl Parse for EventDeclarations {
 
         let mut events = vec![];
         while !content.is_empty() {
-            let event: EventDeclaration = content.parse()?;
+            let event = if content.peek(Ident) && content.peek2(Token![=]) {
+                EventDeclaration::parse_emits(&content)?
+            } else {
+                content.parse()?
+            };
             events.push(event);
         }
         Ok(EventDeclarations { events })
@@ -85,6 +95,28 @@ impl Parse for EventDeclaration {
     }
 }
 
+impl EventDeclaration {
+    /// Parses `emits = MessageType;` as an optional `emit` event which passes
+    /// the message onto the parent.
+    fn parse_emits(input: ParseStream<'_>) -> ParseResult<Self> {
+        let ident: Ident = input.parse()?;
+        if ident != "emits" {
+            Err(Error::new(ident.span(), "expected `emits` or `fn`"))?;
+        }
+        input.parse::<Token![=]>()?;
+        let message: Type = input.parse()?;
+        if input.peek(Token![;]) {
+            input.parse::<Token![;]>()?;
+        } else {
+            Err(input.error("expected `;`"))?;
+        }
+        Ok(syn::parse_quote! {
+            #[optional]
+            fn emit(&self, message: #message);
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -128,4 +160,19 @@ mod test {
         assert_eq!(decl.ident, "on_click");
         assert_eq!(decl.args.len(), 1);
     }
+
+    #[test]
+    fn should_parse_emitted_messages() {
+        let decls: EventDeclarations = syn::parse_str(
+            r#"(
+                emits = CounterMessage;
+            )"#,
+        ).unwrap();
+
+        assert_eq!(decls.events.len(), 1);
+        let decl = &decls.events[0];
+        assert!(decl.attr.is_some());
+        assert_eq!(decl.ident, "emit");
+        assert_eq!(decl.args.len(), 2);
+    }
 }
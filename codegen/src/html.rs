@@ -104,7 +104,13 @@ impl Parse for HtmlRoot {
 
 impl HtmlRoot {
     pub fn expand(&self) -> TokenStream {
-        let expanded: Vec<_> = self.items.iter().map(|i| i.expand()).collect();
+        self.expand_items(true)
+    }
+
+    /// Expands the items, hoisting the children of the static elements among
+    /// them unless they are within one already.
+    fn expand_items(&self, hoist: bool) -> TokenStream {
+        let expanded: Vec<_> = self.items.iter().map(|i| i.expand(hoist)).collect();
         if self.flat_len == 0 {
             quote! {
                 ruukh::vdom::VNode::None
@@ -121,6 +127,24 @@ impl HtmlRoot {
             }
         }
     }
+
+    /// The markup of the items along with their number of nodes, if they are
+    /// made of the literals only.
+    pub fn static_markup(&self) -> Option<(String, usize)> {
+        let mut markup = String::new();
+        let mut nodes = 0;
+        for items in &self.items {
+            let items = match items {
+                HtmlItems::Keyed(ref items) | HtmlItems::Unkeyed(ref items) => items,
+            };
+            for item in items {
+                let (item_markup, item_nodes) = item.static_markup()?;
+                markup.push_str(&item_markup);
+                nodes += item_nodes;
+            }
+        }
+        Some((markup, nodes))
+    }
 }
 
 pub enum HtmlItems {
@@ -129,10 +153,10 @@ pub enum HtmlItems {
 }
 
 impl HtmlItems {
-    fn expand(&self) -> TokenStream {
+    fn expand(&self, hoist: bool) -> TokenStream {
         match self {
             HtmlItems::Keyed(ref items) => {
-                let expanded: Vec<_> = items.iter().map(HtmlItem::expand).collect();
+                let expanded: Vec<_> = items.iter().map(|i| i.expand(hoist)).collect();
                 let capacity = expanded.len();
                 quote! {
                     ruukh::vdom::VNode::from(ruukh::vdom::vlist::VList::from({
@@ -146,7 +170,7 @@ impl HtmlItems {
                 }
             }
             HtmlItems::Unkeyed(ref items) => {
-                let expanded: Vec<_> = items.iter().map(HtmlItem::expand).collect();
+                let expanded: Vec<_> = items.iter().map(|i| i.expand(hoist)).collect();
                 quote! {
                     #(#expanded),*
                 }
@@ -177,10 +201,10 @@ impl Parse for HtmlItem {
 }
 
 impl HtmlItem {
-    fn expand(&self) -> TokenStream {
+    fn expand(&self, hoist: bool) -> TokenStream {
         let expanded = match self {
             HtmlItem::Element(ref element) => {
-                let expanded = element.expand();
+                let expanded = element.expand(hoist);
                 quote! {
                     ruukh::vdom::VNode::from(#expanded)
                 }
@@ -214,6 +238,15 @@ impl HtmlItem {
             _ => None,
         }
     }
+
+    fn static_markup(&self) -> Option<(String, usize)> {
+        match self {
+            HtmlItem::Element(ref el) => el.static_markup(),
+            HtmlItem::ExpressionBlock(_) => None,
+            // Quoted, so that the adjacent texts are told apart from one.
+            HtmlItem::Text(ref text) => Some((format!("{:?}", text.content), 1)),
+        }
+    }
 }
 
 pub struct Text {
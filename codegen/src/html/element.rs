@@ -12,6 +12,14 @@ use syn::{
     token, Token, {Expr, Ident},
 };
 
+/// The number of nodes from which a static subtree is cloned from a template.
+/// A smaller one is created faster than it is cloned.
+const MIN_TEMPLATE_NODES: usize = 4;
+
+/// The number of nodes from which the static children of an element are
+/// hoisted. Fewer ones are built faster than they are looked up.
+const MIN_HOISTED_NODES: usize = 3;
+
 pub enum HtmlElement {
     Normal(NormalHtmlElement),
     SelfClosing(SelfClosingHtmlElement),
@@ -28,9 +36,10 @@ impl Parse for HtmlElement {
 }
 
 impl HtmlElement {
-    pub fn expand(&self) -> TokenStream {
+    /// Expands the element, hoisting its static children if `hoist`.
+    pub fn expand(&self, hoist: bool) -> TokenStream {
         match self {
-            HtmlElement::Normal(ref normal) => normal.expand(),
+            HtmlElement::Normal(ref normal) => normal.expand(hoist),
             HtmlElement::SelfClosing(ref self_closing) => self_closing.expand(),
         }
     }
@@ -41,12 +50,26 @@ impl HtmlElement {
             HtmlElement::SelfClosing(ref el) => el.key(),
         }
     }
+
+    /// The markup of the element along with its number of nodes, if it and
+    /// its descendants are made of the literals only.
+    pub fn static_markup(&self) -> Option<(String, usize)> {
+        match self {
+            HtmlElement::Normal(ref el) => el.static_markup(),
+            HtmlElement::SelfClosing(ref el) => {
+                let tag = &el.tag;
+                let markup =
+                    static_opening_tag(&tag.tag_name, tag.is_dynamic(), &tag.prop_attributes)?;
+                Some((markup, 1))
+            }
+        }
+    }
 }
 
 pub struct NormalHtmlElement {
     pub opening_tag: OpeningTag,
     pub child: Box<HtmlRoot>,
-    pub closing_tag: ClosingTag,
+    pub closing_tag: Box<ClosingTag>,
 }
 
 impl Parse for NormalHtmlElement {
@@ -71,6 +94,14 @@ impl Parse for NormalHtmlElement {
         };
 
         if not_same {
+            if let TagName::Tag { ref name, .. } = closing_tag.tag_name {
+                if kw::is_void(name) {
+                    return Err(Error::new(
+                        closing_tag.tag_name.span(),
+                        "A void element cannot have any children or a closing tag.",
+                    ));
+                }
+            }
             return Err(Error::new(
                 closing_tag.tag_name.span(),
                 "Opening and closing tag must be same.",
@@ -80,15 +111,41 @@ impl Parse for NormalHtmlElement {
         Ok(NormalHtmlElement {
             opening_tag,
             child: Box::new(child),
-            closing_tag,
+            closing_tag: Box::new(closing_tag),
         })
     }
 }
 
 impl NormalHtmlElement {
-    fn expand(&self) -> TokenStream {
-        let child_expanded = self.child.expand();
-        self.opening_tag.expand_with(&child_expanded)
+    fn expand(&self, hoist: bool) -> TokenStream {
+        let markup = self.static_markup();
+        // Only the children are hoisted, so that the element stays an
+        // element. Those of a static element are hoisted as a whole.
+        let child_expanded = match markup {
+            Some((_, nodes)) if hoist && nodes > MIN_HOISTED_NODES => {
+                let child_expanded = self.child.expand_items(false);
+                quote! {
+                    ruukh::vdom::VNode::static_of(|| #child_expanded)
+                }
+            }
+            Some(_) => self.child.expand_items(false),
+            None => self.child.expand_items(true),
+        };
+        let expanded = self.opening_tag.expand_with(&child_expanded);
+        match markup {
+            Some((markup, nodes)) if nodes >= MIN_TEMPLATE_NODES => quote! {
+                #expanded.with_template(#markup)
+            },
+            _ => expanded,
+        }
+    }
+
+    fn static_markup(&self) -> Option<(String, usize)> {
+        let tag = &self.opening_tag;
+        let opening = static_opening_tag(&tag.tag_name, tag.is_dynamic(), &tag.prop_attributes)?;
+        let (child, nodes) = self.child.static_markup()?;
+        let name = tag.tag_name.name()?;
+        Some((format!("{}{}</{}>", opening, child, name), nodes + 1))
     }
 
     pub fn key(&self) -> Option<&KeyAttribute> {
@@ -118,10 +175,35 @@ impl SelfClosingHtmlElement {
     }
 }
 
+/// The markup of an opening tag, if it is of an element whose attributes are
+/// the literals only.
+fn static_opening_tag(
+    tag_name: &TagName,
+    is_dynamic: bool,
+    attributes: &[HtmlAttribute],
+) -> Option<String> {
+    let name = tag_name.name()?;
+    if is_dynamic {
+        return None;
+    }
+    let mut markup = format!("<{}", name);
+    for attr in attributes {
+        match attr.value {
+            Expr::Lit(ref lit) => markup.push_str(&format!(" {}={}", attr.key.name, quote!(#lit))),
+            _ => return None,
+        }
+    }
+    markup.push('>');
+    Some(markup)
+}
+
 pub struct OpeningTag {
     pub lt: Token![<],
     pub tag_name: TagName,
     pub key: Option<KeyAttribute>,
+    pub node_ref: Option<RefAttribute>,
+    pub keep_alive: Option<KeepAliveAttribute>,
+    pub spread: Option<SpreadAttribute>,
     pub prop_attributes: Vec<HtmlAttribute>,
     pub event_attributes: Vec<HtmlAttribute>,
     pub gt: Token![>],
@@ -130,13 +212,43 @@ pub struct OpeningTag {
 impl Parse for OpeningTag {
     fn parse(input: ParseStream<'_>) -> ParseResult<Self> {
         let lt = input.parse()?;
-        let tag_name = input.parse()?;
+        let tag_name: TagName = input.parse()?;
         let mut key = None;
+        let mut node_ref = None;
+        let mut keep_alive = None;
+        let mut spread = None;
 
         let mut attributes: Vec<HtmlAttribute> = vec![];
         while !input.peek(Token![>]) {
             if input.peek(kw::key) {
                 key = Some(input.parse()?);
+            } else if input.peek(Token![ref]) {
+                let attr: RefAttribute = input.parse()?;
+                if tag_name.is_component() {
+                    return Err(Error::new(
+                        attr.ref_.span(),
+                        "A ref can only be set on an element.",
+                    ));
+                }
+                node_ref = Some(attr);
+            } else if input.peek(kw::keep_alive) {
+                let attr: KeepAliveAttribute = input.parse()?;
+                if !tag_name.is_component() {
+                    return Err(Error::new(
+                        attr.keep_alive.span(),
+                        "Only a component can be kept alive.",
+                    ));
+                }
+                keep_alive = Some(attr);
+            } else if input.peek(Token![..]) {
+                let attr: SpreadAttribute = input.parse()?;
+                if tag_name.is_component() {
+                    return Err(Error::new(
+                        attr.dot2.span(),
+                        "Attributes can only be spread on an element.",
+                    ));
+                }
+                spread = Some(attr);
             } else {
                 attributes.push(input.parse()?);
             }
@@ -155,6 +267,9 @@ impl Parse for OpeningTag {
             lt,
             tag_name,
             key,
+            node_ref,
+            keep_alive,
+            spread,
             prop_attributes,
             event_attributes,
             gt,
@@ -163,6 +278,11 @@ impl Parse for OpeningTag {
 }
 
 impl OpeningTag {
+    /// Whether the tag has a ref, a spread or events, which are not static.
+    fn is_dynamic(&self) -> bool {
+        self.node_ref.is_some() || self.spread.is_some() || !self.event_attributes.is_empty()
+    }
+
     fn expand_with(&self, child: &TokenStream) -> TokenStream {
         match self.tag_name {
             TagName::Tag { ref name, .. } => {
@@ -176,14 +296,16 @@ impl OpeningTag {
                     .iter()
                     .map(|e| e.expand_as_event_attribute().unwrap())
                     .collect();
+                let node_ref = self.node_ref.as_ref().map(|r| r.expand());
+                let spread = self.spread.as_ref().map(|s| s.expand());
 
                 quote! {
                     ruukh::vdom::velement::VElement::new(
                         #name,
                         vec![#(#prop_attributes),*],
                         vec![#(#event_attributes),*],
-                        #child
-                    )
+                        Some(#child)
+                    )#spread#node_ref
                 }
             }
             TagName::Component { ref ident } => {
@@ -201,12 +323,13 @@ impl OpeningTag {
 
                 let props_ident = Ident::new(&format!("{}{}", ident, PROPS_SUFFIX), ident.span());
                 let event_ident = Ident::new(&format!("{}{}", ident, EVENT_SUFFIX), ident.span());
+                let keep_alive = self.keep_alive.as_ref().map(|k| k.expand());
                 let span = ident.span();
                 quote_spanned!{span=>
                     ruukh::vdom::vcomponent::VComponent::new::<#ident>(
                         #props_ident!(#(#prop_attributes),*),
                         #event_ident!(#(#event_attributes),*),
-                    )
+                    )#keep_alive
                 }
             }
         }
@@ -235,6 +358,8 @@ pub struct SelfClosingTag {
     pub lt: Token![<],
     pub tag_name: TagName,
     pub key: Option<KeyAttribute>,
+    pub node_ref: Option<RefAttribute>,
+    pub spread: Option<SpreadAttribute>,
     pub prop_attributes: Vec<HtmlAttribute>,
     pub event_attributes: Vec<HtmlAttribute>,
     pub slash: Option<Token![/]>,
@@ -244,13 +369,19 @@ pub struct SelfClosingTag {
 impl Parse for SelfClosingTag {
     fn parse(input: ParseStream<'_>) -> ParseResult<Self> {
         let lt = input.parse()?;
-        let tag_name = input.parse()?;
+        let tag_name: TagName = input.parse()?;
         let mut key = None;
+        let mut node_ref = None;
+        let mut spread = None;
 
         let mut attributes: Vec<HtmlAttribute> = vec![];
         while !input.peek(Token![/]) && !input.peek(Token![>]) {
             if input.peek(kw::key) {
                 key = Some(input.parse()?);
+            } else if input.peek(Token![ref]) {
+                node_ref = Some(input.parse()?);
+            } else if input.peek(Token![..]) {
+                spread = Some(input.parse()?);
             } else {
                 attributes.push(input.parse()?);
             }
@@ -259,6 +390,20 @@ impl Parse for SelfClosingTag {
         let slash = input.parse()?;
         let gt = input.parse()?;
 
+        if input.peek(Token![<]) && input.peek2(Token![/]) {
+            let closing: ClosingTag = input.fork().parse()?;
+            if let (TagName::Tag { name: ref op, .. }, TagName::Tag { name: ref cl, .. }) =
+                (&tag_name, &closing.tag_name)
+            {
+                if op == cl {
+                    return Err(Error::new(
+                        closing.tag_name.span(),
+                        "A void element cannot have any children or a closing tag.",
+                    ));
+                }
+            }
+        }
+
         let (prop_attributes, event_attributes) =
             attributes.into_iter().partition(|attr| attr.at.is_none());
 
@@ -266,6 +411,8 @@ impl Parse for SelfClosingTag {
             lt,
             tag_name,
             key,
+            node_ref,
+            spread,
             prop_attributes,
             event_attributes,
             slash,
@@ -275,6 +422,11 @@ impl Parse for SelfClosingTag {
 }
 
 impl SelfClosingTag {
+    /// Whether the tag has a ref, a spread or events, which are not static.
+    fn is_dynamic(&self) -> bool {
+        self.node_ref.is_some() || self.spread.is_some() || !self.event_attributes.is_empty()
+    }
+
     fn expand(&self) -> TokenStream {
         match self.tag_name {
             TagName::Tag { ref name, .. } => {
@@ -288,13 +440,15 @@ impl SelfClosingTag {
                     .iter()
                     .map(|e| e.expand_as_event_attribute().unwrap())
                     .collect();
+                let node_ref = self.node_ref.as_ref().map(|r| r.expand());
+                let spread = self.spread.as_ref().map(|s| s.expand());
 
                 quote! {
                     ruukh::vdom::velement::VElement::childless(
                         #name,
                         vec![#(#prop_attributes),*],
                         vec![#(#event_attributes),*]
-                    )
+                    )#spread#node_ref
                 }
             }
             _ => unreachable!("The spec specified self-closing tags are the only ones allowed."),
@@ -334,6 +488,80 @@ impl KeyAttribute {
     }
 }
 
+pub struct RefAttribute {
+    pub ref_: Token![ref],
+    pub value: Expr,
+}
+
+impl Parse for RefAttribute {
+    fn parse(input: ParseStream<'_>) -> ParseResult<Self> {
+        let ref_ = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let content;
+        braced!(content in input);
+        let value = content.parse()?;
+        Ok(RefAttribute { ref_, value })
+    }
+}
+
+impl RefAttribute {
+    fn expand(&self) -> TokenStream {
+        let value = &self.value;
+        quote! {
+            .with_node_ref(#value)
+        }
+    }
+}
+
+pub struct KeepAliveAttribute {
+    pub keep_alive: kw::keep_alive,
+    pub value: Expr,
+}
+
+impl Parse for KeepAliveAttribute {
+    fn parse(input: ParseStream<'_>) -> ParseResult<Self> {
+        let keep_alive = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let content;
+        braced!(content in input);
+        let value = content.parse()?;
+        Ok(KeepAliveAttribute { keep_alive, value })
+    }
+}
+
+impl KeepAliveAttribute {
+    fn expand(&self) -> TokenStream {
+        let value = &self.value;
+        quote! {
+            .keep_alive(#value)
+        }
+    }
+}
+
+pub struct SpreadAttribute {
+    pub dot2: Token![..],
+    pub value: Expr,
+}
+
+impl Parse for SpreadAttribute {
+    fn parse(input: ParseStream<'_>) -> ParseResult<Self> {
+        let dot2 = input.parse()?;
+        let content;
+        braced!(content in input);
+        let value = content.parse()?;
+        Ok(SpreadAttribute { dot2, value })
+    }
+}
+
+impl SpreadAttribute {
+    fn expand(&self) -> TokenStream {
+        let value = &self.value;
+        quote! {
+            .with_attributes(#value)
+        }
+    }
+}
+
 pub struct HtmlAttribute {
     pub at: Option<Token![@]>,
     pub key: AttributeName,
@@ -412,6 +640,13 @@ impl TagName {
             TagName::Component { ref ident } => ident.span(),
         }
     }
+    /// The name of the tag, if it is of an element.
+    fn name(&self) -> Option<&str> {
+        match self {
+            TagName::Tag { ref name, .. } => Some(name),
+            TagName::Component { .. } => None,
+        }
+    }
 }
 
 impl Parse for TagName {
@@ -521,6 +756,34 @@ mod test {
         let _: SelfClosingTag = syn::parse_str("<input/>").unwrap();
     }
 
+    #[test]
+    fn should_not_parse_void_element_with_closing_tag() {
+        assert!(syn::parse_str::<HtmlElement>("<br></br>").is_err());
+        assert!(syn::parse_str::<NormalHtmlElement>(r#"<div><input>"a"</input></div>"#).is_err());
+    }
+
+    #[test]
+    fn should_find_the_static_markup_of_literals_only() {
+        let el: HtmlElement =
+            syn::parse_str(r#"<ul class={"menu"}><li>"Home"</li><li>"About"<br></li></ul>"#)
+                .unwrap();
+        assert_eq!(
+            el.static_markup(),
+            Some((
+                r#"<ul class="menu"><li>"Home"</li><li>"About"<br></li></ul>"#.to_string(),
+                6
+            ))
+        );
+
+        let el: HtmlElement = syn::parse_str(r#"<ul><li>{ self.name }</li></ul>"#).unwrap();
+        assert!(el.static_markup().is_none());
+        let el: HtmlElement =
+            syn::parse_str(r#"<ul><li @click={Self::open}>"Home"</li></ul>"#).unwrap();
+        assert!(el.static_markup().is_none());
+        let el: HtmlElement = syn::parse_str(r#"<ul><Item></Item></ul>"#).unwrap();
+        assert!(el.static_markup().is_none());
+    }
+
     #[test]
     fn should_parse_normal_attribute() {
         let attr: HtmlAttribute = syn::parse_str(r#"name={"value"}"#).unwrap();
@@ -533,6 +796,47 @@ mod test {
         assert!(attr.at.is_some());
     }
 
+    #[test]
+    fn should_parse_ref_attribute() {
+        let tag: OpeningTag = syn::parse_str(r#"<div ref={&self.node} class={"a"}>"#).unwrap();
+        assert!(tag.node_ref.is_some());
+        assert_eq!(tag.prop_attributes.len(), 1);
+    }
+
+    #[test]
+    fn should_not_parse_ref_attribute_on_component() {
+        assert!(syn::parse_str::<OpeningTag>(r#"<Button ref={&self.node}>"#).is_err());
+    }
+
+    #[test]
+    fn should_parse_keep_alive_attribute() {
+        let tag: OpeningTag =
+            syn::parse_str(r#"<Editor keep_alive={&self.cache} text={"a"}>"#).unwrap();
+        assert!(tag.keep_alive.is_some());
+        assert_eq!(tag.prop_attributes.len(), 1);
+    }
+
+    #[test]
+    fn should_not_parse_keep_alive_attribute_on_element() {
+        assert!(syn::parse_str::<OpeningTag>(r#"<div keep_alive={&self.cache}>"#).is_err());
+    }
+
+    #[test]
+    fn should_parse_spread_attribute() {
+        let tag: OpeningTag =
+            syn::parse_str(r#"<div ..{self.attributes.clone()} id={"a"}>"#).unwrap();
+        assert!(tag.spread.is_some());
+        assert_eq!(tag.prop_attributes.len(), 1);
+
+        let tag: SelfClosingTag = syn::parse_str(r#"<input ..{attributes}>"#).unwrap();
+        assert!(tag.spread.is_some());
+    }
+
+    #[test]
+    fn should_not_parse_spread_attribute_on_component() {
+        assert!(syn::parse_str::<OpeningTag>(r#"<Button ..{attributes}>"#).is_err());
+    }
+
     #[test]
     fn should_parse_single_tag_name() {
         let parsed: TagName = syn::parse_str("Identifier").unwrap();
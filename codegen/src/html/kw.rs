@@ -2,6 +2,7 @@
 use syn::{custom_keyword, parse::ParseStream, Token};
 
 custom_keyword!(key);
+custom_keyword!(keep_alive);
 
 macro_rules! custom_keywords {
     ($($ident:ident),*) => {
@@ -28,3 +29,12 @@ pub fn is_self_closing(inp: ParseStream<'_>) -> bool {
             [area, base, br, col, embed, hr, img, input, link, meta, param, source, track, wbr]
         ))
 }
+
+/// Whether the tag is of a void element, which cannot have any children.
+pub fn is_void(tag: &str) -> bool {
+    [
+        "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param",
+        "source", "track", "wbr",
+    ]
+    .contains(&tag)
+}
@@ -63,6 +63,42 @@ pub fn derive_lifecycle(input: proc_macro::TokenStream) -> proc_macro::TokenStre
 /// You may place multiple event declarations. Also, these event declarations
 /// require that the event handlers be passed compulsorily.
 ///
+/// Instead of a closure for each event, the component may declare the type
+/// of the messages it emits, usually an enum, with `emits`. It then emits
+/// them with `self.emit(message)` and the parent receives them with a single
+/// handler, like `@emit={Self::dispatch}` which routes them to its reducer.
+/// The handler is optional.
+/// # Example
+/// ```ignore,compile_fail
+/// enum CounterMessage {
+///     Incremented,
+///     Reset,
+/// }
+///
+/// #[component]
+/// #[events(
+///     emits = CounterMessage;
+/// )]
+/// struct Counter;
+/// ```
+///
+/// An event which returns a bare `Markup` is a render prop. The parent passes
+/// a handler which returns its own markup, like `@render-item={Self::item}`,
+/// which is rendered within the component in the context of the parent, so
+/// that the parent handles the events of the markup. The component calls it
+/// in its render, like `self.render_item(item)`, getting a `Markup<Self>`, and
+/// is rerendered along with the parent.
+/// # Example
+/// ```ignore,compile_fail
+/// #[component]
+/// #[events(
+///     fn render_item(&self, item: &Item) -> Markup;
+/// )]
+/// struct List {
+///     items: Vec<Item>,
+/// }
+/// ```
+///
 /// `#[component]` also allows to annotate the struct fields with additional
 /// attributes. Such as:
 ///
@@ -173,6 +209,27 @@ pub fn component(
 ///     "There are "{ count }" people."
 /// }
 /// ```
+///
+/// ## Reference to the DOM node
+/// ```ignore,compile_fail
+/// html! {
+///     <input ref={&self.input}>
+/// }
+/// ```
+///
+/// ## Keeping a removed component alive
+/// ```ignore,compile_fail
+/// html! {
+///     <Editor keep_alive={&self.cache}></Editor>
+/// }
+/// ```
+///
+/// ## Spreading the attributes of a map onto an element
+/// ```ignore,compile_fail
+/// html! {
+///     <button ..{self.attributes.clone()} name={"close"}></button>
+/// }
+/// ```
 #[proc_macro]
 pub fn html(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let parsed = parse_macro_input!(input as HtmlRoot);
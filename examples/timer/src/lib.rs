@@ -1,8 +1,7 @@
 #![feature(proc_macro_hygiene, decl_macro)]
 
-use ruukh::prelude::*;
-use wasm_bindgen::{prelude::*, JsCast};
-use web_sys::window;
+use ruukh::{prelude::*, web_api::set_interval};
+use wasm_bindgen::prelude::*;
 
 #[component]
 struct MainApp {
@@ -11,25 +10,19 @@ struct MainApp {
 }
 
 impl Lifecycle for MainApp {
-    fn created(&self) {
+    fn mounted(&self) {
         // Get a setter to mutate the state within the closure.
         let setter = self.state_setter();
 
-        let closure = Closure::wrap(Box::new(move || {
+        let interval = set_interval(1000, move || {
             setter.set_state(|state| {
                 state.seconds += 1;
             });
-        }) as Box<Fn()>);
+        })
+        .unwrap();
 
-        window()
-            .unwrap()
-            .set_interval_with_callback_and_timeout_and_arguments_0(
-                closure.as_ref().unchecked_ref(),
-                1000,
-            ).unwrap();
-
-        // Forget the closure so that it lives for the lifetime of the program.
-        closure.forget();
+        // Keep the interval going until the component is destroyed.
+        self.scope().keep(interval);
     }
 }
 
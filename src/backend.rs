@@ -0,0 +1,380 @@
+//! The rendering backends the VDOM is patched onto.
+//!
+//! The VDOM and its diffing code do not talk to the browser DOM directly.
+//! Instead, every operation which mutates the rendered tree goes through a
+//! [Backend](trait.Backend.html). The [WebBackend](struct.WebBackend.html)
+//! renders onto the browser DOM and is what an [App](../struct.App.html) uses.
+//! The [TuiBackend](struct.TuiBackend.html) renders onto an in-memory tree
+//! which is laid out as text for a terminal. Other backends (a string
+//! renderer, an in-memory test renderer or a native renderer) may implement
+//! the trait to reuse the same VDOM and diffing code.
+//!
+//! The VDOM is not generic over the backend. Its nodes are type-erased into a
+//! [Node](struct.Node.html) instead, so that the components, the `html!`
+//! markup & the VDOM types are the same whichever backend they are rendered
+//! onto. So, the nodes of two backends mixed together are only told apart at
+//! runtime, where patching them fails with an error.
+
+use crate::stats;
+use std::{
+    any::{Any, TypeId},
+    rc::Rc,
+};
+use wasm_bindgen::prelude::JsValue;
+use web_sys::Event;
+
+pub use self::{
+    tui::{TuiBackend, TuiNode},
+    web::WebBackend,
+};
+pub(crate) use self::void::VoidBackend;
+
+mod tui;
+mod void;
+mod web;
+
+/// Trait to implement a target onto which the VDOM is rendered.
+///
+/// A backend creates the nodes and mutates them as instructed by the patches.
+/// It is cloned into every node it creates, so it is expected to be cheap to
+/// clone.
+pub trait Backend: Clone + 'static {
+    /// The type of the nodes created by this backend.
+    type Node: Clone + 'static;
+    /// A handle to an attached event listener, which is used to detach it.
+    type Listener: 'static;
+
+    /// Creates an element node with the given tag.
+    fn create_element(&self, tag: &str) -> Result<Self::Node, JsValue>;
+
+    /// Creates a text node with the given content.
+    fn create_text_node(&self, content: &str) -> Result<Self::Node, JsValue>;
+
+    /// Creates a comment node with the given content.
+    fn create_comment(&self, content: &str) -> Result<Self::Node, JsValue>;
+
+    /// Replaces the content of a text/comment node.
+    fn set_text_content(&self, node: &Self::Node, content: &str) -> Result<(), JsValue>;
+
+    /// Sets an attribute on an element node.
+    fn set_attribute(&self, node: &Self::Node, key: &str, value: &str) -> Result<(), JsValue>;
+
+    /// Removes an attribute from an element node.
+    fn remove_attribute(&self, node: &Self::Node, key: &str) -> Result<(), JsValue>;
+
+    /// Inserts the `child` in the `parent` before the `next` node. If there
+    /// is no `next` node, the `child` is appended at the end.
+    ///
+    /// If the `child` is already attached to the `parent`, it is moved.
+    fn insert_before(
+        &self,
+        parent: &Self::Node,
+        child: &Self::Node,
+        next: Option<&Self::Node>,
+    ) -> Result<(), JsValue>;
+
+    /// Removes the `child` from the `parent`.
+    fn remove_child(&self, parent: &Self::Node, child: &Self::Node) -> Result<(), JsValue>;
+
+    /// Gets the node which follows the `node` within its parent, if any. It
+    /// is only required along with `clone_node`, to take over the cloned
+    /// nodes.
+    fn next_sibling(&self, _: &Self::Node) -> Option<Self::Node> {
+        None
+    }
+
+    /// Gets the first child of the node, if any. Same as `next_sibling`, it
+    /// is only required along with `clone_node`.
+    fn first_child(&self, _: &Self::Node) -> Option<Self::Node> {
+        None
+    }
+
+    /// Clones the node along with its attributes & descendants, so that the
+    /// static subtrees are cloned from a template instead of being created
+    /// node by node. A backend which cannot clone its nodes creates them
+    /// anew.
+    fn clone_node(&self, _: &Self::Node) -> Option<Self::Node> {
+        None
+    }
+
+    /// Attaches an event listener on the node, which is invoked when an event
+    /// of the given type occurs.
+    fn add_event_listener(
+        &self,
+        node: &Self::Node,
+        type_: &str,
+        listener: Box<dyn Fn(Event)>,
+    ) -> Result<Self::Listener, JsValue>;
+
+    /// Detaches an event listener which was attached earlier.
+    fn remove_event_listener(
+        &self,
+        node: &Self::Node,
+        type_: &str,
+        listener: &Self::Listener,
+    ) -> Result<(), JsValue>;
+
+    /// Moves the focus onto an element node. A backend without any notion of
+    /// focus leaves it be.
+    fn focus(&self, _: &Self::Node) -> Result<(), JsValue> {
+        Ok(())
+    }
+}
+
+/// A node rendered by any of the backends.
+///
+/// The VDOM stores these nodes without knowing the backend that created them.
+/// The nodes created from a node always belong to the same backend.
+#[derive(Clone)]
+pub struct Node(Rc<dyn NodeManager>);
+
+/// A handle to an event listener attached via any of the backends.
+pub(crate) struct Listener(Box<dyn Any>);
+
+impl Node {
+    /// Wraps a node of the given backend. Usually, this is the node the VDOM
+    /// is mounted onto.
+    pub fn new<B: Backend>(backend: B, node: B::Node) -> Node {
+        Node(Rc::new(BackendNode { backend, node }))
+    }
+
+    /// Gets the underlying node if it was created by the backend `B`.
+    pub fn backend_node<B: Backend>(&self) -> Option<&B::Node> {
+        self.0
+            .as_any()
+            .downcast_ref::<BackendNode<B>>()
+            .map(|inner| &inner.node)
+    }
+
+    pub(crate) fn create_element(&self, tag: &str) -> Result<Node, JsValue> {
+        stats::record(|stats| stats.elements_created += 1);
+        self.0.create_element(tag)
+    }
+
+    pub(crate) fn create_text_node(&self, content: &str) -> Result<Node, JsValue> {
+        stats::record(|stats| stats.texts_created += 1);
+        self.0.create_text_node(content)
+    }
+
+    pub(crate) fn create_comment(&self, content: &str) -> Result<Node, JsValue> {
+        stats::record(|stats| stats.texts_created += 1);
+        self.0.create_comment(content)
+    }
+
+    pub(crate) fn set_text_content(&self, content: &str) -> Result<(), JsValue> {
+        stats::record(|stats| stats.text_updates += 1);
+        self.0.set_text_content(content)
+    }
+
+    pub(crate) fn set_attribute(&self, key: &str, value: &str) -> Result<(), JsValue> {
+        stats::record(|stats| stats.attributes_set += 1);
+        self.0.set_attribute(key, value)
+    }
+
+    pub(crate) fn remove_attribute(&self, key: &str) -> Result<(), JsValue> {
+        stats::record(|stats| stats.attributes_removed += 1);
+        self.0.remove_attribute(key)
+    }
+
+    pub(crate) fn insert_before(&self, child: &Node, next: Option<&Node>) -> Result<(), JsValue> {
+        stats::record(|stats| stats.nodes_inserted += 1);
+        self.0.insert_before(child, next)
+    }
+
+    /// Moves the `child`, which is already attached to the node, before the
+    /// `next` node.
+    pub(crate) fn move_before(&self, child: &Node, next: Option<&Node>) -> Result<(), JsValue> {
+        stats::record(|stats| stats.nodes_moved += 1);
+        self.0.insert_before(child, next)
+    }
+
+    pub(crate) fn remove_child(&self, child: &Node) -> Result<(), JsValue> {
+        stats::record(|stats| stats.nodes_removed += 1);
+        self.0.remove_child(child)
+    }
+
+    pub(crate) fn next_sibling(&self) -> Option<Node> {
+        self.0.next_sibling()
+    }
+
+    pub(crate) fn first_child(&self) -> Option<Node> {
+        self.0.first_child()
+    }
+
+    pub(crate) fn clone_node(&self) -> Option<Node> {
+        self.0.clone_node()
+    }
+
+    /// The type which tells apart the nodes of a backend from the others.
+    pub(crate) fn backend_type(&self) -> TypeId {
+        <dyn Any>::type_id(self.0.as_any())
+    }
+
+    pub(crate) fn add_event_listener(
+        &self,
+        type_: &str,
+        listener: Box<dyn Fn(Event)>,
+    ) -> Result<Listener, JsValue> {
+        self.0.add_event_listener(type_, listener)
+    }
+
+    pub(crate) fn remove_event_listener(
+        &self,
+        type_: &str,
+        listener: &Listener,
+    ) -> Result<(), JsValue> {
+        self.0.remove_event_listener(type_, listener)
+    }
+
+    pub(crate) fn focus(&self) -> Result<(), JsValue> {
+        self.0.focus()
+    }
+}
+
+/// A node along with the backend which created it.
+struct BackendNode<B: Backend> {
+    backend: B,
+    node: B::Node,
+}
+
+impl<B: Backend> BackendNode<B> {
+    fn wrap(&self, node: B::Node) -> Node {
+        Node::new(self.backend.clone(), node)
+    }
+
+    fn unwrap<'a>(&self, node: &'a Node) -> Result<&'a B::Node, JsValue> {
+        node.backend_node::<B>()
+            .ok_or_else(|| mixed("Nodes of different backends cannot be mixed"))
+    }
+}
+
+trait NodeManager {
+    fn create_element(&self, tag: &str) -> Result<Node, JsValue>;
+
+    fn create_text_node(&self, content: &str) -> Result<Node, JsValue>;
+
+    fn create_comment(&self, content: &str) -> Result<Node, JsValue>;
+
+    fn set_text_content(&self, content: &str) -> Result<(), JsValue>;
+
+    fn set_attribute(&self, key: &str, value: &str) -> Result<(), JsValue>;
+
+    fn remove_attribute(&self, key: &str) -> Result<(), JsValue>;
+
+    fn insert_before(&self, child: &Node, next: Option<&Node>) -> Result<(), JsValue>;
+
+    fn remove_child(&self, child: &Node) -> Result<(), JsValue>;
+
+    fn next_sibling(&self) -> Option<Node>;
+
+    fn first_child(&self) -> Option<Node>;
+
+    fn clone_node(&self) -> Option<Node>;
+
+    fn add_event_listener(
+        &self,
+        type_: &str,
+        listener: Box<dyn Fn(Event)>,
+    ) -> Result<Listener, JsValue>;
+
+    fn remove_event_listener(&self, type_: &str, listener: &Listener) -> Result<(), JsValue>;
+
+    fn focus(&self) -> Result<(), JsValue>;
+
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<B: Backend> NodeManager for BackendNode<B> {
+    fn create_element(&self, tag: &str) -> Result<Node, JsValue> {
+        Ok(self.wrap(self.backend.create_element(tag)?))
+    }
+
+    fn create_text_node(&self, content: &str) -> Result<Node, JsValue> {
+        Ok(self.wrap(self.backend.create_text_node(content)?))
+    }
+
+    fn create_comment(&self, content: &str) -> Result<Node, JsValue> {
+        Ok(self.wrap(self.backend.create_comment(content)?))
+    }
+
+    fn set_text_content(&self, content: &str) -> Result<(), JsValue> {
+        self.backend.set_text_content(&self.node, content)
+    }
+
+    fn set_attribute(&self, key: &str, value: &str) -> Result<(), JsValue> {
+        self.backend.set_attribute(&self.node, key, value)
+    }
+
+    fn remove_attribute(&self, key: &str) -> Result<(), JsValue> {
+        self.backend.remove_attribute(&self.node, key)
+    }
+
+    fn insert_before(&self, child: &Node, next: Option<&Node>) -> Result<(), JsValue> {
+        let next = match next {
+            Some(next) => Some(self.unwrap(next)?),
+            None => None,
+        };
+        self.backend
+            .insert_before(&self.node, self.unwrap(child)?, next)
+    }
+
+    fn remove_child(&self, child: &Node) -> Result<(), JsValue> {
+        self.backend.remove_child(&self.node, self.unwrap(child)?)
+    }
+
+    fn next_sibling(&self) -> Option<Node> {
+        self.backend
+            .next_sibling(&self.node)
+            .map(|next| self.wrap(next))
+    }
+
+    fn first_child(&self) -> Option<Node> {
+        self.backend
+            .first_child(&self.node)
+            .map(|child| self.wrap(child))
+    }
+
+    fn clone_node(&self) -> Option<Node> {
+        self.backend
+            .clone_node(&self.node)
+            .map(|node| self.wrap(node))
+    }
+
+    fn add_event_listener(
+        &self,
+        type_: &str,
+        listener: Box<dyn Fn(Event)>,
+    ) -> Result<Listener, JsValue> {
+        let listener = self
+            .backend
+            .add_event_listener(&self.node, type_, listener)?;
+        Ok(Listener(Box::new(listener)))
+    }
+
+    fn remove_event_listener(&self, type_: &str, listener: &Listener) -> Result<(), JsValue> {
+        let listener = listener
+            .0
+            .downcast_ref::<B::Listener>()
+            .ok_or_else(|| mixed("Listeners of different backends cannot be mixed"))?;
+        self.backend
+            .remove_event_listener(&self.node, type_, listener)
+    }
+
+    fn focus(&self) -> Result<(), JsValue> {
+        self.backend.focus(&self.node)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// The error of the nodes or the listeners of different backends mixed
+/// together. A `JsValue` can only be created from a string on wasm.
+fn mixed(message: &str) -> JsValue {
+    if cfg!(target_arch = "wasm32") {
+        JsValue::from_str(message)
+    } else {
+        JsValue::NULL
+    }
+}
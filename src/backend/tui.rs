@@ -0,0 +1,356 @@
+//! The backend which renders onto an in-memory tree laid out as terminal text.
+
+use crate::backend::{Backend, Node};
+use indexmap::IndexMap;
+use std::{
+    cell::RefCell,
+    fmt::{self, Display, Formatter},
+    mem,
+    rc::{Rc, Weak},
+};
+use wasm_bindgen::prelude::JsValue;
+use web_sys::Event;
+
+/// The backend which renders onto an in-memory tree of
+/// [TuiNode](struct.TuiNode.html)s.
+///
+/// It does not require a browser, so it is useful for demos, server-side
+/// previews and testing the diffing of the VDOM.
+#[derive(Clone, Copy, Default)]
+pub struct TuiBackend;
+
+/// A node rendered by the `TuiBackend`.
+///
+/// Displaying a node lays it out as text, where only a subset of elements are
+/// understood:
+/// 1. `div` and `p` are blocks which start on a new line.
+/// 2. `ul` and `ol` are lists whose `li` children are bulleted or numbered
+///    and indented when nested.
+/// 3. `br` breaks the line.
+/// 4. Any other element, like `span`, is laid out inline along with the text.
+#[derive(Clone)]
+pub struct TuiNode(Rc<RefCell<TuiNodeData>>);
+
+struct TuiNodeData {
+    kind: TuiNodeKind,
+    parent: Option<Weak<RefCell<TuiNodeData>>>,
+    children: Vec<TuiNode>,
+}
+
+thread_local! {
+    /// The node which has the focus, like the active element of a document.
+    static FOCUSED: RefCell<Weak<RefCell<TuiNodeData>>> = const { RefCell::new(Weak::new()) };
+}
+
+#[derive(Clone)]
+enum TuiNodeKind {
+    Element {
+        tag: String,
+        attributes: IndexMap<String, String>,
+    },
+    Text(String),
+    Comment(String),
+}
+
+impl TuiNode {
+    /// Creates a root node to mount an App onto.
+    pub fn root() -> TuiNode {
+        TuiNode::new(TuiNodeKind::Element {
+            tag: "div".to_string(),
+            attributes: IndexMap::new(),
+        })
+    }
+
+    fn new(kind: TuiNodeKind) -> TuiNode {
+        TuiNode(Rc::new(RefCell::new(TuiNodeData {
+            kind,
+            parent: None,
+            children: vec![],
+        })))
+    }
+
+    /// The tag of the node, if it is an element.
+    pub fn tag(&self) -> Option<String> {
+        match self.0.borrow().kind {
+            TuiNodeKind::Element { ref tag, .. } => Some(tag.clone()),
+            _ => None,
+        }
+    }
+
+    /// The value of an attribute, if it is an element and has the attribute.
+    pub fn attribute(&self, key: &str) -> Option<String> {
+        match self.0.borrow().kind {
+            TuiNodeKind::Element { ref attributes, .. } => attributes.get(key).cloned(),
+            _ => None,
+        }
+    }
+
+    /// The text within the node and its descendants, excluding the comments.
+    pub fn text_content(&self) -> String {
+        let data = self.0.borrow();
+        match data.kind {
+            TuiNodeKind::Text(ref text) => text.clone(),
+            TuiNodeKind::Comment(_) => String::new(),
+            TuiNodeKind::Element { .. } => data.children.iter().map(TuiNode::text_content).collect(),
+        }
+    }
+
+    /// The children of the node.
+    pub fn children(&self) -> Vec<TuiNode> {
+        self.0.borrow().children.clone()
+    }
+
+    /// Whether the node was the last one to be focused.
+    pub fn is_focused(&self) -> bool {
+        FOCUSED.with(|focused| {
+            focused
+                .borrow()
+                .upgrade()
+                .is_some_and(|node| Rc::ptr_eq(&node, &self.0))
+        })
+    }
+
+    /// Clones the node along with its descendants, detached from any parent.
+    fn deep_clone(&self) -> TuiNode {
+        let data = self.0.borrow();
+        let clone = TuiNode::new(data.kind.clone());
+        for child in &data.children {
+            let child = child.deep_clone();
+            child.0.borrow_mut().parent = Some(Rc::downgrade(&clone.0));
+            clone.0.borrow_mut().children.push(child);
+        }
+        clone
+    }
+
+    fn is_same(&self, other: &TuiNode) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Removes the node from its current parent, if any.
+    fn detach(&self) {
+        let parent = self.0.borrow_mut().parent.take();
+        if let Some(parent) = parent.and_then(|parent| parent.upgrade()) {
+            parent
+                .borrow_mut()
+                .children
+                .retain(|child| !child.is_same(self));
+        }
+    }
+
+    fn layout(&self, layout: &mut Layout) {
+        let data = self.0.borrow();
+        match data.kind {
+            TuiNodeKind::Text(ref text) => layout.push(text),
+            TuiNodeKind::Comment(_) => {}
+            TuiNodeKind::Element { ref tag, .. } => match tag.as_str() {
+                "div" | "p" => {
+                    layout.break_line();
+                    for child in data.children.iter() {
+                        child.layout(layout);
+                    }
+                    layout.break_line();
+                }
+                "ul" | "ol" => {
+                    layout.break_line();
+                    let mut number = 0;
+                    for child in data.children.iter() {
+                        if child.tag().as_deref() == Some("li") {
+                            number += 1;
+                            let bullet = if tag == "ol" {
+                                format!("{}. ", number)
+                            } else {
+                                "- ".to_string()
+                            };
+                            layout.push(&bullet);
+                            layout.indent += bullet.len();
+                            for grand_child in child.0.borrow().children.iter() {
+                                grand_child.layout(layout);
+                            }
+                            layout.indent -= bullet.len();
+                            layout.break_line();
+                        } else {
+                            child.layout(layout);
+                        }
+                    }
+                }
+                "br" => layout.force_break_line(),
+                _ => {
+                    for child in data.children.iter() {
+                        child.layout(layout);
+                    }
+                }
+            },
+        }
+    }
+}
+
+impl Display for TuiNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut layout = Layout::default();
+        self.layout(&mut layout);
+        layout.break_line();
+        write!(f, "{}", layout.lines.join("\n"))
+    }
+}
+
+/// Lines of text being laid out.
+#[derive(Default)]
+struct Layout {
+    lines: Vec<String>,
+    current: String,
+    indent: usize,
+}
+
+impl Layout {
+    fn push(&mut self, text: &str) {
+        if self.current.is_empty() {
+            self.current = " ".repeat(self.indent);
+        }
+        self.current.push_str(text);
+    }
+
+    /// Starts a new line, unless the current one is empty.
+    fn break_line(&mut self) {
+        if !self.current.is_empty() {
+            self.lines.push(mem::take(&mut self.current));
+        }
+    }
+
+    /// Starts a new line, even if the current one is empty.
+    fn force_break_line(&mut self) {
+        self.lines.push(mem::take(&mut self.current));
+    }
+}
+
+impl Backend for TuiBackend {
+    type Node = TuiNode;
+    type Listener = ();
+
+    fn create_element(&self, tag: &str) -> Result<Self::Node, JsValue> {
+        Ok(TuiNode::new(TuiNodeKind::Element {
+            tag: tag.to_string(),
+            attributes: IndexMap::new(),
+        }))
+    }
+
+    fn create_text_node(&self, content: &str) -> Result<Self::Node, JsValue> {
+        Ok(TuiNode::new(TuiNodeKind::Text(content.to_string())))
+    }
+
+    fn create_comment(&self, content: &str) -> Result<Self::Node, JsValue> {
+        Ok(TuiNode::new(TuiNodeKind::Comment(content.to_string())))
+    }
+
+    fn set_text_content(&self, node: &Self::Node, content: &str) -> Result<(), JsValue> {
+        let mut data = node.0.borrow_mut();
+        match data.kind {
+            TuiNodeKind::Text(ref mut text) | TuiNodeKind::Comment(ref mut text) => {
+                *text = content.to_string();
+            }
+            TuiNodeKind::Element { .. } => {
+                let text = TuiNode::new(TuiNodeKind::Text(content.to_string()));
+                text.0.borrow_mut().parent = Some(Rc::downgrade(&node.0));
+                data.children = vec![text];
+            }
+        }
+        Ok(())
+    }
+
+    fn set_attribute(&self, node: &Self::Node, key: &str, value: &str) -> Result<(), JsValue> {
+        if let TuiNodeKind::Element {
+            ref mut attributes,
+            ..
+        } = node.0.borrow_mut().kind
+        {
+            attributes.insert(key.to_string(), value.to_string());
+        }
+        Ok(())
+    }
+
+    fn remove_attribute(&self, node: &Self::Node, key: &str) -> Result<(), JsValue> {
+        if let TuiNodeKind::Element {
+            ref mut attributes,
+            ..
+        } = node.0.borrow_mut().kind
+        {
+            attributes.shift_remove(key);
+        }
+        Ok(())
+    }
+
+    fn insert_before(
+        &self,
+        parent: &Self::Node,
+        child: &Self::Node,
+        next: Option<&Self::Node>,
+    ) -> Result<(), JsValue> {
+        child.detach();
+        child.0.borrow_mut().parent = Some(Rc::downgrade(&parent.0));
+
+        let mut parent = parent.0.borrow_mut();
+        let index = next
+            .and_then(|next| parent.children.iter().position(|c| c.is_same(next)))
+            .unwrap_or_else(|| parent.children.len());
+        parent.children.insert(index, child.clone());
+        Ok(())
+    }
+
+    fn remove_child(&self, parent: &Self::Node, child: &Self::Node) -> Result<(), JsValue> {
+        let index = parent
+            .0
+            .borrow()
+            .children
+            .iter()
+            .position(|c| c.is_same(child))
+            .expect("The child to be removed is expected to be attached to the parent");
+        parent.0.borrow_mut().children.remove(index);
+        child.0.borrow_mut().parent = None;
+        Ok(())
+    }
+
+    fn next_sibling(&self, node: &Self::Node) -> Option<Self::Node> {
+        let parent = node.0.borrow().parent.as_ref()?.upgrade()?;
+        let parent = parent.borrow();
+        let index = parent.children.iter().position(|c| c.is_same(node))?;
+        parent.children.get(index + 1).cloned()
+    }
+
+    fn first_child(&self, node: &Self::Node) -> Option<Self::Node> {
+        node.0.borrow().children.first().cloned()
+    }
+
+    fn clone_node(&self, node: &Self::Node) -> Option<Self::Node> {
+        Some(node.deep_clone())
+    }
+
+    /// Events are never dispatched on a terminal, so the listeners are
+    /// dropped as is.
+    fn add_event_listener(
+        &self,
+        _: &Self::Node,
+        _: &str,
+        _: Box<dyn Fn(Event)>,
+    ) -> Result<Self::Listener, JsValue> {
+        Ok(())
+    }
+
+    fn remove_event_listener(
+        &self,
+        _: &Self::Node,
+        _: &str,
+        _: &Self::Listener,
+    ) -> Result<(), JsValue> {
+        Ok(())
+    }
+
+    fn focus(&self, node: &Self::Node) -> Result<(), JsValue> {
+        FOCUSED.with(|focused| *focused.borrow_mut() = Rc::downgrade(&node.0));
+        Ok(())
+    }
+}
+
+impl From<TuiNode> for Node {
+    fn from(node: TuiNode) -> Node {
+        Node::new(TuiBackend, node)
+    }
+}
@@ -0,0 +1,72 @@
+//! The backend which renders nothing.
+
+use crate::backend::Backend;
+use wasm_bindgen::prelude::JsValue;
+use web_sys::Event;
+
+/// The backend which does not create any nodes at all.
+///
+/// It is used when only the VDOM is of interest, like when rendering an App to
+/// a string where the VDOM itself is written as HTML.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct VoidBackend;
+
+impl Backend for VoidBackend {
+    type Node = ();
+    type Listener = ();
+
+    fn create_element(&self, _: &str) -> Result<Self::Node, JsValue> {
+        Ok(())
+    }
+
+    fn create_text_node(&self, _: &str) -> Result<Self::Node, JsValue> {
+        Ok(())
+    }
+
+    fn create_comment(&self, _: &str) -> Result<Self::Node, JsValue> {
+        Ok(())
+    }
+
+    fn set_text_content(&self, _: &Self::Node, _: &str) -> Result<(), JsValue> {
+        Ok(())
+    }
+
+    fn set_attribute(&self, _: &Self::Node, _: &str, _: &str) -> Result<(), JsValue> {
+        Ok(())
+    }
+
+    fn remove_attribute(&self, _: &Self::Node, _: &str) -> Result<(), JsValue> {
+        Ok(())
+    }
+
+    fn insert_before(
+        &self,
+        _: &Self::Node,
+        _: &Self::Node,
+        _: Option<&Self::Node>,
+    ) -> Result<(), JsValue> {
+        Ok(())
+    }
+
+    fn remove_child(&self, _: &Self::Node, _: &Self::Node) -> Result<(), JsValue> {
+        Ok(())
+    }
+
+    fn add_event_listener(
+        &self,
+        _: &Self::Node,
+        _: &str,
+        _: Box<dyn Fn(Event)>,
+    ) -> Result<Self::Listener, JsValue> {
+        Ok(())
+    }
+
+    fn remove_event_listener(
+        &self,
+        _: &Self::Node,
+        _: &str,
+        _: &Self::Listener,
+    ) -> Result<(), JsValue> {
+        Ok(())
+    }
+}
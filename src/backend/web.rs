@@ -0,0 +1,152 @@
+//! The backend which renders onto the browser DOM.
+
+use crate::backend::{Backend, Node};
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{
+    window, Document, Element, Event, HtmlElement, HtmlInputElement, HtmlOptionElement,
+    HtmlSelectElement, HtmlTextAreaElement,
+};
+
+/// The backend which renders onto the browser DOM.
+#[derive(Clone, Copy, Default)]
+pub struct WebBackend;
+
+impl WebBackend {
+    fn document(&self) -> Document {
+        window().unwrap().document().unwrap()
+    }
+}
+
+impl Backend for WebBackend {
+    type Node = web_sys::Node;
+    type Listener = Closure<dyn Fn(Event)>;
+
+    fn create_element(&self, tag: &str) -> Result<Self::Node, JsValue> {
+        Ok(self.document().create_element(tag)?.into())
+    }
+
+    fn create_text_node(&self, content: &str) -> Result<Self::Node, JsValue> {
+        Ok(self.document().create_text_node(content).into())
+    }
+
+    fn create_comment(&self, content: &str) -> Result<Self::Node, JsValue> {
+        Ok(self.document().create_comment(content).into())
+    }
+
+    fn set_text_content(&self, node: &Self::Node, content: &str) -> Result<(), JsValue> {
+        node.set_text_content(Some(content));
+        Ok(())
+    }
+
+    fn set_attribute(&self, node: &Self::Node, key: &str, value: &str) -> Result<(), JsValue> {
+        node.unchecked_ref::<Element>().set_attribute(key, value)?;
+        sync_property(node, key, Some(value));
+        Ok(())
+    }
+
+    fn remove_attribute(&self, node: &Self::Node, key: &str) -> Result<(), JsValue> {
+        node.unchecked_ref::<Element>().remove_attribute(key)?;
+        sync_property(node, key, None);
+        Ok(())
+    }
+
+    fn insert_before(
+        &self,
+        parent: &Self::Node,
+        child: &Self::Node,
+        next: Option<&Self::Node>,
+    ) -> Result<(), JsValue> {
+        parent.insert_before(child, next)?;
+        Ok(())
+    }
+
+    fn remove_child(&self, parent: &Self::Node, child: &Self::Node) -> Result<(), JsValue> {
+        parent.remove_child(child)?;
+        Ok(())
+    }
+
+    fn next_sibling(&self, node: &Self::Node) -> Option<Self::Node> {
+        node.next_sibling()
+    }
+
+    fn first_child(&self, node: &Self::Node) -> Option<Self::Node> {
+        node.first_child()
+    }
+
+    fn clone_node(&self, node: &Self::Node) -> Option<Self::Node> {
+        node.clone_node_with_deep(true).ok()
+    }
+
+    fn add_event_listener(
+        &self,
+        node: &Self::Node,
+        type_: &str,
+        listener: Box<dyn Fn(Event)>,
+    ) -> Result<Self::Listener, JsValue> {
+        let js_closure: Closure<dyn Fn(Event)> = Closure::wrap(listener);
+        node.add_event_listener_with_callback(type_, js_closure.as_ref().unchecked_ref())?;
+        Ok(js_closure)
+    }
+
+    fn remove_event_listener(
+        &self,
+        node: &Self::Node,
+        type_: &str,
+        listener: &Self::Listener,
+    ) -> Result<(), JsValue> {
+        node.remove_event_listener_with_callback(type_, listener.as_ref().unchecked_ref())
+    }
+
+    fn focus(&self, node: &Self::Node) -> Result<(), JsValue> {
+        match node.dyn_ref::<HtmlElement>() {
+            Some(el) => el.focus(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Sets the live property of a form control along with its attribute. The
+/// attributes only set the initial state of the controls, which is lost once
+/// the user interacts with them.
+fn sync_property(node: &web_sys::Node, key: &str, value: Option<&str>) {
+    match key {
+        "value" => {
+            let value = value.unwrap_or("");
+            // Setting the same value moves the caret to the end.
+            if let Some(input) = node.dyn_ref::<HtmlInputElement>() {
+                if input.value() != value {
+                    input.set_value(value);
+                }
+            } else if let Some(textarea) = node.dyn_ref::<HtmlTextAreaElement>() {
+                if textarea.value() != value {
+                    textarea.set_value(value);
+                }
+            } else if let Some(select) = node.dyn_ref::<HtmlSelectElement>() {
+                select.set_value(value);
+            }
+        }
+        "checked" => {
+            if let Some(input) = node.dyn_ref::<HtmlInputElement>() {
+                input.set_checked(value.is_some());
+            }
+        }
+        "selected" => {
+            if let Some(option) = node.dyn_ref::<HtmlOptionElement>() {
+                option.set_selected(value.is_some());
+            }
+        }
+        _ => {}
+    }
+}
+
+impl From<web_sys::Node> for Node {
+    fn from(node: web_sys::Node) -> Node {
+        Node::new(WebBackend, node)
+    }
+}
+
+impl From<Element> for Node {
+    fn from(el: Element) -> Node {
+        Node::new(WebBackend, el.into())
+    }
+}
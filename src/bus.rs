@@ -0,0 +1,177 @@
+//! Events published by any component to the components subscribed to them,
+//! like the siblings, without passing the callbacks down the props.
+//!
+//! An event published on an [EventBus](struct.EventBus.html) is not handled
+//! right away. It is queued till the app flushes, where the handlers of the
+//! subscribed components are invoked before any of them are rerendered. So a
+//! handler may set the state of its component, which is then rendered in the
+//! same flush.
+//!
+//! # Example
+//! ```ignore
+//! struct Saved(String);
+//!
+//! impl Lifecycle for Toast {
+//!     fn created(&self) {
+//!         let setter = self.state_setter();
+//!         EventBus::<Saved>::global().subscribe(self, move |Saved(name)| {
+//!             let message = format!("Saved {}", name);
+//!             setter.set_state(|state| state.message = message.clone());
+//!         });
+//!     }
+//! }
+//!
+//! // Anywhere in the app, like an event handler of another component.
+//! EventBus::<Saved>::global().publish(Saved("draft.txt".to_string()));
+//! ```
+
+use crate::component::{status_of, Component};
+use std::{
+    any::{Any, TypeId},
+    cell::{Cell, RefCell},
+    collections::{HashMap, VecDeque},
+    rc::{Rc, Weak},
+};
+
+thread_local! {
+    /// The global buses keyed by the type of their events.
+    static GLOBAL_BUSES: RefCell<HashMap<TypeId, Box<dyn Any>>> = RefCell::new(HashMap::new());
+    /// The events published since the last flush, to be delivered in order.
+    static PENDING: RefCell<VecDeque<Box<dyn FnOnce()>>> = RefCell::new(VecDeque::new());
+}
+
+/// A channel of the events of type `T`. The clones of a bus share the same
+/// subscribers.
+pub struct EventBus<T>(Rc<BusInner<T>>);
+
+struct BusInner<T> {
+    handlers: RefCell<Vec<Handler<T>>>,
+    next_id: Cell<usize>,
+}
+
+struct Handler<T> {
+    id: usize,
+    handle: Rc<dyn Fn(&T)>,
+    /// Requests the app of the subscribed component to flush.
+    wake: Box<dyn Fn()>,
+}
+
+/// Unsubscribes when dropped, i.e. when the subscribed component is destroyed.
+struct Subscription<T> {
+    bus: Weak<BusInner<T>>,
+    id: usize,
+}
+
+impl<T: 'static> EventBus<T> {
+    /// Creates a bus without any subscribers.
+    pub fn new() -> EventBus<T> {
+        EventBus(Rc::new(BusInner {
+            handlers: RefCell::new(vec![]),
+            next_id: Cell::new(0),
+        }))
+    }
+
+    /// Gets the global bus of the event type, which is created on first use.
+    pub fn global() -> EventBus<T> {
+        GLOBAL_BUSES.with(|buses| {
+            buses
+                .borrow_mut()
+                .entry(TypeId::of::<T>())
+                .or_insert_with(|| Box::new(EventBus::<T>::new()))
+                .downcast_ref::<EventBus<T>>()
+                .unwrap()
+                .clone()
+        })
+    }
+
+    /// Invokes the handler with the events published on the bus, until the
+    /// component is destroyed.
+    pub fn subscribe<COMP: Component>(&self, component: &COMP, handler: impl Fn(&T) + 'static) {
+        let status = Rc::downgrade(status_of(component));
+        let id = self.0.next_id.get() + 1;
+        self.0.next_id.set(id);
+        self.0.handlers.borrow_mut().push(Handler {
+            id,
+            handle: Rc::new(handler),
+            wake: Box::new(move || {
+                if let Some(status) = status.upgrade() {
+                    // It is not woken while its state is being set, which
+                    // queues a flush anyway.
+                    if let Ok(status) = status.try_borrow() {
+                        status.do_react();
+                    }
+                }
+            }),
+        });
+        component.scope().keep(Subscription {
+            bus: Rc::downgrade(&self.0),
+            id,
+        });
+    }
+
+    /// Publishes the event to the components subscribed by the next flush.
+    /// It is dropped if there are none.
+    pub fn publish(&self, event: T) {
+        let handlers = self.0.handlers.borrow();
+        if handlers.is_empty() {
+            return;
+        }
+        let bus = Rc::downgrade(&self.0);
+        PENDING.with(|pending| {
+            pending.borrow_mut().push_back(Box::new(move || {
+                if let Some(bus) = bus.upgrade() {
+                    bus.deliver(&event);
+                }
+            }))
+        });
+        for handler in handlers.iter() {
+            (handler.wake)();
+        }
+    }
+}
+
+impl<T> BusInner<T> {
+    /// Invokes the handlers subscribed by now with the event.
+    fn deliver(&self, event: &T) {
+        // Collected first, so that a handler may subscribe or unsubscribe.
+        let handles: Vec<_> = self
+            .handlers
+            .borrow()
+            .iter()
+            .map(|handler| handler.handle.clone())
+            .collect();
+        for handle in handles {
+            handle(event);
+        }
+    }
+}
+
+/// Delivers the events published since the last flush, including the ones
+/// published by their handlers.
+pub(crate) fn deliver_pending() {
+    while let Some(deliver) = PENDING.with(|pending| pending.borrow_mut().pop_front()) {
+        deliver();
+    }
+}
+
+impl<T: 'static> Default for EventBus<T> {
+    fn default() -> EventBus<T> {
+        EventBus::new()
+    }
+}
+
+impl<T> Clone for EventBus<T> {
+    fn clone(&self) -> EventBus<T> {
+        EventBus(self.0.clone())
+    }
+}
+
+impl<T> Drop for Subscription<T> {
+    fn drop(&mut self) {
+        if let Some(bus) = self.bus.upgrade() {
+            bus.handlers
+                .borrow_mut()
+                .retain(|handler| handler.id != self.id);
+        }
+    }
+}
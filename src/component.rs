@@ -60,7 +60,27 @@
 //! Note: Docs on component macros are located
 //! [here](../../ruukh_codegen/index.html).
 
-use crate::{Markup, MessageSender, Shared};
+use crate::{
+    errors,
+    islands::Hydration,
+    streams,
+    vdom::{
+        suspense::{self, Boundary},
+        vcomponent::Rerender,
+    },
+    Markup, MessageSender, Shared,
+};
+use futures_core::Stream;
+use serde_json::Value;
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    future::Future,
+    mem,
+    pin::Pin,
+    rc::{Rc, Weak},
+};
+use wasm_bindgen::prelude::JsValue;
 
 /// Trait to define a component. You do not need to implement this trait. Auto
 /// implement this trait by using `#[component]` on a component struct (which
@@ -105,6 +125,17 @@ pub trait Component: 'static {
     /// name with `State`.
     type State: Default;
 
+    /// The name of the component, which tells it apart in the devtools, the
+    /// traces & the error reports.
+    ///
+    /// ## Internals
+    ///
+    /// It is the name of the `#[component]` struct. Otherwise, it is the name
+    /// of the type without the path of its module.
+    fn type_name() -> &'static str {
+        errors::short_name::<Self>()
+    }
+
     /// Creates a new component with the props, events and state passed to it.
     ///
     /// ## Internals
@@ -127,12 +158,122 @@ pub trait Component: 'static {
     /// in comparing closures.
     fn update(&mut self, props: Self::Props, events: Self::Events) -> Option<Self::Props>;
 
+    /// Whether the newer props are the same as the current ones, in which
+    /// case the component is patched without invoking `before_update` nor
+    /// walking through what it rendered. The props are taken to have changed
+    /// by default.
+    ///
+    /// ## Internals
+    ///
+    /// It compares each prop like `update` does, without updating them.
+    #[allow(unused_variables)]
+    fn is_props_same(&self, props: &Self::Props) -> bool {
+        false
+    }
+
     /// Updates the state fields if the status is mutated and returns if
     /// actually any changes occured.
     fn refresh_state(&mut self) -> bool;
 
-    /// Get the status of the component.
+    /// Get the status of the component, which every `#[component]` has.
+    ///
+    /// The scope, the rerenders & the subscriptions of the component are kept
+    /// in its status. So, the methods which need them, like `scope` or
+    /// `force_update`, panic for a component implemented by hand without one.
     fn status(&self) -> Option<&Shared<Status<Self::State>>>;
+
+    /// Get the scope of the component, which keeps the handles to the web
+    /// sockets, timers, etc. alive until the component is destroyed.
+    fn scope(&self) -> Scope {
+        status_of(self).borrow().scope().clone()
+    }
+
+    /// Rerenders the component even if neither its props nor its state
+    /// changed, e.g. when it renders the time or some other data which the
+    /// framework cannot see changing.
+    fn force_update(&self) {
+        status_of(self).borrow_mut().force_update();
+    }
+
+    /// Get the removal of the component, which is to be proceeded with once
+    /// it is delayed by returning `false` from
+    /// [before_destroy](trait.Lifecycle.html#method.before_destroy).
+    fn removal(&self) -> Removal {
+        status_of(self).borrow().removal().clone()
+    }
+
+    /// Watches a value selected from the state and invokes the callback with
+    /// the new value, once the component is rerendered with it.
+    ///
+    /// # Example
+    /// ```ignore
+    /// fn created(&self) {
+    ///     self.watch(|state| state.query.clone(), |query| {
+    ///         // Fetch the results of the new query.
+    ///     });
+    /// }
+    /// ```
+    fn watch<W: PartialEq + Clone + 'static>(
+        &self,
+        select: impl Fn(&Self::State) -> W + 'static,
+        callback: impl Fn(&W) + 'static,
+    ) {
+        status_of(self).borrow_mut().watch(select, callback);
+    }
+
+    /// Feeds the items of a stream, like the messages of a web socket, into
+    /// the state with the handler, until the stream ends or the component is
+    /// destroyed. The stream is polled once the app flushes, so that the state
+    /// is rendered in the same flush.
+    ///
+    /// # Example
+    /// ```ignore
+    /// fn created(&self) {
+    ///     self.subscribe(socket.messages(), |state, message| {
+    ///         state.messages.push(message);
+    ///     });
+    /// }
+    /// ```
+    fn subscribe<S: Stream + 'static>(
+        &self,
+        stream: S,
+        handler: impl Fn(&mut Self::State, S::Item) + 'static,
+    ) {
+        let status = status_of(self);
+        let subscription = streams::subscribe(status, stream, handler);
+        self.scope().keep(subscription);
+    }
+
+    /// Suspends the innermost [Suspense](../vdom/suspense/struct.Suspense.html)
+    /// the component is created within on the future, e.g. one which fetches
+    /// its data, so that the fallback is shown in place of the content until
+    /// the future resolves. The future is polled once the app flushes, and is
+    /// dropped if the component is destroyed before it resolves.
+    ///
+    /// # Example
+    /// ```ignore
+    /// fn created(&self) {
+    ///     let setter = self.state_setter();
+    ///     self.suspend(async move {
+    ///         let user = fetch_user().await;
+    ///         setter.set_state(move |state| state.user = Some(user.clone()));
+    ///     });
+    /// }
+    /// ```
+    fn suspend(&self, future: impl Future<Output = ()> + 'static) {
+        let status = status_of(self);
+        let pending = suspense::suspend(status, future);
+        self.scope().keep(pending);
+    }
+}
+
+/// Gets the status of the component, which every `#[component]` has.
+pub(crate) fn status_of<COMP: Component + ?Sized>(
+    component: &COMP,
+) -> &Shared<Status<COMP::State>> {
+    component
+        .status()
+        .expect("A component implemented without a status cannot be scoped nor rerendered")
 }
 
 /// Trait to allow mutatation of a component state.
@@ -177,6 +318,49 @@ pub trait SetState {
     fn set_state(&self, mutator: impl FnMut(&mut Self::State));
 }
 
+/// Trait to mutate the state of a component by dispatching messages, as an
+/// alternative to the ad-hoc `set_state` closures. All the transitions of the
+/// state are then enumerated by the message type and can be tested by
+/// invoking `reduce` on a state.
+///
+/// # Example
+/// ```ignore
+/// enum CounterMessage {
+///     Increment,
+///     Reset,
+/// }
+///
+/// impl Reducer for Counter {
+///     type Message = CounterMessage;
+///
+///     fn reduce(state: &mut CounterState, message: CounterMessage) {
+///         match message {
+///             CounterMessage::Increment => state.count += 1,
+///             CounterMessage::Reset => state.count = 0,
+///         }
+///     }
+/// }
+///
+/// self.dispatch(CounterMessage::Increment);
+/// ```
+pub trait Reducer: SetState {
+    /// The type of the messages which mutate the state.
+    type Message;
+
+    /// Mutates the state as per the message.
+    fn reduce(state: &mut Self::State, message: Self::Message);
+
+    /// Mutates the state of the component as per the message.
+    fn dispatch(&self, message: Self::Message) {
+        let mut message = Some(message);
+        self.set_state(|state| {
+            if let Some(message) = message.take() {
+                Self::reduce(state, message);
+            }
+        });
+    }
+}
+
 /// Trait to get a component setter to be used within `'static` closures.
 ///
 /// ```ignore
@@ -203,9 +387,22 @@ pub struct Status<T> {
     state: T,
     state_dirty: bool,
     props_dirty: bool,
+    render_dirty: bool,
     rx_sender: MessageSender,
+    /// The rendered component, so that only it is rerendered on a state
+    /// change.
+    instance: Option<Weak<RefCell<dyn Rerender>>>,
+    scope: Scope,
+    removal: Removal,
+    watchers: Vec<Watcher<T>>,
+    /// The innermost suspense boundary the component was created within.
+    boundary: Option<Boundary>,
 }
 
+/// Compares the selected value with the last one and returns the callback to
+/// be invoked if it changed.
+type Watcher<T> = Box<dyn FnMut(&T) -> Option<Box<dyn FnOnce()>>>;
+
 impl<T> Status<T> {
     /// Creates a new status with a given state and message sender.
     pub(crate) fn new(state: T, rx_sender: MessageSender) -> Status<T> {
@@ -213,7 +410,13 @@ impl<T> Status<T> {
             state,
             state_dirty: false,
             props_dirty: false,
+            render_dirty: false,
             rx_sender,
+            instance: None,
+            scope: Scope::default(),
+            removal: Removal::default(),
+            watchers: vec![],
+            boundary: suspense::current(),
         }
     }
 
@@ -237,6 +440,17 @@ impl<T> Status<T> {
         self.props_dirty
     }
 
+    /// Set render dirty with value. A render dirty component is rerendered
+    /// even if its props and state did not change.
+    pub fn set_render_dirty(&mut self, value: bool) {
+        self.render_dirty = value;
+    }
+
+    /// Is render dirty.
+    pub fn is_render_dirty(&self) -> bool {
+        self.render_dirty
+    }
+
     /// Gets the state immutably.
     pub fn state_as_ref(&self) -> &T {
         &self.state
@@ -249,7 +463,185 @@ impl<T> Status<T> {
 
     /// Sends a request to the App to react to the state changes.
     pub fn do_react(&self) {
-        self.rx_sender.do_react();
+        self.rx_sender.do_react(self.instance.clone());
+    }
+
+    /// Marks the component render dirty and requests the App to rerender it.
+    pub fn force_update(&mut self) {
+        self.set_render_dirty(true);
+        self.do_react();
+    }
+
+    /// Sets the rendered component which is to be rerendered on a state
+    /// change.
+    pub(crate) fn set_instance(&mut self, instance: Weak<RefCell<dyn Rerender>>) {
+        self.instance = Some(instance);
+    }
+
+    /// Gets the scope of the component.
+    pub fn scope(&self) -> &Scope {
+        &self.scope
+    }
+
+    /// Gets the removal of the component.
+    pub fn removal(&self) -> &Removal {
+        &self.removal
+    }
+
+    /// Gets the suspense boundary the component was created within, if any.
+    pub(crate) fn boundary(&self) -> Option<Boundary> {
+        self.boundary.clone()
+    }
+
+    /// Watches a value selected from the state. See
+    /// [Component::watch](trait.Component.html#method.watch).
+    pub fn watch<W: PartialEq + Clone + 'static>(
+        &mut self,
+        select: impl Fn(&T) -> W + 'static,
+        callback: impl Fn(&W) + 'static,
+    ) {
+        let callback = Rc::new(callback);
+        let mut last = select(&self.state);
+        self.watchers.push(Box::new(move |state| {
+            let value = select(state);
+            if value == last {
+                return None;
+            }
+            last = value.clone();
+            let callback = callback.clone();
+            Some(Box::new(move || callback(&value)))
+        }));
+    }
+
+    /// Gets the callbacks of the watchers whose values changed. They are to be
+    /// invoked once the status is no longer borrowed, so that they can change
+    /// the state too.
+    pub(crate) fn changed_watchers(&mut self) -> Vec<Box<dyn FnOnce()>> {
+        let state = &self.state;
+        self.watchers
+            .iter_mut()
+            .filter_map(|watcher| watcher(state))
+            .collect()
+    }
+}
+
+/// Holds onto the values which are required to live as long as a component
+/// does, like the handles which cancel a subscription when dropped.
+///
+/// The values kept by the scope of a component are dropped right after it is
+/// `destroyed`, in the reverse order they were kept in, so that a value may
+/// rely on the ones kept before it. So the listeners, timers, observers &
+/// subscriptions of a component are cleaned up without doing it manually in
+/// `destroyed`.
+///
+/// # Example
+/// ```ignore
+/// fn mounted(&self) {
+///     let socket = WebSocket::connect("wss://example.com")?;
+///     self.scope().keep(socket);
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct Scope(Rc<RefCell<Vec<Box<dyn Any>>>>);
+
+impl Scope {
+    /// Keeps the value alive until the scope is cleared.
+    pub fn keep<T: 'static>(&self, value: T) {
+        self.0.borrow_mut().push(Box::new(value));
+    }
+
+    /// Invokes the cleanup when the scope is cleared, for an effect which
+    /// does not come with a handle, like a script to be torn down.
+    ///
+    /// # Example
+    /// ```ignore
+    /// fn mounted(&self) {
+    ///     let chart = Chart::render_into(self.canvas.get()?);
+    ///     self.scope().on_clear(move || chart.destroy());
+    /// }
+    /// ```
+    pub fn on_clear(&self, cleanup: impl FnOnce() + 'static) {
+        self.keep(Cleanup(Some(Box::new(cleanup))));
+    }
+
+    /// Gets the value kept under the key, keeping a default one at first. It
+    /// is like a state which does not rerender the component, e.g. the
+    /// pending invocation of a debounced listener.
+    pub(crate) fn keyed<T: Default + 'static>(&self, key: TypeId) -> Rc<T> {
+        let kept = self
+            .0
+            .borrow()
+            .iter()
+            .filter_map(|value| value.downcast_ref::<Keyed<T>>())
+            .find(|keyed| keyed.key == key)
+            .map(|keyed| keyed.value.clone());
+        kept.unwrap_or_else(|| {
+            let value = Rc::new(T::default());
+            self.keep(Keyed {
+                key,
+                value: value.clone(),
+            });
+            value
+        })
+    }
+
+    /// Drops all the values kept till now, the last kept first.
+    pub fn clear(&self) {
+        // Drop them outside of the borrow, so that the values can use the
+        // scope while being dropped.
+        let mut values = mem::take(&mut *self.0.borrow_mut());
+        while let Some(value) = values.pop() {
+            drop(value);
+        }
+    }
+}
+
+/// A value kept in a scope under a key.
+struct Keyed<T> {
+    key: TypeId,
+    value: Rc<T>,
+}
+
+/// Invokes a cleanup when dropped.
+struct Cleanup(Option<Box<dyn FnOnce()>>);
+
+impl Drop for Cleanup {
+    fn drop(&mut self) {
+        if let Some(cleanup) = self.0.take() {
+            cleanup();
+        }
+    }
+}
+
+/// The removal of a component which delayed it from
+/// [before_destroy](trait.Lifecycle.html#method.before_destroy). The
+/// component stays on the DOM, and keeps on rerendering, until the removal is
+/// proceeded with.
+#[derive(Clone, Default)]
+pub struct Removal(Rc<RefCell<Option<RemoveFn>>>);
+
+type RemoveFn = Box<dyn FnOnce() -> Result<(), JsValue>>;
+
+impl Removal {
+    /// Delays the removal until `proceed` is invoked.
+    pub(crate) fn delay(&self, remove: RemoveFn) {
+        *self.0.borrow_mut() = Some(remove);
+    }
+
+    /// Removes the component from the DOM and destroys it. Does nothing if
+    /// the removal is not delayed or is already done.
+    pub fn proceed(&self) -> Result<(), JsValue> {
+        // Take it out of the borrow, as the removal drops the component.
+        let remove = self.0.borrow_mut().take();
+        match remove {
+            Some(remove) => remove(),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the removal is delayed and yet to be proceeded with.
+    pub fn is_delayed(&self) -> bool {
+        self.0.borrow().is_some()
     }
 }
 
@@ -261,6 +653,15 @@ pub trait Lifecycle: Component {
     /// Invoked when the component is first created.
     fn created(&self) {}
 
+    /// Invoked before the component is updated with the props passed by its
+    /// parent, while its DOM is yet to be patched. It is the place to take a
+    /// snapshot of the DOM, like the scroll position, before it changes.
+    ///
+    /// It is not invoked when the new props are the same as the current ones,
+    /// as told by `Component::is_props_same`.
+    #[allow(unused_variables)]
+    fn before_update(&self, new_props: &Self::Props) {}
+
     /// Invoked when the component props are updated.
     #[allow(unused_variables)]
     fn updated(&self, old_props: Self::Props) {}
@@ -268,14 +669,134 @@ pub trait Lifecycle: Component {
     /// Invoked when the component is mounted onto the DOM tree.
     fn mounted(&self) {}
 
-    /// Invoked when the component is removed from the DOM tree.
+    /// Invoked before the component is removed from the DOM tree. Return
+    /// `false` to delay the removal, e.g. to play a leave animation or to
+    /// prompt for the unsaved changes, and then proceed with the
+    /// [removal](trait.Component.html#method.removal) when done.
+    ///
+    /// # Example
+    /// ```ignore
+    /// fn before_destroy(&self) -> bool {
+    ///     let removal = self.removal();
+    ///     set_timeout(300, move || removal.proceed().unwrap()).forget();
+    ///     self.set_state(|state| state.leaving = true);
+    ///     false
+    /// }
+    /// ```
+    ///
+    /// A component without any props or state is removed regardless.
+    fn before_destroy(&self) -> bool {
+        true
+    }
+
+    /// Invoked when the component is removed from the DOM tree. The values
+    /// kept in its [scope](struct.Scope.html) are dropped right after.
     fn destroyed(&self) {}
+
+    /// Invoked when the component kept alive in a
+    /// [KeepAlive](../vdom/struct.KeepAlive.html) cache is re-attached to the
+    /// DOM tree.
+    fn activated(&self) {}
+
+    /// Invoked when the component kept alive in a
+    /// [KeepAlive](../vdom/struct.KeepAlive.html) cache is detached from the
+    /// DOM tree, e.g. to pause its timers and subscriptions while hidden.
+    fn deactivated(&self) {}
+
+    /// Invoked when the render of the component fails to be patched onto the
+    /// DOM. The error is propagated by default. Handle it to switch the
+    /// component to a degraded state instead, which is rerendered over what
+    /// got patched.
+    ///
+    /// # Example
+    /// ```ignore
+    /// fn on_render_error(&self, _: JsValue) -> Result<(), JsValue> {
+    ///     self.set_state(|state| state.fallback = true);
+    ///     Ok(())
+    /// }
+    /// ```
+    fn on_render_error(&self, error: JsValue) -> Result<(), JsValue> {
+        Err(error)
+    }
 }
 
 /// Trait to render a view for the component.
 pub trait Render: Lifecycle + Sized {
     /// Render a markup for the component by using the html! macro.
     fn render(&self) -> Markup<Self>;
+
+    /// The CSS scoped to the component, which is injected once into the
+    /// document head. See [styles](../styles/index.html).
+    ///
+    /// # Example
+    /// ```ignore
+    /// fn style() -> Option<&'static str> {
+    ///     Some("& { display: flex; } p { margin: 0; }")
+    /// }
+    /// ```
+    fn style() -> Option<&'static str> {
+        None
+    }
+
+    /// How the component rendered on the server is brought to life in the
+    /// browser. See [islands](../islands/index.html).
+    ///
+    /// # Example
+    /// ```ignore
+    /// fn hydration() -> Hydration {
+    ///     Hydration::Island(Load::Visible)
+    /// }
+    /// ```
+    fn hydration() -> Hydration {
+        Hydration::Full
+    }
+
+    /// The state sent along with the HTML rendered on the server, to be
+    /// restored with [rehydrate](#method.rehydrate) in the browser instead of
+    /// rendering the component with its default state.
+    ///
+    /// # Example
+    /// ```ignore
+    /// fn dehydrate(&self) -> Option<Value> {
+    ///     serde_json::to_value(&self.posts).ok()
+    /// }
+    /// ```
+    fn dehydrate(&self) -> Option<Value> {
+        None
+    }
+
+    /// Restores the state sent from the server, before the component is first
+    /// rendered in the browser.
+    ///
+    /// # Example
+    /// ```ignore
+    /// fn rehydrate(&self, state: Value) {
+    ///     if let Ok(posts) = serde_json::from_value(state) {
+    ///         self.set_state(|state| state.posts = posts);
+    ///     }
+    /// }
+    /// ```
+    fn rehydrate(&self, _: Value) {}
+
+    /// Prepares the data of the component, like fetching it into its state,
+    /// when the [App](../struct.App.html) is rendered with
+    /// `render_to_string_async`. It is invoked once the component is created,
+    /// and the component is rendered with the state it prepared once the
+    /// future completes.
+    ///
+    /// # Example
+    /// ```ignore
+    /// fn prepare(&self) -> Option<Pin<Box<dyn Future<Output = ()>>>> {
+    ///     let setter = self.state_setter();
+    ///     Some(Box::pin(async move {
+    ///         let posts = fetch_posts().await;
+    ///         setter.set_state(|state| state.posts = posts);
+    ///     }))
+    /// }
+    /// ```
+    fn prepare(&self) -> Option<Pin<Box<dyn Future<Output = ()>>>> {
+        None
+    }
 }
 
 /// Trait to convert from a event props to a events type.
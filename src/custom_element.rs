@@ -0,0 +1,204 @@
+//! Embedding the Ruukh components in any page as Custom Elements.
+//!
+//! A component defined as a custom element is mounted within the element
+//! when it is connected to the document and is destroyed when disconnected.
+//! The observed attributes of the element are mapped to the props, so that a
+//! change in them updates the component.
+
+use crate::{
+    backend::Node,
+    component::{Render, RootParent},
+    errors,
+    vdom::vcomponent::{ComponentManager, ComponentWrapper},
+    MessageListener, MessageSender, Shared,
+};
+use js_sys::{Array, Function};
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{window, Element};
+
+/// Trait to define a component as a custom element.
+///
+/// # Example
+/// ```ignore
+/// #[component]
+/// #[derive(Lifecycle)]
+/// struct Greeting {
+///     name: String,
+/// }
+///
+/// impl CustomElement for Greeting {
+///     const OBSERVED_ATTRIBUTES: &'static [&'static str] = &["name"];
+///
+///     fn props(element: &Element) -> GreetingProps {
+///         GreetingProps!(name: element.get_attribute("name").unwrap_or_default())
+///     }
+/// }
+///
+/// define_custom_element::<Greeting>("x-greeting")?;
+/// ```
+pub trait CustomElement: Render<Events = ()> {
+    /// The attributes of the element which are mapped to the props. The
+    /// component is updated when any of them changes.
+    const OBSERVED_ATTRIBUTES: &'static [&'static str];
+
+    /// Creates the props from the attributes of the element.
+    fn props(element: &Element) -> Self::Props;
+}
+
+#[wasm_bindgen(inline_js = "
+export function custom_element_class(
+    connected, disconnected, attributeChanged, observedAttributes
+) {
+    return class extends HTMLElement {
+        static get observedAttributes() { return observedAttributes; }
+        connectedCallback() { connected(this); }
+        disconnectedCallback() { disconnected(this); }
+        attributeChangedCallback() { attributeChanged(this); }
+    };
+}")]
+extern "C" {
+    /// Creates the class of a custom element which invokes the callbacks
+    /// with the element. It is defined in a module instead of being evaluated
+    /// from a string, so that it is allowed by a strict CSP.
+    fn custom_element_class(
+        connected: &JsValue,
+        disconnected: &JsValue,
+        attribute_changed: &JsValue,
+        observed_attributes: &Array,
+    ) -> Function;
+}
+
+/// A component mounted within a custom element.
+struct Mounted<COMP: CustomElement> {
+    element: Element,
+    manager: Shared<ComponentWrapper<COMP, RootParent>>,
+    parent: Node,
+    root_parent: Shared<RootParent>,
+    sender: MessageSender,
+    _listener: MessageListener,
+}
+
+/// The components mounted within the connected elements.
+type Instances<COMP> = Rc<RefCell<Vec<Mounted<COMP>>>>;
+
+/// Registers a custom element with the tag name, which renders the component.
+///
+/// The name of a custom element must contain a dash, e.g. `my-widget`. The
+/// errors the component fails to be mounted, updated or unmounted with are
+/// reported like the panics, see [errors](../errors/index.html).
+pub fn define_custom_element<COMP: CustomElement>(name: &str) -> Result<(), JsValue> {
+    let instances: Instances<COMP> = Rc::new(RefCell::new(vec![]));
+
+    let connected = {
+        let instances = instances.clone();
+        Closure::wrap(Box::new(move |element: Element| {
+            match mount::<COMP>(element) {
+                Ok(mounted) => instances.borrow_mut().push(mounted),
+                Err(error) => errors::report(&error),
+            }
+        }) as Box<dyn Fn(Element)>)
+    };
+    let disconnected = {
+        let instances = instances.clone();
+        Closure::wrap(Box::new(move |element: Element| {
+            let position = instances
+                .borrow()
+                .iter()
+                .position(|mounted| mounted.element == element);
+            if let Some(position) = position {
+                let mounted = instances.borrow_mut().remove(position);
+                let removed = mounted.manager.borrow().remove(&mounted.parent);
+                if let Err(error) = removed {
+                    errors::report(&error);
+                }
+            }
+        }) as Box<dyn Fn(Element)>)
+    };
+    let attribute_changed = Closure::wrap(Box::new(move |element: Element| {
+        // The attributes of an element which is not yet connected are read
+        // when it is mounted.
+        if let Some(mounted) = instances
+            .borrow()
+            .iter()
+            .find(|mounted| mounted.element == element)
+        {
+            if let Err(error) = update(mounted) {
+                errors::report(&error);
+            }
+        }
+    }) as Box<dyn Fn(Element)>);
+
+    let observed_attributes = COMP::OBSERVED_ATTRIBUTES
+        .iter()
+        .map(|attr| JsValue::from_str(attr))
+        .collect::<Array>();
+    let class = custom_element_class(
+        connected.as_ref(),
+        disconnected.as_ref(),
+        attribute_changed.as_ref(),
+        &observed_attributes,
+    );
+    window()
+        .unwrap()
+        .custom_elements()
+        .define(name, class.unchecked_ref())?;
+
+    // The element is defined for the lifetime of the program.
+    connected.forget();
+    disconnected.forget();
+    attribute_changed.forget();
+    Ok(())
+}
+
+/// Mounts the component within the element and rerenders it on state changes.
+fn mount<COMP: CustomElement>(element: Element) -> Result<Mounted<COMP>, JsValue> {
+    let parent = Node::from(element.clone());
+    let (receiver, sender) = crate::app_message_channel();
+    let root_parent = Rc::new(RefCell::new(()));
+    let manager = Rc::new(RefCell::new(ComponentWrapper::<COMP, RootParent>::new(
+        COMP::props(&element),
+        (),
+    )));
+
+    manager
+        .borrow_mut()
+        .render_walk(&parent, None, root_parent.clone(), sender.clone())?;
+
+    let listener = {
+        let sender = sender.clone();
+        receiver.react_on_message(move || {
+            if let Err(error) = sender.rerender_dirty() {
+                errors::report(&error);
+            }
+        })
+    };
+
+    Ok(Mounted {
+        element,
+        manager,
+        parent,
+        root_parent,
+        sender,
+        _listener: listener,
+    })
+}
+
+/// Updates the component with the props read from the attributes.
+fn update<COMP: CustomElement>(mounted: &Mounted<COMP>) -> Result<(), JsValue> {
+    let mut manager = ComponentWrapper::<COMP, RootParent>::new(COMP::props(&mounted.element), ());
+    let mut old = mounted.manager.borrow_mut();
+    manager.patch(
+        Some(&mut *old),
+        &mounted.parent,
+        None,
+        mounted.root_parent.clone(),
+    )?;
+    *old = manager;
+    old.render_walk(
+        &mounted.parent,
+        None,
+        mounted.root_parent.clone(),
+        mounted.sender.clone(),
+    )
+}
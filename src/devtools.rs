@@ -0,0 +1,180 @@
+//! Tools to inspect an app while developing it.
+//!
+//! # Example
+//! ```ignore
+//! if cfg!(debug_assertions) {
+//!     devtools::highlight_rerenders(true);
+//!     devtools::warn_unkeyed_lists(true);
+//!     devtools::check_purity(true);
+//! }
+//! App::<MyApp>::new().mount("app");
+//! ```
+
+use crate::{
+    backend::{Node, WebBackend},
+    component::Render,
+    hot,
+    vdom::VNode,
+    web_api::set_timeout,
+    Markup,
+};
+use std::cell::Cell;
+use wasm_bindgen::JsCast;
+use web_sys::{window, Element};
+
+/// The class which outlines a rerendered element.
+const HIGHLIGHT_CLASS: &str = "ruukh-rerendered";
+/// How long the outline stays on.
+const HIGHLIGHT_MILLIS: i32 = 400;
+
+thread_local! {
+    static HIGHLIGHT: Cell<bool> = const { Cell::new(false) };
+    static WARN_UNKEYED: Cell<bool> = const { Cell::new(false) };
+    static CHECK_PURITY: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Briefly outlines the elements of a component each time it is rerendered,
+/// which shows the components an interaction rerenders.
+pub fn highlight_rerenders(enabled: bool) {
+    HIGHLIGHT.with(|highlight| highlight.set(enabled));
+    if enabled {
+        inject_style();
+    }
+}
+
+/// Warns of the lists built in an expression, like the items collected from
+/// an iterator, which are rendered without the keys, along with the element
+/// & the component they are rendered in. Such items are patched by their
+/// position, so that the state of one, like a text typed in it, passes onto
+/// another once they are reordered. It is only checked in the debug builds.
+pub fn warn_unkeyed_lists(enabled: bool) {
+    WARN_UNKEYED.with(|warn| warn.set(enabled));
+}
+
+/// Whether the unkeyed lists are to be warned of.
+pub(crate) fn warns_unkeyed_lists() -> bool {
+    cfg!(debug_assertions) && WARN_UNKEYED.with(Cell::get)
+}
+
+/// Renders each component twice, panicking unless both the trees are
+/// structurally equal, i.e. have the same elements, attributes, texts, keys &
+/// components. A render which differs, e.g. as it reads the clock or mutates
+/// a counter, breaks the memoization & the rendering on the server. It is
+/// only checked in the debug builds.
+///
+/// The side effects of a render, like a fetch it starts, happen twice as well.
+pub fn check_purity(enabled: bool) {
+    CHECK_PURITY.with(|check| check.set(enabled));
+}
+
+/// Renders the component, with the render replacing its own if any. It is
+/// rendered a second time and compared, if the purity is checked.
+///
+/// # Panics
+/// If the component rendered a different tree the second time.
+pub(crate) fn render<COMP: Render>(component: &COMP) -> Markup<COMP> {
+    let markup = hot::render(component);
+    if cfg!(debug_assertions) && CHECK_PURITY.with(Cell::get) {
+        let (first, second) = (markup.dump_tree(), hot::render(component).dump_tree());
+        assert!(
+            first == second,
+            "<{}> rendered a different tree when rendered twice, so its render is not \
+             pure.\nFirst:\n{}Second:\n{}",
+            COMP::type_name(),
+            first,
+            second
+        );
+    }
+    markup
+}
+
+/// Outlines the topmost elements rendered by a component which was just
+/// rerendered, if enabled.
+pub(crate) fn highlight<COMP: Render>(vnode: &VNode<COMP>) {
+    if !HIGHLIGHT.with(Cell::get) {
+        return;
+    }
+    for el in vnode.root_nodes().iter().filter_map(web_element) {
+        if el.class_list().add_1(HIGHLIGHT_CLASS).is_err() {
+            continue;
+        }
+        if let Ok(timeout) = set_timeout(HIGHLIGHT_MILLIS, move || {
+            let _ = el.class_list().remove_1(HIGHLIGHT_CLASS);
+        }) {
+            timeout.forget();
+        }
+    }
+}
+
+/// Gets the element of a node rendered onto the DOM of the browser.
+fn web_element(node: &Node) -> Option<Element> {
+    node.backend_node::<WebBackend>()
+        .and_then(|node| node.dyn_ref::<Element>())
+        .cloned()
+}
+
+/// Appends the style of the outline into the document head, unless done
+/// already.
+fn inject_style() {
+    let document = match window().and_then(|window| window.document()) {
+        Some(document) => document,
+        None => return,
+    };
+    let head = match document.head() {
+        Some(head) => head,
+        None => return,
+    };
+    if let Ok(Some(_)) = head.query_selector("style[data-ruukh-devtools]") {
+        return;
+    }
+    let style = document
+        .create_element("style")
+        .expect("Could not create a style tag");
+    let _ = style.set_attribute("data-ruukh-devtools", "");
+    style.set_text_content(Some(&format!(
+        ".{} {{ outline: 2px solid rgba(255, 64, 129, 0.8) !important; \
+         outline-offset: -1px; }}",
+        HIGHLIGHT_CLASS
+    )));
+    let _ = head.append_child(&style);
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::{
+        component::root_render_ctx,
+        dom::DOMPatch,
+        vdom::{velement::VElement, vtext::VText},
+    };
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn should_outline_the_rerendered_elements() {
+        let div = window()
+            .unwrap()
+            .document()
+            .unwrap()
+            .create_element("div")
+            .unwrap();
+        let mut vnode: VNode<()> = VNode::from(vec![
+            VNode::from(VElement::childless("p", vec![], vec![])),
+            VNode::from(VText::text("Hello")),
+        ]);
+        vnode
+            .patch(
+                None,
+                &div.clone().into(),
+                None,
+                root_render_ctx(),
+                crate::message_sender(),
+            )
+            .expect("To patch div");
+
+        highlight_rerenders(true);
+        highlight(&vnode);
+        highlight_rerenders(false);
+
+        assert_eq!(div.inner_html(), r#"<p class="ruukh-rerendered"></p>Hello"#);
+    }
+}
@@ -1,6 +1,5 @@
-use crate::{component::Render, MessageSender, Shared};
+use crate::{backend::Node, component::Render, MessageSender, Shared};
 use wasm_bindgen::prelude::JsValue;
-use web_sys::Node;
 
 /// Trait to patch the DOM to reflect the VDOM structure.
 pub(crate) trait DOMPatch
@@ -41,5 +40,56 @@ where
     fn remove(&self, parent: &Self::Node) -> Result<(), JsValue>;
 
     /// Gets the node value of the DOM attached VDOM.
-    fn node(&self) -> Option<&Node>;
+    fn node(&self) -> Option<Node>;
+}
+
+/// Where the nodes of a component are rendered, so that it can be patched on
+/// its own later on.
+///
+/// An empty comment is kept right after the nodes as their end marker, before
+/// which the nodes are inserted. So the insertion point stays right even when
+/// the node which followed them is gone, replaced by a sibling component which
+/// was rerendered on its own, or when nothing got rendered at all. The marker
+/// is one of the root nodes of the component, so it is moved & removed along
+/// with the others. A list has no anchor, as it is only ever patched as a part
+/// of its parent, which moves its items one by one.
+pub(crate) struct Anchor {
+    parent: Node,
+    end: Node,
+}
+
+impl Anchor {
+    /// Inserts the end marker before the `next` node, i.e. right after the
+    /// nodes rendered before it.
+    pub(crate) fn new(parent: &Node, next: Option<&Node>) -> Result<Anchor, JsValue> {
+        let end = parent.create_comment("")?;
+        parent.insert_before(&end, next)?;
+        Ok(Anchor {
+            parent: parent.clone(),
+            end,
+        })
+    }
+
+    /// The node which holds the rendered nodes.
+    pub(crate) fn parent(&self) -> &Node {
+        &self.parent
+    }
+
+    /// The end marker, before which the nodes are inserted.
+    pub(crate) fn end(&self) -> &Node {
+        &self.end
+    }
+
+    /// Moves the end marker before the `next` node of the `parent`, once the
+    /// nodes are moved there.
+    pub(crate) fn reorder(&mut self, parent: &Node, next: Option<&Node>) -> Result<(), JsValue> {
+        parent.move_before(&self.end, next)?;
+        self.parent = parent.clone();
+        Ok(())
+    }
+
+    /// Removes the end marker, once the nodes are removed.
+    pub(crate) fn remove(&self) -> Result<(), JsValue> {
+        self.parent.remove_child(&self.end)
+    }
 }
@@ -0,0 +1,217 @@
+//! Reporting the panics of an app along with the components they occurred in.
+//!
+//! A panic in a wasm app traps with an opaque `unreachable` error. Once the
+//! error reporting is installed, a panic is logged as a console error along
+//! with the path of the components being rendered or patched, and dispatched
+//! to the reporter, if any.
+//!
+//! # Example
+//! ```ignore
+//! errors::install_error_reporting_with(|report| {
+//!     send_to_the_server(&report.to_string());
+//! });
+//! App::<MyApp>::new().mount("app");
+//! ```
+//!
+//! The errors an app fails to render or patch with are handled by the app
+//! itself, with [App::on_error](../struct.App.html#method.on_error) &
+//! [App::crash_screen](../struct.App.html#method.crash_screen), which is also
+//! shown once it panics.
+
+use crate::component::Component;
+use std::{
+    cell::{Cell, RefCell},
+    fmt::{self, Display, Formatter},
+    panic::{self, PanicHookInfo},
+};
+use wasm_bindgen::JsValue;
+
+/// Reports an error, e.g. onto the crash screen.
+pub(crate) type ReportFn = Box<dyn Fn(&ErrorReport)>;
+
+thread_local! {
+    static INSTALLED: Cell<bool> = const { Cell::new(false) };
+    /// The names of the components being rendered or patched, from the root.
+    static COMPONENTS: RefCell<Vec<&'static str>> = const { RefCell::new(vec![]) };
+    static REPORTER: RefCell<Option<ReportFn>> = RefCell::new(None);
+    /// Shows the crash screen of the app, once it panicked.
+    static CRASH: RefCell<Option<ReportFn>> = RefCell::new(None);
+}
+
+/// A panic which occurred in the app.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ErrorReport {
+    /// The message of the panic.
+    pub message: String,
+    /// The file, line & column where it panicked, if known.
+    pub location: Option<String>,
+    /// The names of the components being rendered or patched when it
+    /// panicked, from the root.
+    pub components: Vec<&'static str>,
+}
+
+impl ErrorReport {
+    /// The report of an error which the app failed to render or patch with.
+    /// The error can only be read on wasm, so it is reported as is otherwise.
+    pub(crate) fn from_error(error: &JsValue) -> ErrorReport {
+        let message = if cfg!(target_arch = "wasm32") {
+            error.as_string().unwrap_or_else(|| format!("{:?}", error))
+        } else {
+            "The backend failed".to_string()
+        };
+        ErrorReport {
+            message,
+            location: None,
+            components: vec![],
+        }
+    }
+}
+
+impl Display for ErrorReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(ref location) = self.location {
+            write!(f, " at {}", location)?;
+        }
+        if !self.components.is_empty() {
+            write!(f, " in <{}>", self.components.join("> <"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Sets a panic hook which logs the panics as console errors, along with the
+/// components they occurred in.
+pub fn install_error_reporting() {
+    INSTALLED.with(|installed| installed.set(true));
+    panic::set_hook(Box::new(report_panic));
+}
+
+/// Sets a panic hook like `install_error_reporting`, which also dispatches
+/// the panics to the reporter.
+pub fn install_error_reporting_with(reporter: impl Fn(&ErrorReport) + 'static) {
+    REPORTER.with(|r| *r.borrow_mut() = Some(Box::new(reporter)));
+    install_error_reporting();
+}
+
+fn report_panic(info: &PanicHookInfo<'_>) {
+    let payload = info.payload();
+    let message = if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    };
+    let report = ErrorReport {
+        message,
+        location: info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+        components: COMPONENTS.with(|components| components.borrow().clone()),
+    };
+
+    dispatch("panicked", &report);
+    CRASH.with(|crash| {
+        if let Some(ref crash) = *crash.borrow() {
+            crash(&report);
+        }
+    });
+}
+
+/// Reports an error which cannot be returned to anyone, like the one a
+/// callback invoked by the browser fails with, the same as a panic.
+pub(crate) fn report(error: &JsValue) {
+    dispatch("failed", &ErrorReport::from_error(error));
+}
+
+/// Logs the report as a console error and dispatches it to the reporter, if
+/// any.
+fn dispatch(failure: &str, report: &ErrorReport) {
+    let error = format!("ruukh: {} with {}", failure, report);
+    if cfg!(target_arch = "wasm32") {
+        web_sys::console::error_1(&error.into());
+    } else {
+        eprintln!("{}", error);
+    }
+    REPORTER.with(|reporter| {
+        if let Some(ref reporter) = *reporter.borrow() {
+            reporter(report);
+        }
+    });
+}
+
+/// Invokes the handler once the app panics, installing the error reporting if
+/// it is not.
+pub(crate) fn on_crash(handler: impl Fn(&ErrorReport) + 'static) {
+    CRASH.with(|crash| *crash.borrow_mut() = Some(Box::new(handler)));
+    if !INSTALLED.with(Cell::get) {
+        install_error_reporting();
+    }
+}
+
+/// Tracks the component being rendered or patched until the returned guard
+/// is dropped, if the error reporting is installed or in a debug build.
+pub(crate) fn enter<COMP: Component>() -> Option<ComponentGuard> {
+    if !cfg!(debug_assertions) && !INSTALLED.with(Cell::get) {
+        return None;
+    }
+    COMPONENTS.with(|components| components.borrow_mut().push(COMP::type_name()));
+    Some(ComponentGuard)
+}
+
+/// The name of the type of the component, without the path of its module.
+/// The paths within its generics are kept, like `List<app::Item>`.
+pub(crate) fn short_name<COMP: ?Sized>() -> &'static str {
+    let name = std::any::type_name::<COMP>();
+    let generics = name.find('<').unwrap_or(name.len());
+    let start = name[..generics].rfind("::").map_or(0, |index| index + 2);
+    &name[start..]
+}
+
+/// The path of the components being rendered or patched, like
+/// `<MainApp> <Broken>`.
+pub(crate) fn component_path() -> String {
+    COMPONENTS.with(|components| format!("<{}>", components.borrow().join("> <")))
+}
+
+/// Logs a warning as a console warning.
+pub(crate) fn warn(warning: &str) {
+    if cfg!(target_arch = "wasm32") {
+        web_sys::console::warn_1(&warning.into());
+    } else {
+        eprintln!("{}", warning);
+    }
+}
+
+/// Stops tracking the component when dropped, even when unwinding a panic.
+pub(crate) struct ComponentGuard;
+
+impl Drop for ComponentGuard {
+    fn drop(&mut self) {
+        COMPONENTS.with(|components| components.borrow_mut().pop());
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::short_name;
+    use wasm_bindgen_test::*;
+
+    struct Item;
+
+    struct List<T>(T);
+
+    #[wasm_bindgen_test]
+    fn should_strip_the_module_path_of_the_name() {
+        assert_eq!(short_name::<Item>(), "Item");
+    }
+
+    #[wasm_bindgen_test]
+    fn should_keep_the_generics_of_the_name() {
+        assert_eq!(
+            short_name::<List<Item>>(),
+            "List<ruukh::errors::test::Item>"
+        );
+    }
+}
@@ -0,0 +1,524 @@
+//! Forms bound to their inputs by name, with the validation of their values.
+//!
+//! A [Form](struct.Form.html) is kept as a state of the component. The inputs
+//! are bound to its fields by their `name`, updating the form on their `input`
+//! & `blur` events. Once submitted, it is converted into the model it is
+//! typed with, if all of its fields are valid.
+//!
+//! # Example
+//! ```ignore
+//! struct Signup {
+//!     email: String,
+//! }
+//!
+//! impl FromForm for Signup {
+//!     fn from_form(form: &Form<Self>) -> Result<Self, (&'static str, String)> {
+//!         Ok(Signup {
+//!             email: form.value("email").to_string(),
+//!         })
+//!     }
+//! }
+//!
+//! #[component]
+//! struct SignupForm {
+//!     #[state(default = Form::new().field("email", "").validate("email", forms::required()))]
+//!     form: Form<Signup>,
+//! }
+//!
+//! impl Render for SignupForm {
+//!     fn render(&self) -> Markup<Self> {
+//!         html! {
+//!             <form @submit={Self::on_submit}>
+//!                 <input
+//!                     name={"email"}
+//!                     value={self.form.value("email")}
+//!                     @input={Self::on_input}
+//!                     @blur={Self::on_blur}
+//!                 >
+//!                 <p>{ self.form.error("email").unwrap_or_default() }</p>
+//!             </form>
+//!         }
+//!     }
+//! }
+//!
+//! impl SignupForm {
+//!     fn on_input(&self, event: Event) {
+//!         self.set_state(|state| state.form.input(&event));
+//!     }
+//!
+//!     fn on_blur(&self, event: Event) {
+//!         self.set_state(|state| state.form.blur(&event));
+//!     }
+//!
+//!     fn on_submit(&self, event: Event) {
+//!         let mut signup = None;
+//!         self.set_state(|state| signup = state.form.submit(&event));
+//!         if let Some(signup) = signup {
+//!             // Send it to the server.
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! # Choices
+//! A `<select>` or a group of radio buttons is bound to an enum implementing
+//! [Choice](trait.Choice.html) with [select](fn.select.html),
+//! [select_multiple](fn.select_multiple.html) &
+//! [radio_group](fn.radio_group.html). They render an option for each of the
+//! choices, checking the ones given & invoking the handler with the choices
+//! picked by the user.
+//!
+//! ```ignore
+//! #[derive(Clone, Copy, PartialEq)]
+//! enum Size {
+//!     Small,
+//!     Large,
+//! }
+//!
+//! impl Choice for Size {
+//!     fn choices() -> Vec<Size> {
+//!         vec![Size::Small, Size::Large]
+//!     }
+//!
+//!     fn value(&self) -> &'static str {
+//!         match self {
+//!             Size::Small => "small",
+//!             Size::Large => "large",
+//!         }
+//!     }
+//! }
+//!
+//! html! {
+//!     { forms::select(self.size, |this: &Self, size| this.set_state(|state| state.size = size)) }
+//! }
+//! ```
+
+use crate::{
+    component::Render,
+    vdom::{
+        velement::{Attribute, EventListener, VElement},
+        VNode,
+    },
+};
+use indexmap::IndexMap;
+use std::{marker::PhantomData, rc::Rc};
+use wasm_bindgen::JsCast;
+use web_sys::{Event, HtmlInputElement, HtmlOptionElement, HtmlSelectElement, HtmlTextAreaElement};
+
+/// Checks the value of a field, returning the error to be shown if invalid.
+pub type Validator = Rc<dyn Fn(&str) -> Result<(), String>>;
+
+/// Converts the values of a submitted form into a model.
+pub trait FromForm: Sized {
+    /// Builds the model out of the valid values of the form. A value which
+    /// still cannot be converted is reported as the error of its field.
+    fn from_form(form: &Form<Self>) -> Result<Self, (&'static str, String)>;
+}
+
+/// The values of the fields of a form, along with their validation.
+pub struct Form<T> {
+    fields: IndexMap<&'static str, Field>,
+    submitted: bool,
+    model: PhantomData<T>,
+}
+
+#[derive(Clone)]
+struct Field {
+    initial: String,
+    value: String,
+    touched: bool,
+    validators: Vec<Validator>,
+    error: Option<String>,
+}
+
+impl<T> Form<T> {
+    /// Creates a form without any fields.
+    pub fn new() -> Form<T> {
+        Form {
+            fields: IndexMap::new(),
+            submitted: false,
+            model: PhantomData,
+        }
+    }
+
+    /// Adds a field with its initial value.
+    pub fn field(mut self, name: &'static str, initial: &str) -> Form<T> {
+        let mut field = Field {
+            initial: initial.to_string(),
+            value: initial.to_string(),
+            touched: false,
+            validators: vec![],
+            error: None,
+        };
+        field.validate();
+        self.fields.insert(name, field);
+        self
+    }
+
+    /// Adds a validator on the field.
+    ///
+    /// # Panics
+    /// If there is no such field.
+    pub fn validate(
+        mut self,
+        name: &'static str,
+        validator: impl Fn(&str) -> Result<(), String> + 'static,
+    ) -> Form<T> {
+        let field = self.field_mut(name);
+        field.validators.push(Rc::new(validator));
+        field.validate();
+        self
+    }
+
+    fn field_mut(&mut self, name: &str) -> &mut Field {
+        self.fields
+            .get_mut(name)
+            .unwrap_or_else(|| panic!("There is no field named `{}` in the form", name))
+    }
+
+    /// The value of the field, or an empty one if there is no such field.
+    pub fn value(&self, name: &str) -> &str {
+        self.fields.get(name).map_or("", |field| &field.value)
+    }
+
+    /// Sets the value of the field and validates it.
+    ///
+    /// # Panics
+    /// If there is no such field.
+    pub fn set_value(&mut self, name: &str, value: &str) {
+        let field = self.field_mut(name);
+        field.value = value.to_string();
+        field.validate();
+    }
+
+    /// Marks the field as visited by the user, so that its error is shown.
+    pub fn touch(&mut self, name: &str) {
+        if let Some(field) = self.fields.get_mut(name) {
+            field.touched = true;
+        }
+    }
+
+    /// Whether the value of the field differs from its initial value.
+    pub fn is_dirty(&self, name: &str) -> bool {
+        self.fields
+            .get(name)
+            .is_some_and(|field| field.value != field.initial)
+    }
+
+    /// Whether the field was visited by the user.
+    pub fn is_touched(&self, name: &str) -> bool {
+        self.fields.get(name).is_some_and(|field| field.touched)
+    }
+
+    /// Whether all the fields are valid.
+    pub fn is_valid(&self) -> bool {
+        self.fields.values().all(|field| field.error.is_none())
+    }
+
+    /// Whether the form was submitted.
+    pub fn is_submitted(&self) -> bool {
+        self.submitted
+    }
+
+    /// The error of the field to be shown, once it is touched or the form is
+    /// submitted.
+    pub fn error(&self, name: &str) -> Option<&str> {
+        let field = self.fields.get(name)?;
+        if field.touched || self.submitted {
+            field.error.as_deref()
+        } else {
+            None
+        }
+    }
+
+    /// Sets the value of the field named after the input of an `input` or a
+    /// `change` event. Checkboxes have a value of `true` when checked and an
+    /// empty one otherwise.
+    pub fn input(&mut self, event: &Event) {
+        if let Some((name, value)) = target_name_and_value(event) {
+            if self.fields.contains_key(name.as_str()) {
+                self.set_value(&name, &value);
+            }
+        }
+    }
+
+    /// Touches the field named after the input of a `blur` event.
+    pub fn blur(&mut self, event: &Event) {
+        if let Some((name, _)) = target_name_and_value(event) {
+            self.touch(&name);
+        }
+    }
+
+    /// Resets all the fields to their initial values.
+    pub fn reset(&mut self) {
+        for field in self.fields.values_mut() {
+            field.value = field.initial.clone();
+            field.touched = false;
+            field.validate();
+        }
+        self.submitted = false;
+    }
+}
+
+impl<T: FromForm> Form<T> {
+    /// Submits the form, preventing the browser from submitting it on a
+    /// `submit` event. Returns the model if all of the fields are valid.
+    pub fn submit(&mut self, event: &Event) -> Option<T> {
+        event.prevent_default();
+        self.try_submit()
+    }
+
+    /// Submits the form, returning the model if all of the fields are valid.
+    pub fn try_submit(&mut self) -> Option<T> {
+        self.submitted = true;
+        if !self.is_valid() {
+            return None;
+        }
+        match T::from_form(self) {
+            Ok(model) => Some(model),
+            Err((name, error)) => {
+                self.field_mut(name).error = Some(error);
+                None
+            }
+        }
+    }
+}
+
+impl Field {
+    fn validate(&mut self) {
+        let value = &self.value;
+        self.error = self
+            .validators
+            .iter()
+            .find_map(|validator| validator(value).err());
+    }
+}
+
+impl<T> Default for Form<T> {
+    fn default() -> Form<T> {
+        Form::new()
+    }
+}
+
+impl<T> Clone for Form<T> {
+    fn clone(&self) -> Form<T> {
+        Form {
+            fields: self.fields.clone(),
+            submitted: self.submitted,
+            model: PhantomData,
+        }
+    }
+}
+
+impl<T> PartialEq for Form<T> {
+    // The validators are left out, as they do not change once set.
+    fn eq(&self, other: &Form<T>) -> bool {
+        self.submitted == other.submitted
+            && self.fields.len() == other.fields.len()
+            && self.fields.iter().zip(other.fields.iter()).all(|(l, r)| {
+                l.0 == r.0
+                    && l.1.value == r.1.value
+                    && l.1.touched == r.1.touched
+                    && l.1.error == r.1.error
+            })
+    }
+}
+
+/// Gets the name & value of the input, select or textarea which fired the
+/// event.
+fn target_name_and_value(event: &Event) -> Option<(String, String)> {
+    let target = event.target()?;
+    if let Some(input) = target.dyn_ref::<HtmlInputElement>() {
+        let value = if input.type_() == "checkbox" {
+            if input.checked() { "true" } else { "" }.to_string()
+        } else {
+            input.value()
+        };
+        Some((input.name(), value))
+    } else if let Some(select) = target.dyn_ref::<HtmlSelectElement>() {
+        Some((select.name(), select.value()))
+    } else {
+        target
+            .dyn_ref::<HtmlTextAreaElement>()
+            .map(|textarea| (textarea.name(), textarea.value()))
+    }
+}
+
+/// A value picked out of a fixed set of choices, like the variants of an enum.
+pub trait Choice: Clone + PartialEq + 'static {
+    /// All of the choices, in the order they are rendered.
+    fn choices() -> Vec<Self>;
+
+    /// The value of the option of the choice, which is unique among them.
+    fn value(&self) -> &'static str;
+
+    /// The label shown for the choice. Defaults to its value.
+    fn label(&self) -> String {
+        self.value().to_string()
+    }
+
+    /// Gets the choice having the value.
+    fn from_value(value: &str) -> Option<Self> {
+        Self::choices()
+            .into_iter()
+            .find(|choice| choice.value() == value)
+    }
+}
+
+/// Renders a `<select>` with an option for each of the choices, with the
+/// given one selected. The handler is invoked with the choice the user
+/// selects.
+pub fn select<RCTX: Render, C: Choice>(
+    selected: C,
+    on_change: impl Fn(&RCTX, C) + 'static,
+) -> VElement<RCTX> {
+    let listener = EventListener::new(
+        "change",
+        Box::new(move |render_ctx: &RCTX, event: Event| {
+            let choice = event
+                .target()
+                .and_then(|target| target.dyn_into::<HtmlSelectElement>().ok())
+                .and_then(|select| C::from_value(&select.value()));
+            if let Some(choice) = choice {
+                on_change(render_ctx, choice);
+            }
+        }),
+    );
+    VElement::new(
+        "select",
+        vec![],
+        vec![listener],
+        options(|choice: &C| *choice == selected),
+    )
+}
+
+/// Renders a `<select multiple>` with an option for each of the choices, with
+/// the given ones selected. The handler is invoked with all the choices
+/// selected by the user, in their order.
+pub fn select_multiple<RCTX: Render, C: Choice>(
+    selected: &[C],
+    on_change: impl Fn(&RCTX, Vec<C>) + 'static,
+) -> VElement<RCTX> {
+    let listener = EventListener::new(
+        "change",
+        Box::new(move |render_ctx: &RCTX, event: Event| {
+            let select = match event
+                .target()
+                .and_then(|target| target.dyn_into::<HtmlSelectElement>().ok())
+            {
+                Some(select) => select,
+                None => return,
+            };
+            let options = select.selected_options();
+            let choices = (0..options.length())
+                .filter_map(|index| options.item(index))
+                .filter_map(|option| option.dyn_into::<HtmlOptionElement>().ok())
+                .filter_map(|option| C::from_value(&option.value()))
+                .collect();
+            on_change(render_ctx, choices);
+        }),
+    );
+    VElement::new(
+        "select",
+        vec![Attribute::new("multiple", true)],
+        vec![listener],
+        options(|choice: &C| selected.contains(choice)),
+    )
+}
+
+fn options<RCTX: Render, C: Choice>(
+    is_selected: impl Fn(&C) -> bool,
+) -> impl Iterator<Item = VElement<RCTX>> {
+    C::choices().into_iter().map(move |choice| {
+        VElement::new(
+            "option",
+            vec![
+                Attribute::new("value", choice.value()),
+                Attribute::new("selected", is_selected(&choice)),
+            ],
+            vec![],
+            Some(choice.label()),
+        )
+    })
+}
+
+/// Renders a group of radio buttons named `name`, each within a `<label>`,
+/// with the given choice checked. The handler is invoked with the choice the
+/// user checks.
+pub fn radio_group<RCTX: Render, C: Choice>(
+    name: &'static str,
+    selected: C,
+    on_change: impl Fn(&RCTX, C) + 'static,
+) -> VNode<RCTX> {
+    let on_change = Rc::new(on_change);
+    C::choices()
+        .into_iter()
+        .map(|choice| {
+            let on_change = on_change.clone();
+            let listener = EventListener::new(
+                "change",
+                Box::new(move |render_ctx: &RCTX, event: Event| {
+                    let choice = event
+                        .target()
+                        .and_then(|target| target.dyn_into::<HtmlInputElement>().ok())
+                        .filter(|input| input.checked())
+                        .and_then(|input| C::from_value(&input.value()));
+                    if let Some(choice) = choice {
+                        on_change(render_ctx, choice);
+                    }
+                }),
+            );
+            let radio = VElement::childless(
+                "input",
+                vec![
+                    Attribute::new("type", "radio"),
+                    Attribute::new("name", name),
+                    Attribute::new("value", choice.value()),
+                    Attribute::new("checked", choice == selected),
+                ],
+                vec![listener],
+            );
+            VElement::new(
+                "label",
+                vec![],
+                vec![],
+                vec![VNode::from(radio), VNode::from(choice.label())],
+            )
+            .into()
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+/// Requires the field to have a value.
+pub fn required() -> impl Fn(&str) -> Result<(), String> {
+    |value: &str| {
+        if value.trim().is_empty() {
+            Err("This field is required.".to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Requires the value to have at least the given number of characters.
+pub fn min_length(min: usize) -> impl Fn(&str) -> Result<(), String> {
+    move |value: &str| {
+        if value.chars().count() < min {
+            Err(format!("This field requires at least {} characters.", min))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Requires the value to have at most the given number of characters.
+pub fn max_length(max: usize) -> impl Fn(&str) -> Result<(), String> {
+    move |value: &str| {
+        if value.chars().count() > max {
+            Err(format!("This field allows at most {} characters.", max))
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,264 @@
+//! Management of the `<title>`, `<meta>` and `<link>` tags in the document
+//! head.
+//!
+//! A component sets the tags with a [Head](struct.Head.html), which is kept
+//! alive along with the component. When multiple heads set a tag of the same
+//! identity, e.g. the title or a meta of the same name, the one set later
+//! wins. Once a head is dropped, the tags set by the heads before it, or the
+//! ones already in the document, are restored.
+//!
+//! A head is best set when the component is `created`, so that the heads of
+//! the children, which are created after their parent, win over it.
+//!
+//! # Example
+//! ```ignore
+//! impl Lifecycle for AboutPage {
+//!     fn created(&self) {
+//!         let head = Head::new(vec![
+//!             HeadTag::title("About Us"),
+//!             HeadTag::meta("description", "Who we are."),
+//!         ]);
+//!         self.scope().keep(head);
+//!     }
+//! }
+//! ```
+
+use crate::html::HtmlWriter;
+use fnv::FnvBuildHasher;
+use indexmap::IndexMap;
+use std::{cell::RefCell, collections::HashMap};
+use wasm_bindgen::JsCast;
+use web_sys::{window, Element};
+
+thread_local! {
+    static MANAGER: RefCell<Manager> = RefCell::new(Manager::default());
+}
+
+/// A tag in the document head.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeadTag {
+    tag: &'static str,
+    /// The attributes which identify the tag, so that only one tag of such an
+    /// identity is in the head.
+    identity: Vec<(&'static str, String)>,
+    attributes: Vec<(&'static str, String)>,
+    text: Option<String>,
+}
+
+impl HeadTag {
+    /// A `<title>` of the document.
+    pub fn title(text: &str) -> HeadTag {
+        HeadTag {
+            tag: "title",
+            identity: vec![],
+            attributes: vec![],
+            text: Some(text.to_string()),
+        }
+    }
+
+    /// A `<meta name content>`, e.g. a description.
+    pub fn meta(name: &str, content: &str) -> HeadTag {
+        HeadTag {
+            tag: "meta",
+            identity: vec![("name", name.to_string())],
+            attributes: vec![("content", content.to_string())],
+            text: None,
+        }
+    }
+
+    /// A `<meta property content>`, e.g. an Open Graph `og:title`.
+    pub fn meta_property(property: &str, content: &str) -> HeadTag {
+        HeadTag {
+            tag: "meta",
+            identity: vec![("property", property.to_string())],
+            attributes: vec![("content", content.to_string())],
+            text: None,
+        }
+    }
+
+    /// A `<link rel href>`, e.g. a canonical URL or a stylesheet.
+    pub fn link(rel: &str, href: &str) -> HeadTag {
+        HeadTag {
+            tag: "link",
+            identity: vec![("rel", rel.to_string()), ("href", href.to_string())],
+            attributes: vec![],
+            text: None,
+        }
+    }
+
+    /// The CSS selector which matches the tags of the same identity.
+    fn selector(&self) -> String {
+        let mut selector = self.tag.to_string();
+        for (key, value) in self.identity.iter() {
+            selector.push_str(&format!(r#"[{}="{}"]"#, key, value.replace('"', "\\\"")));
+        }
+        selector
+    }
+
+    fn all_attributes(&self) -> impl Iterator<Item = &(&'static str, String)> {
+        self.identity.iter().chain(self.attributes.iter())
+    }
+
+    /// Creates the element of the tag.
+    fn create_element(&self) -> Element {
+        let document = window().unwrap().document().unwrap();
+        let el = document
+            .create_element(self.tag)
+            .expect("Could not create a head tag");
+        for (key, value) in self.all_attributes() {
+            el.set_attribute(key, value)
+                .expect("Could not set an attribute on a head tag");
+        }
+        if let Some(ref text) = self.text {
+            el.set_text_content(Some(text));
+        }
+        el
+    }
+}
+
+/// The tags set in the document head, which are removed when it is dropped.
+#[must_use = "The tags are removed from the head as soon as it is dropped"]
+pub struct Head {
+    id: usize,
+}
+
+impl Head {
+    /// Sets the tags in the document head.
+    pub fn new(tags: Vec<HeadTag>) -> Head {
+        MANAGER.with(|manager| {
+            let mut manager = manager.borrow_mut();
+            manager.next_id += 1;
+            let id = manager.next_id;
+            manager.heads.push((id, tags));
+            manager.apply();
+            Head { id }
+        })
+    }
+
+    /// Replaces the tags set earlier, e.g. when the component is updated.
+    pub fn set(&self, tags: Vec<HeadTag>) {
+        MANAGER.with(|manager| {
+            let mut manager = manager.borrow_mut();
+            if let Some(head) = manager.heads.iter_mut().find(|(id, _)| *id == self.id) {
+                head.1 = tags;
+            }
+            manager.apply();
+        });
+    }
+}
+
+impl Drop for Head {
+    fn drop(&mut self) {
+        MANAGER.with(|manager| {
+            let mut manager = manager.borrow_mut();
+            manager.heads.retain(|(id, _)| *id != self.id);
+            manager.apply();
+        });
+    }
+}
+
+/// Writes the tags set in the document head as HTML, for the apps rendered
+/// on the server. See
+/// [App::render_with_head](../struct.App.html#method.render_with_head).
+pub(crate) fn write_html(w: &mut HtmlWriter<'_>) -> std::fmt::Result {
+    MANAGER.with(|manager| {
+        for tag in manager.borrow().tags().values() {
+            w.open_tag(
+                tag.tag,
+                tag.all_attributes()
+                    .map(|(key, value)| (*key, Some(value.as_str()))),
+                std::iter::empty(),
+            )?;
+            if let Some(ref text) = tag.text {
+                w.text(text)?;
+            }
+            w.close_tag(tag.tag)?;
+        }
+        Ok(())
+    })
+}
+
+/// Starts applying the tags onto the document head of the browser. Until
+/// then, the tags are only tracked to be written as HTML.
+pub(crate) fn attach_to_document() {
+    MANAGER.with(|manager| {
+        let mut manager = manager.borrow_mut();
+        manager.in_document = true;
+        manager.apply();
+    });
+}
+
+/// Tracks the tags set by all the heads and applies them onto the document.
+#[derive(Default)]
+struct Manager {
+    next_id: usize,
+    heads: Vec<(usize, Vec<HeadTag>)>,
+    in_document: bool,
+    /// The tags applied onto the document, keyed by their selector.
+    applied: HashMap<String, (HeadTag, Element)>,
+    /// The tags which were in the document before, which are to be restored
+    /// once none of the heads sets them.
+    displaced: HashMap<String, Element>,
+}
+
+impl Manager {
+    /// The tags set by the heads, where the ones set later win.
+    fn tags(&self) -> IndexMap<String, &HeadTag, FnvBuildHasher> {
+        let mut tags = IndexMap::default();
+        for tag in self.heads.iter().flat_map(|(_, tags)| tags.iter()) {
+            tags.insert(tag.selector(), tag);
+        }
+        tags
+    }
+
+    /// Patches the document head with the tags that changed since the last
+    /// time.
+    fn apply(&mut self) {
+        if !self.in_document {
+            return;
+        }
+        let head: Element = match window().unwrap().document().unwrap().head() {
+            Some(head) => head.unchecked_into(),
+            None => return,
+        };
+
+        let tags = self
+            .tags()
+            .into_iter()
+            .map(|(selector, tag)| (selector, tag.clone()))
+            .collect::<Vec<_>>();
+
+        // Remove the ones which are no longer set and restore what they
+        // displaced.
+        let stale = self
+            .applied
+            .keys()
+            .filter(|selector| !tags.iter().any(|(s, _)| s == *selector))
+            .cloned()
+            .collect::<Vec<_>>();
+        for selector in stale {
+            let (_, el) = self.applied.remove(&selector).unwrap();
+            el.remove();
+            if let Some(displaced) = self.displaced.remove(&selector) {
+                let _ = head.append_child(&displaced);
+            }
+        }
+
+        for (selector, tag) in tags {
+            match self.applied.get(&selector) {
+                Some((applied, _)) if *applied == tag => continue,
+                Some((_, el)) => el.remove(),
+                None => {
+                    // Take out the one already in the document.
+                    if let Ok(Some(existing)) = head.query_selector(&selector) {
+                        existing.remove();
+                        self.displaced.insert(selector.clone(), existing);
+                    }
+                }
+            }
+            let el = tag.create_element();
+            let _ = head.append_child(&el);
+            self.applied.insert(selector, (tag, el));
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Swapping the render of a component while the app is running, for the hot
+//! reload of the dev tooling.
+//!
+//! The live instances of the component are rerendered with the new render,
+//! keeping their props & state. Only the components with a status, which
+//! every `#[component]` has, are rerendered.
+//! The instances are tracked with the `hot-reload` feature only, so that it
+//! costs nothing otherwise. Without it, only the later instances are rendered
+//! with the new render.
+//!
+//! # Example
+//! ```ignore
+//! hot::replace_render::<Counter>(|counter| html! {
+//!     <button>"Clicked " { counter.count } " times"</button>
+//! });
+//! ```
+
+use crate::{component::Render, Markup, Shared};
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+thread_local! {
+    /// The renders which replace the ones of the components, by their type.
+    static RENDERS: RefCell<HashMap<TypeId, Box<dyn Any>>> = RefCell::new(HashMap::new());
+    /// The instances of the components, by their type.
+    static INSTANCES: RefCell<HashMap<TypeId, Vec<Box<dyn Tracked>>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// An instance of a component, which may be dropped by now.
+trait Tracked {
+    fn is_alive(&self) -> bool;
+
+    /// Marks the instance for a rerender. Returns whether it is still alive.
+    fn force_update(&self) -> bool;
+}
+
+impl<COMP: Render> Tracked for Weak<RefCell<COMP>> {
+    fn is_alive(&self) -> bool {
+        self.strong_count() > 0
+    }
+
+    fn force_update(&self) -> bool {
+        match self.upgrade() {
+            Some(component) => {
+                if let Some(status) = component.borrow().status() {
+                    status.borrow_mut().force_update();
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+type RenderFn<COMP> = Rc<dyn Fn(&COMP) -> Markup<COMP>>;
+
+/// Renders the live & the later instances of `COMP` with `render` instead of
+/// its own, until restored.
+pub fn replace_render<COMP: Render>(render: impl Fn(&COMP) -> Markup<COMP> + 'static) {
+    let render: RenderFn<COMP> = Rc::new(render);
+    RENDERS.with(|renders| {
+        renders
+            .borrow_mut()
+            .insert(TypeId::of::<COMP>(), Box::new(render))
+    });
+    rerender_instances::<COMP>();
+}
+
+/// Renders `COMP` with its own render again.
+pub fn restore_render<COMP: Render>() {
+    let removed = RENDERS.with(|renders| renders.borrow_mut().remove(&TypeId::of::<COMP>()));
+    if removed.is_some() {
+        rerender_instances::<COMP>();
+    }
+}
+
+/// Renders the component with the render replacing its own, if any.
+pub(crate) fn render<COMP: Render>(component: &COMP) -> Markup<COMP> {
+    // Cloned out, as the render may render with the others replaced.
+    let replaced = RENDERS.with(|renders| {
+        renders
+            .borrow()
+            .get(&TypeId::of::<COMP>())
+            .and_then(|render| render.downcast_ref::<RenderFn<COMP>>())
+            .cloned()
+    });
+    match replaced {
+        Some(render) => render(component),
+        None => component.render(),
+    }
+}
+
+/// Tracks the instance of the component, so that it is rerendered once its
+/// render is replaced, with the `hot-reload` feature.
+pub(crate) fn track<COMP: Render>(component: &Shared<COMP>) {
+    if !cfg!(feature = "hot-reload") {
+        return;
+    }
+    INSTANCES.with(|instances| {
+        let mut instances = instances.borrow_mut();
+        let instances = instances.entry(TypeId::of::<COMP>()).or_default();
+        // Prune the dropped ones before growing, so that they do not pile
+        // up.
+        if instances.len() == instances.capacity() {
+            instances.retain(|instance| instance.is_alive());
+        }
+        instances.push(Box::new(Rc::downgrade(component)));
+    });
+}
+
+fn rerender_instances<COMP: Render>() {
+    INSTANCES.with(|instances| {
+        if let Some(instances) = instances.borrow_mut().get_mut(&TypeId::of::<COMP>()) {
+            instances.retain(|instance| instance.force_update());
+        }
+    });
+}
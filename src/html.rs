@@ -0,0 +1,232 @@
+//! Rendering of the VDOM into an HTML string.
+
+use std::{
+    borrow::Cow,
+    fmt::{self, Formatter, Write},
+    mem,
+};
+
+/// The elements which cannot have any children and so are written without a
+/// closing tag.
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Whether the element of the given tag is a void element.
+pub(crate) fn is_void(tag: &str) -> bool {
+    VOID_ELEMENTS.contains(&tag)
+}
+
+/// Whether the name is a valid attribute name, i.e. it has none of the
+/// characters which end an attribute or the tag in HTML.
+pub(crate) fn is_attribute_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.chars().any(|c| {
+            c.is_whitespace() || c.is_control() || ['"', '\'', '<', '>', '/', '='].contains(&c)
+        })
+}
+
+/// Trait to write the VDOM as HTML.
+pub(crate) trait WriteHtml {
+    /// Writes the HTML of `self` and its children into the writer.
+    fn write_html(&self, w: &mut HtmlWriter<'_>) -> fmt::Result;
+}
+
+/// Writes the HTML tags, texts and comments into an output. The texts and the
+/// attribute values are escaped, so that the HTML is safe to inject into a
+/// page.
+///
+/// By default, the HTML is written along with the hydration markers i.e. an
+/// empty comment in between the adjacent texts (so that the texts are not
+/// merged when parsed) and a `data-ruukh-events` attribute listing the events
+/// listened on an element. A static writer strips them to write clean HTML.
+///
+/// A pretty writer is a static writer which also writes every tag, text and
+/// comment on its own line indented by its depth.
+pub(crate) struct HtmlWriter<'a> {
+    out: &'a mut dyn Write,
+    is_static: bool,
+    /// Whether the HTML is to be hydrated, even if parts of it are static.
+    is_hydratable: bool,
+    is_pretty: bool,
+    /// Whether the last thing written was a text.
+    after_text: bool,
+    /// Whether the last thing written was an opening tag.
+    after_open_tag: bool,
+    /// Whether anything has been written yet.
+    written: bool,
+    /// The depth of the element being written into.
+    depth: usize,
+}
+
+impl<'a> HtmlWriter<'a> {
+    /// Creates a writer which writes the hydration markers.
+    pub(crate) fn new(out: &'a mut dyn Write) -> HtmlWriter<'a> {
+        HtmlWriter {
+            out,
+            is_static: false,
+            is_hydratable: true,
+            is_pretty: false,
+            after_text: false,
+            after_open_tag: false,
+            written: false,
+            depth: 0,
+        }
+    }
+
+    /// Creates a writer which writes clean HTML.
+    pub(crate) fn new_static(out: &'a mut dyn Write) -> HtmlWriter<'a> {
+        HtmlWriter {
+            is_static: true,
+            is_hydratable: false,
+            ..HtmlWriter::new(out)
+        }
+    }
+
+    /// Creates a writer which writes clean and indented HTML.
+    pub(crate) fn new_pretty(out: &'a mut dyn Write) -> HtmlWriter<'a> {
+        HtmlWriter {
+            is_static: true,
+            is_hydratable: false,
+            is_pretty: true,
+            ..HtmlWriter::new(out)
+        }
+    }
+
+    /// Creates a writer for a `Display` impl. It writes clean HTML which is
+    /// indented when formatted with `{:#}`.
+    pub(crate) fn display(f: &'a mut Formatter<'_>) -> HtmlWriter<'a> {
+        if f.alternate() {
+            HtmlWriter::new_pretty(f)
+        } else {
+            HtmlWriter::new_static(f)
+        }
+    }
+
+    /// Whether the HTML is to be hydrated in the browser.
+    pub(crate) fn is_hydratable(&self) -> bool {
+        self.is_hydratable
+    }
+
+    /// Writes a part of the HTML without any of the hydration markers.
+    pub(crate) fn static_part(
+        &mut self,
+        f: impl FnOnce(&mut HtmlWriter<'a>) -> fmt::Result,
+    ) -> fmt::Result {
+        let is_static = mem::replace(&mut self.is_static, true);
+        let result = f(self);
+        self.is_static = is_static;
+        result
+    }
+
+    /// Starts a new indented line, if pretty printing.
+    fn new_line(&mut self) -> fmt::Result {
+        if self.is_pretty {
+            if self.written {
+                writeln!(self.out)?;
+            }
+            write!(self.out, "{:1$}", "", self.depth * 2)?;
+        }
+        self.written = true;
+        Ok(())
+    }
+
+    /// Writes the opening tag with its attributes and the types of events
+    /// listened on it.
+    pub(crate) fn open_tag<'b>(
+        &mut self,
+        tag: &str,
+        attributes: impl Iterator<Item = (&'b str, Option<&'b str>)>,
+        events: impl Iterator<Item = &'b str>,
+    ) -> fmt::Result {
+        self.new_line()?;
+        self.after_text = false;
+        self.after_open_tag = !is_void(tag);
+        if self.after_open_tag {
+            self.depth += 1;
+        }
+        write!(self.out, "<{}", tag)?;
+        // The names spread at runtime may be invalid, which are left out
+        // instead of injecting the markup.
+        for (key, value) in attributes.filter(|(key, _)| is_attribute_name(key)) {
+            match value {
+                Some(value) => write!(self.out, r#" {}="{}""#, key, escape(value))?,
+                None => write!(self.out, " {}", key)?,
+            }
+        }
+        if !self.is_static {
+            let events = events.collect::<Vec<_>>();
+            if !events.is_empty() {
+                write!(self.out, r#" data-ruukh-events="{}""#, events.join(" "))?;
+            }
+        }
+        write!(self.out, ">")
+    }
+
+    /// Writes the closing tag. Void elements do not have one.
+    pub(crate) fn close_tag(&mut self, tag: &str) -> fmt::Result {
+        if is_void(tag) {
+            return Ok(());
+        }
+        self.depth -= 1;
+        // Keep the tags of an empty element on the same line.
+        if !self.after_open_tag {
+            self.new_line()?;
+        }
+        self.after_text = false;
+        self.after_open_tag = false;
+        write!(self.out, "</{}>", tag)
+    }
+
+    /// Writes a text.
+    pub(crate) fn text(&mut self, content: &str) -> fmt::Result {
+        if self.after_text && !self.is_static {
+            write!(self.out, "<!---->")?;
+        }
+        self.new_line()?;
+        self.after_text = true;
+        self.after_open_tag = false;
+        write!(self.out, "{}", escape(content))
+    }
+
+    /// Writes the text of a raw text element like `<style>`, which is not
+    /// escaped.
+    pub(crate) fn raw_text(&mut self, content: &str) -> fmt::Result {
+        self.new_line()?;
+        self.after_text = false;
+        self.after_open_tag = false;
+        // Only a closing tag can end a raw text early.
+        write!(self.out, "{}", content.replace("</", "<\\/"))
+    }
+
+    /// Writes a comment.
+    pub(crate) fn comment(&mut self, content: &str) -> fmt::Result {
+        self.new_line()?;
+        self.after_text = false;
+        self.after_open_tag = false;
+        // A comment cannot be closed early without a `>`.
+        write!(self.out, "<!--{}-->", content.replace('>', "&gt;"))
+    }
+}
+
+/// Escapes the characters which have a special meaning in HTML texts and
+/// attribute values.
+fn escape(value: &str) -> Cow<'_, str> {
+    if !value.contains(|c| ['&', '<', '>', '"', '\''].contains(&c)) {
+        return Cow::Borrowed(value);
+    }
+
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
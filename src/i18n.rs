@@ -0,0 +1,425 @@
+//! Translating the texts of an app into the locale of the user.
+//!
+//! The messages of each locale are added to a [Catalog](struct.Catalog.html),
+//! which is loaded along with the locale to be shown. A message is translated
+//! with the [t!](../macro.t.html) macro, which renders it as a text node, or
+//! with [translate](fn.translate.html). The `{name}` placeholders within a
+//! message are replaced with the arguments of the same name.
+//!
+//! A message with a `count` argument is pluralized. The message under the
+//! key suffixed with the plural category of the count in the locale, i.e.
+//! `.zero`, `.one`, `.two`, `.few`, `.many` or `.other`, is picked, falling
+//! back onto the `.other` one & then the key itself.
+//!
+//! The components which translate any message in their render are rerendered
+//! once the locale is switched with [set_locale](fn.set_locale.html).
+//!
+//! # Example
+//! ```ignore
+//! i18n::load(
+//!     Catalog::new()
+//!         .add("en", &[
+//!             ("greeting", "Hello, {name}!"),
+//!             ("inbox.one", "You have {count} message."),
+//!             ("inbox.other", "You have {count} messages."),
+//!         ])
+//!         .add("fr", &[
+//!             ("greeting", "Bonjour, {name} !"),
+//!             ("inbox.one", "Vous avez {count} message."),
+//!             ("inbox.other", "Vous avez {count} messages."),
+//!         ])
+//!         .fallback("en"),
+//!     "en",
+//! );
+//!
+//! html! {
+//!     <h1>{ t!("greeting", name = self.name.as_str()) }</h1>
+//!     <p>{ t!("inbox", count = self.unread) }</p>
+//! }
+//! ```
+
+use crate::{
+    component::{status_of, Component, Status},
+    Shared,
+};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    rc::{Rc, Weak},
+};
+
+thread_local! {
+    static I18N: RefCell<I18n> = RefCell::new(I18n::default());
+    /// The statuses of the components being rendered, from the outermost
+    /// one. `None` for the ones without a status.
+    static RENDERING: RefCell<Vec<Option<Translator>>> =
+        RefCell::new(vec![]);
+}
+
+/// The messages of the locales.
+#[derive(Default)]
+pub struct Catalog {
+    messages: HashMap<String, HashMap<String, String>>,
+    plural_rules: HashMap<String, fn(f64) -> Plural>,
+    fallback: Option<String>,
+}
+
+/// A plural category of a count, as defined by the Unicode CLDR.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Plural {
+    /// Like 0 in Arabic.
+    Zero,
+    /// Like 1 in English.
+    One,
+    /// Like 2 in Arabic.
+    Two,
+    /// Like 2, 3 & 4 in Russian.
+    Few,
+    /// Like 5 in Russian.
+    Many,
+    /// Any other count.
+    Other,
+}
+
+/// An argument of a message, which is either a text or a number.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Arg {
+    /// A text placed as is.
+    Text(String),
+    /// A number, which may be the count the message is pluralized by.
+    Number(f64),
+}
+
+#[derive(Default)]
+struct I18n {
+    catalog: Catalog,
+    locale: String,
+    /// The statuses of the components which translated any message.
+    translators: Vec<Translator>,
+}
+
+/// A component which translated a message.
+type Translator = Weak<RefCell<dyn ForceUpdate>>;
+
+/// A status which can be marked to be rerendered, regardless of its state.
+trait ForceUpdate {
+    fn force_update(&mut self);
+}
+
+impl<T> ForceUpdate for Status<T> {
+    fn force_update(&mut self) {
+        Status::force_update(self);
+    }
+}
+
+impl Catalog {
+    /// Creates an empty catalog.
+    pub fn new() -> Catalog {
+        Catalog::default()
+    }
+
+    /// Adds the messages of the locale, like `en` or `en-US`, by their keys.
+    pub fn add(mut self, locale: &str, messages: &[(&str, &str)]) -> Catalog {
+        self.messages
+            .entry(locale.to_string())
+            .or_default()
+            .extend(
+                messages
+                    .iter()
+                    .map(|(key, message)| (key.to_string(), message.to_string())),
+            );
+        self
+    }
+
+    /// Sets the locale whose messages are shown for the keys missing in the
+    /// current one.
+    pub fn fallback(mut self, locale: &str) -> Catalog {
+        self.fallback = Some(locale.to_string());
+        self
+    }
+
+    /// Pluralizes the counts in the locale with the rule, in place of the
+    /// built-in one of its language.
+    pub fn plural_rule(mut self, locale: &str, rule: fn(f64) -> Plural) -> Catalog {
+        self.plural_rules.insert(locale.to_string(), rule);
+        self
+    }
+
+    /// The message of the key in the locale, the language of the locale or
+    /// the fallback locale, in that order.
+    fn message(&self, locale: &str, key: &str) -> Option<&str> {
+        let in_locale = |locale: &str| {
+            self.messages
+                .get(locale)
+                .and_then(|messages| messages.get(key))
+                .map(String::as_str)
+        };
+        in_locale(locale)
+            .or_else(|| in_locale(language(locale)))
+            .or_else(|| self.fallback.as_ref().and_then(|locale| in_locale(locale)))
+    }
+
+    fn plural(&self, locale: &str, count: f64) -> Plural {
+        match self
+            .plural_rules
+            .get(locale)
+            .or_else(|| self.plural_rules.get(language(locale)))
+        {
+            Some(rule) => rule(count),
+            None => plural(locale, count),
+        }
+    }
+}
+
+/// The language of the locale, i.e. `en` of `en-US`.
+fn language(locale: &str) -> &str {
+    locale
+        .split(['-', '_'])
+        .next()
+        .unwrap_or(locale)
+}
+
+/// The plural category of the count in the locale, by the built-in rules of
+/// the common languages. The fractions are taken to be `Other`.
+pub fn plural(locale: &str, count: f64) -> Plural {
+    if count.fract() != 0.0 || count < 0.0 {
+        return Plural::Other;
+    }
+    let n = count as u64;
+    let (n10, n100) = (n % 10, n % 100);
+    match language(locale) {
+        "ja" | "zh" | "ko" | "th" | "vi" | "id" | "ms" | "tr" => Plural::Other,
+        "fr" | "pt" | "hi" | "bn" if n <= 1 => Plural::One,
+        "ru" | "uk" | "be" | "sr" | "hr" | "bs" => match (n10, n100) {
+            (1, n100) if n100 != 11 => Plural::One,
+            (2..=4, n100) if !(12..=14).contains(&n100) => Plural::Few,
+            _ => Plural::Many,
+        },
+        "pl" => match (n, n10, n100) {
+            (1, _, _) => Plural::One,
+            (_, 2..=4, n100) if !(12..=14).contains(&n100) => Plural::Few,
+            _ => Plural::Many,
+        },
+        "cs" | "sk" => match n {
+            1 => Plural::One,
+            2..=4 => Plural::Few,
+            _ => Plural::Other,
+        },
+        "ar" => match (n, n100) {
+            (0, _) => Plural::Zero,
+            (1, _) => Plural::One,
+            (2, _) => Plural::Two,
+            (_, 3..=10) => Plural::Few,
+            (_, 11..=99) => Plural::Many,
+            _ => Plural::Other,
+        },
+        _ if n == 1 => Plural::One,
+        _ => Plural::Other,
+    }
+}
+
+impl Plural {
+    /// The suffix of the keys of the messages in this category.
+    fn as_str(self) -> &'static str {
+        match self {
+            Plural::Zero => "zero",
+            Plural::One => "one",
+            Plural::Two => "two",
+            Plural::Few => "few",
+            Plural::Many => "many",
+            Plural::Other => "other",
+        }
+    }
+}
+
+/// Loads the catalog, showing its messages in the locale.
+pub fn load(catalog: Catalog, locale: &str) {
+    I18N.with(|i18n| {
+        let mut i18n = i18n.borrow_mut();
+        i18n.catalog = catalog;
+        i18n.locale = locale.to_string();
+    });
+    rerender_translators();
+}
+
+/// The locale the messages are shown in.
+pub fn locale() -> String {
+    I18N.with(|i18n| i18n.borrow().locale.clone())
+}
+
+/// Switches the locale the messages are shown in, rerendering the
+/// components which translated any.
+pub fn set_locale(locale: &str) {
+    I18N.with(|i18n| i18n.borrow_mut().locale = locale.to_string());
+    rerender_translators();
+}
+
+/// Translates the message of the key with the arguments in the current
+/// locale. The key is returned as is if there is no such message.
+///
+/// The component being rendered, if any, is rerendered once the locale is
+/// switched.
+pub fn translate(key: &str, args: &[(&str, Arg)]) -> String {
+    track_rendering();
+    I18N.with(|i18n| {
+        let i18n = i18n.borrow();
+        let count = args.iter().find_map(|(name, arg)| match arg {
+            Arg::Number(count) if *name == "count" => Some(*count),
+            _ => None,
+        });
+        let plural_message = count.and_then(|count| {
+            let category = i18n.catalog.plural(&i18n.locale, count);
+            i18n.catalog
+                .message(&i18n.locale, &format!("{}.{}", key, category.as_str()))
+                .or_else(|| {
+                    i18n.catalog
+                        .message(&i18n.locale, &format!("{}.other", key))
+                })
+        });
+        match plural_message.or_else(|| i18n.catalog.message(&i18n.locale, key)) {
+            Some(message) => interpolate(message, args),
+            None => key.to_string(),
+        }
+    })
+}
+
+/// Replaces the `{name}` placeholders with the arguments. The ones without an
+/// argument are left as is.
+fn interpolate(message: &str, args: &[(&str, Arg)]) -> String {
+    let mut out = String::with_capacity(message.len());
+    let mut rest = message;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        let end = match placeholder.find('}') {
+            Some(end) => end,
+            None => break,
+        };
+        let name = &placeholder[1..end];
+        match args.iter().find(|(arg, _)| *arg == name) {
+            Some((_, value)) => out.push_str(&value.to_string()),
+            None => out.push_str(&placeholder[..=end]),
+        }
+        rest = &placeholder[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Marks the component as rendering, so that the messages it translates
+/// rerender it once the locale is switched, until the returned guard is
+/// dropped.
+pub(crate) fn enter<T: 'static>(status: Option<&Shared<Status<T>>>) -> RenderingGuard {
+    let status = status.map(|status| {
+        let status: Weak<RefCell<dyn ForceUpdate>> = Rc::downgrade(status) as _;
+        status
+    });
+    RENDERING.with(|rendering| rendering.borrow_mut().push(status));
+    RenderingGuard
+}
+
+/// Leaves the component being rendered when dropped, even when unwinding a
+/// panic.
+pub(crate) struct RenderingGuard;
+
+impl Drop for RenderingGuard {
+    fn drop(&mut self) {
+        RENDERING.with(|rendering| rendering.borrow_mut().pop());
+    }
+}
+
+fn track_rendering() {
+    let status = RENDERING.with(|rendering| rendering.borrow().last().cloned().flatten());
+    if let Some(status) = status {
+        I18N.with(|i18n| {
+            let translators = &mut i18n.borrow_mut().translators;
+            if !translators
+                .iter()
+                .any(|translator| Weak::ptr_eq(translator, &status))
+            {
+                translators.push(status);
+            }
+        });
+    }
+}
+
+fn rerender_translators() {
+    let translators = I18N.with(|i18n| {
+        let mut i18n = i18n.borrow_mut();
+        i18n.translators
+            .retain(|translator| translator.strong_count() > 0);
+        i18n.translators.clone()
+    });
+    for translator in translators.iter().filter_map(Weak::upgrade) {
+        translator.borrow_mut().force_update();
+    }
+}
+
+/// Rerenders the component once the locale is switched, even if it does not
+/// translate any message itself, e.g. as it formats the dates in the locale.
+pub fn subscribe<COMP: Component>(component: &COMP) {
+    let status = status_of(component);
+    let status: Weak<RefCell<dyn ForceUpdate>> = Rc::downgrade(status) as _;
+    I18N.with(|i18n| i18n.borrow_mut().translators.push(status));
+}
+
+impl Display for Arg {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Arg::Text(text) => f.write_str(text),
+            Arg::Number(number) => write!(f, "{}", number),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Arg {
+    fn from(text: &'a str) -> Arg {
+        Arg::Text(text.to_string())
+    }
+}
+
+impl From<String> for Arg {
+    fn from(text: String) -> Arg {
+        Arg::Text(text)
+    }
+}
+
+impl<'a> From<&'a String> for Arg {
+    fn from(text: &'a String) -> Arg {
+        Arg::Text(text.clone())
+    }
+}
+
+macro_rules! impl_number_arg {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Arg {
+                fn from(number: $t) -> Arg {
+                    Arg::Number(number as f64)
+                }
+            }
+        )*
+    };
+}
+
+impl_number_arg!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+/// Translates the message of the key with the named arguments in the current
+/// locale, as a text node. See the [i18n](i18n/index.html) module.
+///
+/// # Example
+/// ```ignore
+/// html! {
+///     <p>{ t!("inbox", count = self.unread, name = self.name.as_str()) }</p>
+/// }
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($key:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::vdom::vtext::VText::text($crate::i18n::translate(
+            $key,
+            &[$((stringify!($name), $crate::i18n::Arg::from($value))),*],
+        ))
+    };
+}
@@ -0,0 +1,213 @@
+//! Lazy client rendering of the interactive parts of the apps rendered on the
+//! server.
+//!
+//! A page rendered on the server is mostly static, with only a few parts of
+//! it being interactive. Instead of mounting the whole app in the browser, the
+//! interactive components are rendered as islands which are rendered anew on
+//! their own once loaded, whereas the rest of the page is left as rendered.
+//!
+//! How a component is brought to life in the browser is declared with
+//! [Render::hydration](../component/trait.Render.html#method.hydration):
+//! 1. `Hydration::Full` is hydrated along with its parent, as usual.
+//! 2. `Hydration::Static` is never hydrated, so it is written without any of
+//!    the hydration markers or the events it listens to.
+//! 3. `Hydration::Island` is written within a `<ruukh-island>` tag, which is
+//!    rendered anew with the component once it is loaded.
+//!
+//! # Example
+//! ```ignore
+//! impl Render for Comments {
+//!     fn render(&self) -> Markup<Self> { ... }
+//!
+//!     fn hydration() -> Hydration {
+//!         Hydration::Island(Load::Visible)
+//!     }
+//! }
+//!
+//! // In the browser, instead of mounting the whole app.
+//! islands::register::<Comments>();
+//! islands::render_lazily()?;
+//! ```
+//!
+//! An island is not hydrated, i.e. its nodes rendered on the server are not
+//! taken over. It is rendered anew in their place instead, so it cannot have
+//! any props. They are replaced within the same task, so nothing is painted
+//! in between, though the focus & the scroll within the island are lost. Its
+//! components are rehydrated with the state they had on the server, like when
+//! mounting an app. See
+//! [Render::dehydrate](../component/trait.Render.html#method.dehydrate).
+
+use crate::{component::Render, html::HtmlWriter, web_api::IntersectionObserver, App};
+use fnv::FnvHasher;
+use std::{any, cell::RefCell, collections::HashMap, fmt, hash::Hasher, rc::Rc};
+use wasm_bindgen::prelude::*;
+use web_sys::{window, Element};
+
+thread_local! {
+    static ISLANDS: RefCell<Islands> = RefCell::new(Islands::default());
+}
+
+/// The islands registered till now.
+#[derive(Default)]
+struct Islands {
+    /// Mounts the component of an island onto its element, by its id.
+    mounts: HashMap<String, Rc<dyn Fn(Element)>>,
+    /// Observes the islands which are loaded once visible.
+    observer: Option<IntersectionObserver>,
+}
+
+/// How a component rendered on the server is brought to life in the browser.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Hydration {
+    /// Hydrated along with its parent.
+    Full,
+    /// Never hydrated, so it does not listen to any events.
+    Static,
+    /// Rendered anew on its own when loaded.
+    Island(Load),
+}
+
+/// When an island is loaded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Load {
+    /// As soon as the islands are rendered.
+    Eager,
+    /// Once it enters the viewport.
+    Visible,
+}
+
+impl Load {
+    fn as_str(self) -> &'static str {
+        match self {
+            Load::Eager => "eager",
+            Load::Visible => "visible",
+        }
+    }
+}
+
+/// The id of the component. It is named after the type, so that it is the
+/// same on the server and in the browser.
+pub(crate) fn component_id<COMP: 'static>() -> String {
+    let mut hasher = FnvHasher::default();
+    hasher.write(any::type_name::<COMP>().as_bytes());
+    format!("{:08x}", hasher.finish() as u32)
+}
+
+/// Writes the HTML of the component as declared by its hydration. The index
+/// of the component is the one its state is rehydrated with.
+pub(crate) fn write_html<'a, COMP: Render>(
+    w: &mut HtmlWriter<'a>,
+    index: Option<usize>,
+    f: impl FnOnce(&mut HtmlWriter<'a>) -> fmt::Result,
+) -> fmt::Result {
+    match COMP::hydration() {
+        Hydration::Full => f(w),
+        Hydration::Static => w.static_part(f),
+        // There is nothing to mount the island onto, if not hydrated.
+        Hydration::Island(_) if !w.is_hydratable() => f(w),
+        Hydration::Island(load) => {
+            let id = component_id::<COMP>();
+            let index = index.map(|index| index.to_string());
+            w.open_tag(
+                "ruukh-island",
+                vec![
+                    ("data-ruukh-island", Some(id.as_str())),
+                    ("data-ruukh-load", Some(load.as_str())),
+                    ("data-ruukh-index", index.as_deref()),
+                    // Do not affect the layout of the page.
+                    ("style", Some("display: contents")),
+                ]
+                .into_iter()
+                .filter(|(_, value)| value.is_some()),
+                std::iter::empty(),
+            )?;
+            f(w)?;
+            w.close_tag("ruukh-island")
+        }
+    }
+}
+
+/// Registers the component to be rendered onto its islands once they are
+/// loaded.
+pub fn register<COMP>()
+where
+    COMP: Render<Props = (), Events = ()>,
+{
+    let mount = |el: Element| {
+        let first = el
+            .get_attribute("data-ruukh-index")
+            .and_then(|index| index.parse().ok());
+        // Replace the markup rendered on the server.
+        el.set_inner_html("");
+        App::<COMP>::new().mount_from(el, first);
+    };
+    ISLANDS.with(|islands| {
+        islands
+            .borrow_mut()
+            .mounts
+            .insert(component_id::<COMP>(), Rc::new(mount));
+    });
+}
+
+/// Renders the registered components anew onto their islands in the
+/// document, each when it is to be loaded, replacing the markup rendered on
+/// the server. The islands of the unregistered components are left as
+/// rendered.
+pub fn render_lazily() -> Result<(), JsValue> {
+    let document = window().unwrap().document().unwrap();
+    let collection = document.get_elements_by_tag_name("ruukh-island");
+    // The collection is live, whereas mounting an island removes the ones
+    // nested within it.
+    let elements = (0..collection.length())
+        .filter_map(|index| collection.item(index))
+        .collect::<Vec<_>>();
+
+    for el in elements {
+        // An island within another one is mounted along with it.
+        if let Some(parent) = el.parent_element() {
+            if parent.closest("ruukh-island")?.is_some() {
+                continue;
+            }
+        }
+        let load = el.get_attribute("data-ruukh-load");
+        match load.as_deref() {
+            Some("visible") => observe(&el)?,
+            _ => mount(el),
+        }
+    }
+    Ok(())
+}
+
+/// Mounts the registered component onto the island.
+fn mount(el: Element) {
+    let mount = el
+        .get_attribute("data-ruukh-island")
+        .and_then(|id| ISLANDS.with(|islands| islands.borrow().mounts.get(&id).cloned()));
+    if let Some(mount) = mount {
+        mount(el);
+    }
+}
+
+/// Mounts the island once it enters the viewport.
+fn observe(el: &Element) -> Result<(), JsValue> {
+    ISLANDS.with(|islands| {
+        let mut islands = islands.borrow_mut();
+        if islands.observer.is_none() {
+            islands.observer = Some(IntersectionObserver::new(|entries| {
+                for entry in entries.into_iter().filter(|entry| entry.is_intersecting()) {
+                    let el = entry.target();
+                    ISLANDS.with(|islands| {
+                        if let Some(ref observer) = islands.borrow().observer {
+                            observer.unobserve(&el);
+                        }
+                    });
+                    mount(el);
+                }
+            })?);
+        }
+        if let Some(ref observer) = islands.observer {
+            observer.observe(el);
+        }
+        Ok(())
+    })
+}
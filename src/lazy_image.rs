@@ -0,0 +1,280 @@
+//! An image which is loaded only once it is near the viewport.
+//!
+//! A [LazyImage](struct.LazyImage.html) renders an `<img>` with the
+//! placeholder as its source, until an intersection observer sees the image
+//! scrolled near the viewport. Its source is then swapped with the real one.
+//! The `data-state` attribute of the image tells whether it shows the
+//! `placeholder`, or is `loading`, `loaded` or `failed`, to be styled upon.
+//!
+//! # Example
+//! ```ignore
+//! html! {
+//!     {
+//!         LazyImage::new("/photos/sunset.jpg")
+//!             .alt("A sunset")
+//!             .placeholder("/photos/sunset.thumb.jpg")
+//!             .on_error(|| errors::warn("The photo could not be loaded"))
+//!     }
+//! }
+//! ```
+
+use crate::{
+    component::{Component, Lifecycle, Render, Status},
+    vdom::{
+        vcomponent::VComponent,
+        velement::{Attribute, EventListener, VElement},
+        NodeRef, VNode,
+    },
+    web_api::IntersectionObserver,
+    Markup, Shared,
+};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+/// How far from the viewport the image is loaded, by default.
+const ROOT_MARGIN: &str = "200px";
+
+/// An image loaded once it is near the viewport, which is rendered within the
+/// markup of a component.
+#[derive(Clone)]
+pub struct LazyImage {
+    src: String,
+    alt: String,
+    placeholder: Option<String>,
+    root_margin: String,
+    on_load: Option<Rc<dyn Fn()>>,
+    on_error: Option<Rc<dyn Fn()>>,
+}
+
+/// The component which renders a lazy image.
+pub struct LazyImageComponent {
+    image: LazyImage,
+    node_ref: NodeRef,
+    status: Shared<Status<LazyImageState>>,
+}
+
+/// The state of a lazy image.
+#[derive(Default)]
+pub struct LazyImageState {
+    phase: Phase,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum Phase {
+    #[default]
+    Placeholder,
+    Loading,
+    Loaded,
+    Failed,
+}
+
+impl LazyImage {
+    /// Creates an image of the source, which is loaded once it is near the
+    /// viewport.
+    pub fn new(src: impl Into<String>) -> LazyImage {
+        LazyImage {
+            src: src.into(),
+            alt: String::new(),
+            placeholder: None,
+            root_margin: ROOT_MARGIN.to_string(),
+            on_load: None,
+            on_error: None,
+        }
+    }
+
+    /// Sets the alternate text of the image.
+    pub fn alt(mut self, alt: impl Into<String>) -> LazyImage {
+        self.alt = alt.into();
+        self
+    }
+
+    /// Sets the source shown until the image is loaded, like a tiny blurred
+    /// thumbnail.
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> LazyImage {
+        self.placeholder = Some(placeholder.into());
+        self
+    }
+
+    /// Sets how far from the viewport the image starts loading, like
+    /// `"500px"`. It is `"200px"` by default.
+    pub fn root_margin(mut self, root_margin: impl Into<String>) -> LazyImage {
+        self.root_margin = root_margin.into();
+        self
+    }
+
+    /// Invokes the handler once the image is loaded.
+    pub fn on_load(mut self, handler: impl Fn() + 'static) -> LazyImage {
+        self.on_load = Some(Rc::new(handler));
+        self
+    }
+
+    /// Invokes the handler if the image fails to load.
+    pub fn on_error(mut self, handler: impl Fn() + 'static) -> LazyImage {
+        self.on_error = Some(Rc::new(handler));
+        self
+    }
+
+    fn is_same(&self, other: &LazyImage) -> bool {
+        self.src == other.src
+            && self.alt == other.alt
+            && self.placeholder == other.placeholder
+            && self.root_margin == other.root_margin
+    }
+}
+
+impl<RCTX: Render> From<LazyImage> for VNode<RCTX> {
+    fn from(image: LazyImage) -> VNode<RCTX> {
+        VNode::from(VComponent::new::<LazyImageComponent>(image, ()))
+    }
+}
+
+impl Phase {
+    fn as_str(self) -> &'static str {
+        match self {
+            Phase::Placeholder => "placeholder",
+            Phase::Loading => "loading",
+            Phase::Loaded => "loaded",
+            Phase::Failed => "failed",
+        }
+    }
+}
+
+impl LazyImageComponent {
+    fn phase(&self) -> Phase {
+        self.status.borrow().state_as_ref().phase
+    }
+
+    /// Settles the loading image as loaded or failed. The events of the
+    /// placeholder are not of interest.
+    fn settle(&self, phase: Phase) {
+        if self.phase() != Phase::Loading {
+            return;
+        }
+        set_phase(&self.status, phase);
+        let handler = match phase {
+            Phase::Loaded => &self.image.on_load,
+            _ => &self.image.on_error,
+        };
+        if let Some(handler) = handler {
+            handler();
+        }
+    }
+}
+
+fn set_phase(status: &Shared<Status<LazyImageState>>, phase: Phase) {
+    let mut status = status.borrow_mut();
+    status.state_as_mut().phase = phase;
+    status.set_state_dirty(true);
+    status.do_react();
+}
+
+/// Starts loading the image which is still a placeholder.
+fn load(status: &Weak<RefCell<Status<LazyImageState>>>) {
+    if let Some(status) = status.upgrade() {
+        if status.borrow().state_as_ref().phase == Phase::Placeholder {
+            set_phase(&status, Phase::Loading);
+        }
+    }
+}
+
+impl Component for LazyImageComponent {
+    type Props = LazyImage;
+    type Events = ();
+    type State = LazyImageState;
+
+    fn type_name() -> &'static str {
+        "LazyImage"
+    }
+
+    fn init(image: LazyImage, _: (), status: Status<LazyImageState>) -> LazyImageComponent {
+        LazyImageComponent {
+            image,
+            node_ref: NodeRef::new(),
+            status: Rc::new(RefCell::new(status)),
+        }
+    }
+
+    fn update(&mut self, image: LazyImage, _: ()) -> Option<LazyImage> {
+        if self.image.is_same(&image) {
+            // The handlers are updated blindly.
+            self.image = image;
+            return None;
+        }
+        if self.image.src != image.src && self.phase() != Phase::Placeholder {
+            // The image is already near the viewport.
+            self.status.borrow_mut().state_as_mut().phase = Phase::Loading;
+        }
+        Some(std::mem::replace(&mut self.image, image))
+    }
+
+    fn is_props_same(&self, image: &LazyImage) -> bool {
+        // The handlers are not compared, as they are closures.
+        self.image.is_same(image)
+    }
+
+    fn refresh_state(&mut self) -> bool {
+        // The state is only kept in the status.
+        true
+    }
+
+    fn status(&self) -> Option<&Shared<Status<LazyImageState>>> {
+        Some(&self.status)
+    }
+}
+
+impl Lifecycle for LazyImageComponent {
+    fn mounted(&self) {
+        let status = Rc::downgrade(&self.status);
+        let element = match self.node_ref.element() {
+            Some(element) => element,
+            // There is no viewport to be near to outside of a browser.
+            None => return load(&status),
+        };
+        let observer =
+            IntersectionObserver::with_root_margin(&self.image.root_margin, move |entries| {
+                if entries.iter().any(|entry| entry.is_intersecting()) {
+                    load(&status);
+                }
+            });
+        match observer {
+            Ok(observer) => {
+                observer.observe(&element);
+                self.scope().keep(observer);
+            }
+            // Load right away where the observer is not supported.
+            Err(_) => load(&Rc::downgrade(&self.status)),
+        }
+    }
+}
+
+impl Render for LazyImageComponent {
+    fn render(&self) -> Markup<Self> {
+        let phase = self.phase();
+        let src = match phase {
+            Phase::Placeholder => self.image.placeholder.clone(),
+            _ => Some(self.image.src.clone()),
+        };
+        let image = VElement::childless(
+            "img",
+            vec![
+                Attribute::new("src", src),
+                Attribute::new("alt", self.image.alt.as_str()),
+                Attribute::new("data-state", phase.as_str()),
+            ],
+            vec![
+                EventListener::new(
+                    "load",
+                    Box::new(|this: &LazyImageComponent, _| this.settle(Phase::Loaded)),
+                ),
+                EventListener::new(
+                    "error",
+                    Box::new(|this: &LazyImageComponent, _| this.settle(Phase::Failed)),
+                ),
+            ],
+        )
+        .with_node_ref(&self.node_ref);
+        VNode::from(image)
+    }
+}
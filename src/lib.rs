@@ -50,16 +50,63 @@ use wasm_bindgen_test::*;
 wasm_bindgen_test_configure!(run_in_browser);
 
 use crate::{
+    backend::{Node, VoidBackend, WebBackend},
     component::{Render, RootParent},
-    vdom::vcomponent::{ComponentManager, ComponentWrapper},
+    dom::DOMPatch,
+    errors::{ErrorReport, ReportFn},
+    html::HtmlWriter,
+    theme::Theme,
+    vdom::{
+        profiler,
+        vcomponent::{ComponentManager, ComponentWrapper, Rerender},
+        velement,
+    },
+};
+use std::{
+    cell::RefCell,
+    mem,
+    rc::{Rc, Weak},
 };
-use std::{cell::RefCell, rc::Rc};
 use wasm_bindgen::{prelude::*, JsCast};
 use web_sys::{window, Element, MessageChannel, MessagePort};
 
+pub mod backend;
+pub mod bus;
 pub mod component;
+pub mod custom_element;
+pub mod devtools;
 mod dom;
+pub mod errors;
+pub mod forms;
+pub mod head;
+pub mod hot;
+pub mod i18n;
+mod html;
+pub mod islands;
+pub mod lazy_image;
+#[cfg(feature = "markdown")]
+pub mod markdown;
+pub mod persist;
+mod prepare;
+pub mod program;
+mod rehydration;
+pub mod router;
+#[cfg(feature = "stats")]
+pub mod stats;
+#[cfg(not(feature = "stats"))]
+mod stats;
+pub mod store;
+mod streams;
+pub mod styles;
+pub mod theme;
+#[cfg(feature = "time-travel")]
+pub mod time_travel;
+#[cfg(not(feature = "time-travel"))]
+mod time_travel;
+pub mod transition;
+pub mod undo;
 pub mod vdom;
+pub mod web_api;
 
 /// A VDOM Markup which is generated by using `html!` macro.
 pub type Markup<RCTX> = vdom::VNode<RCTX>;
@@ -67,8 +114,17 @@ pub type Markup<RCTX> = vdom::VNode<RCTX>;
 /// Things you'll require to build the next great App. Just glob import the
 /// prelude and start building your app.
 pub mod prelude {
-    pub use crate::component::{Component, Lifecycle, Render, SetState, StateSetter};
-    pub use crate::{App, Markup};
+    pub use crate::component::{Component, Lifecycle, Reducer, Render, SetState, StateSetter};
+    pub use crate::{
+        classes,
+        i18n::Catalog,
+        lazy_image::LazyImage,
+        vdom::{
+            modal::Modal, portal::Portal, profiler::Profiler, suspense::Suspense, vraw::VRaw, Classes,
+            KeepAlive, NodeRef,
+        },
+        t, vlist, App, Markup,
+    };
     pub use ruukh_codegen::*;
 }
 
@@ -84,6 +140,8 @@ where
     COMP: Render<Props = (), Events = ()>,
 {
     manager: ComponentWrapper<COMP, RootParent>,
+    recovery: Recovery,
+    theme: Option<Theme>,
 }
 
 impl<COMP> App<COMP>
@@ -119,6 +177,54 @@ where
         Default::default()
     }
 
+    /// Invokes the handler with the errors the app fails to render or patch
+    /// with, e.g. to report them, instead of panicking. The nodes of the app
+    /// are then removed, so that it is not left half-patched.
+    ///
+    /// # Example
+    /// ```ignore
+    /// App::<MyApp>::new()
+    ///     .on_error(|report| send_to_the_server(&report.to_string()))
+    ///     .crash_screen(|_| html!(<p>"Something went wrong."</p>))
+    ///     .mount("app");
+    /// ```
+    pub fn on_error(mut self, handler: impl Fn(&ErrorReport) + 'static) -> App<COMP> {
+        self.recovery.on_error = Some(Box::new(handler));
+        self
+    }
+
+    /// Renders the markup in place of the app once it fails to render or
+    /// patch, instead of panicking.
+    ///
+    /// It is also written in place of the app once it panics in the browser,
+    /// for which the [error reporting](errors/index.html) is installed.
+    pub fn crash_screen(
+        mut self,
+        screen: impl Fn(&ErrorReport) -> Markup<RootParent> + 'static,
+    ) -> App<COMP> {
+        self.recovery.crash_screen = Some(Rc::new(screen));
+        self
+    }
+
+    /// Mounts the app anew once it fails to render or patch, instead of
+    /// panicking. The crash screen, if any, is rendered only if it fails to be
+    /// mounted again. A panic is not recovered from.
+    pub fn remount_on_error(mut self) -> App<COMP> {
+        self.recovery.remount = true;
+        self
+    }
+
+    /// Provides the app with the light & dark color schemes, which are
+    /// reflected onto the element it is mounted on. See the
+    /// [theme](theme/index.html) module.
+    ///
+    /// Only one app is provided with a theme at a time, so mounting another
+    /// app with a theme replaces the theme of this one.
+    pub fn theme(mut self, theme: Theme) -> App<COMP> {
+        self.theme = Some(theme);
+        self
+    }
+
     /// Mounts the app on the given element in the DOM.
     ///
     /// The element may be anything that implements
@@ -145,24 +251,285 @@ where
     /// # }
     /// App::<MyApp>::new().mount("app");
     /// ```
-    pub fn mount(mut self, element: impl AppMount) {
-        let parent = element.app_mount();
+    pub fn mount(self, element: impl AppMount) {
+        self.mount_from(element, Some(0));
+    }
+
+    /// Mounts the app, rehydrating its components from the given index of
+    /// the payload rendered on the server.
+    pub(crate) fn mount_from(mut self, element: impl AppMount, first: Option<usize>) {
+        let element = element.app_mount();
+        let parent = Node::from(element.clone());
         let (receiver, sender) = app_message_channel();
+        head::attach_to_document();
+        styles::attach_to_document();
+        if let Some(theme) = self.theme.take() {
+            theme::provide(theme, Some(element.clone()));
+        }
 
         // Every component requires a render context, so provided a void context.
         let root_parent = Rc::new(RefCell::new(()));
 
         // The first render
-        self.manager
-            .render_walk(parent.as_ref(), None, root_parent.clone(), sender.clone())
-            .unwrap();
-
-        // Rerender when it receives update messages.
-        receiver.react_on_message(move || {
-            self.manager
-                .render_walk(parent.as_ref(), None, root_parent.clone(), sender.clone())
+        stats::start_flush();
+        if let Some(first) = first {
+            rehydration::start_from_document(first);
+        }
+        let rendered = self
+            .manager
+            .render_walk(&parent, None, root_parent.clone(), sender.clone());
+        rehydration::finish();
+        if let Err(error) = rendered {
+            self.recovery
+                .recover(&mut self.manager, &parent, &sender, error)
                 .unwrap();
+        }
+        velement::focus_pending().unwrap();
+        time_travel::record();
+        profiler::report();
+
+        if let Some(crash_screen) = self.recovery.crash_screen.clone() {
+            // Nothing is to be patched after a panic, so the crash screen is
+            // written as is.
+            errors::on_crash(move |report| {
+                element.set_inner_html(&crash_screen(report).to_string());
+            });
+        }
+
+        // Rerender the dirty components when it receives update messages.
+        let App {
+            mut manager,
+            mut recovery,
+            ..
+        } = self;
+        let listener = receiver.react_on_message(move || {
+            if let Err(error) = sender.rerender_dirty() {
+                recovery
+                    .recover(&mut manager, &parent, &sender, error)
+                    .unwrap();
+            }
         });
+
+        // Leak the listener along with the components so that the app lives
+        // on for 'static lifetimes.
+        mem::forget(listener);
+    }
+
+    /// Mounts the app on a node of any [Backend](backend/trait.Backend.html).
+    ///
+    /// Unlike `mount`, there is no event loop to rerender the app on state
+    /// changes. The returned [HeadlessApp](struct.HeadlessApp.html) needs to
+    /// be flushed to reflect them.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let root = TuiNode::root();
+    /// let mut app = App::<MyApp>::new().mount_headless(root.clone().into())?;
+    /// println!("{}", root);
+    /// ```
+    pub fn mount_headless(mut self, node: Node) -> Result<HeadlessApp<COMP>, JsValue> {
+        let sender = headless_message_sender();
+        if let Some(theme) = self.theme.take() {
+            let root = node
+                .backend_node::<WebBackend>()
+                .and_then(|node| node.dyn_ref::<Element>())
+                .cloned();
+            theme::provide(theme, root);
+        }
+        let root_parent = Rc::new(RefCell::new(()));
+
+        // The first render
+        stats::start_flush();
+        let rendered = self
+            .manager
+            .render_walk(&node, None, root_parent.clone(), sender.clone());
+        if let Err(error) = rendered {
+            self.recovery
+                .recover(&mut self.manager, &node, &sender, error)?;
+        }
+        velement::focus_pending()?;
+        time_travel::record();
+        profiler::report();
+
+        Ok(HeadlessApp {
+            manager: self.manager,
+            sender,
+            recovery: self.recovery,
+            node,
+        })
+    }
+
+    /// Renders the app into an HTML string.
+    ///
+    /// The HTML contains the markers required to hydrate it later, i.e. an
+    /// empty comment in between the adjacent texts and a `data-ruukh-events`
+    /// attribute on the elements listening to events.
+    ///
+    /// The attributes are written in the order they are declared, followed by
+    /// the spread ones sorted by their keys. So, the same app renders the same
+    /// HTML on every run, which can be cached by its hash.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let html = App::<MyApp>::new().render_to_string()?;
+    /// ```
+    pub fn render_to_string(self) -> Result<String, JsValue> {
+        let mut html = String::new();
+        self.render_html(&mut HtmlWriter::new(&mut html), None)?;
+        Ok(html)
+    }
+
+    /// Renders the app into an HTML string along with the tags its components
+    /// set in the document head and their scoped styles, as `(head, body)`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let (head, body) = App::<MyApp>::new().render_with_head()?;
+    /// ```
+    pub fn render_with_head(self) -> Result<(String, String), JsValue> {
+        let (mut head, mut body) = (String::new(), String::new());
+        self.render_html(
+            &mut HtmlWriter::new(&mut body),
+            Some(&mut HtmlWriter::new_static(&mut head)),
+        )?;
+        Ok((head, body))
+    }
+
+    /// Renders the app into an HTML string without any of the hydration
+    /// markers or event related attributes, which is suitable for emails and
+    /// static sites.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let html = App::<MyApp>::new().render_to_static_markup()?;
+    /// ```
+    pub fn render_to_static_markup(self) -> Result<String, JsValue> {
+        let mut html = String::new();
+        self.render_html(&mut HtmlWriter::new_static(&mut html), None)?;
+        Ok(html)
+    }
+
+    /// Renders the app into an HTML string, like `render_to_string`, once the
+    /// data of its components is prepared.
+    ///
+    /// The futures returned by
+    /// [Render::prepare](component/trait.Render.html#method.prepare) of the
+    /// components are awaited together. The components are then rendered with
+    /// the state they prepared, until there are no newer components to be
+    /// prepared.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let html = App::<MyApp>::new().render_to_string_async().await?;
+    /// ```
+    pub async fn render_to_string_async(mut self) -> Result<String, JsValue> {
+        let sender = headless_message_sender();
+        prepare::start();
+        let prepared = self.render_prepared(&sender).await;
+        prepare::finish();
+        prepared?;
+
+        let mut html = String::new();
+        self.write_html(&mut HtmlWriter::new(&mut html), None);
+        Ok(html)
+    }
+
+    async fn render_prepared(&mut self, sender: &MessageSender) -> Result<(), JsValue> {
+        self.render_vdom(sender)?;
+        loop {
+            let prepares = prepare::take();
+            if prepares.is_empty() {
+                return Ok(());
+            }
+            prepare::join_all(prepares).await;
+            sender.rerender_dirty()?;
+        }
+    }
+
+    fn render_html(
+        mut self,
+        w: &mut HtmlWriter<'_>,
+        head: Option<&mut HtmlWriter<'_>>,
+    ) -> Result<(), JsValue> {
+        self.render_vdom(&headless_message_sender())?;
+        self.write_html(w, head);
+        Ok(())
+    }
+
+    /// Renders the VDOM without creating any nodes, so that it can be written
+    /// as is.
+    fn render_vdom(&mut self, sender: &MessageSender) -> Result<(), JsValue> {
+        let root = Node::new(VoidBackend, ());
+        stats::start_flush();
+        rehydration::start();
+        self.manager
+            .render_walk(&root, None, Rc::new(RefCell::new(())), sender.clone())?;
+        profiler::report();
+        // There is nothing to focus on a string.
+        velement::focus_pending()
+    }
+
+    fn write_html(&self, w: &mut HtmlWriter<'_>, head: Option<&mut HtmlWriter<'_>>) {
+        self.manager
+            .write_html(w)
+            .expect("Writing to a string should not fail");
+        let states = rehydration::finish();
+        if w.is_hydratable() {
+            rehydration::write_html(w, states).expect("Writing to a string should not fail");
+        }
+        // The heads are dropped along with the app, so write them before.
+        if let Some(head) = head {
+            head::write_html(head)
+                .and_then(|()| styles::write_html(head))
+                .expect("Writing to a string should not fail");
+        }
+    }
+}
+
+/// An app mounted without an event loop.
+pub struct HeadlessApp<COMP>
+where
+    COMP: Render<Props = (), Events = ()>,
+{
+    /// Keeps the components of the app alive.
+    manager: ComponentWrapper<COMP, RootParent>,
+    sender: MessageSender,
+    recovery: Recovery,
+    /// The node it is mounted on.
+    node: Node,
+}
+
+impl<COMP> HeadlessApp<COMP>
+where
+    COMP: Render<Props = (), Events = ()>,
+{
+    /// Rerenders the app if any of its components requested it. Returns
+    /// whether a rerender happened.
+    ///
+    /// Outside of a browser, the timers which are due are fired before, see
+    /// [set_timeout](web_api/fn.set_timeout.html).
+    pub fn flush(&mut self) -> Result<bool, JsValue> {
+        #[cfg(not(target_arch = "wasm32"))]
+        web_api::timers::fire_due();
+        let is_queued = *self.sender.is_queued.borrow();
+        if is_queued {
+            // Unblock the queue before rendering, so that the state changes
+            // during the render are not lost.
+            *self.sender.is_queued.borrow_mut() = false;
+            if let Err(error) = self.sender.rerender_dirty() {
+                self.recovery
+                    .recover(&mut self.manager, &self.node, &self.sender, error)?;
+            }
+        }
+        Ok(is_queued)
+    }
+
+    /// Dumps the tree rendered by the app as indented text, see
+    /// [VNode::dump_tree](vdom/enum.VNode.html#method.dump_tree).
+    pub fn dump_tree(&self) -> String {
+        let mut out = String::new();
+        self.manager.dump(None, 0, &mut out);
+        out
     }
 }
 
@@ -174,10 +541,95 @@ where
     fn default() -> Self {
         App {
             manager: ComponentWrapper::new((), ()),
+            recovery: Recovery::default(),
+            theme: None,
         }
     }
 }
 
+type CrashScreenFn = Rc<dyn Fn(&ErrorReport) -> Markup<RootParent>>;
+
+/// How an app recovers once it fails to render or patch.
+#[derive(Default)]
+struct Recovery {
+    on_error: Option<ReportFn>,
+    crash_screen: Option<CrashScreenFn>,
+    remount: bool,
+    /// The crash screen once it is rendered, kept alive along with the app.
+    crashed: Option<Markup<RootParent>>,
+}
+
+impl Recovery {
+    /// Discards whatever got patched along with the components, and then
+    /// remounts the app or renders the crash screen in its place. The error
+    /// is returned as is if the app does not recover from the errors.
+    fn recover<COMP>(
+        &mut self,
+        manager: &mut ComponentWrapper<COMP, RootParent>,
+        parent: &Node,
+        sender: &MessageSender,
+        error: JsValue,
+    ) -> Result<(), JsValue>
+    where
+        COMP: Render<Props = (), Events = ()>,
+    {
+        if self.on_error.is_none() && self.crash_screen.is_none() && !self.remount {
+            return Err(error);
+        }
+        let mut report = self.report(error);
+        discard(manager, parent)?;
+
+        let root_parent = Rc::new(RefCell::new(()));
+        if self.remount {
+            match manager.render_walk(parent, None, root_parent.clone(), sender.clone()) {
+                Ok(()) => return Ok(()),
+                Err(error) => {
+                    report = self.report(error);
+                    discard(manager, parent)?;
+                }
+            }
+        }
+        if let Some(ref crash_screen) = self.crash_screen {
+            let mut screen = crash_screen(&report);
+            screen.patch(None, parent, None, root_parent.clone(), sender.clone())?;
+            screen.render_walk(parent, None, root_parent, sender.clone())?;
+            self.crashed = Some(screen);
+        }
+        Ok(())
+    }
+
+    /// Invokes the handler with the report of the error.
+    fn report(&self, error: JsValue) -> ErrorReport {
+        let report = ErrorReport::from_error(&error);
+        if let Some(ref on_error) = self.on_error {
+            on_error(&report);
+        }
+        report
+    }
+}
+
+/// Destroys the components of the app and removes its nodes from the parent,
+/// leaving a fresh app to be mounted. The other nodes of the parent are left
+/// be.
+fn discard<COMP>(
+    manager: &mut ComponentWrapper<COMP, RootParent>,
+    parent: &Node,
+) -> Result<(), JsValue>
+where
+    COMP: Render<Props = (), Events = ()>,
+{
+    let failed = mem::replace(manager, ComponentWrapper::new((), ()));
+    let nodes = failed.root_nodes();
+    if failed.remove(parent).is_err() {
+        // Whatever of its nodes is left is removed on its own, where the ones
+        // already removed fail again.
+        for node in nodes {
+            let _ = parent.remove_child(&node);
+        }
+    }
+    Ok(())
+}
+
 /// Create a `MessageChannel` to propagate state change message to the app.
 fn app_message_channel() -> (MessageReceiver, MessageSender) {
     let msg_channel = MessageChannel::new().unwrap();
@@ -188,12 +640,23 @@ fn app_message_channel() -> (MessageReceiver, MessageSender) {
             is_queued: is_queued.clone(),
         },
         MessageSender {
-            port: msg_channel.port1(),
+            port: Some(msg_channel.port1()),
             is_queued,
+            dirty: Rc::new(RefCell::new(vec![])),
         },
     )
 }
 
+/// Create a `MessageSender` which only queues the state change messages to be
+/// flushed by a `HeadlessApp`.
+fn headless_message_sender() -> MessageSender {
+    MessageSender {
+        port: None,
+        is_queued: Rc::new(RefCell::new(false)),
+        dirty: Rc::new(RefCell::new(vec![])),
+    }
+}
+
 /// The receiving end of the message port which notifies the app for any state
 /// changes.
 struct MessageReceiver {
@@ -202,8 +665,9 @@ struct MessageReceiver {
 }
 
 impl MessageReceiver {
-    /// Invokes the handler, when it receives a message.
-    fn react_on_message(self, mut handler: impl FnMut() + 'static) {
+    /// Invokes the handler, when it receives a message, until the returned
+    /// listener is dropped.
+    fn react_on_message(self, mut handler: impl FnMut() + 'static) -> MessageListener {
         let is_queued = self.is_queued.clone();
         let closure: Closure<dyn FnMut(JsValue)> = Closure::wrap(Box::new(move |_| {
             handler();
@@ -214,31 +678,85 @@ impl MessageReceiver {
         self.port
             .set_onmessage(Some(closure.as_ref().unchecked_ref()));
 
-        // Leak the closure so that the app lives on for 'static lifetimes.
-        closure.forget();
+        MessageListener {
+            port: self.port,
+            _closure: closure,
+        }
+    }
+}
+
+/// Listens to the messages on the port until dropped.
+struct MessageListener {
+    port: MessagePort,
+    _closure: Closure<dyn FnMut(JsValue)>,
+}
+
+impl Drop for MessageListener {
+    fn drop(&mut self) {
+        self.port.set_onmessage(None);
     }
 }
 
 /// MessageSender is responsible to message the App about state changes.
 #[derive(Clone)]
 struct MessageSender {
-    port: MessagePort,
+    /// The port to message on. A headless app does not have any.
+    port: Option<MessagePort>,
     is_queued: Shared<bool>,
+    /// The components which requested to be rerendered.
+    dirty: Shared<Vec<Weak<RefCell<dyn Rerender>>>>,
 }
 
 impl MessageSender {
     /// Sends an update message to the App.
     ///
     /// The components need to call this method when they desire the app to
-    /// be notified of state changes.
-    fn do_react(&self) {
+    /// be notified of state changes. The component which is to be rerendered
+    /// is passed along, so that the app does not need to walk from the root.
+    fn do_react(&self, instance: Option<Weak<RefCell<dyn Rerender>>>) {
+        if let Some(instance) = instance {
+            self.dirty.borrow_mut().push(instance);
+        }
         let is_queued = *self.is_queued.borrow();
         if !is_queued {
             *self.is_queued.borrow_mut() = true;
             // Just send a `null` as we have only a single message to be sent.
-            self.port
-                .post_message(&JsValue::null())
-                .expect("Could not send the message");
+            if let Some(ref port) = self.port {
+                port.post_message(&JsValue::null())
+                    .expect("Could not send the message");
+            }
+        }
+    }
+
+    /// Rerenders the components which requested it, until none are left.
+    ///
+    /// A component which was already rerendered along with its ancestor or
+    /// was removed since is skipped. The streams which woke are polled & the
+    /// events published on the buses are delivered before each round.
+    fn rerender_dirty(&self) -> Result<(), JsValue> {
+        let mut is_flushing = false;
+        loop {
+            // The streams & the handlers of the events may set the states to
+            // be rendered.
+            streams::poll_ready();
+            bus::deliver_pending();
+            let dirty = mem::take(&mut *self.dirty.borrow_mut());
+            if dirty.is_empty() {
+                if is_flushing {
+                    time_travel::record();
+                    profiler::report();
+                }
+                return velement::focus_pending();
+            }
+            if !is_flushing {
+                // Keep the stats of the last flush, if there is nothing to
+                // rerender.
+                stats::start_flush();
+                is_flushing = true;
+            }
+            for instance in dirty.iter().filter_map(Weak::upgrade) {
+                instance.borrow_mut().rerender(self.clone())?;
+            }
         }
     }
 }
@@ -0,0 +1,794 @@
+//! Rendering the Markdown text as the vnodes it is made of.
+//!
+//! A [Markdown](struct.Markdown.html) is converted into the elements & the
+//! texts, which are patched like any other markup, instead of replacing an
+//! `innerHTML` on every change. It supports the common subset of CommonMark:
+//! the ATX headings, paragraphs, block quotes, bulleted & ordered lists, the
+//! fenced & indented code blocks, thematic breaks, the emphasis, strong &
+//! ~~struck~~ texts, code spans, links, images, autolinks & hard breaks. The
+//! HTML within the text is rendered as a text, and the links & images of the
+//! URL schemes other than `http`, `https` & `mailto` are left out.
+//!
+//! It requires the `markdown` feature.
+//!
+//! # Example
+//! ```ignore
+//! html! {
+//!     <div class="message">{ Markdown::new(&message.body) }</div>
+//! }
+//! ```
+
+use crate::{
+    component::Render,
+    vdom::{
+        velement::{Attribute, VElement},
+        vlist::VList,
+        vraw::{self, Sanitizer},
+        vtext::VText,
+        VNode,
+    },
+};
+
+/// The depth of the quotes & the lists, and of the emphasis & the links, past
+/// which they are parsed as texts, so that a hostile text nested too deep
+/// does not overflow the stack.
+const MAX_DEPTH: usize = 32;
+
+/// The Markdown text, which is rendered within the markup of a component.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Markdown {
+    source: String,
+}
+
+/// A block of the text.
+#[derive(Debug, PartialEq)]
+enum Block {
+    Heading(usize, String),
+    Paragraph(String),
+    Code {
+        info: String,
+        code: String,
+    },
+    Quote(Vec<Block>),
+    List {
+        /// The number of the first item, if it is an ordered list.
+        start: Option<u32>,
+        /// Whether the paragraphs of the items are rendered without a `<p>`.
+        tight: bool,
+        items: Vec<Vec<Block>>,
+    },
+    Rule,
+}
+
+/// An inline within a block.
+#[derive(Debug, PartialEq)]
+enum Inline {
+    Text(String),
+    Code(String),
+    Emphasis(Vec<Inline>),
+    Strong(Vec<Inline>),
+    Strikethrough(Vec<Inline>),
+    Link {
+        href: String,
+        title: Option<String>,
+        children: Vec<Inline>,
+    },
+    Image {
+        src: String,
+        alt: String,
+        title: Option<String>,
+    },
+    Break,
+}
+
+/// The marker of a list item.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Marker {
+    /// The bullet, or the delimiter after the number of an ordered item.
+    symbol: char,
+    number: Option<u32>,
+    /// The columns up to the content of the item.
+    width: usize,
+}
+
+impl Markdown {
+    /// Creates the Markdown of the text.
+    pub fn new(source: impl Into<String>) -> Markdown {
+        Markdown {
+            source: source.into(),
+        }
+    }
+}
+
+impl<RCTX: Render> From<Markdown> for VNode<RCTX> {
+    fn from(markdown: Markdown) -> VNode<RCTX> {
+        let lines = markdown.source.lines().map(expand_tabs).collect();
+        let renderer = Renderer {
+            sanitizer: Sanitizer::new(),
+        };
+        renderer.blocks(parse_blocks(lines, 0), false)
+    }
+}
+
+/// Expands the tabs of the indentation into the spaces, up to the next
+/// multiple of four columns.
+fn expand_tabs(line: &str) -> String {
+    let mut expanded = String::with_capacity(line.len());
+    for (index, c) in line.char_indices() {
+        match c {
+            '\t' => {
+                let columns = 4 - expanded.len() % 4;
+                expanded.extend(std::iter::repeat_n(' ', columns));
+            }
+            ' ' => expanded.push(' '),
+            _ => {
+                expanded.push_str(&line[index..]);
+                break;
+            }
+        }
+    }
+    expanded
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+/// Strips up to the columns of the indentation.
+fn strip_columns(line: &str, columns: usize) -> String {
+    line[indentation(line).min(columns)..].to_string()
+}
+
+fn parse_blocks(lines: Vec<String>, depth: usize) -> Vec<Block> {
+    let mut blocks = vec![];
+    let mut i = 0;
+    while i < lines.len() {
+        let line = &lines[i];
+        if is_blank(line) {
+            i += 1;
+            continue;
+        }
+        if indentation(line) >= 4 {
+            let mut code = vec![];
+            while i < lines.len() && (is_blank(&lines[i]) || indentation(&lines[i]) >= 4) {
+                code.push(strip_columns(&lines[i], 4));
+                i += 1;
+            }
+            while code.last().is_some_and(|line| is_blank(line)) {
+                code.pop();
+            }
+            blocks.push(Block::Code {
+                info: String::new(),
+                code: code.join("\n"),
+            });
+            continue;
+        }
+        let trimmed = line.trim_start();
+        if let Some(heading) = heading(trimmed) {
+            blocks.push(heading);
+            i += 1;
+        } else if is_rule(trimmed) {
+            blocks.push(Block::Rule);
+            i += 1;
+        } else if let Some((symbol, len, info)) = fence(trimmed) {
+            let indent = indentation(line);
+            let mut code = vec![];
+            i += 1;
+            while i < lines.len() && !is_closing_fence(&lines[i], symbol, len) {
+                code.push(strip_columns(&lines[i], indent));
+                i += 1;
+            }
+            // Past the closing fence, if any.
+            i += 1;
+            blocks.push(Block::Code {
+                info,
+                code: code.join("\n"),
+            });
+        } else if trimmed.starts_with('>') && depth < MAX_DEPTH {
+            let mut quoted = vec![];
+            while i < lines.len() && !is_blank(&lines[i]) {
+                let line = lines[i].trim_start();
+                let line = match line.strip_prefix('>') {
+                    Some(line) => line.strip_prefix(' ').unwrap_or(line),
+                    // A lazy continuation of the quoted paragraph.
+                    None if !starts_block(&lines[i]) => line,
+                    None => break,
+                };
+                quoted.push(line.to_string());
+                i += 1;
+            }
+            blocks.push(Block::Quote(parse_blocks(quoted, depth + 1)));
+        } else if let Some(marker) = list_marker(line).filter(|_| depth < MAX_DEPTH) {
+            blocks.push(parse_list(&lines, &mut i, marker, depth + 1));
+        } else {
+            let mut paragraph = vec![trimmed.to_string()];
+            i += 1;
+            while i < lines.len() && !is_blank(&lines[i]) && !starts_block(&lines[i]) {
+                paragraph.push(lines[i].trim_start().to_string());
+                i += 1;
+            }
+            blocks.push(Block::Paragraph(
+                paragraph.join("\n").trim_end().to_string(),
+            ));
+        }
+    }
+    blocks
+}
+
+/// Whether the line interrupts a paragraph with another block.
+fn starts_block(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    indentation(line) < 4
+        && (heading(trimmed).is_some()
+            || is_rule(trimmed)
+            || fence(trimmed).is_some()
+            || trimmed.starts_with('>')
+            || list_marker(line).is_some_and(|marker| {
+                // An ordered list interrupts only if it starts from one.
+                marker.number.is_none_or(|number| number == 1)
+                    && !is_blank(&line[marker.width.min(line.len())..])
+            }))
+}
+
+fn heading(line: &str) -> Option<Block> {
+    let level = line.len() - line.trim_start_matches('#').len();
+    let rest = &line[level..];
+    if level == 0 || level > 6 || !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+    let mut text = rest.trim();
+    // The closing sequence of the `#`s, if any.
+    let without_closing = text.trim_end_matches('#');
+    if without_closing.is_empty() || without_closing.ends_with(' ') {
+        text = without_closing.trim_end();
+    }
+    Some(Block::Heading(level, text.to_string()))
+}
+
+fn is_rule(line: &str) -> bool {
+    let mut chars = line.chars().filter(|c| !c.is_whitespace());
+    let first = match chars.next() {
+        Some(c @ '*') | Some(c @ '-') | Some(c @ '_') => c,
+        _ => return false,
+    };
+    let mut count = 1;
+    for c in chars {
+        if c != first {
+            return false;
+        }
+        count += 1;
+    }
+    count >= 3
+}
+
+/// The symbol & the length of the opening fence, along with the language
+/// of the code.
+fn fence(line: &str) -> Option<(char, usize, String)> {
+    let symbol = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = line.len() - line.trim_start_matches(symbol).len();
+    let info = line[len..].trim();
+    if len < 3 || (symbol == '`' && info.contains('`')) {
+        return None;
+    }
+    let info = info.split_whitespace().next().unwrap_or("").to_string();
+    Some((symbol, len, info))
+}
+
+fn is_closing_fence(line: &str, symbol: char, len: usize) -> bool {
+    let trimmed = line.trim();
+    indentation(line) < 4 && trimmed.len() >= len && trimmed.chars().all(|c| c == symbol)
+}
+
+/// The marker of the list item the line starts with, if it does.
+fn list_marker(line: &str) -> Option<Marker> {
+    let indent = indentation(line);
+    if indent >= 4 {
+        return None;
+    }
+    let rest = &line[indent..];
+    let (symbol, number, len) = match rest.chars().next()? {
+        symbol @ '-' | symbol @ '*' | symbol @ '+' => (symbol, None, 1),
+        _ => {
+            let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            let symbol = rest[digits..].chars().next()?;
+            if digits == 0 || digits > 9 || !(symbol == '.' || symbol == ')') {
+                return None;
+            }
+            (symbol, Some(rest[..digits].parse().ok()?), digits + 1)
+        }
+    };
+    let after = &rest[len..];
+    if !(after.is_empty() || after.starts_with(' ')) {
+        return None;
+    }
+    let spaces = indentation(after);
+    // The content indented by more than four columns is an indented code.
+    let spaces = if spaces == 0 || spaces > 4 || is_blank(after) {
+        1
+    } else {
+        spaces
+    };
+    Some(Marker {
+        symbol,
+        number,
+        width: indent + len + spaces,
+    })
+}
+
+fn parse_list(lines: &[String], i: &mut usize, first: Marker, depth: usize) -> Block {
+    let mut items = vec![];
+    let mut tight = true;
+    let mut marker = first;
+    loop {
+        let line = &lines[*i];
+        let mut item = vec![line.get(marker.width..).unwrap_or("").to_string()];
+        *i += 1;
+        while *i < lines.len() {
+            let line = &lines[*i];
+            if is_blank(line) {
+                item.push(String::new());
+            } else if indentation(line) >= marker.width {
+                item.push(strip_columns(line, marker.width));
+            } else if item.last().is_some_and(|last| !is_blank(last)) && !starts_block(line) {
+                // A lazy continuation of the paragraph of the item.
+                item.push(line.trim_start().to_string());
+            } else {
+                break;
+            }
+            *i += 1;
+        }
+        let mut ends_with_blank = false;
+        while item.last().is_some_and(|line| is_blank(line)) {
+            item.pop();
+            ends_with_blank = true;
+        }
+        // The blank lines in between the blocks of an item make it loose.
+        if item.iter().skip(1).any(|line| is_blank(line)) {
+            tight = false;
+        }
+        items.push(parse_blocks(item, depth));
+
+        let next = match lines.get(*i).and_then(|line| list_marker(line)) {
+            Some(next)
+                if next.symbol == first.symbol
+                    && next.number.is_some() == first.number.is_some() =>
+            {
+                next
+            }
+            _ => break,
+        };
+        if ends_with_blank {
+            tight = false;
+        }
+        marker = next;
+    }
+    Block::List {
+        start: first.number,
+        tight,
+        items,
+    }
+}
+
+fn parse_inlines(text: &str, depth: usize) -> Vec<Inline> {
+    let nests = depth < MAX_DEPTH;
+    let mut inlines = vec![];
+    let mut buffer = String::new();
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        let c = rest.chars().next().unwrap();
+        let parsed = match c {
+            '\\' => match rest[1..].chars().next() {
+                Some('\n') => Some((Inline::Break, 2)),
+                Some(escaped) if escaped.is_ascii_punctuation() => {
+                    buffer.push(escaped);
+                    i += 2;
+                    continue;
+                }
+                _ => None,
+            },
+            '`' => code_span(rest),
+            '*' | '_' | '~' if nests => emphasis(text, i, depth + 1),
+            '!' if nests && rest[1..].starts_with('[') => link(rest, true, depth + 1),
+            '[' if nests => link(rest, false, depth + 1),
+            '<' => autolink(rest),
+            '&' => match reference(rest) {
+                Some((character, len)) => {
+                    buffer.push(character);
+                    i += len;
+                    continue;
+                }
+                None => None,
+            },
+            '\n' => {
+                let is_hard = buffer.ends_with("  ");
+                let trimmed = buffer.trim_end_matches(' ').len();
+                buffer.truncate(trimmed);
+                if is_hard {
+                    Some((Inline::Break, 1))
+                } else {
+                    buffer.push('\n');
+                    i += 1;
+                    continue;
+                }
+            }
+            _ => None,
+        };
+        match parsed {
+            Some((inline, len)) => {
+                if !buffer.is_empty() {
+                    inlines.push(Inline::Text(std::mem::take(&mut buffer)));
+                }
+                inlines.push(inline);
+                i += len;
+            }
+            None => {
+                // The delimiters which do not form an inline are texts.
+                let len = match c {
+                    '`' | '*' | '_' | '~' => rest.len() - rest.trim_start_matches(c).len(),
+                    _ => c.len_utf8(),
+                };
+                buffer.push_str(&rest[..len]);
+                i += len;
+            }
+        }
+    }
+    if !buffer.is_empty() {
+        inlines.push(Inline::Text(buffer));
+    }
+    inlines
+}
+
+/// The character of the reference, like `&amp;`, the text starts with,
+/// along with its length.
+fn reference(text: &str) -> Option<(char, usize)> {
+    let end = text[1..].find(';').filter(|end| *end <= 32)?;
+    Some((vraw::character(&text[1..=end])?, end + 2))
+}
+
+/// The code span the text starts with, along with its length.
+fn code_span(text: &str) -> Option<(Inline, usize)> {
+    let ticks = text.len() - text.trim_start_matches('`').len();
+    let mut offset = ticks;
+    while let Some(start) = text[offset..].find('`') {
+        let start = offset + start;
+        let run = text[start..].len() - text[start..].trim_start_matches('`').len();
+        if run == ticks {
+            let code = text[ticks..start].replace('\n', " ");
+            let code = if code.len() > 1 && code.starts_with(' ') && code.ends_with(' ') {
+                code[1..code.len() - 1].to_string()
+            } else {
+                code
+            };
+            return Some((Inline::Code(code), start + run));
+        }
+        offset = start + run;
+    }
+    None
+}
+
+/// The emphasis, strong or struck text which starts at the index, along
+/// with its length.
+fn emphasis(text: &str, start: usize, depth: usize) -> Option<(Inline, usize)> {
+    let rest = &text[start..];
+    let symbol = rest.chars().next()?;
+    let run = rest.len() - rest.trim_start_matches(symbol).len();
+    let len = match symbol {
+        '~' if run == 2 => 2,
+        '~' => return None,
+        _ => run.min(2),
+    };
+    // The opening delimiter is followed by a text, and an `_` is not within
+    // a word.
+    let after = rest[len..].chars().next()?;
+    let before = text[..start].chars().next_back();
+    if after.is_whitespace() || (symbol == '_' && before.is_some_and(char::is_alphanumeric)) {
+        return None;
+    }
+    let end = closing_delimiter(rest, symbol, len)?;
+    let children = parse_inlines(&rest[len..end], depth);
+    let inline = match (symbol, len) {
+        ('~', _) => Inline::Strikethrough(children),
+        (_, 2) => Inline::Strong(children),
+        _ => Inline::Emphasis(children),
+    };
+    Some((inline, end + len))
+}
+
+/// The index of the delimiter run of the length which closes the one the
+/// text starts with. The runs of the other lengths & the code spans are
+/// skipped over, as they are nested within.
+fn closing_delimiter(text: &str, symbol: char, len: usize) -> Option<usize> {
+    // Past the whole opening run, so that the text within is not empty.
+    let mut i = text.len() - text.trim_start_matches(symbol).len();
+    while i < text.len() {
+        let rest = &text[i..];
+        let c = rest.chars().next()?;
+        if c == '`' {
+            i += code_span(rest).map_or(1, |(_, len)| len);
+            continue;
+        }
+        if c == '\\' {
+            i += 1 + rest[1..].chars().next().map_or(0, char::len_utf8);
+            continue;
+        }
+        if c != symbol {
+            i += c.len_utf8();
+            continue;
+        }
+        let run = rest.len() - rest.trim_start_matches(symbol).len();
+        let before = text[..i].chars().next_back()?;
+        let after = rest[run..].chars().next();
+        if run == len
+            && !before.is_whitespace()
+            && !(symbol == '_' && after.is_some_and(char::is_alphanumeric))
+        {
+            return Some(i);
+        }
+        i += run;
+    }
+    None
+}
+
+/// The link or the image the text starts with, along with its length.
+fn link(text: &str, is_image: bool, depth: usize) -> Option<(Inline, usize)> {
+    let open = if is_image { 2 } else { 1 };
+    let mut brackets = 1;
+    let mut close = None;
+    let mut chars = text[open..].char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' => brackets += 1,
+            ']' => {
+                brackets -= 1;
+                if brackets == 0 {
+                    close = Some(open + index);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let close = close?;
+    let label = &text[open..close];
+    let rest = text[close + 1..].strip_prefix('(')?;
+    let destination = rest.trim_start_matches([' ', '\n']);
+    let (href, after) = if let Some(destination) = destination.strip_prefix('<') {
+        let end = destination.find('>')?;
+        (&destination[..end], &destination[end + 1..])
+    } else {
+        let mut parens = 0;
+        let end = destination
+            .char_indices()
+            .find(|&(_, c)| match c {
+                '(' => {
+                    parens += 1;
+                    false
+                }
+                ')' if parens == 0 => true,
+                ')' => {
+                    parens -= 1;
+                    false
+                }
+                c => c.is_whitespace(),
+            })
+            .map_or(destination.len(), |(end, _)| end);
+        (&destination[..end], &destination[end..])
+    };
+    let after = after.trim_start_matches([' ', '\n']);
+    let (title, after) = match after.chars().next()? {
+        quote @ '"' | quote @ '\'' => {
+            let end = after[1..].find(quote)? + 1;
+            (
+                Some(after[1..end].to_string()),
+                after[end + 1..].trim_start_matches([' ', '\n']),
+            )
+        }
+        _ => (None, after),
+    };
+    let after = after.strip_prefix(')')?;
+    let len = text.len() - after.len();
+    let href = href.to_string();
+    let inline = if is_image {
+        Inline::Image {
+            src: href,
+            alt: plain_text(&parse_inlines(label, depth)),
+            title,
+        }
+    } else {
+        Inline::Link {
+            href,
+            title,
+            children: parse_inlines(label, depth),
+        }
+    };
+    Some((inline, len))
+}
+
+/// The autolink, like `<https://ruukh.rs>`, the text starts with, along
+/// with its length.
+fn autolink(text: &str) -> Option<(Inline, usize)> {
+    let end = text.find('>')?;
+    let url = &text[1..end];
+    if url.is_empty() || url.contains(|c: char| c.is_whitespace() || c == '<') {
+        return None;
+    }
+    let href = match url.find(':') {
+        Some(scheme)
+            if scheme > 1
+                && url[..scheme]
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '.' || c == '-') =>
+        {
+            url.to_string()
+        }
+        _ if url.contains('@') && !url.contains(':') => format!("mailto:{}", url),
+        _ => return None,
+    };
+    Some((
+        Inline::Link {
+            href,
+            title: None,
+            children: vec![Inline::Text(url.to_string())],
+        },
+        end + 1,
+    ))
+}
+
+/// The text of the inlines, e.g. as the alternate text of an image.
+fn plain_text(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(text) | Inline::Code(text) => text.clone(),
+            Inline::Emphasis(children)
+            | Inline::Strong(children)
+            | Inline::Strikethrough(children)
+            | Inline::Link { children, .. } => plain_text(children),
+            Inline::Image { alt, .. } => alt.clone(),
+            Inline::Break => "\n".to_string(),
+        })
+        .collect()
+}
+
+/// Converts the blocks & the inlines into the vnodes.
+struct Renderer {
+    /// Checks the schemes of the URLs.
+    sanitizer: Sanitizer,
+}
+
+impl Renderer {
+    fn blocks<RCTX: Render>(&self, blocks: Vec<Block>, tight: bool) -> VNode<RCTX> {
+        children(
+            blocks
+                .into_iter()
+                .map(|block| self.block(block, tight))
+                .collect(),
+        )
+    }
+
+    fn block<RCTX: Render>(&self, block: Block, tight: bool) -> VNode<RCTX> {
+        match block {
+            Block::Heading(level, text) => {
+                const HEADINGS: [&str; 6] = ["h1", "h2", "h3", "h4", "h5", "h6"];
+                element(HEADINGS[level - 1], vec![], self.inlines(&text))
+            }
+            // The paragraphs of a tight list item are not wrapped.
+            Block::Paragraph(text) if tight => self.inlines(&text),
+            Block::Paragraph(text) => element("p", vec![], self.inlines(&text)),
+            Block::Code { info, code } => {
+                let class = if info.is_empty() {
+                    None
+                } else {
+                    Some(format!("language-{}", info))
+                };
+                element(
+                    "pre",
+                    vec![],
+                    element(
+                        "code",
+                        vec![Attribute::new("class", class)],
+                        VText::text(code).into(),
+                    ),
+                )
+            }
+            Block::Quote(blocks) => element("blockquote", vec![], self.blocks(blocks, false)),
+            Block::List {
+                start,
+                tight,
+                items,
+            } => {
+                let items = items
+                    .into_iter()
+                    .map(|item| element("li", vec![], self.blocks(item, tight)))
+                    .collect();
+                match start {
+                    Some(start) => element(
+                        "ol",
+                        vec![Attribute::new(
+                            "start",
+                            Some(start).filter(|start| *start != 1),
+                        )],
+                        children(items),
+                    ),
+                    None => element("ul", vec![], children(items)),
+                }
+            }
+            Block::Rule => VElement::childless("hr", vec![], vec![]).into(),
+        }
+    }
+
+    fn inlines<RCTX: Render>(&self, text: &str) -> VNode<RCTX> {
+        self.inline_nodes(parse_inlines(text, 0))
+    }
+
+    fn inline_nodes<RCTX: Render>(&self, inlines: Vec<Inline>) -> VNode<RCTX> {
+        children(
+            inlines
+                .into_iter()
+                .map(|inline| self.inline(inline))
+                .collect(),
+        )
+    }
+
+    fn inline<RCTX: Render>(&self, inline: Inline) -> VNode<RCTX> {
+        match inline {
+            Inline::Text(text) => VText::text(text).into(),
+            Inline::Code(code) => element("code", vec![], VText::text(code).into()),
+            Inline::Emphasis(children) => element("em", vec![], self.inline_nodes(children)),
+            Inline::Strong(children) => element("strong", vec![], self.inline_nodes(children)),
+            Inline::Strikethrough(children) => element("del", vec![], self.inline_nodes(children)),
+            // The text of a link to a URL which is not safe is still shown.
+            Inline::Link { href, children, .. } if !self.sanitizer.is_url_allowed(&href) => {
+                self.inline_nodes(children)
+            }
+            Inline::Link {
+                href,
+                title,
+                children,
+            } => element(
+                "a",
+                vec![Attribute::new("href", href), Attribute::new("title", title)],
+                self.inline_nodes(children),
+            ),
+            Inline::Image { src, alt, .. } if !self.sanitizer.is_url_allowed(&src) => {
+                VText::text(alt).into()
+            }
+            Inline::Image { src, alt, title } => VElement::childless(
+                "img",
+                vec![
+                    Attribute::new("src", src),
+                    Attribute::new("alt", alt),
+                    Attribute::new("title", title),
+                ],
+                vec![],
+            )
+            .into(),
+            Inline::Break => VElement::childless("br", vec![], vec![]).into(),
+        }
+    }
+}
+
+fn element<RCTX: Render>(
+    tag: &'static str,
+    attributes: Vec<Attribute>,
+    child: VNode<RCTX>,
+) -> VNode<RCTX> {
+    VElement::new(tag, attributes, vec![], Some(child)).into()
+}
+
+/// The vnode of the children, which is a list only if there are many.
+fn children<RCTX: Render>(mut nodes: Vec<VNode<RCTX>>) -> VNode<RCTX> {
+    match nodes.len() {
+        0 => VNode::None,
+        1 => nodes.remove(0),
+        _ => VList::from(nodes).into(),
+    }
+}
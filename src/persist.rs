@@ -0,0 +1,252 @@
+//! Persisting the state of a store or a component in the `localStorage`, so
+//! that it survives the reloads of the page.
+//!
+//! The state is restored as soon as it is persisted and saved a while after
+//! each change, so that a burst of changes is written only once. It is
+//! stored along with a version, so that the state saved by an older version of
+//! the app can be migrated onto the current one instead of being discarded.
+//!
+//! # Example
+//! ```ignore
+//! Store::<Cart>::global().persist(
+//!     Persist::new("cart")
+//!         .version(2)
+//!         .migrate(|version, mut state| {
+//!             if version == 1 {
+//!                 // The items were renamed in the version 2.
+//!                 state["items"] = state["products"].take();
+//!             }
+//!             Some(state)
+//!         }),
+//! );
+//! ```
+
+use crate::{
+    component::{status_of, Component},
+    errors,
+    web_api::{set_timeout, Storage, Timeout},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::JsValue;
+
+/// How long to wait after a change before saving, by default.
+const DEFAULT_DEBOUNCE_MILLIS: i32 = 300;
+
+/// Serializes the state to be saved, once it is due.
+type Pending = Box<dyn Fn() -> serde_json::Result<Value>>;
+
+/// Migrates the state saved with an older version.
+type Migrate = Box<dyn Fn(u32, Value) -> Option<Value>>;
+
+/// The options to persist a state with.
+pub struct Persist {
+    key: String,
+    version: u32,
+    migrate: Option<Migrate>,
+    millis: i32,
+}
+
+impl Persist {
+    /// Persists the state under the key in the `localStorage`.
+    pub fn new(key: &str) -> Persist {
+        Persist {
+            key: key.to_string(),
+            version: 0,
+            migrate: None,
+            millis: DEFAULT_DEBOUNCE_MILLIS,
+        }
+    }
+
+    /// Sets the version of the state, which is to be increased once its shape
+    /// changes. It is 0 by default.
+    pub fn version(mut self, version: u32) -> Persist {
+        self.version = version;
+        self
+    }
+
+    /// Migrates the JSON of the state saved with another version, given that
+    /// version. The state is discarded if it returns `None` or if there is no
+    /// migration.
+    pub fn migrate(mut self, migrate: impl Fn(u32, Value) -> Option<Value> + 'static) -> Persist {
+        self.migrate = Some(Box::new(migrate));
+        self
+    }
+
+    /// Sets how long to wait after a change before saving, in milliseconds.
+    pub fn debounce(mut self, millis: i32) -> Persist {
+        self.millis = millis;
+        self
+    }
+
+    /// Persists a part of the state of a component, which is selected from it
+    /// and applied back onto it once restored. It is to be invoked when the
+    /// component is `created`, so that it is rerendered with the restored
+    /// state right away. The pending change is saved once the component is
+    /// destroyed.
+    ///
+    /// # Example
+    /// ```ignore
+    /// fn created(&self) {
+    ///     Persist::new("draft").state(
+    ///         self,
+    ///         |state| state.draft.clone(),
+    ///         |state, draft| state.draft = draft,
+    ///     );
+    /// }
+    /// ```
+    pub fn state<COMP, T>(
+        self,
+        component: &COMP,
+        select: impl Fn(&COMP::State) -> T + 'static,
+        apply: impl FnOnce(&mut COMP::State, T),
+    ) where
+        COMP: Component,
+        T: Serialize + DeserializeOwned + PartialEq + Clone + 'static,
+    {
+        let status = status_of(component);
+        if let Some(restored) = self.restore() {
+            let mut status = status.borrow_mut();
+            apply(status.state_as_mut(), restored);
+            status.set_state_dirty(true);
+            status.do_react();
+        }
+
+        let saver = self.into_saver();
+        let scope = status.borrow().scope().clone();
+        {
+            let saver = saver.clone();
+            scope.on_clear(move || saver.save_now());
+        }
+        status.borrow_mut().watch(select, move |value: &T| {
+            let value = value.clone();
+            saver.save_later(Box::new(move || serde_json::to_value(&value)));
+        });
+    }
+
+    /// Gets the saved state, migrated onto the current version, if any.
+    pub(crate) fn restore<T: DeserializeOwned>(&self) -> Option<T> {
+        let json = Storage::local().ok()?.get_item(&self.key).ok()??;
+        let mut saved: Value = serde_json::from_str(&json).ok()?;
+        let version = saved["version"].as_u64()? as u32;
+        let mut state = saved["state"].take();
+        if version != self.version {
+            state = self.migrate.as_ref()?(version, state)?;
+        }
+        serde_json::from_value(state).ok()
+    }
+
+    /// Turns the options into a saver of the state.
+    pub(crate) fn into_saver(self) -> Rc<Saver> {
+        Rc::new(Saver {
+            key: self.key,
+            version: self.version,
+            millis: self.millis,
+            pending: RefCell::new(None),
+            timeout: RefCell::new(None),
+        })
+    }
+}
+
+/// Saves the changes of a state once they stop for a while.
+pub(crate) struct Saver {
+    key: String,
+    version: u32,
+    millis: i32,
+    pending: RefCell<Option<Pending>>,
+    timeout: RefCell<Option<Timeout>>,
+}
+
+impl Saver {
+    /// Saves the state once no other change is made for a while.
+    pub(crate) fn save_later(self: &Rc<Self>, pending: Pending) {
+        *self.pending.borrow_mut() = Some(pending);
+        let saver = Rc::downgrade(self);
+        // Replacing the timeout cancels the previous one.
+        *self.timeout.borrow_mut() = set_timeout(self.millis, move || {
+            if let Some(saver) = saver.upgrade() {
+                saver.save_now();
+            }
+        })
+        .ok();
+    }
+
+    /// Saves the pending change right away, if any.
+    pub(crate) fn save_now(&self) {
+        let pending = match self.pending.borrow_mut().take() {
+            Some(pending) => pending,
+            None => return,
+        };
+        let saved = pending()
+            .map_err(|err| JsValue::from_str(&err.to_string()))
+            .and_then(|state| {
+                let saved = json!({ "version": self.version, "state": state });
+                Storage::local()?.set_item(&self.key, &saved.to_string())
+            });
+        if let Err(err) = saved {
+            errors::warn(&format!(
+                "ruukh: could not persist the state of `{}`: {:?}",
+                self.key, err
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::store::Store;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn should_restore_the_saved_state() {
+        let storage = Storage::local().unwrap();
+        storage
+            .set_item("todos", r#"{"version":1,"state":["Buy milk"]}"#)
+            .unwrap();
+
+        let store = Store::new(Vec::<String>::new());
+        store.persist(Persist::new("todos").version(1));
+        assert_eq!(*store.state(), vec!["Buy milk".to_string()]);
+        storage.clear().unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    fn should_migrate_the_state_of_another_version() {
+        let storage = Storage::local().unwrap();
+        storage
+            .set_item("todos", r#"{"version":1,"state":"Buy milk"}"#)
+            .unwrap();
+
+        assert_eq!(
+            Persist::new("todos").version(2).restore::<Vec<String>>(),
+            None
+        );
+        let persist = Persist::new("todos").version(2).migrate(|version, state| {
+            assert_eq!(version, 1);
+            Some(json!([state]))
+        });
+        assert_eq!(
+            persist.restore::<Vec<String>>(),
+            Some(vec!["Buy milk".to_string()])
+        );
+        storage.clear().unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    fn should_save_the_last_change_with_the_version() {
+        let storage = Storage::local().unwrap();
+        let saver = Persist::new("todos").version(3).into_saver();
+        saver.save_later(Box::new(|| Ok(json!(["Buy milk"]))));
+        saver.save_later(Box::new(|| Ok(json!(["Buy milk", "Walk the dog"]))));
+        assert_eq!(storage.get_item("todos").unwrap(), None);
+
+        saver.save_now();
+        assert_eq!(
+            storage.get_item("todos").unwrap(),
+            Some(r#"{"state":["Buy milk","Walk the dog"],"version":3}"#.to_string())
+        );
+        storage.clear().unwrap();
+    }
+}
@@ -0,0 +1,84 @@
+//! Preparing the data of the components before the app is rendered on the
+//! server.
+
+use crate::component::Render;
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// A future which prepares the data of a component.
+type Prepare = Pin<Box<dyn Future<Output = ()>>>;
+
+thread_local! {
+    /// The futures of the components created since they were last taken, if
+    /// collecting.
+    static PREPARES: RefCell<Option<Vec<Prepare>>> = RefCell::new(None);
+}
+
+/// Starts collecting the futures of the components created from now on.
+pub(crate) fn start() {
+    PREPARES.with(|prepares| *prepares.borrow_mut() = Some(vec![]));
+}
+
+/// Stops collecting the futures.
+pub(crate) fn finish() {
+    PREPARES.with(|prepares| *prepares.borrow_mut() = None);
+}
+
+/// Collects the future of the component, if collecting.
+pub(crate) fn collect<COMP: Render>(component: &COMP) {
+    let is_collecting = PREPARES.with(|prepares| prepares.borrow().is_some());
+    if !is_collecting {
+        return;
+    }
+    if let Some(prepare) = component.prepare() {
+        PREPARES.with(|prepares| {
+            if let Some(ref mut prepares) = *prepares.borrow_mut() {
+                prepares.push(prepare);
+            }
+        });
+    }
+}
+
+/// Takes the futures collected till now.
+pub(crate) fn take() -> Vec<Prepare> {
+    PREPARES.with(|prepares| {
+        prepares
+            .borrow_mut()
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    })
+}
+
+/// Awaits all of the futures together.
+pub(crate) fn join_all(prepares: Vec<Prepare>) -> impl Future<Output = ()> {
+    JoinAll(prepares.into_iter().map(Some).collect())
+}
+
+struct JoinAll(Vec<Option<Prepare>>);
+
+impl Future for JoinAll {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut is_ready = true;
+        for slot in self.0.iter_mut() {
+            if let Some(ref mut prepare) = *slot {
+                if prepare.as_mut().poll(cx).is_ready() {
+                    *slot = None;
+                } else {
+                    is_ready = false;
+                }
+            }
+        }
+        if is_ready {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! Components written in the Elm architecture, as a model updated by the
+//! messages & viewed as a markup.
+//!
+//! A [Program](trait.Program.html) does not deal with the `#[component]`, its
+//! `Status` or `set_state`. Its model is created with `init`, each message
+//! sent from its view is passed to `update` & the model is then viewed again.
+//! It is run as the [ProgramComponent](struct.ProgramComponent.html), which
+//! is mounted as an app or rendered within the markup of another component
+//! with [embed](fn.embed.html).
+//!
+//! # Example
+//! ```ignore
+//! struct Counter;
+//!
+//! #[derive(Clone)]
+//! enum Message {
+//!     Increment,
+//!     Reset,
+//! }
+//!
+//! impl Program for Counter {
+//!     type Model = i32;
+//!     type Message = Message;
+//!
+//!     fn init() -> i32 {
+//!         0
+//!     }
+//!
+//!     fn update(count: &mut i32, message: Message) {
+//!         match message {
+//!             Message::Increment => *count += 1,
+//!             Message::Reset => *count = 0,
+//!         }
+//!     }
+//!
+//!     fn view(count: &i32) -> View<Self> {
+//!         html! {
+//!             <button @click={program::send(Message::Increment)}>{ *count }</button>
+//!             <button @click={program::send(Message::Reset)}>"Reset"</button>
+//!         }
+//!     }
+//! }
+//!
+//! App::<ProgramComponent<Counter>>::new().mount("app");
+//! // Or within another component.
+//! html! {
+//!     <p>"Count: "{ program::embed::<Counter, _>() }</p>
+//! }
+//! ```
+
+use crate::{
+    component::{Component, Lifecycle, Render, Scope, Status},
+    vdom::{vcomponent::VComponent, VNode},
+    Markup, Shared,
+};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+use web_sys::Event;
+
+/// The markup viewed by a program, whose event listeners are invoked with its
+/// component.
+pub type View<P> = Markup<ProgramComponent<P>>;
+
+/// Trait to write a component in the Elm architecture.
+pub trait Program: Sized + 'static {
+    /// The state of the program.
+    type Model;
+    /// The messages which update the model.
+    type Message;
+
+    /// Creates the model once the component is created.
+    fn init() -> Self::Model;
+
+    /// Updates the model with a message sent from the view.
+    fn update(model: &mut Self::Model, message: Self::Message);
+
+    /// Views the model as a markup, whose event listeners send the messages
+    /// with [send](fn.send.html) or [send_with](fn.send_with.html).
+    fn view(model: &Self::Model) -> View<Self>;
+
+    /// Subscribes to the messages which come from outside the view, like the
+    /// ticks of a timer, once the component is created. The handles to the
+    /// subscriptions are to be kept in the scope, which drops them along with
+    /// the component.
+    ///
+    /// # Example
+    /// ```ignore
+    /// fn subscriptions(sender: Sender<Self>, scope: &Scope) {
+    ///     let tick = set_interval(1000, move || sender.send(Message::Tick)).unwrap();
+    ///     scope.keep(tick);
+    /// }
+    /// ```
+    fn subscriptions(_sender: Sender<Self>, _scope: &Scope) {}
+}
+
+/// The component which runs a program.
+pub struct ProgramComponent<P: Program> {
+    status: Shared<Status<ProgramModel<P>>>,
+}
+
+/// The model of a program, which is created with `Program::init`.
+pub struct ProgramModel<P: Program>(P::Model);
+
+/// Sends the messages to a program, until its component is destroyed.
+pub struct Sender<P: Program>(Weak<RefCell<Status<ProgramModel<P>>>>);
+
+impl<P: Program> ProgramComponent<P> {
+    /// Updates the model with the message, which is then viewed again.
+    pub fn send(&self, message: P::Message) {
+        update(&self.status, message);
+    }
+
+    /// Gets a sender of the messages, which may outlive the borrow of the
+    /// component.
+    pub fn sender(&self) -> Sender<P> {
+        Sender(Rc::downgrade(&self.status))
+    }
+}
+
+impl<P: Program> Sender<P> {
+    /// Updates the model with the message, unless the component is
+    /// destroyed.
+    pub fn send(&self, message: P::Message) {
+        if let Some(status) = self.0.upgrade() {
+            update(&status, message);
+        }
+    }
+}
+
+fn update<P: Program>(status: &Shared<Status<ProgramModel<P>>>, message: P::Message) {
+    let mut status = status.borrow_mut();
+    P::update(&mut status.state_as_mut().0, message);
+    status.set_state_dirty(true);
+    status.do_react();
+}
+
+/// An event listener which sends a message when the event fires.
+pub fn send<P: Program>(message: P::Message) -> impl Fn(&ProgramComponent<P>, Event)
+where
+    P::Message: Clone,
+{
+    move |program: &ProgramComponent<P>, _: Event| program.send(message.clone())
+}
+
+/// An event listener which sends the message made from the event, like one
+/// with the value of an input.
+pub fn send_with<P: Program>(
+    message: impl Fn(Event) -> P::Message,
+) -> impl Fn(&ProgramComponent<P>, Event) {
+    move |program: &ProgramComponent<P>, event: Event| program.send(message(event))
+}
+
+/// Renders the program within the markup of a component.
+pub fn embed<P: Program, RCTX: Render>() -> Markup<RCTX> {
+    VNode::from(VComponent::new::<ProgramComponent<P>>((), ()))
+}
+
+impl<P: Program> Clone for Sender<P> {
+    fn clone(&self) -> Sender<P> {
+        Sender(self.0.clone())
+    }
+}
+
+impl<P: Program> Default for ProgramModel<P> {
+    fn default() -> ProgramModel<P> {
+        ProgramModel(P::init())
+    }
+}
+
+impl<P: Program> Component for ProgramComponent<P> {
+    type Props = ();
+    type Events = ();
+    type State = ProgramModel<P>;
+
+    fn type_name() -> &'static str {
+        crate::errors::short_name::<P>()
+    }
+
+    fn init(_: (), _: (), status: Status<ProgramModel<P>>) -> ProgramComponent<P> {
+        ProgramComponent {
+            status: Rc::new(RefCell::new(status)),
+        }
+    }
+
+    fn update(&mut self, _: (), _: ()) -> Option<()> {
+        None
+    }
+
+    fn refresh_state(&mut self) -> bool {
+        // The model is only kept in the status and any message may change it.
+        true
+    }
+
+    fn status(&self) -> Option<&Shared<Status<ProgramModel<P>>>> {
+        Some(&self.status)
+    }
+}
+
+impl<P: Program> Lifecycle for ProgramComponent<P> {
+    fn created(&self) {
+        let scope = self.status.borrow().scope().clone();
+        P::subscriptions(self.sender(), &scope);
+    }
+}
+
+impl<P: Program> Render for ProgramComponent<P> {
+    fn render(&self) -> View<P> {
+        P::view(&self.status.borrow().state_as_ref().0)
+    }
+}
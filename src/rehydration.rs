@@ -0,0 +1,134 @@
+//! Sending the state of the components rendered on the server to the browser.
+//!
+//! The components are indexed in the order they are created, which is the same
+//! on the server and in the browser, given they are rendered with the same
+//! state. The state a component dehydrates on the server is written as a JSON
+//! payload along with the HTML. When mounted in the browser, the component of
+//! the same index is rehydrated with it before its first render.
+
+use crate::{component::Render, html::HtmlWriter, islands};
+use serde_json::{Map, Value};
+use std::{cell::RefCell, fmt};
+use web_sys::window;
+
+/// The id of the script tag holding the payload.
+const PAYLOAD_ID: &str = "ruukh-state";
+
+thread_local! {
+    static REHYDRATION: RefCell<Option<Rehydration>> = const { RefCell::new(None) };
+    /// The payload read from the document, which is kept for the islands
+    /// mounted later on.
+    static PAYLOAD: RefCell<Option<Map<String, Value>>> = const { RefCell::new(None) };
+}
+
+/// The components being indexed along with their states.
+struct Rehydration {
+    next: usize,
+    /// The type and the state of the components by their index.
+    states: Map<String, Value>,
+}
+
+/// Starts indexing the components created from now on, to collect their
+/// states on the server.
+pub(crate) fn start() {
+    REHYDRATION.with(|rehydration| {
+        *rehydration.borrow_mut() = Some(Rehydration {
+            next: 0,
+            states: Map::new(),
+        });
+    });
+}
+
+/// Starts indexing the components created from now on from the given index, to
+/// rehydrate them with the payload in the document. Nothing is indexed, if
+/// there is no payload.
+pub(crate) fn start_from_document(first: usize) {
+    let states = PAYLOAD.with(|payload| {
+        let mut payload = payload.borrow_mut();
+        if payload.is_none() {
+            *payload = read_payload();
+        }
+        payload.clone()
+    });
+    if let Some(states) = states {
+        REHYDRATION.with(|rehydration| {
+            *rehydration.borrow_mut() = Some(Rehydration {
+                next: first,
+                states,
+            });
+        });
+    }
+}
+
+fn read_payload() -> Option<Map<String, Value>> {
+    let script = window()?.document()?.get_element_by_id(PAYLOAD_ID)?;
+    match serde_json::from_str(&script.text_content()?) {
+        Ok(Value::Object(states)) => Some(states),
+        _ => None,
+    }
+}
+
+/// Stops indexing the components, returning the states collected.
+pub(crate) fn finish() -> Map<String, Value> {
+    REHYDRATION
+        .with(|rehydration| rehydration.borrow_mut().take())
+        .map(|rehydration| rehydration.states)
+        .unwrap_or_default()
+}
+
+/// Indexes a component being created, if indexing.
+pub(crate) fn next_index() -> Option<usize> {
+    REHYDRATION.with(|rehydration| {
+        rehydration.borrow_mut().as_mut().map(|rehydration| {
+            rehydration.next += 1;
+            rehydration.next - 1
+        })
+    })
+}
+
+/// Gets the state of the component of the index, if it is of the same type.
+pub(crate) fn state_of<COMP: Render>(index: usize) -> Option<Value> {
+    REHYDRATION.with(|rehydration| {
+        let mut rehydration = rehydration.borrow_mut();
+        let entry = rehydration.as_mut()?.states.remove(&index.to_string())?;
+        match entry {
+            Value::Array(mut entry) if entry.len() == 2 => {
+                let state = entry.pop()?;
+                if entry.pop()? == Value::String(islands::component_id::<COMP>()) {
+                    Some(state)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Collects the dehydrated state of the component of the index, if
+/// collecting.
+pub(crate) fn dehydrate<COMP: Render>(index: usize, component: &COMP) {
+    REHYDRATION.with(|rehydration| {
+        if let Some(ref mut rehydration) = *rehydration.borrow_mut() {
+            if let Some(state) = component.dehydrate() {
+                let entry =
+                    Value::Array(vec![Value::String(islands::component_id::<COMP>()), state]);
+                rehydration.states.insert(index.to_string(), entry);
+            }
+        }
+    });
+}
+
+/// Writes the states as a JSON payload within a script tag.
+pub(crate) fn write_html(w: &mut HtmlWriter<'_>, states: Map<String, Value>) -> fmt::Result {
+    if states.is_empty() {
+        return Ok(());
+    }
+    w.open_tag(
+        "script",
+        vec![("type", Some("application/json")), ("id", Some(PAYLOAD_ID))].into_iter(),
+        std::iter::empty(),
+    )?;
+    w.raw_text(&Value::Object(states).to_string())?;
+    w.close_tag("script")
+}
@@ -0,0 +1,108 @@
+//! Navigation within the App without a full page load.
+//!
+//! A navigation pushes the new URL into the browser history and then
+//! dispatches a `popstate` event on the window, the same event which is
+//! dispatched on the back/forward navigation. So, anything which matches the
+//! routes only needs to listen to `popstate` (see
+//! [on_pop_state](../web_api/history/fn.on_pop_state.html)) to re-match them.
+
+use crate::{
+    component::Render,
+    vdom::{
+        velement::{Attribute, EventListener, VElement},
+        VNode,
+    },
+    web_api::history,
+};
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{window, Element, Event, MouseEvent, PopStateEvent, Url};
+
+/// Navigates to the given URL without a full page load.
+pub fn navigate(url: &str) -> Result<(), JsValue> {
+    history::push_state(url)?;
+    window()
+        .unwrap()
+        .dispatch_event(PopStateEvent::new("popstate")?.as_ref())?;
+    Ok(())
+}
+
+/// Creates an `<a href>` element which navigates to the `href` without a full
+/// page load when clicked.
+///
+/// The clicks with a modifier key or a non-primary button are left to the
+/// browser, so that the link can still be opened in a new tab or window. So
+/// are the links to another origin, the ones with a `target` or `download`
+/// attribute and the ones whose URL cannot be pushed into the history.
+///
+/// # Example
+/// ```ignore
+/// html! {
+///     <nav>
+///         { link("/about", vec![], html!("About")) }
+///     </nav>
+/// }
+/// ```
+pub fn link<RCTX: Render>(
+    href: &str,
+    mut attributes: Vec<Attribute>,
+    child: VNode<RCTX>,
+) -> VElement<RCTX> {
+    let url = href.to_string();
+    attributes.push(Attribute::new("href", href));
+    VElement::new(
+        "a",
+        attributes,
+        vec![EventListener::new(
+            "click",
+            Box::new(move |_: &RCTX, event: Event| {
+                if !is_plain_click(&event) || !is_in_app(&event, &url) {
+                    return;
+                }
+                if navigate(&url).is_ok() {
+                    event.prevent_default();
+                }
+            }),
+        )],
+        Some(child),
+    )
+}
+
+/// Whether the click is a primary button click without any modifier keys.
+fn is_plain_click(event: &Event) -> bool {
+    match event.dyn_ref::<MouseEvent>() {
+        Some(event) => {
+            event.button() == 0
+                && !event.ctrl_key()
+                && !event.meta_key()
+                && !event.shift_key()
+                && !event.alt_key()
+        }
+        None => true,
+    }
+}
+
+/// Whether the clicked link is to be navigated to within the App, i.e. its URL
+/// is of the same origin and it is neither opened elsewhere nor downloaded.
+fn is_in_app(event: &Event, url: &str) -> bool {
+    let anchor = match event
+        .current_target()
+        .and_then(|target| target.dyn_into::<Element>().ok())
+    {
+        Some(anchor) => anchor,
+        None => return false,
+    };
+    if anchor.has_attribute("target") || anchor.has_attribute("download") {
+        return false;
+    }
+    let location = match window() {
+        Some(window) => window.location(),
+        None => return false,
+    };
+    match (location.href(), location.origin()) {
+        (Ok(base), Ok(origin)) => match Url::new_with_base(url, &base) {
+            Ok(url) => url.origin() == origin,
+            Err(_) => false,
+        },
+        _ => false,
+    }
+}
@@ -0,0 +1,83 @@
+//! Counting the mutations made by the patches onto the rendered tree.
+//!
+//! The counts are only kept with the `stats` feature, so that the regressions
+//! in the diffing can be measured in an app or a benchmark, without costing
+//! anything otherwise. A flush is the first render of an app or a rerender of
+//! its dirty components.
+//!
+//! # Example
+//! ```ignore
+//! app.flush()?;
+//! let stats = stats::last_flush();
+//! assert_eq!(stats.nodes_moved, 1);
+//! ```
+
+use std::cell::Cell;
+
+thread_local! {
+    static LAST_FLUSH: Cell<FlushStats> = Cell::new(FlushStats::default());
+    static TOTAL: Cell<FlushStats> = Cell::new(FlushStats::default());
+}
+
+/// The number of mutations of each kind.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FlushStats {
+    /// The elements created.
+    pub elements_created: usize,
+    /// The texts & comments created.
+    pub texts_created: usize,
+    /// The nodes inserted into a parent.
+    pub nodes_inserted: usize,
+    /// The nodes moved within their parent, when a keyed list is reordered.
+    pub nodes_moved: usize,
+    /// The nodes removed from their parent.
+    pub nodes_removed: usize,
+    /// The attributes set.
+    pub attributes_set: usize,
+    /// The attributes removed.
+    pub attributes_removed: usize,
+    /// The contents of the texts & comments replaced.
+    pub text_updates: usize,
+    /// The static subtrees cloned from a template, instead of creating their
+    /// nodes.
+    pub templates_cloned: usize,
+}
+
+/// The mutations made by the last flush.
+#[cfg(feature = "stats")]
+pub fn last_flush() -> FlushStats {
+    LAST_FLUSH.with(Cell::get)
+}
+
+/// The mutations made by all the flushes since the last reset.
+#[cfg(feature = "stats")]
+pub fn total() -> FlushStats {
+    TOTAL.with(Cell::get)
+}
+
+/// Resets the counts of all the flushes.
+#[cfg(feature = "stats")]
+pub fn reset() {
+    LAST_FLUSH.with(|stats| stats.set(FlushStats::default()));
+    TOTAL.with(|stats| stats.set(FlushStats::default()));
+}
+
+/// Starts counting the mutations of a new flush.
+pub(crate) fn start_flush() {
+    if cfg!(feature = "stats") {
+        LAST_FLUSH.with(|stats| stats.set(FlushStats::default()));
+    }
+}
+
+/// Counts a mutation.
+pub(crate) fn record(f: impl Fn(&mut FlushStats)) {
+    if cfg!(feature = "stats") {
+        for counts in &[&LAST_FLUSH, &TOTAL] {
+            counts.with(|stats| {
+                let mut updated = stats.get();
+                f(&mut updated);
+                stats.set(updated);
+            });
+        }
+    }
+}
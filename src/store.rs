@@ -0,0 +1,275 @@
+//! A global state shared by the components across the app.
+//!
+//! A [Store](struct.Store.html) holds a state which any component can read in
+//! its render. The components which `subscribe` to it are rerendered when the
+//! state is mutated via `dispatch`, while the rest of the app is untouched.
+//! The components which only depend on a part of the state may subscribe to a
+//! [Computed](struct.Computed.html) value instead, to be rerendered only when
+//! that value changes.
+//!
+//! # Example
+//! ```ignore
+//! #[derive(Default)]
+//! struct Cart {
+//!     items: Vec<String>,
+//! }
+//!
+//! impl Lifecycle for CartBadge {
+//!     fn created(&self) {
+//!         Store::<Cart>::global().subscribe(self);
+//!     }
+//! }
+//!
+//! impl Render for CartBadge {
+//!     fn render(&self) -> Markup<Self> {
+//!         let count = Store::<Cart>::global().state().items.len();
+//!         html! {
+//!             <span>{ count }</span>
+//!         }
+//!     }
+//! }
+//!
+//! // Anywhere in the app, like an event handler.
+//! Store::<Cart>::global().dispatch(|cart| cart.items.push("Apple".to_string()));
+//! ```
+
+use crate::{
+    component::{status_of, Component, Status},
+    persist::Persist,
+};
+use serde::{de::DeserializeOwned, ser, Serialize};
+use std::{
+    any::{Any, TypeId},
+    cell::{Cell, Ref, RefCell},
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+thread_local! {
+    /// The global stores keyed by the type of their state.
+    static GLOBAL_STORES: RefCell<HashMap<TypeId, Box<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// A state shared by the components. The clones of a store share the same
+/// state.
+pub struct Store<S>(Rc<StoreInner<S>>);
+
+struct StoreInner<S> {
+    state: RefCell<S>,
+    subscribers: Rc<Subscribers>,
+    /// Saves the state on a change, if persisted.
+    persisted: RefCell<Option<Subscription>>,
+}
+
+/// A value derived from the state of a store, which is only recomputed when
+/// the state is mutated. Its subscribers are notified only if the value
+/// actually changes.
+///
+/// # Example
+/// ```ignore
+/// fn created(&self) {
+///     // Rerender only when the number of items changes.
+///     Store::<Cart>::global()
+///         .computed(|cart| cart.items.len())
+///         .subscribe(self);
+/// }
+/// ```
+pub struct Computed<T>(Rc<ComputedInner<T>>);
+
+struct ComputedInner<T> {
+    value: RefCell<T>,
+    subscribers: Rc<Subscribers>,
+    /// The subscription to the store it is derived from.
+    _upstream: Subscription,
+}
+
+type Callback = Box<dyn Fn()>;
+
+/// The callbacks to be invoked on a change.
+#[derive(Default)]
+struct Subscribers {
+    list: RefCell<Vec<(usize, Callback)>>,
+    next_id: Cell<usize>,
+}
+
+/// Unsubscribes when dropped, i.e. when the subscribed component is destroyed.
+struct Subscription {
+    subscribers: Weak<Subscribers>,
+    id: usize,
+    /// Keeps the source of the changes alive, if required.
+    _source: Option<Rc<dyn Any>>,
+}
+
+impl<S: 'static> Store<S> {
+    /// Creates a store with the initial state.
+    pub fn new(state: S) -> Store<S> {
+        Store(Rc::new(StoreInner {
+            state: RefCell::new(state),
+            subscribers: Rc::new(Subscribers::default()),
+            persisted: RefCell::new(None),
+        }))
+    }
+
+    /// Gets the global store of the state type, which is created with the
+    /// default state on first use.
+    pub fn global() -> Store<S>
+    where
+        S: Default,
+    {
+        GLOBAL_STORES.with(|stores| {
+            stores
+                .borrow_mut()
+                .entry(TypeId::of::<S>())
+                .or_insert_with(|| Box::new(Store::new(S::default())))
+                .downcast_ref::<Store<S>>()
+                .unwrap()
+                .clone()
+        })
+    }
+
+    /// Reads the current state.
+    pub fn state(&self) -> Ref<'_, S> {
+        self.0.state.borrow()
+    }
+
+    /// Mutates the state and notifies the subscribers.
+    pub fn dispatch(&self, mutator: impl FnOnce(&mut S)) {
+        mutator(&mut *self.0.state.borrow_mut());
+        self.0.subscribers.notify();
+    }
+
+    /// Subscribes the component to the changes in the state until it is
+    /// destroyed.
+    pub fn subscribe<COMP: Component>(&self, component: &COMP) {
+        subscribe_component(&self.0.subscribers, component, None);
+    }
+
+    /// Restores the state saved in the `localStorage`, if any, and saves it
+    /// there after the changes. See the [persist](../persist/index.html)
+    /// module.
+    pub fn persist(&self, persist: Persist)
+    where
+        S: Serialize + DeserializeOwned,
+    {
+        if let Some(state) = persist.restore() {
+            self.dispatch(|current| *current = state);
+        }
+        let saver = persist.into_saver();
+        let store = Rc::downgrade(&self.0);
+        let subscription = self.0.subscribers.add(
+            Box::new(move || {
+                let store = store.clone();
+                // The saver is dropped along with the store, so that the store
+                // is alive once the change is due.
+                saver.save_later(Box::new(move || match store.upgrade() {
+                    Some(store) => serde_json::to_value(&*store.state.borrow()),
+                    None => Err(ser::Error::custom("The store is dropped")),
+                }));
+            }),
+            None,
+        );
+        *self.0.persisted.borrow_mut() = Some(subscription);
+    }
+
+    /// Creates a value derived from the state.
+    pub fn computed<T: PartialEq + 'static>(
+        &self,
+        derive: impl Fn(&S) -> T + 'static,
+    ) -> Computed<T> {
+        let value = derive(&self.state());
+        let store = Rc::downgrade(&self.0);
+        Computed(Rc::new_cyclic(|computed: &Weak<ComputedInner<T>>| {
+            let computed = computed.clone();
+            let upstream = self.0.subscribers.add(
+                Box::new(move || {
+                    if let (Some(store), Some(computed)) = (store.upgrade(), computed.upgrade()) {
+                        let value = derive(&store.state.borrow());
+                        if value != *computed.value.borrow() {
+                            *computed.value.borrow_mut() = value;
+                            computed.subscribers.notify();
+                        }
+                    }
+                }),
+                None,
+            );
+            ComputedInner {
+                value: RefCell::new(value),
+                subscribers: Rc::new(Subscribers::default()),
+                _upstream: upstream,
+            }
+        }))
+    }
+}
+
+impl<T: 'static> Computed<T> {
+    /// Reads the current value.
+    pub fn get(&self) -> Ref<'_, T> {
+        self.0.value.borrow()
+    }
+
+    /// Subscribes the component to the changes in the value until it is
+    /// destroyed. The computed value is kept alive till then.
+    pub fn subscribe<COMP: Component>(&self, component: &COMP) {
+        subscribe_component(&self.0.subscribers, component, Some(self.0.clone()));
+    }
+}
+
+/// Subscribes the component to be rerendered on a change, until it is
+/// destroyed.
+fn subscribe_component<COMP: Component>(
+    subscribers: &Rc<Subscribers>,
+    component: &COMP,
+    source: Option<Rc<dyn Any>>,
+) {
+    let status = Rc::downgrade(status_of(component));
+    let subscription = subscribers.add(Box::new(move || rerender(&status)), source);
+    component.scope().keep(subscription);
+}
+
+fn rerender<T>(status: &Weak<RefCell<Status<T>>>) {
+    if let Some(status) = status.upgrade() {
+        status.borrow_mut().force_update();
+    }
+}
+
+impl Subscribers {
+    fn add(self: &Rc<Self>, callback: Callback, source: Option<Rc<dyn Any>>) -> Subscription {
+        let id = self.next_id.get() + 1;
+        self.next_id.set(id);
+        self.list.borrow_mut().push((id, callback));
+        Subscription {
+            subscribers: Rc::downgrade(self),
+            id,
+            _source: source,
+        }
+    }
+
+    fn notify(&self) {
+        for (_, callback) in self.list.borrow().iter() {
+            callback();
+        }
+    }
+}
+
+impl<S> Clone for Store<S> {
+    fn clone(&self) -> Store<S> {
+        Store(self.0.clone())
+    }
+}
+
+impl<T> Clone for Computed<T> {
+    fn clone(&self) -> Computed<T> {
+        Computed(self.0.clone())
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(subscribers) = self.subscribers.upgrade() {
+            subscribers
+                .list
+                .borrow_mut()
+                .retain(|(id, _)| *id != self.id);
+        }
+    }
+}
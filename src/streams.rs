@@ -0,0 +1,163 @@
+//! Polling the streams subscribed by the components, and the futures they
+//! suspend on.
+//!
+//! A stream is not polled by an executor. Once it wakes, it is queued and
+//! the app of its component is requested to flush, which polls it before
+//! the components are rerendered. So its items are fed into the state which
+//! is rendered in the same flush.
+
+use crate::{component::Status, Shared};
+use futures_core::Stream;
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    pin::Pin,
+    rc::{Rc, Weak},
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+};
+
+thread_local! {
+    static STREAMS: RefCell<HashMap<usize, Weak<dyn Polled>>> = RefCell::new(HashMap::new());
+    /// The ids of the streams which woke since the last flush.
+    static READY: RefCell<Vec<usize>> = const { RefCell::new(vec![]) };
+    static NEXT_ID: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A stream or a future which is polled on the flushes of the app.
+pub(crate) trait Polled {
+    /// Requests the app of the component to flush.
+    fn request_flush(&self);
+
+    /// Feeds the items ready into the state, if a stream. Returns whether
+    /// it ended.
+    fn poll(&self, cx: &mut Context<'_>) -> bool;
+}
+
+type HandlerFn<T, I> = Box<dyn Fn(&mut T, I)>;
+
+struct Subscription<S: Stream, T> {
+    stream: RefCell<Pin<Box<S>>>,
+    handler: HandlerFn<T, S::Item>,
+    status: Weak<RefCell<Status<T>>>,
+}
+
+/// Stops polling the stream when dropped, i.e. when the component is
+/// destroyed.
+pub(crate) struct SubscriptionGuard {
+    id: usize,
+    _subscription: Rc<dyn Polled>,
+}
+
+/// Wakes a stream by its id, as the wakers are required to be `Send`.
+struct StreamWaker(usize);
+
+/// Polls the stream on the flushes of the app, feeding its items into the
+/// state with the handler, until the returned guard is dropped.
+pub(crate) fn subscribe<S, T>(
+    status: &Shared<Status<T>>,
+    stream: S,
+    handler: impl Fn(&mut T, S::Item) + 'static,
+) -> SubscriptionGuard
+where
+    S: Stream + 'static,
+    T: 'static,
+{
+    register(Rc::new(Subscription {
+        stream: RefCell::new(Box::pin(stream)),
+        handler: Box::new(handler),
+        status: Rc::downgrade(status),
+    }))
+}
+
+/// Polls on the flushes of the app until it ends or the returned guard is
+/// dropped.
+pub(crate) fn register(subscription: Rc<dyn Polled>) -> SubscriptionGuard {
+    let id = NEXT_ID.with(|next_id| {
+        let id = next_id.get() + 1;
+        next_id.set(id);
+        id
+    });
+    STREAMS.with(|streams| {
+        streams
+            .borrow_mut()
+            .insert(id, Rc::downgrade(&subscription))
+    });
+    // It is first polled on the next flush.
+    wake(id);
+    SubscriptionGuard {
+        id,
+        _subscription: subscription,
+    }
+}
+
+/// Polls the streams which woke since the last flush.
+pub(crate) fn poll_ready() {
+    let ready = READY.with(|ready| ready.replace(vec![]));
+    for id in ready {
+        let subscription = STREAMS.with(|streams| streams.borrow().get(&id).cloned());
+        let subscription = match subscription.and_then(|s| s.upgrade()) {
+            Some(subscription) => subscription,
+            None => continue,
+        };
+        let waker = Waker::from(Arc::new(StreamWaker(id)));
+        if subscription.poll(&mut Context::from_waker(&waker)) {
+            STREAMS.with(|streams| streams.borrow_mut().remove(&id));
+        }
+    }
+}
+
+fn wake(id: usize) {
+    READY.with(|ready| {
+        let mut ready = ready.borrow_mut();
+        if !ready.contains(&id) {
+            ready.push(id);
+        }
+    });
+    let subscription = STREAMS.with(|streams| streams.borrow().get(&id).cloned());
+    if let Some(subscription) = subscription.and_then(|s| s.upgrade()) {
+        subscription.request_flush();
+    }
+}
+
+impl<S: Stream, T> Polled for Subscription<S, T> {
+    fn request_flush(&self) {
+        if let Some(status) = self.status.upgrade() {
+            // A flush is queued anyway while the state is being set.
+            if let Ok(status) = status.try_borrow() {
+                status.do_react();
+            }
+        }
+    }
+
+    fn poll(&self, cx: &mut Context<'_>) -> bool {
+        let status = match self.status.upgrade() {
+            Some(status) => status,
+            None => return true,
+        };
+        loop {
+            match self.stream.borrow_mut().as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    let mut status = status.borrow_mut();
+                    (self.handler)(status.state_as_mut(), item);
+                    status.set_state_dirty(true);
+                    status.do_react();
+                }
+                Poll::Ready(None) => return true,
+                Poll::Pending => return false,
+            }
+        }
+    }
+}
+
+impl Wake for StreamWaker {
+    fn wake(self: Arc<Self>) {
+        wake(self.0);
+    }
+}
+
+impl Drop for SubscriptionGuard {
+    fn drop(&mut self) {
+        STREAMS.with(|streams| streams.borrow_mut().remove(&self.id));
+    }
+}
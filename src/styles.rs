@@ -0,0 +1,256 @@
+//! The CSS scoped to a component, without a shadow DOM.
+//!
+//! A component declares its CSS with
+//! [Render::style](../component/trait.Render.html#method.style). The CSS is
+//! injected once into a `<style>` tag in the document head, with each of its
+//! selectors scoped under a class generated for the component. The class is
+//! added to the root elements of the component whenever it is rendered.
+//!
+//! A selector matches the elements within the root elements, whereas a `&` in
+//! a selector stands for the root elements themselves.
+//!
+//! ```css
+//! & { display: flex; }        /* .ruukh-1a2b3c4d { display: flex; } */
+//! p > a { color: tomato; }    /* .ruukh-1a2b3c4d p > a { color: tomato; } */
+//! ```
+//!
+//! As the child components are within the root elements too, the styles of a
+//! component also reach their elements.
+
+use crate::{component::Render, html::HtmlWriter, vdom::VNode};
+use fnv::FnvHasher;
+use std::{
+    any::{self, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    hash::Hasher,
+    rc::Rc,
+};
+use web_sys::window;
+
+thread_local! {
+    static STYLES: RefCell<Styles> = RefCell::new(Styles::default());
+}
+
+/// The styles injected till now.
+#[derive(Default)]
+struct Styles {
+    /// The scope class of each of the components.
+    scopes: HashMap<TypeId, Rc<str>>,
+    /// The scoped CSS along with its scope class, in the order injected.
+    sheets: Vec<(Rc<str>, String)>,
+    in_document: bool,
+}
+
+/// Adds the scope class of the component onto the root elements it rendered,
+/// injecting its CSS if not done already.
+pub(crate) fn scope_roots<COMP: Render>(vnode: VNode<COMP>) -> VNode<COMP> {
+    match COMP::style() {
+        Some(css) => add_class(vnode, &scope_of::<COMP>(css)),
+        None => vnode,
+    }
+}
+
+fn add_class<RCTX: Render>(vnode: VNode<RCTX>, class: &str) -> VNode<RCTX> {
+    match vnode {
+        VNode::Element(el) => VNode::Element(el.with_class(class)),
+        VNode::List(list) => VNode::List(list.map(|vnode| add_class(vnode, class))),
+        vnode => vnode,
+    }
+}
+
+/// Gets the scope class of the component, injecting its CSS the first time.
+fn scope_of<COMP: 'static>(css: &str) -> Rc<str> {
+    STYLES.with(|styles| {
+        let mut styles = styles.borrow_mut();
+        if let Some(scope) = styles.scopes.get(&TypeId::of::<COMP>()) {
+            return scope.clone();
+        }
+
+        // Named after the type, so that the class is the same on the server
+        // and in the browser.
+        let mut hasher = FnvHasher::default();
+        hasher.write(any::type_name::<COMP>().as_bytes());
+        let scope: Rc<str> = format!("ruukh-{:08x}", hasher.finish() as u32).into();
+
+        let sheet = scope_css(css, &scope);
+        if styles.in_document {
+            inject(&scope, &sheet);
+        }
+        styles.sheets.push((scope.clone(), sheet));
+        styles.scopes.insert(TypeId::of::<COMP>(), scope.clone());
+        scope
+    })
+}
+
+/// Writes the CSS injected till now as `<style>` tags, for the apps rendered
+/// on the server.
+pub(crate) fn write_html(w: &mut HtmlWriter<'_>) -> fmt::Result {
+    STYLES.with(|styles| {
+        for (scope, sheet) in styles.borrow().sheets.iter() {
+            w.open_tag(
+                "style",
+                vec![("data-ruukh-scope", Some(&**scope))].into_iter(),
+                std::iter::empty(),
+            )?;
+            w.raw_text(sheet)?;
+            w.close_tag("style")?;
+        }
+        Ok(())
+    })
+}
+
+/// Injects the CSS into the document head from now on, along with the ones
+/// tracked before.
+pub(crate) fn attach_to_document() {
+    STYLES.with(|styles| {
+        let mut styles = styles.borrow_mut();
+        if !styles.in_document {
+            styles.in_document = true;
+            for (scope, sheet) in styles.sheets.iter() {
+                inject(scope, sheet);
+            }
+        }
+    });
+}
+
+/// Appends a `<style>` tag into the document head, unless it was already
+/// rendered by the server.
+fn inject(scope: &str, sheet: &str) {
+    let document = window().unwrap().document().unwrap();
+    let head = match document.head() {
+        Some(head) => head,
+        None => return,
+    };
+    let selector = format!(r#"style[data-ruukh-scope="{}"]"#, scope);
+    if let Ok(Some(_)) = head.query_selector(&selector) {
+        return;
+    }
+    let style = document
+        .create_element("style")
+        .expect("Could not create a style tag");
+    let _ = style.set_attribute("data-ruukh-scope", scope);
+    style.set_text_content(Some(sheet));
+    let _ = head.append_child(&style);
+}
+
+/// Scopes every selector of the CSS under the class.
+fn scope_css(css: &str, scope: &str) -> String {
+    let mut scoped = String::new();
+    scope_rules(&strip_comments(css), scope, &mut scoped);
+    scoped
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut stripped = String::new();
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        stripped.push_str(&rest[..start]);
+        rest = match rest[start + 2..].find("*/") {
+            Some(end) => &rest[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+fn scope_rules(css: &str, scope: &str, out: &mut String) {
+    let mut rest = css.trim_start();
+    while !rest.is_empty() {
+        let open = rest.find('{');
+        let semi = rest.find(';');
+        // A statement like `@import` which has no block.
+        if let Some(semi) = semi.filter(|&semi| open.is_none_or(|open| semi < open)) {
+            out.push_str(rest[..=semi].trim());
+            rest = rest[semi + 1..].trim_start();
+            continue;
+        }
+        let open = match open {
+            Some(open) => open,
+            None => break,
+        };
+        let close = matching_brace(rest, open);
+        let prelude = rest[..open].trim();
+        let block = &rest[open + 1..close];
+
+        if prelude.starts_with("@media") || prelude.starts_with("@supports") {
+            out.push_str(prelude);
+            out.push('{');
+            scope_rules(block, scope, out);
+            out.push('}');
+        } else if prelude.starts_with('@') {
+            // Like `@keyframes` or `@font-face`, which have no selectors.
+            out.push_str(prelude);
+            out.push('{');
+            out.push_str(block.trim());
+            out.push('}');
+        } else {
+            let selectors = prelude
+                .split(',')
+                .map(|selector| scope_selector(selector.trim(), scope))
+                .collect::<Vec<_>>();
+            out.push_str(&selectors.join(","));
+            out.push('{');
+            out.push_str(block.trim());
+            out.push('}');
+        }
+        rest = rest.get(close + 1..).unwrap_or("").trim_start();
+    }
+}
+
+/// The index of the brace closing the one at `open`, or the end of the CSS
+/// if it is never closed.
+fn matching_brace(css: &str, open: usize) -> usize {
+    let mut depth = 0;
+    for (i, c) in css[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return open + i;
+                }
+            }
+            _ => {}
+        }
+    }
+    css.len()
+}
+
+fn scope_selector(selector: &str, scope: &str) -> String {
+    if selector.contains('&') {
+        selector.replace('&', &format!(".{}", scope))
+    } else {
+        format!(".{} {}", scope, selector)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn should_scope_the_selectors() {
+        assert_eq!(
+            scope_css("& { color: red; } p > a, li { margin: 0 }", "s"),
+            ".s{color: red;}.s p > a,.s li{margin: 0}"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn should_scope_within_media_but_not_keyframes() {
+        let css = r#"
+            /* Fade in. */
+            @keyframes fade { from { opacity: 0 } to { opacity: 1 } }
+            @media (max-width: 600px) { &.wide, p { display: none; } }
+        "#;
+        assert_eq!(
+            scope_css(css, "s"),
+            "@keyframes fade{from { opacity: 0 } to { opacity: 1 }}\
+             @media (max-width: 600px){.s.wide,.s p{display: none;}}"
+        );
+    }
+}
@@ -0,0 +1,303 @@
+//! The light & dark color schemes of an app.
+//!
+//! An app is provided with a [Theme](struct.Theme.html) with `App::theme`.
+//! Its color scheme follows the `prefers-color-scheme` of the system, unless
+//! the user picks one with [set_preference](fn.set_preference.html), which is
+//! remembered in the `localStorage` if the theme is persisted.
+//!
+//! The element the app is mounted on gets a `data-theme` attribute of either
+//! `light` or `dark`, along with a `dark` class in the dark scheme, for the
+//! stylesheets to be themed upon. The components which render differently
+//! per scheme read it with [current](fn.current.html) & `subscribe` to be
+//! rerendered when it changes.
+//!
+//! The theme is provided to a single app at a time. Mounting another app with
+//! a theme replaces the one provided earlier, along with the components
+//! subscribed to it.
+//!
+//! # Example
+//! ```ignore
+//! App::<MyApp>::new().theme(Theme::new().persist("theme")).mount("app");
+//!
+//! impl Lifecycle for ThemeToggle {
+//!     fn created(&self) {
+//!         theme::subscribe(self);
+//!     }
+//! }
+//!
+//! impl ThemeToggle {
+//!     fn toggle(&self, _: Event) {
+//!         theme::set_preference(Some(theme::current().opposite()));
+//!     }
+//! }
+//!
+//! impl Render for ThemeToggle {
+//!     fn render(&self) -> Markup<Self> {
+//!         let label = match theme::current() {
+//!             ColorScheme::Light => "Dark mode",
+//!             ColorScheme::Dark => "Light mode",
+//!         };
+//!         html! {
+//!             <button @click={Self::toggle}>{ label }</button>
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::{
+    component::{status_of, Component, Status},
+    web_api::{elements, media::MediaQuery, EventListenerHandle, Storage},
+};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+use web_sys::Element;
+
+/// The media query which matches the dark preference of the system.
+const PREFERS_DARK: &str = "(prefers-color-scheme: dark)";
+
+thread_local! {
+    /// The theme provided to the app, if any.
+    static PROVIDED: RefCell<Option<Provided>> = const { RefCell::new(None) };
+}
+
+/// A color scheme.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ColorScheme {
+    /// Dark text on a light background.
+    Light,
+    /// Light text on a dark background.
+    Dark,
+}
+
+impl ColorScheme {
+    /// The name of the scheme, as set on the `data-theme` attribute.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ColorScheme::Light => "light",
+            ColorScheme::Dark => "dark",
+        }
+    }
+
+    /// The other scheme, e.g. to toggle between them.
+    pub fn opposite(self) -> ColorScheme {
+        match self {
+            ColorScheme::Light => ColorScheme::Dark,
+            ColorScheme::Dark => ColorScheme::Light,
+        }
+    }
+
+    fn from_str(name: &str) -> Option<ColorScheme> {
+        match name {
+            "light" => Some(ColorScheme::Light),
+            "dark" => Some(ColorScheme::Dark),
+            _ => None,
+        }
+    }
+}
+
+/// The theme an app is provided with.
+#[derive(Clone, Debug, Default)]
+pub struct Theme {
+    storage_key: Option<String>,
+}
+
+impl Theme {
+    /// Creates a theme which follows the system, unless a scheme is picked
+    /// for as long as the app runs.
+    pub fn new() -> Theme {
+        Theme::default()
+    }
+
+    /// Remembers the scheme picked by the user under the key in the
+    /// `localStorage`, so that it is restored on the next visit.
+    pub fn persist(mut self, key: &str) -> Theme {
+        self.storage_key = Some(key.to_string());
+        self
+    }
+}
+
+/// A theme as provided to the running app.
+struct Provided {
+    theme: Theme,
+    preference: Option<ColorScheme>,
+    /// The preference of the system, which is only known in a browser.
+    system: Option<MediaQuery>,
+    _system_listener: Option<EventListenerHandle>,
+    /// The element the app is mounted on.
+    root: Option<Element>,
+    /// The subscribed components, which are rerendered when the scheme
+    /// changes.
+    subscribers: Vec<Box<dyn Subscriber>>,
+}
+
+/// The status of a subscribed component, which may be destroyed by now.
+trait Subscriber {
+    fn is_alive(&self) -> bool;
+
+    /// Marks the component for a rerender. Returns whether it is still alive.
+    fn force_update(&self) -> bool;
+}
+
+impl<T: 'static> Subscriber for Weak<RefCell<Status<T>>> {
+    fn is_alive(&self) -> bool {
+        self.strong_count() > 0
+    }
+
+    fn force_update(&self) -> bool {
+        match self.upgrade() {
+            Some(status) => {
+                status.borrow_mut().force_update();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Provided {
+    fn current(&self) -> ColorScheme {
+        self.preference.unwrap_or_else(|| match self.system {
+            Some(ref system) if system.matches() => ColorScheme::Dark,
+            _ => ColorScheme::Light,
+        })
+    }
+
+    fn storage(&self) -> Option<(Storage, &str)> {
+        // There is no storage outside of a browser.
+        self.root.as_ref()?;
+        let key = self.theme.storage_key.as_ref()?;
+        Some((Storage::local().ok()?, key))
+    }
+}
+
+/// Provides the theme to the app mounted on the element, which is only given
+/// in a browser. The theme provided earlier is replaced, along with its
+/// subscribers & its listener of the system.
+pub(crate) fn provide(theme: Theme, root: Option<Element>) {
+    let system = root.as_ref().map(|_| MediaQuery::new(PREFERS_DARK));
+    let system_listener = system
+        .as_ref()
+        .and_then(|system| system.on_change(|_| changed()).ok());
+    let mut provided = Provided {
+        theme,
+        preference: None,
+        system,
+        _system_listener: system_listener,
+        root,
+        subscribers: vec![],
+    };
+    provided.preference = provided.storage().and_then(|(storage, key)| {
+        let stored = storage.get_item(key).ok()??;
+        ColorScheme::from_str(&stored)
+    });
+    PROVIDED.with(|provided_| *provided_.borrow_mut() = Some(provided));
+    apply_to_root();
+}
+
+/// The color scheme of the app, which is `Light` unless it is provided with
+/// a theme.
+pub fn current() -> ColorScheme {
+    PROVIDED.with(|provided| {
+        provided
+            .borrow()
+            .as_ref()
+            .map_or(ColorScheme::Light, Provided::current)
+    })
+}
+
+/// The scheme picked by the user, if any.
+pub fn preference() -> Option<ColorScheme> {
+    PROVIDED.with(|provided| {
+        provided
+            .borrow()
+            .as_ref()
+            .and_then(|provided| provided.preference)
+    })
+}
+
+/// Picks the scheme of the app regardless of the system, or follows the
+/// system again with `None`. It is remembered if the theme is persisted.
+///
+/// # Panics
+/// Panics if the app is not provided with a theme.
+pub fn set_preference(preference: Option<ColorScheme>) {
+    PROVIDED.with(|provided| {
+        let mut provided = provided.borrow_mut();
+        let provided = provided
+            .as_mut()
+            .expect("The app is not provided with a theme, see `App::theme`");
+        provided.preference = preference;
+        if let Some((storage, key)) = provided.storage() {
+            // The scheme is still picked for as long as the app runs.
+            let _ = match preference {
+                Some(scheme) => storage.set_item(key, scheme.as_str()),
+                None => storage.remove_item(key),
+            };
+        }
+    });
+    changed();
+}
+
+/// Rerenders the component every time the color scheme changes, until it is
+/// destroyed.
+///
+/// # Panics
+/// Panics if the app is not provided with a theme.
+pub fn subscribe<COMP: Component>(component: &COMP) {
+    let status = Rc::downgrade(status_of(component));
+    PROVIDED.with(|provided| {
+        let mut provided = provided.borrow_mut();
+        let subscribers = &mut provided
+            .as_mut()
+            .expect("The app is not provided with a theme, see `App::theme`")
+            .subscribers;
+        // Prune the destroyed ones before growing, so that they do not pile
+        // up while the scheme stays the same.
+        if subscribers.len() == subscribers.capacity() {
+            subscribers.retain(|subscriber| subscriber.is_alive());
+        }
+        subscribers.push(Box::new(status));
+    });
+}
+
+/// Reflects a change of the scheme onto the root & the subscribed
+/// components.
+fn changed() {
+    apply_to_root();
+    let subscribers = PROVIDED.with(|provided| {
+        provided
+            .borrow_mut()
+            .as_mut()
+            .map(|provided| std::mem::take(&mut provided.subscribers))
+            .unwrap_or_default()
+    });
+    // They are invoked without the theme borrowed, as a rerender may read it.
+    let mut alive: Vec<_> = subscribers
+        .into_iter()
+        .filter(|subscriber| subscriber.force_update())
+        .collect();
+    PROVIDED.with(|provided| {
+        if let Some(ref mut provided) = *provided.borrow_mut() {
+            alive.append(&mut provided.subscribers);
+            provided.subscribers = alive;
+        }
+    });
+}
+
+fn apply_to_root() {
+    PROVIDED.with(|provided| {
+        if let Some(ref provided) = *provided.borrow() {
+            if let Some(ref root) = provided.root {
+                let scheme = provided.current();
+                let _ = root.set_attribute("data-theme", scheme.as_str());
+                let classes = elements::class_list(root);
+                let _ = match scheme {
+                    ColorScheme::Dark => classes.add("dark"),
+                    ColorScheme::Light => classes.remove("dark"),
+                };
+            }
+        }
+    });
+}
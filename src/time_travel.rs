@@ -0,0 +1,206 @@
+//! Recording the states of the components on each flush, to travel back to
+//! them while debugging.
+//!
+//! The states are only recorded with the `time-travel` feature, so that it
+//! costs nothing otherwise. A component takes part by serializing its state
+//! with [Render::dehydrate](../component/trait.Render.html#method.dehydrate)
+//! & restoring it with
+//! [Render::rehydrate](../component/trait.Render.html#method.rehydrate), like
+//! when it is rendered on the server. The last `capacity` snapshots are kept.
+//!
+//! # Example
+//! ```ignore
+//! // Undo the last flush.
+//! time_travel::restore(time_travel::len() - 2);
+//! app.flush()?;
+//! ```
+
+use crate::{component::Render, Shared};
+use serde_json::Value;
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    rc::{Rc, Weak},
+};
+
+/// The number of snapshots kept by default.
+const DEFAULT_CAPACITY: usize = 100;
+
+thread_local! {
+    static INSTANCES: RefCell<Vec<Box<dyn Recorded>>> = RefCell::new(vec![]);
+    static SNAPSHOTS: RefCell<VecDeque<Snapshot>> = const { RefCell::new(VecDeque::new()) };
+    static CAPACITY: Cell<usize> = const { Cell::new(DEFAULT_CAPACITY) };
+    /// Whether the next flush rerenders a restored snapshot, which is not
+    /// recorded again.
+    static RESTORING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The states of the components after a flush.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Snapshot {
+    /// The states of the components which dehydrate one, in the order they
+    /// were created.
+    pub states: Vec<ComponentState>,
+}
+
+/// The state of a component in a snapshot.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComponentState {
+    /// The id of the instance of the component.
+    pub id: u64,
+    /// The name of the component.
+    pub name: &'static str,
+    /// The state dehydrated by the component.
+    pub state: Value,
+}
+
+/// The number of snapshots recorded, of which the last one is the current
+/// state of the app.
+#[cfg(feature = "time-travel")]
+pub fn len() -> usize {
+    SNAPSHOTS.with(|snapshots| snapshots.borrow().len())
+}
+
+/// Gets the snapshot recorded at the index, from the oldest one kept.
+#[cfg(feature = "time-travel")]
+pub fn snapshot(index: usize) -> Option<Snapshot> {
+    SNAPSHOTS.with(|snapshots| snapshots.borrow().get(index).cloned())
+}
+
+/// Restores the components which are still alive to their states in the
+/// snapshot at the index, which are rerendered on the next flush. Returns
+/// whether there is such a snapshot.
+#[cfg(feature = "time-travel")]
+pub fn restore(index: usize) -> bool {
+    let snapshot = match snapshot(index) {
+        Some(snapshot) => snapshot,
+        None => return false,
+    };
+    // Cloned out, as a component may be created while being rehydrated.
+    let instances: Vec<_> = INSTANCES.with(|instances| {
+        instances
+            .borrow()
+            .iter()
+            .filter_map(|instance| instance.upgrade())
+            .collect()
+    });
+    for instance in instances {
+        let id = instance.id();
+        if let Some(recorded) = snapshot.states.iter().find(|recorded| recorded.id == id) {
+            instance.rehydrate(recorded.state.clone());
+            RESTORING.with(|restoring| restoring.set(true));
+        }
+    }
+    true
+}
+
+/// Keeps the last `capacity` snapshots, dropping the older ones.
+#[cfg(feature = "time-travel")]
+pub fn set_capacity(capacity: usize) {
+    CAPACITY.with(|c| c.set(capacity));
+    SNAPSHOTS.with(|snapshots| {
+        let mut snapshots = snapshots.borrow_mut();
+        while snapshots.len() > capacity {
+            snapshots.pop_front();
+        }
+    });
+}
+
+/// Drops all the snapshots recorded.
+#[cfg(feature = "time-travel")]
+pub fn clear() {
+    SNAPSHOTS.with(|snapshots| snapshots.borrow_mut().clear());
+}
+
+/// Tracks the instance of the component, to record its state.
+pub(crate) fn track<COMP: Render>(id: u64, component: &Shared<COMP>) {
+    if cfg!(feature = "time-travel") {
+        let instance = Tracked {
+            id,
+            component: Rc::downgrade(component),
+        };
+        INSTANCES.with(|instances| instances.borrow_mut().push(Box::new(instance)));
+    }
+}
+
+/// Records the states of the components alive after a flush, unless it
+/// rerendered a restored snapshot.
+pub(crate) fn record() {
+    if !cfg!(feature = "time-travel") || RESTORING.with(|restoring| restoring.replace(false)) {
+        return;
+    }
+    let instances: Vec<_> = INSTANCES.with(|instances| {
+        let mut instances = instances.borrow_mut();
+        instances.retain(|instance| instance.upgrade().is_some());
+        instances
+            .iter()
+            .filter_map(|instance| instance.upgrade())
+            .collect()
+    });
+    let states = instances
+        .iter()
+        .filter_map(|instance| {
+            Some(ComponentState {
+                id: instance.id(),
+                name: instance.name(),
+                state: instance.dehydrate()?,
+            })
+        })
+        .collect();
+    SNAPSHOTS.with(|snapshots| {
+        let mut snapshots = snapshots.borrow_mut();
+        snapshots.push_back(Snapshot { states });
+        while snapshots.len() > CAPACITY.with(Cell::get) {
+            snapshots.pop_front();
+        }
+    });
+}
+
+/// A component whose state is recorded.
+trait Recorded {
+    /// Gets hold of the component, if it is still alive.
+    fn upgrade(&self) -> Option<Box<dyn Alive>>;
+}
+
+/// A component which is alive.
+trait Alive {
+    fn id(&self) -> u64;
+
+    fn name(&self) -> &'static str;
+
+    fn dehydrate(&self) -> Option<Value>;
+
+    #[cfg(feature = "time-travel")]
+    fn rehydrate(&self, state: Value);
+}
+
+struct Tracked<COMP> {
+    id: u64,
+    component: Weak<RefCell<COMP>>,
+}
+
+impl<COMP: Render> Recorded for Tracked<COMP> {
+    fn upgrade(&self) -> Option<Box<dyn Alive>> {
+        let component = self.component.upgrade()?;
+        Some(Box::new((self.id, component)))
+    }
+}
+
+impl<COMP: Render> Alive for (u64, Shared<COMP>) {
+    fn id(&self) -> u64 {
+        self.0
+    }
+
+    fn name(&self) -> &'static str {
+        COMP::type_name()
+    }
+
+    fn dehydrate(&self) -> Option<Value> {
+        self.1.borrow().dehydrate()
+    }
+
+    #[cfg(feature = "time-travel")]
+    fn rehydrate(&self, state: Value) {
+        self.1.borrow().rehydrate(state)
+    }
+}
@@ -0,0 +1,292 @@
+//! CSS transitions on the elements entering and leaving the DOM.
+//!
+//! A [Transition](struct.Transition.html) of name `fade` applies the following
+//! classes, which are to be styled to transition between:
+//!
+//! 1. `fade-enter` & `fade-enter-active` as soon as the element is inserted.
+//!    On the next frame, `fade-enter` is swapped with `fade-enter-to`. Both
+//!    `fade-enter-active` & `fade-enter-to` are removed once the transition
+//!    ends.
+//! 2. `fade-leave` & `fade-leave-active` as soon as the element is removed.
+//!    On the next frame, `fade-leave` is swapped with `fade-leave-to`. The
+//!    element is removed from the DOM once the transition ends.
+//!
+//! ```css
+//! .fade-enter-active, .fade-leave-active { transition: opacity .3s; }
+//! .fade-enter, .fade-leave-to { opacity: 0; }
+//! ```
+//!
+//! A [TransitionGroup](struct.TransitionGroup.html) of a keyed list also
+//! applies `fade-move` on the items whose position changed, while they
+//! transition from their older position to the newer one.
+//!
+//! ```css
+//! .fade-move { transition: transform .3s; }
+//! /* Take the leaving items out of the flow, so the others move right away. */
+//! .fade-leave-active { position: absolute; }
+//! ```
+//!
+//! The transitions are only played in the browser. The nodes of any other
+//! backend are inserted, moved and removed right away.
+
+use crate::{
+    backend::{Node, WebBackend},
+    component::Render,
+    vdom::VNode,
+    web_api::flip::Flip,
+};
+use std::rc::Rc;
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{window, AddEventListenerOptions, Element, Event, HtmlElement};
+
+/// Plays the enter & leave transitions of the elements it wraps.
+///
+/// # Example
+/// ```ignore
+/// let fade = Transition::new("fade");
+/// html! {
+///     { fade.wrap(html!(<p>"Saved"</p>)) }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Transition {
+    name: Rc<str>,
+}
+
+impl Transition {
+    /// Creates a transition which prefixes its classes with the name.
+    pub fn new(name: &str) -> Transition {
+        Transition { name: name.into() }
+    }
+
+    /// Wraps the element, or each of the elements in a list, to be
+    /// transitioned when inserted or removed. Texts and components are left
+    /// as is.
+    pub fn wrap<RCTX: Render>(&self, vnode: VNode<RCTX>) -> VNode<RCTX> {
+        match vnode {
+            VNode::Element(el) => VNode::Element(el.with_transition(self)),
+            VNode::List(list) => VNode::List(list.map(|vnode| self.wrap(vnode))),
+            vnode => vnode,
+        }
+    }
+
+    /// The name of the class at the given stage of the transition.
+    fn class(&self, stage: &str) -> String {
+        format!("{}-{}", self.name, stage)
+    }
+
+    /// Plays the enter transition on the inserted node.
+    pub(crate) fn enter(&self, node: &Node) -> Result<(), JsValue> {
+        let el = match web_element(node) {
+            Some(el) => el,
+            None => return Ok(()),
+        };
+        let (enter, active, to) = (
+            self.class("enter"),
+            self.class("enter-active"),
+            self.class("enter-to"),
+        );
+        el.class_list().add_2(&enter, &active)?;
+
+        next_frame(move || {
+            let classes = el.class_list();
+            let _ = classes.remove_1(&enter);
+            let _ = classes.add_1(&to);
+            on_transition_end(&el, move |el| {
+                let _ = el.class_list().remove_2(&active, &to);
+            });
+        })
+    }
+
+    /// Plays the leave transition on a copy of the node being removed, so that
+    /// the VDOM can go on with the removal right away. The copy is removed
+    /// from the DOM once the transition ends.
+    pub(crate) fn leave(&self, parent: &Node, node: &Node) -> Result<(), JsValue> {
+        let (parent, el) = match (web_element(parent), web_element(node)) {
+            (Some(parent), Some(el)) => (parent, el),
+            _ => return Ok(()),
+        };
+        let ghost: Element = el.clone_node_with_deep(true)?.unchecked_into();
+        parent.insert_before(&ghost, Some(&el))?;
+
+        let (leave, active, to) = (
+            self.class("leave"),
+            self.class("leave-active"),
+            self.class("leave-to"),
+        );
+        ghost.class_list().add_2(&leave, &active)?;
+
+        next_frame(move || {
+            let classes = ghost.class_list();
+            let _ = classes.remove_1(&leave);
+            let _ = classes.add_1(&to);
+            on_transition_end(&ghost, Element::remove);
+        })
+    }
+}
+
+/// Plays the transitions on the items of a keyed list, as they are inserted,
+/// removed or moved.
+///
+/// # Example
+/// ```ignore
+/// let group = TransitionGroup::new("fade");
+/// html! {
+///     <ul>
+///         {
+///             group.wrap(html! {
+///                 <li key={1}>"First"</li>
+///                 <li key={2}>"Second"</li>
+///             })
+///         }
+///     </ul>
+/// }
+/// ```
+#[derive(Clone)]
+pub struct TransitionGroup {
+    transition: Transition,
+}
+
+impl TransitionGroup {
+    /// Creates a transition group which prefixes its classes with the name.
+    pub fn new(name: &str) -> TransitionGroup {
+        TransitionGroup {
+            transition: Transition::new(name),
+        }
+    }
+
+    /// Wraps the list so that its elements are transitioned when inserted,
+    /// removed or moved.
+    pub fn wrap<RCTX: Render>(&self, vnode: VNode<RCTX>) -> VNode<RCTX> {
+        match self.transition.wrap(vnode) {
+            VNode::List(list) => VNode::List(list.with_motion(Motion::Group(self.clone()))),
+            vnode => vnode,
+        }
+    }
+}
+
+/// Animates the moved items of a keyed list with
+/// [FLIP](../web_api/flip/index.html), transitioning with the given CSS
+/// transition, e.g. `transform 300ms ease-out`.
+///
+/// # Example
+/// ```ignore
+/// html! {
+///     <ul>
+///         {
+///             flip("transform 300ms", html! {
+///                 <li key={1}>"First"</li>
+///                 <li key={2}>"Second"</li>
+///             })
+///         }
+///     </ul>
+/// }
+/// ```
+pub fn flip<RCTX: Render>(transition: &str, vnode: VNode<RCTX>) -> VNode<RCTX> {
+    match vnode {
+        VNode::List(list) => VNode::List(list.with_motion(Motion::Flip(transition.into()))),
+        vnode => vnode,
+    }
+}
+
+/// How the moved items of a keyed list are animated.
+#[derive(Clone)]
+pub(crate) enum Motion {
+    /// With the `move` class of a transition group.
+    Group(TransitionGroup),
+    /// With a CSS transition set inline.
+    Flip(Rc<str>),
+}
+
+impl Motion {
+    /// Records the position of an item before it is patched.
+    pub(crate) fn first(&self, node: &Node) -> Option<Flip> {
+        web_element(node).map(|el| Flip::first(&el))
+    }
+
+    /// Moves the item from its recorded position to where it is now.
+    pub(crate) fn play(&self, flip: Flip) {
+        match self {
+            Motion::Group(group) => {
+                let class = group.transition.class("move");
+                if flip.play_with_class(&class) {
+                    on_transition_end(flip.element(), move |el| {
+                        let _ = el.class_list().remove_1(&class);
+                    });
+                }
+            }
+            Motion::Flip(transition) => {
+                if flip.play(transition) {
+                    on_transition_end(flip.element(), |el| {
+                        if let Some(el) = el.dyn_ref::<HtmlElement>() {
+                            let _ = el.style().remove_property("transition");
+                        }
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Gets the element of a node rendered onto the DOM of the browser.
+fn web_element(node: &Node) -> Option<Element> {
+    node.backend_node::<WebBackend>()
+        .and_then(|node| node.dyn_ref::<Element>())
+        .cloned()
+}
+
+/// Invokes the handler after the next frame is painted, so that the styles
+/// of the classes added till now are applied before it.
+fn next_frame(handler: impl FnOnce() + 'static) -> Result<(), JsValue> {
+    let window = window().unwrap();
+    let next = window.clone();
+    let inner = Closure::once_into_js(move || {
+        let _ = next.request_animation_frame(Closure::once_into_js(handler).unchecked_ref());
+    });
+    window.request_animation_frame(inner.unchecked_ref())?;
+    Ok(())
+}
+
+/// Invokes the handler once the transition on the element ends, or right away
+/// if the element has no transition.
+fn on_transition_end(el: &Element, handler: impl FnOnce(&Element) + 'static) {
+    if !has_transition(el) {
+        handler(el);
+        return;
+    }
+
+    let target = el.clone();
+    let listener = Closure::once_into_js(move |event: Event| {
+        // The transitions of the children bubble up too, so wait on.
+        let is_target = event
+            .target()
+            .is_some_and(|t| t.dyn_ref::<Element>() == Some(&target));
+        if is_target {
+            handler(&target);
+        } else {
+            on_transition_end(&target, handler);
+        }
+    });
+    let options = AddEventListenerOptions::new();
+    options.set_once(true);
+    let _ = el.add_event_listener_with_callback_and_add_event_listener_options(
+        "transitionend",
+        listener.unchecked_ref(),
+        &options,
+    );
+}
+
+/// Whether any of the transitions on the element lasts for a while.
+fn has_transition(el: &Element) -> bool {
+    let style = match window().unwrap().get_computed_style(el) {
+        Ok(Some(style)) => style,
+        _ => return false,
+    };
+    let durations = style
+        .get_property_value("transition-duration")
+        .unwrap_or_default();
+    durations
+        .split(',')
+        .filter_map(|duration| duration.trim().trim_end_matches('s').parse::<f64>().ok())
+        .any(|duration| duration > 0.0)
+}
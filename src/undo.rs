@@ -0,0 +1,163 @@
+//! A state which keeps its history to be undone & redone, for editor-style
+//! apps.
+//!
+//! An [UndoableState](struct.UndoableState.html) is kept as a state of the
+//! component. Each change made with `set` is recorded, so that it can be
+//! undone & redone within `set_state` or a `Reducer`, which then rerenders the
+//! component.
+//!
+//! # Example
+//! ```ignore
+//! #[component]
+//! struct Editor {
+//!     #[state]
+//!     text: UndoableState<String>,
+//! }
+//!
+//! impl Editor {
+//!     fn on_input(&self, event: Event) {
+//!         let value = input_value(&event);
+//!         self.set_state(|state| state.text.set(|text| *text = value.clone()));
+//!     }
+//!
+//!     fn on_undo(&self, _: Event) {
+//!         self.set_state(|state| {
+//!             state.text.undo();
+//!         });
+//!     }
+//! }
+//! ```
+
+use std::{ops::Deref, rc::Rc};
+
+/// The number of changes kept to be undone, by default.
+const DEFAULT_LIMIT: usize = 100;
+
+/// A value along with its earlier values to be undone to, and the values
+/// undone to be redone.
+#[derive(Clone)]
+pub struct UndoableState<T> {
+    present: T,
+    // The history is shared with the clones, as its values do not change.
+    past: Vec<Rc<T>>,
+    future: Vec<Rc<T>>,
+    limit: usize,
+}
+
+impl<T: Clone> UndoableState<T> {
+    /// Creates a state with the value and no history.
+    pub fn new(value: T) -> UndoableState<T> {
+        UndoableState {
+            present: value,
+            past: vec![],
+            future: vec![],
+            limit: DEFAULT_LIMIT,
+        }
+    }
+
+    /// Keeps only the last `limit` changes to be undone.
+    pub fn with_limit(mut self, limit: usize) -> UndoableState<T> {
+        self.limit = limit;
+        self.trim();
+        self
+    }
+
+    /// Gets the current value.
+    pub fn get(&self) -> &T {
+        &self.present
+    }
+
+    /// Changes the value, recording the change to be undone. The values
+    /// undone so far can no longer be redone.
+    pub fn set(&mut self, mutator: impl FnOnce(&mut T)) {
+        self.past.push(Rc::new(self.present.clone()));
+        self.future.clear();
+        mutator(&mut self.present);
+        self.trim();
+    }
+
+    /// Replaces the value, recording the change to be undone.
+    pub fn replace(&mut self, value: T) {
+        self.set(|present| *present = value);
+    }
+
+    /// Goes back to the value before the last change. Returns whether there
+    /// was a change to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.past.pop() {
+            Some(previous) => {
+                let present = std::mem::replace(&mut self.present, unwrap(previous));
+                self.future.push(Rc::new(present));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Goes forth to the value before the last undo. Returns whether there
+    /// was an undo to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.future.pop() {
+            Some(next) => {
+                let present = std::mem::replace(&mut self.present, unwrap(next));
+                self.past.push(Rc::new(present));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether there is a change to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.past.is_empty()
+    }
+
+    /// Whether there is an undo to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.future.is_empty()
+    }
+
+    /// Forgets the history, keeping the current value.
+    pub fn clear_history(&mut self) {
+        self.past.clear();
+        self.future.clear();
+    }
+
+    /// Drops the oldest changes beyond the limit.
+    fn trim(&mut self) {
+        if self.past.len() > self.limit {
+            let excess = self.past.len() - self.limit;
+            self.past.drain(..excess);
+        }
+    }
+}
+
+/// Takes the value out of the history, cloning it if it is still shared with
+/// a clone of the state.
+fn unwrap<T: Clone>(value: Rc<T>) -> T {
+    Rc::try_unwrap(value).unwrap_or_else(|value| (*value).clone())
+}
+
+impl<T: Clone> Deref for UndoableState<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.present
+    }
+}
+
+impl<T: Clone + Default> Default for UndoableState<T> {
+    fn default() -> UndoableState<T> {
+        UndoableState::new(T::default())
+    }
+}
+
+impl<T: PartialEq> PartialEq for UndoableState<T> {
+    // The sizes of the history are compared instead of the values in it, so
+    // that the component is rerendered once it can no longer undo or redo.
+    fn eq(&self, other: &UndoableState<T>) -> bool {
+        self.present == other.present
+            && self.past.len() == other.past.len()
+            && self.future.len() == other.future.len()
+    }
+}
@@ -1,9 +1,13 @@
 //! The Virtual DOM library which backs the `ruukh` frontend framework.
 
 use crate::{
+    backend::Node,
     component::Render,
     dom::DOMPatch,
+    html::{HtmlWriter, WriteHtml},
     vdom::{
+        dump::Dump,
+        metrics::Measure,
         vcomponent::VComponent,
         velement::VElement,
         vlist::VList,
@@ -12,15 +16,35 @@ use crate::{
     MessageSender,
     Shared
 };
-use std::borrow::Cow;
+use std::{borrow::Cow, fmt::{self, Display, Formatter}};
 use wasm_bindgen::prelude::JsValue;
-use web_sys::Node;
 
+pub use self::{
+    classes::Classes, keep_alive::KeepAlive, metrics::Metrics, node_ref::NodeRef, visit::Visitor,
+};
+
+pub mod modal;
+pub mod portal;
+pub mod profiler;
+pub mod string_template;
+pub mod suspense;
 pub mod vcomponent;
 pub mod velement;
 pub mod vlist;
+pub mod vraw;
 pub mod vtext;
+mod classes;
 mod conversions;
+mod dump;
+mod hoisted;
+mod keep_alive;
+mod lazy;
+mod metrics;
+mod nesting;
+mod node_ref;
+mod rendered;
+mod template;
+mod visit;
 
 /// A virtual node in a virtual DOM tree.
 pub enum VNode<RCTX> {
@@ -36,6 +60,132 @@ pub enum VNode<RCTX> {
     None
 }
 
+impl<RCTX: Render> VNode<RCTX> {
+    /// Measures the number of nodes, the depth, the number of listeners and
+    /// of components in the tree, e.g. to track the complexity of a render.
+    pub fn metrics(&self) -> Metrics {
+        let mut metrics = Metrics::default();
+        self.measure(1, &mut metrics);
+        metrics
+    }
+
+    /// Dumps the tree as indented text, one node per line, e.g. for the logs
+    /// & the bug reports. The components are named, along with what they
+    /// rendered indented under them, and the keyed items are prefixed with
+    /// their `#key`.
+    ///
+    /// # Example
+    /// ```text
+    /// <TodoList>
+    ///   ul class="todos"
+    ///     #1 <TodoItem>
+    ///       li
+    ///         "Write the docs"
+    /// ```
+    pub fn dump_tree(&self) -> String {
+        let mut out = String::new();
+        self.dump(None, 0, &mut out);
+        out
+    }
+
+    /// Builds the static markup once for the place it is written in, and
+    /// clones it on the later renders, where it is not patched at all.
+    ///
+    /// `html!` hoists the children of the elements made of the literals only
+    /// by itself.
+    ///
+    /// A markup with a component within is built on each render instead, as
+    /// it cannot be cloned.
+    ///
+    /// # Example
+    /// ```ignore
+    /// VNode::static_of(|| html! {
+    ///     <footer>{ footer_links() }</footer>
+    /// })
+    /// ```
+    pub fn static_of(build: impl FnOnce() -> VNode<RCTX> + 'static) -> VNode<RCTX> {
+        hoisted::hoist(build)
+    }
+
+    /// Whether the old vnode can be patched into `self` without being
+    /// replaced, i.e. it is of the same variant, tag, component or static
+    /// markup.
+    pub(crate) fn is_same_type(&self, old: &VNode<RCTX>) -> bool {
+        match (self, old) {
+            (VNode::Text(_), VNode::Text(_)) => true,
+            (VNode::Element(el), VNode::Element(old)) => el.tag() == old.tag(),
+            (VNode::List(li), VNode::List(old)) => match (li.hoisted_from(), old.hoisted_from()) {
+                (Some(key), Some(old_key)) => key == old_key,
+                _ => true,
+            },
+            (VNode::Component(comp), VNode::Component(old)) => {
+                comp.component_type() == old.component_type()
+            }
+            (VNode::None, VNode::None) => true,
+            _ => false,
+        }
+    }
+
+    /// Whether the vnode renders the same content as the old one, as told by
+    /// the vnodes alone up to the first difference. The components are taken
+    /// to be changed, as their content is only known once they are rendered.
+    pub(crate) fn is_same_content(&self, old: &VNode<RCTX>) -> bool {
+        match (self, old) {
+            (VNode::Text(txt), VNode::Text(old)) => {
+                txt.is_comment() == old.is_comment() && txt.content() == old.content()
+            }
+            (VNode::Element(el), VNode::Element(old)) => {
+                el.tag() == old.tag()
+                    && el.attributes().eq(old.attributes())
+                    && el.child().is_same_content(old.child())
+            }
+            (VNode::List(li), VNode::List(old)) => {
+                li.len() == old.len()
+                    && li
+                        .items()
+                        .zip(old.items())
+                        .all(|((key, item), (old_key, old))| {
+                            key == old_key && item.is_same_content(old)
+                        })
+            }
+            (VNode::None, VNode::None) => true,
+            _ => false,
+        }
+    }
+
+    /// The topmost nodes of the tree, including the ones rendered by the
+    /// components at its root.
+    pub(crate) fn root_nodes(&self) -> Vec<Node> {
+        match self {
+            VNode::Text(txt) => txt.node().into_iter().collect(),
+            VNode::Element(el) => el.node().into_iter().collect(),
+            VNode::List(li) => li.root_nodes(),
+            VNode::Component(comp) => comp.root_nodes(),
+            VNode::None => vec![],
+        }
+    }
+
+    /// Takes the nodes cloned from a template as the ones of the tree, where
+    /// `next` is the node of its first item, and is left with the one after
+    /// its last item.
+    pub(crate) fn adopt(&mut self, next: &mut Option<Node>) {
+        let mut take = || {
+            let node = next
+                .take()
+                .expect("The template is expected to be of the same markup");
+            *next = node.next_sibling();
+            node
+        };
+        match self {
+            VNode::Text(txt) => txt.adopt(take()),
+            VNode::Element(el) => el.adopt(take()),
+            VNode::List(li) => li.adopt(next),
+            VNode::Component(_) => unreachable!("A static subtree has no components"),
+            VNode::None => {}
+        }
+    }
+}
+
 impl<RCTX> VNode<RCTX> {
     /// Whether the VNode is of `None` variant. 
     pub fn is_none(&self) -> bool {
@@ -44,6 +194,48 @@ impl<RCTX> VNode<RCTX> {
             _ => false
         }
     }
+
+    /// The element, if the VNode is one.
+    pub fn as_element(&self) -> Option<&VElement<RCTX>> {
+        match self {
+            VNode::Element(el) => Some(el),
+            _ => None
+        }
+    }
+
+    /// The text or the comment, if the VNode is one.
+    pub fn as_text(&self) -> Option<&VText<RCTX>> {
+        match self {
+            VNode::Text(txt) => Some(txt),
+            _ => None
+        }
+    }
+
+    /// The list, if the VNode is one.
+    pub fn as_list(&self) -> Option<&VList<RCTX>> {
+        match self {
+            VNode::List(li) => Some(li),
+            _ => None
+        }
+    }
+}
+
+impl<RCTX: Render> VNode<RCTX> {
+    /// Clones the tree, so that it can be cached or rendered in multiple
+    /// places without being built again. The clone is created anew when
+    /// patched.
+    ///
+    /// Returns `None` if a component is within the tree, as its props &
+    /// events are moved into it and so cannot be cloned.
+    pub fn try_clone(&self) -> Option<Self> {
+        Some(match self {
+            VNode::Text(txt) => VNode::Text(txt.clone()),
+            VNode::Element(el) => VNode::Element(el.try_clone()?),
+            VNode::List(li) => VNode::List(li.try_clone()?),
+            VNode::Component(_) => return None,
+            VNode::None => VNode::None,
+        })
+    }
 }
 
 macro_rules! patch {
@@ -141,7 +333,7 @@ impl<RCTX: Render> DOMPatch for VNode<RCTX> {
         }
     }
     
-    fn node(&self) -> Option<&Node> {
+    fn node(&self) -> Option<Node> {
         match self {
             VNode::Text(txt) => txt.node(),
             VNode::Element(el) => el.node(),
@@ -152,6 +344,50 @@ impl<RCTX: Render> DOMPatch for VNode<RCTX> {
     }
 }
 
+impl<RCTX: Render> WriteHtml for VNode<RCTX> {
+    fn write_html(&self, w: &mut HtmlWriter<'_>) -> fmt::Result {
+        match self {
+            VNode::Text(txt) => txt.write_html(w),
+            VNode::Element(el) => el.write_html(w),
+            VNode::List(li) => li.write_html(w),
+            VNode::Component(comp) => comp.write_html(w),
+            VNode::None => Ok(())
+        }
+    }
+}
+
+impl<RCTX: Render> Measure for VNode<RCTX> {
+    fn measure(&self, depth: usize, metrics: &mut Metrics) {
+        match self {
+            VNode::Text(txt) => txt.measure(depth, metrics),
+            VNode::Element(el) => el.measure(depth, metrics),
+            VNode::List(li) => li.measure(depth, metrics),
+            VNode::Component(comp) => comp.measure(depth, metrics),
+            VNode::None => {}
+        }
+    }
+}
+
+impl<RCTX: Render> Dump for VNode<RCTX> {
+    fn dump(&self, key: Option<&Key>, depth: usize, out: &mut String) {
+        match self {
+            VNode::Text(txt) => txt.dump(key, depth, out),
+            VNode::Element(el) => el.dump(key, depth, out),
+            VNode::List(li) => li.dump(key, depth, out),
+            VNode::Component(comp) => comp.dump(key, depth, out),
+            VNode::None => {}
+        }
+    }
+}
+
+impl<RCTX: Render> Display for VNode<RCTX> {
+    /// Writes the VNode as escaped HTML, without any of the hydration markers.
+    /// The HTML is indented when formatted with `{:#}`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write_html(&mut HtmlWriter::display(f))
+    }
+}
+
 /// Keys to identify a VNode in VDOM.
 /// 
 /// Users don't need to explicitly use the `Key` type in html! macro. Any 
@@ -180,6 +416,18 @@ impl Key {
     }
 }
 
+impl Display for Key {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::I32(key) => write!(f, "{}", key),
+            Key::I64(key) => write!(f, "{}", key),
+            Key::U32(key) => write!(f, "{}", key),
+            Key::U64(key) => write!(f, "{}", key),
+            Key::String(key) => write!(f, "{}", key),
+        }
+    }
+}
+
 macro_rules! convert {
     ([$($f:ty),*] to I32) => {
         $(
@@ -0,0 +1,122 @@
+//! Building the class list of an element out of conditional parts.
+
+use crate::vdom::velement::AttributeValue;
+use std::fmt::{self, Display, Formatter};
+
+/// A list of classes, set as the `class` attribute of an element. It is
+/// usually built with the [classes!](../macro.classes.html) macro.
+///
+/// # Example
+/// ```ignore
+/// let classes = Classes::new()
+///     .with("btn")
+///     .with(self.size)
+///     .with(("active", self.is_active));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Classes(Vec<String>);
+
+/// A part of a class list, which may be empty.
+pub trait IntoClass {
+    /// Adds the class, if any, onto the list.
+    fn add_to(self, classes: &mut Classes);
+}
+
+impl Classes {
+    /// Creates an empty class list.
+    pub fn new() -> Classes {
+        Classes::default()
+    }
+
+    /// Adds the class onto the list.
+    pub fn with(mut self, class: impl IntoClass) -> Classes {
+        class.add_to(&mut self);
+        self
+    }
+
+    /// Whether the list has no classes.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn push(&mut self, class: &str) {
+        // A part may carry multiple classes, like "btn btn-primary".
+        for class in class.split_whitespace() {
+            if !self.0.iter().any(|c| c == class) {
+                self.0.push(class.to_string());
+            }
+        }
+    }
+}
+
+impl IntoClass for &str {
+    fn add_to(self, classes: &mut Classes) {
+        classes.push(self);
+    }
+}
+
+impl IntoClass for String {
+    fn add_to(self, classes: &mut Classes) {
+        classes.push(&self);
+    }
+}
+
+impl<T: IntoClass> IntoClass for Option<T> {
+    fn add_to(self, classes: &mut Classes) {
+        if let Some(class) = self {
+            class.add_to(classes);
+        }
+    }
+}
+
+impl<T: IntoClass> IntoClass for (T, bool) {
+    fn add_to(self, classes: &mut Classes) {
+        if self.1 {
+            self.0.add_to(classes);
+        }
+    }
+}
+
+impl IntoClass for Classes {
+    fn add_to(self, classes: &mut Classes) {
+        for class in self.0 {
+            classes.push(&class);
+        }
+    }
+}
+
+impl Display for Classes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.join(" "))
+    }
+}
+
+impl From<Classes> for AttributeValue {
+    fn from(classes: Classes) -> AttributeValue {
+        // Do not leave an empty class attribute behind.
+        if classes.is_empty() {
+            AttributeValue::None
+        } else {
+            AttributeValue::String(classes.to_string())
+        }
+    }
+}
+
+/// Builds a [Classes](vdom/struct.Classes.html) out of static strings,
+/// `Option`s of a class and `(class, bool)` pairs, where the class is only
+/// added when the bool is true.
+///
+/// # Example
+/// ```ignore
+/// html! {
+///     <button class={classes!["btn", self.size, ("active", self.is_active)]}>
+///         "Save"
+///     </button>
+/// }
+/// ```
+#[macro_export]
+macro_rules! classes {
+    ($($class:expr),* $(,)?) => {
+        $crate::vdom::Classes::new()$(.with($class))*
+    };
+}
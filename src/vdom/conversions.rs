@@ -48,11 +48,11 @@ macro_rules! impl_with_to_string {
 }
 
 impl_with_to_string!(
-    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, bool
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, bool, char
 );
 
 impl<RCTX> From<Vec<VNode<RCTX>>> for VNode<RCTX> {
     fn from(value: Vec<VNode<RCTX>>) -> VNode<RCTX> {
-        VNode::from(VList::from(value))
+        VNode::from(VList::from(value).collected())
     }
 }
@@ -0,0 +1,22 @@
+//! Dumping a VDOM tree as indented text, e.g. for the logs & the bug reports.
+
+use crate::vdom::Key;
+
+/// Trait to dump the VDOM.
+pub(crate) trait Dump {
+    /// Writes `self` and its children at the depth, where the first line is
+    /// prefixed with its key, if it is a keyed item.
+    fn dump(&self, key: Option<&Key>, depth: usize, out: &mut String);
+}
+
+/// Writes a line at the depth, indented by two spaces per level.
+pub(crate) fn write_line(out: &mut String, key: Option<&Key>, depth: usize, line: &str) {
+    for _ in 0..depth {
+        out.push_str("  ");
+    }
+    if let Some(key) = key {
+        out.push_str(&format!("#{} ", key));
+    }
+    out.push_str(line);
+    out.push('\n');
+}
@@ -0,0 +1,50 @@
+//! Building the static markup once, instead of on each render.
+//!
+//! A static markup is built on its first render and kept for each place it is
+//! written in, from which its later renders are cloned. The clones of the same
+//! markup are not patched onto each other, as they cannot differ.
+
+use crate::{
+    component::Render,
+    vdom::{vlist::VList, VNode},
+};
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+};
+
+thread_local! {
+    static HOISTED: RefCell<HashMap<TypeId, Box<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// Clones the markup kept under the type of `build`, building it the first
+/// time. A markup which cannot be cloned is not kept, and so is built on each
+/// render.
+pub(crate) fn hoist<RCTX: Render, F>(build: F) -> VNode<RCTX>
+where
+    F: FnOnce() -> VNode<RCTX> + 'static,
+{
+    let key = TypeId::of::<F>();
+    let kept = HOISTED.with(|hoisted| {
+        hoisted
+            .borrow()
+            .get(&key)
+            .and_then(|markup| markup.downcast_ref::<VNode<RCTX>>())
+            .and_then(VNode::try_clone)
+    });
+    let markup = match kept {
+        Some(markup) => markup,
+        None => {
+            // Built without borrowing the kept ones, as it may hoist its own.
+            let markup = build();
+            let clone = match markup.try_clone() {
+                Some(clone) => clone,
+                None => return markup,
+            };
+            HOISTED.with(|hoisted| hoisted.borrow_mut().insert(key, Box::new(clone)));
+            markup
+        }
+    };
+    VNode::List(VList::hoisted(markup, key))
+}
@@ -0,0 +1,101 @@
+//! Caching of the components which are removed from the DOM.
+
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    rc::Rc,
+};
+
+/// A cache which keeps the removed components alive, so that they are
+/// re-attached with their state intact when rendered again instead of being
+/// created anew.
+///
+/// It is set on a component with the `keep_alive` attribute and is usually
+/// stored as a state of the parent component. A single component of each type
+/// is cached at a time. The cached components are destroyed when the cache is
+/// dropped.
+///
+/// A cached component is `deactivated` when removed and `activated` when
+/// re-attached, in place of being `destroyed` and `created`.
+///
+/// # Example
+/// ```ignore
+/// html! {
+///     if self.show_editor {
+///         <Editor keep_alive={&self.cache}></Editor>
+///     } else {
+///         <Preview keep_alive={&self.cache}></Preview>
+///     }
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct KeepAlive(Rc<RefCell<Vec<CachedOf>>>);
+
+/// A cached component, along with its type.
+type CachedOf = (TypeId, Box<dyn Cached>);
+
+/// A component removed from the DOM which is kept in a cache.
+pub(crate) trait Cached {
+    /// Destroys the component, as it is no longer to be re-attached.
+    fn destroy(&self);
+
+    /// Converts into `Any` to be downcast to the actual type.
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl KeepAlive {
+    /// Creates an empty cache.
+    pub fn new() -> KeepAlive {
+        KeepAlive::default()
+    }
+
+    /// Whether a component of the given type is cached.
+    pub fn contains<COMP: 'static>(&self) -> bool {
+        self.0
+            .borrow()
+            .iter()
+            .any(|(type_id, _)| *type_id == TypeId::of::<COMP>())
+    }
+
+    /// Caches the component, destroying the one of the same type cached
+    /// earlier.
+    pub(crate) fn insert<COMP: 'static>(&self, cached: Box<dyn Cached>) {
+        let older = self.take::<COMP>();
+        self.0.borrow_mut().push((TypeId::of::<COMP>(), cached));
+        if let Some(older) = older {
+            older.destroy();
+        }
+    }
+
+    /// Takes the cached component of the given type out of the cache.
+    pub(crate) fn take<COMP: 'static>(&self) -> Option<Box<dyn Cached>> {
+        let mut cached = self.0.borrow_mut();
+        let position = cached
+            .iter()
+            .position(|(type_id, _)| *type_id == TypeId::of::<COMP>())?;
+        Some(cached.remove(position).1)
+    }
+
+    /// Destroys all the cached components.
+    pub fn clear(&self) {
+        // Destroy them outside of the borrow, as they may use the cache too.
+        let cached = self.0.replace(vec![]);
+        for (_, cached) in cached {
+            cached.destroy();
+        }
+    }
+}
+
+impl PartialEq for KeepAlive {
+    fn eq(&self, other: &KeepAlive) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Drop for KeepAlive {
+    fn drop(&mut self) {
+        if Rc::strong_count(&self.0) == 1 {
+            self.clear();
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! Components whose markup is loaded on their first render.
+
+use crate::{
+    backend::Node,
+    component::Render,
+    dom::{Anchor, DOMPatch},
+    html::{HtmlWriter, WriteHtml},
+    streams::{self, Polled, SubscriptionGuard},
+    vdom::{
+        dump::{self, Dump},
+        keep_alive::KeepAlive,
+        metrics::{Measure, Metrics},
+        suspense::{self, Boundary},
+        vcomponent::{ComponentManager, Rerender},
+        Key, VNode,
+    },
+    MessageSender, Shared,
+};
+use std::{
+    any::{Any, TypeId},
+    cell::{Cell, RefCell},
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::{Rc, Weak},
+    task::{Context, Poll},
+};
+use wasm_bindgen::prelude::JsValue;
+
+/// Loads the markup once invoked.
+type Load<RCTX> = Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = VNode<RCTX>>>>>;
+
+/// The vnode of a lazy component, which takes over the loaded markup of the
+/// one it is patched onto.
+pub(crate) struct LazyManager<RCTX> {
+    /// The type of the loader, which tells the lazy components apart.
+    loader_type: TypeId,
+    /// The loader, until it is rendered.
+    load: Option<Load<RCTX>>,
+    instance: Option<Shared<LazyInstance<RCTX>>>,
+}
+
+/// A lazy component which is rendered onto the DOM.
+struct LazyInstance<RCTX> {
+    /// The markup once it is loaded, until it is rendered.
+    loaded: Option<VNode<RCTX>>,
+    rendered: VNode<RCTX>,
+    anchor: Anchor,
+    render_ctx: Shared<RCTX>,
+    /// The innermost suspense boundary it was created within.
+    boundary: Option<Boundary>,
+    /// Polls the loading markup, until it is loaded.
+    loading: Option<SubscriptionGuard>,
+}
+
+/// The markup being loaded, which suspends the boundary until it is.
+struct Loading<RCTX> {
+    future: RefCell<Pin<Box<dyn Future<Output = VNode<RCTX>>>>>,
+    /// The boundary, until the markup is loaded or dropped.
+    boundary: Cell<Option<Boundary>>,
+    instance: Weak<RefCell<LazyInstance<RCTX>>>,
+    rx_sender: MessageSender,
+}
+
+impl<RCTX: Render> LazyManager<RCTX> {
+    pub(crate) fn new<F, Fut>(load: F) -> LazyManager<RCTX>
+    where
+        F: FnOnce() -> Fut + 'static,
+        Fut: Future<Output = VNode<RCTX>> + 'static,
+    {
+        LazyManager {
+            loader_type: TypeId::of::<F>(),
+            load: Some(Box::new(move || Box::pin(load()))),
+            instance: None,
+        }
+    }
+}
+
+impl<RCTX: Render> Rerender for LazyInstance<RCTX> {
+    fn rerender(&mut self, rx_sender: MessageSender) -> Result<(), JsValue> {
+        let mut loaded = match self.loaded.take() {
+            Some(loaded) => loaded,
+            None => return Ok(()),
+        };
+        self.loading = None;
+        let _boundary = suspense::enter(self.boundary.clone());
+        let (parent, next) = (self.anchor.parent().clone(), self.anchor.end().clone());
+        loaded.patch(
+            Some(&mut self.rendered),
+            &parent,
+            Some(&next),
+            self.render_ctx.clone(),
+            rx_sender.clone(),
+        )?;
+        self.rendered = loaded;
+        self.rendered
+            .render_walk(&parent, Some(&next), self.render_ctx.clone(), rx_sender)
+    }
+}
+
+impl<RCTX> Loading<RCTX> {
+    fn resolve(&self) {
+        if let Some(boundary) = self.boundary.take() {
+            boundary.resolve();
+        }
+    }
+}
+
+impl<RCTX: Render> Polled for Loading<RCTX> {
+    fn request_flush(&self) {
+        self.rx_sender.do_react(None);
+    }
+
+    fn poll(&self, cx: &mut Context<'_>) -> bool {
+        let loaded = match self.future.borrow_mut().as_mut().poll(cx) {
+            Poll::Ready(loaded) => loaded,
+            Poll::Pending => return false,
+        };
+        if let Some(instance) = self.instance.upgrade() {
+            instance.borrow_mut().loaded = Some(loaded);
+            let instance: Shared<dyn Rerender> = instance;
+            // It is rendered before the boundary shows it.
+            self.rx_sender.do_react(Some(Rc::downgrade(&instance)));
+        }
+        self.resolve();
+        true
+    }
+}
+
+impl<RCTX> Drop for Loading<RCTX> {
+    fn drop(&mut self) {
+        self.resolve();
+    }
+}
+
+impl<RCTX: Render> ComponentManager for LazyManager<RCTX> {
+    type RenderContext = RCTX;
+
+    fn render_walk(
+        &mut self,
+        parent: &Node,
+        next: Option<&Node>,
+        render_ctx: Shared<Self::RenderContext>,
+        rx_sender: MessageSender,
+    ) -> Result<(), JsValue> {
+        if let Some(ref instance) = self.instance {
+            let mut instance = instance.borrow_mut();
+            instance.render_ctx = render_ctx.clone();
+            let _boundary = suspense::enter(instance.boundary.clone());
+            let next = instance.anchor.end().clone();
+            return instance
+                .rendered
+                .render_walk(parent, Some(&next), render_ctx, rx_sender);
+        }
+
+        let boundary = suspense::current();
+        if let Some(ref boundary) = boundary {
+            boundary.add();
+        }
+        let instance = Rc::new(RefCell::new(LazyInstance {
+            loaded: None,
+            rendered: VNode::None,
+            anchor: Anchor::new(parent, next)?,
+            render_ctx,
+            boundary: boundary.clone(),
+            loading: None,
+        }));
+        let load = self.load.take().unwrap();
+        let loading = streams::register(Rc::new(Loading {
+            future: RefCell::new(load()),
+            boundary: Cell::new(boundary),
+            instance: Rc::downgrade(&instance),
+            rx_sender,
+        }));
+        instance.borrow_mut().loading = Some(loading);
+        self.instance = Some(instance);
+        Ok(())
+    }
+
+    fn patch(
+        &mut self,
+        old: Option<&mut dyn ComponentManager<RenderContext = Self::RenderContext>>,
+        parent: &Node,
+        _: Option<&Node>,
+        _: Shared<Self::RenderContext>,
+    ) -> Result<(), JsValue> {
+        if let Some(old) = old {
+            let loader_type = self.loader_type;
+            let old_instance = old
+                .as_any_mut()
+                .downcast_mut::<LazyManager<RCTX>>()
+                .filter(|old| old.loader_type == loader_type)
+                .and_then(|old| old.instance.take());
+            match old_instance {
+                // The markup is loaded only once, on the first render.
+                Some(instance) => self.instance = Some(instance),
+                None => old.remove(parent)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn reorder(&self, parent: &Node, next: Option<&Node>) -> Result<(), JsValue> {
+        if let Some(ref instance) = self.instance {
+            let mut instance = instance.borrow_mut();
+            instance.rendered.reorder(parent, next)?;
+            instance.anchor.reorder(parent, next)?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, parent: &Node) -> Result<(), JsValue> {
+        if let Some(ref instance) = self.instance {
+            let mut instance = instance.borrow_mut();
+            // Stops loading, if it still is.
+            instance.loading = None;
+            instance.rendered.remove(parent)?;
+            instance.anchor.remove()?;
+        }
+        Ok(())
+    }
+
+    fn node(&self) -> Option<Node> {
+        self.instance.as_ref().and_then(|instance| {
+            let instance = instance.borrow();
+            instance
+                .rendered
+                .node()
+                .or_else(|| Some(instance.anchor.end().clone()))
+        })
+    }
+
+    fn write_html(&self, w: &mut HtmlWriter<'_>) -> fmt::Result {
+        if let Some(ref instance) = self.instance {
+            instance.borrow().rendered.write_html(w)?;
+        }
+        Ok(())
+    }
+
+    fn measure(&self, depth: usize, metrics: &mut Metrics) {
+        if let Some(ref instance) = self.instance {
+            instance.borrow().rendered.measure(depth, metrics);
+        }
+    }
+
+    fn dump(&self, key: Option<&Key>, depth: usize, out: &mut String) {
+        dump::write_line(out, key, depth, "<Lazy>");
+        if let Some(ref instance) = self.instance {
+            instance.borrow().rendered.dump(None, depth + 1, out);
+        }
+    }
+
+    fn root_nodes(&self) -> Vec<Node> {
+        self.instance
+            .as_ref()
+            .map(|instance| {
+                let instance = instance.borrow();
+                let mut nodes = instance.rendered.root_nodes();
+                nodes.push(instance.anchor.end().clone());
+                nodes
+            })
+            .unwrap_or_default()
+    }
+
+    fn component_type(&self) -> TypeId {
+        self.loader_type
+    }
+
+    fn type_name(&self) -> &'static str {
+        "Lazy"
+    }
+
+    fn instance_id(&self) -> Option<u64> {
+        None
+    }
+
+    fn set_keep_alive(&mut self, _: KeepAlive) {
+        // A lazy component is not cached, though the one it loads may be.
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -0,0 +1,36 @@
+//! Measuring the complexity of a VDOM tree.
+
+use std::cmp;
+
+/// The stats of a VDOM tree, as returned by
+/// [VNode::metrics](enum.VNode.html#method.metrics).
+///
+/// The components are measured along with what they rendered, if they were
+/// rendered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// The number of elements, texts and comments.
+    pub nodes: usize,
+    /// The depth of the most nested element, text or comment, where the
+    /// topmost ones are at the depth of 1. The lists & the components do not
+    /// add to the depth.
+    pub depth: usize,
+    /// The number of event listeners on the elements.
+    pub listeners: usize,
+    /// The number of components.
+    pub components: usize,
+}
+
+impl Metrics {
+    /// Counts a node at the depth.
+    pub(crate) fn add_node(&mut self, depth: usize) {
+        self.nodes += 1;
+        self.depth = cmp::max(self.depth, depth);
+    }
+}
+
+/// Trait to measure the VDOM.
+pub(crate) trait Measure {
+    /// Adds the stats of `self` and its children, given it is at the depth.
+    fn measure(&self, depth: usize, metrics: &mut Metrics);
+}
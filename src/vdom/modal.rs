@@ -0,0 +1,357 @@
+//! Modal dialogs, which are rendered over the page & keep the focus within
+//! them while open.
+//!
+//! A [Modal](struct.Modal.html) renders its content within a
+//! `<div role="dialog" aria-modal="true">` onto the `<body>` with a
+//! [Portal](../portal/struct.Portal.html). While it is rendered:
+//!
+//! * the page behind it is not scrolled, with
+//!   [lock_scroll](../../web_api/scroll/fn.lock_scroll.html),
+//! * the dialog is focused once opened, unless an element within it is
+//!   [autofocused](../velement/struct.VElement.html#method.autofocus),
+//! * `Tab` & `Shift+Tab` cycle through the focusable elements within it,
+//! * `Escape` invokes the `on_close` handler,
+//! * the element focused before it was opened is focused again once closed.
+//!
+//! It is open for as long as it is rendered.
+//!
+//! # Example
+//! ```ignore
+//! html! {
+//!     <button @click={Self::open}>"Delete"</button>
+//!     {
+//!         if self.is_open {
+//!             Modal::new(html! {
+//!                 <p>"Delete the file?"</p>
+//!                 <button @click={Self::delete}>"Delete"</button>
+//!                 <button @click={Self::close}>"Cancel"</button>
+//!             })
+//!             .label("Delete the file")
+//!             .on_close(Self::close)
+//!             .into()
+//!         } else {
+//!             Markup::None
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::{
+    backend::{Node, WebBackend},
+    component::Render,
+    dom::DOMPatch,
+    html::{HtmlWriter, WriteHtml},
+    vdom::{
+        dump::{self, Dump},
+        keep_alive::KeepAlive,
+        metrics::{Measure, Metrics},
+        portal::Portal,
+        vcomponent::{ComponentManager, VComponent},
+        velement::{Attribute, EventListener, VElement},
+        Key, VNode,
+    },
+    web_api::{
+        elements,
+        keyboard::KeyMatch,
+        scroll::{self, ScrollLock},
+    },
+    MessageSender, Shared,
+};
+use std::{
+    any::{Any, TypeId},
+    fmt,
+    rc::Rc,
+};
+use wasm_bindgen::{prelude::JsValue, JsCast};
+use web_sys::{window, Element, Event, HtmlElement, KeyboardEvent};
+
+/// The elements which take the focus on `Tab`.
+const FOCUSABLE: &str = "a[href], area[href], button:not([disabled]), \
+                         input:not([disabled]):not([type=hidden]), select:not([disabled]), \
+                         textarea:not([disabled]), iframe, [contenteditable], \
+                         [tabindex]:not([tabindex='-1'])";
+
+type OnCloseFn<RCTX> = Rc<dyn Fn(&RCTX)>;
+
+/// A dialog rendered over the page, which traps the focus within it.
+pub struct Modal<RCTX> {
+    content: VNode<RCTX>,
+    label: Option<String>,
+    on_close: Option<OnCloseFn<RCTX>>,
+}
+
+impl<RCTX: Render> Modal<RCTX> {
+    /// Creates a modal with the content.
+    pub fn new(content: VNode<RCTX>) -> Modal<RCTX> {
+        Modal {
+            content,
+            label: None,
+            on_close: None,
+        }
+    }
+
+    /// Sets the accessible name of the dialog, as read by the screen readers.
+    pub fn label(mut self, label: impl Into<String>) -> Modal<RCTX> {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Invokes the handler when `Escape` is pressed within the modal, which
+    /// is expected to stop rendering it.
+    pub fn on_close(mut self, handler: impl Fn(&RCTX) + 'static) -> Modal<RCTX> {
+        self.on_close = Some(Rc::new(handler));
+        self
+    }
+
+    /// The dialog onto the body, within which the content is rendered.
+    fn into_dialog(self) -> VNode<RCTX> {
+        let Modal {
+            content,
+            label,
+            on_close,
+        } = self;
+        let escape = KeyMatch::new("Escape");
+        let on_keydown = move |render_ctx: &RCTX, event: Event| {
+            if escape.matches(&event) {
+                if let Some(ref on_close) = on_close {
+                    event.prevent_default();
+                    on_close(render_ctx);
+                }
+            } else {
+                trap_focus(&event);
+            }
+        };
+        let dialog = VElement::new(
+            "div",
+            vec![
+                Attribute::new("role", "dialog"),
+                Attribute::new("aria-modal", "true"),
+                Attribute::new("aria-label", label),
+                Attribute::new("tabindex", "-1"),
+            ],
+            vec![EventListener::new("keydown", Box::new(on_keydown))],
+            Some(content),
+        )
+        // It is only focused when it is created, i.e. opened.
+        .autofocus();
+        VNode::from(Portal::new(VNode::from(dialog)))
+    }
+}
+
+impl<RCTX: Render> From<Modal<RCTX>> for VNode<RCTX> {
+    fn from(modal: Modal<RCTX>) -> VNode<RCTX> {
+        VNode::from(VComponent::managed(Box::new(ModalManager {
+            modal: Some(modal),
+            instance: None,
+        })))
+    }
+}
+
+/// Moves the focus onto the first focusable element within the dialog when
+/// tabbing past the last one, and vice versa.
+fn trap_focus(event: &Event) {
+    let is_tab = event
+        .dyn_ref::<KeyboardEvent>()
+        .is_some_and(|event| event.key() == "Tab");
+    let dialog = event
+        .current_target()
+        .and_then(|target| target.dyn_into::<Element>().ok());
+    let dialog = match dialog {
+        Some(ref dialog) if is_tab => dialog,
+        _ => return,
+    };
+    let focusable = elements::query_selector_all_within(dialog, FOCUSABLE).unwrap_or_default();
+    let (first, last) = match (focusable.first(), focusable.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => {
+            // Nothing to tab onto, so the focus stays on the dialog.
+            event.prevent_default();
+            return;
+        }
+    };
+    let active = window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.active_element());
+    let is_shift = event
+        .dyn_ref::<KeyboardEvent>()
+        .is_some_and(|event| event.shift_key());
+    let wrap_to = match active {
+        Some(ref active) if is_shift && (active == first || active == dialog) => last,
+        Some(ref active) if !is_shift && active == last => first,
+        _ => return,
+    };
+    if let Some(element) = wrap_to.dyn_ref::<HtmlElement>() {
+        event.prevent_default();
+        let _ = element.focus();
+    }
+}
+
+/// The vnode of a modal, which takes over the rendered modal of the one it is
+/// patched onto.
+struct ModalManager<RCTX> {
+    /// The modal to be rendered, until it is.
+    modal: Option<Modal<RCTX>>,
+    instance: Option<ModalInstance<RCTX>>,
+}
+
+/// A modal which is open, releasing the scroll & restoring the focus once
+/// dropped, i.e. closed.
+struct ModalInstance<RCTX> {
+    dialog: VNode<RCTX>,
+    _scroll_lock: Option<ScrollLock>,
+    /// The element focused before the modal was opened.
+    restore_focus: Option<HtmlElement>,
+}
+
+impl<RCTX> ModalInstance<RCTX> {
+    fn open(parent: &Node) -> ModalInstance<RCTX> {
+        // Neither the scroll nor the focus is of the document outside of a
+        // browser.
+        let is_web = parent.backend_node::<WebBackend>().is_some();
+        let restore_focus = if is_web {
+            window()
+                .and_then(|window| window.document())
+                .and_then(|document| document.active_element())
+                .and_then(|element| element.dyn_into::<HtmlElement>().ok())
+        } else {
+            None
+        };
+        ModalInstance {
+            dialog: VNode::None,
+            _scroll_lock: if is_web {
+                Some(scroll::lock_scroll())
+            } else {
+                None
+            },
+            restore_focus,
+        }
+    }
+}
+
+impl<RCTX> Drop for ModalInstance<RCTX> {
+    fn drop(&mut self) {
+        if let Some(ref element) = self.restore_focus {
+            let _ = element.focus();
+        }
+    }
+}
+
+impl<RCTX: Render> ComponentManager for ModalManager<RCTX> {
+    type RenderContext = RCTX;
+
+    fn render_walk(
+        &mut self,
+        parent: &Node,
+        next: Option<&Node>,
+        render_ctx: Shared<Self::RenderContext>,
+        rx_sender: MessageSender,
+    ) -> Result<(), JsValue> {
+        let instance = self
+            .instance
+            .get_or_insert_with(|| ModalInstance::open(parent));
+        if let Some(modal) = self.modal.take() {
+            let mut dialog = modal.into_dialog();
+            dialog.patch(
+                Some(&mut instance.dialog),
+                parent,
+                next,
+                render_ctx.clone(),
+                rx_sender.clone(),
+            )?;
+            instance.dialog = dialog;
+        }
+        instance
+            .dialog
+            .render_walk(parent, next, render_ctx, rx_sender)
+    }
+
+    fn patch(
+        &mut self,
+        old: Option<&mut dyn ComponentManager<RenderContext = Self::RenderContext>>,
+        parent: &Node,
+        _: Option<&Node>,
+        _: Shared<Self::RenderContext>,
+    ) -> Result<(), JsValue> {
+        if let Some(old) = old {
+            let old_instance = old
+                .as_any_mut()
+                .downcast_mut::<ModalManager<RCTX>>()
+                .and_then(|old| old.instance.take());
+            match old_instance {
+                // The dialog is patched once walked on.
+                Some(instance) => self.instance = Some(instance),
+                None => old.remove(parent)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn reorder(&self, parent: &Node, next: Option<&Node>) -> Result<(), JsValue> {
+        match self.instance {
+            Some(ref instance) => instance.dialog.reorder(parent, next),
+            None => Ok(()),
+        }
+    }
+
+    fn remove(&self, parent: &Node) -> Result<(), JsValue> {
+        match self.instance {
+            Some(ref instance) => instance.dialog.remove(parent),
+            None => Ok(()),
+        }
+    }
+
+    fn node(&self) -> Option<Node> {
+        self.instance
+            .as_ref()
+            .and_then(|instance| instance.dialog.node())
+    }
+
+    fn write_html(&self, w: &mut HtmlWriter<'_>) -> fmt::Result {
+        match self.instance {
+            Some(ref instance) => instance.dialog.write_html(w),
+            None => Ok(()),
+        }
+    }
+
+    fn measure(&self, depth: usize, metrics: &mut Metrics) {
+        if let Some(ref instance) = self.instance {
+            instance.dialog.measure(depth, metrics);
+        }
+    }
+
+    fn dump(&self, key: Option<&Key>, depth: usize, out: &mut String) {
+        dump::write_line(out, key, depth, "<Modal>");
+        if let Some(ref instance) = self.instance {
+            instance.dialog.dump(None, depth + 1, out);
+        } else if let Some(ref modal) = self.modal {
+            modal.content.dump(None, depth + 1, out);
+        }
+    }
+
+    fn root_nodes(&self) -> Vec<Node> {
+        self.instance
+            .as_ref()
+            .map(|instance| instance.dialog.root_nodes())
+            .unwrap_or_default()
+    }
+
+    fn component_type(&self) -> TypeId {
+        TypeId::of::<Modal<RCTX>>()
+    }
+
+    fn type_name(&self) -> &'static str {
+        "Modal"
+    }
+
+    fn instance_id(&self) -> Option<u64> {
+        None
+    }
+
+    fn set_keep_alive(&mut self, _: KeepAlive) {
+        // A modal is closed once removed, rather than cached.
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -0,0 +1,188 @@
+//! Warning of the invalid nesting of elements in the debug builds.
+//!
+//! The browser silently rewrites the DOM created with an invalid nesting, like
+//! a `<p>` within another one, so that it no longer matches the VDOM and the
+//! later patches go astray. It is only caught while the elements are created.
+
+use crate::errors;
+use std::{borrow::Cow, cell::RefCell};
+
+thread_local! {
+    /// The tags of the elements being patched, from the outermost.
+    static ANCESTORS: RefCell<Vec<Cow<'static, str>>> = const { RefCell::new(vec![]) };
+}
+
+/// The elements which close an open `<p>`.
+const CLOSES_P: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "div",
+    "dl",
+    "fieldset",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+];
+
+/// The elements which cannot be within a `<button>` or an `<a>`.
+const INTERACTIVE: &[&str] = &[
+    "a", "button", "details", "embed", "iframe", "input", "label", "select", "textarea",
+];
+
+/// Warns if the element being created cannot be within its ancestors, along
+/// with the path of the components it is created in.
+pub(crate) fn check(tag: &str) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    let invalid = ANCESTORS.with(|ancestors| {
+        let ancestors = ancestors.borrow();
+        invalid_nesting(&ancestors.iter().map(|a| a.as_ref()).collect::<Vec<_>>(), tag)
+    });
+    if let Some(reason) = invalid {
+        errors::warn(&format!(
+            "ruukh: invalid nesting of <{}>, as {} in {}",
+            tag,
+            reason,
+            errors::component_path()
+        ));
+    }
+}
+
+/// Tracks the element whose children are being patched until the returned
+/// guard is dropped, in the debug builds.
+// The tag is cloned in the debug builds only.
+#[allow(clippy::ptr_arg)]
+pub(crate) fn enter(tag: &Cow<'static, str>) -> Option<NestingGuard> {
+    if !cfg!(debug_assertions) {
+        return None;
+    }
+    ANCESTORS.with(|ancestors| ancestors.borrow_mut().push(tag.clone()));
+    Some(NestingGuard)
+}
+
+/// The tag of the element whose children are being patched, if any, in the
+/// debug builds.
+pub(crate) fn parent() -> Option<Cow<'static, str>> {
+    ANCESTORS.with(|ancestors| ancestors.borrow().last().cloned())
+}
+
+/// Stops tracking the element when dropped.
+pub(crate) struct NestingGuard;
+
+impl Drop for NestingGuard {
+    fn drop(&mut self) {
+        ANCESTORS.with(|ancestors| ancestors.borrow_mut().pop());
+    }
+}
+
+/// Why the element cannot be within its ancestors, if so.
+fn invalid_nesting(ancestors: &[&str], tag: &str) -> Option<String> {
+    let parent = ancestors.last().cloned();
+    let within = |tags: &[&str]| ancestors.iter().rev().find(|a| tags.contains(a)).cloned();
+
+    match (tag, parent) {
+        ("tr", Some(parent)) if !["table", "thead", "tbody", "tfoot"].contains(&parent) => {
+            return Some(format!("it is within a <{}> instead of a table", parent));
+        }
+        ("td", Some(parent)) | ("th", Some(parent)) if parent != "tr" => {
+            return Some(format!("it is within a <{}> instead of a <tr>", parent));
+        }
+        ("thead", Some(parent))
+        | ("tbody", Some(parent))
+        | ("tfoot", Some(parent))
+        | ("caption", Some(parent))
+        | ("colgroup", Some(parent))
+            if parent != "table" =>
+        {
+            return Some(format!("it is within a <{}> instead of a <table>", parent));
+        }
+        ("li", Some(parent)) if !["ul", "ol", "menu"].contains(&parent) => {
+            return Some(format!("it is within a <{}> instead of a list", parent));
+        }
+        _ => {}
+    }
+    match parent {
+        Some(parent @ "table")
+        | Some(parent @ "thead")
+        | Some(parent @ "tbody")
+        | Some(parent @ "tfoot")
+            if !["caption", "colgroup", "thead", "tbody", "tfoot", "tr"].contains(&tag) =>
+        {
+            return Some(format!("a <{}> only holds the rows", parent));
+        }
+        Some("tr") if !["td", "th"].contains(&tag) => {
+            return Some("a <tr> only holds the cells".to_string());
+        }
+        _ => {}
+    }
+
+    if CLOSES_P.contains(&tag) && within(&["p"]).is_some() {
+        return Some("it closes the <p> it is within".to_string());
+    }
+    if tag == "form" && within(&["form"]).is_some() {
+        return Some("it is within another <form>".to_string());
+    }
+    if INTERACTIVE.contains(&tag) {
+        if let Some(ancestor) = within(&["a", "button"]) {
+            return Some(format!("it is interactive within an <{}>", ancestor));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::invalid_nesting;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn should_allow_valid_nesting() {
+        assert_eq!(invalid_nesting(&["div", "p"], "span"), None);
+        assert_eq!(invalid_nesting(&["table", "tbody", "tr"], "td"), None);
+        assert_eq!(invalid_nesting(&["ul"], "li"), None);
+        assert_eq!(invalid_nesting(&["button"], "span"), None);
+        assert_eq!(invalid_nesting(&[], "tr"), None);
+    }
+
+    #[wasm_bindgen_test]
+    fn should_catch_block_within_p() {
+        assert!(invalid_nesting(&["p"], "p").is_some());
+        assert!(invalid_nesting(&["p", "span"], "div").is_some());
+    }
+
+    #[wasm_bindgen_test]
+    fn should_catch_tables_out_of_structure() {
+        assert!(invalid_nesting(&["div"], "tr").is_some());
+        assert!(invalid_nesting(&["table"], "td").is_some());
+        assert!(invalid_nesting(&["table", "tbody"], "div").is_some());
+        assert!(invalid_nesting(&["table", "tr"], "span").is_some());
+    }
+
+    #[wasm_bindgen_test]
+    fn should_catch_interactive_within_button() {
+        assert!(invalid_nesting(&["button"], "button").is_some());
+        assert!(invalid_nesting(&["button", "span"], "input").is_some());
+        assert!(invalid_nesting(&["a"], "a").is_some());
+        assert!(invalid_nesting(&["form", "div"], "form").is_some());
+    }
+}
@@ -0,0 +1,104 @@
+//! Reference to the DOM node of an element.
+
+use crate::{
+    backend::{Node, WebBackend},
+    web_api::{
+        measure::{self, Rect},
+        scroll::{self, ScrollBehavior, ScrollIntoViewOptions},
+    },
+};
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::JsCast;
+use web_sys::Element;
+
+/// A reference to the node of an element, which is set once the element is
+/// patched onto the DOM and unset when it is removed.
+///
+/// It is set on an element with the `ref` attribute and is usually stored as
+/// a state of the component, so that the node can be accessed after the
+/// component is `mounted`.
+///
+/// # Example
+/// ```ignore
+/// html! {
+///     <input ref={&self.input}>
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct NodeRef(Rc<RefCell<Option<Node>>>);
+
+impl NodeRef {
+    /// Creates a reference which is not set yet.
+    pub fn new() -> NodeRef {
+        NodeRef::default()
+    }
+
+    /// Gets the referenced node, if set.
+    pub fn get(&self) -> Option<Node> {
+        self.0.borrow().clone()
+    }
+
+    /// Gets the referenced DOM element, if set and rendered onto the DOM of
+    /// the browser.
+    pub fn element(&self) -> Option<Element> {
+        self.0.borrow().as_ref().and_then(|node| {
+            node.backend_node::<WebBackend>()
+                .and_then(|node| node.dyn_ref::<Element>())
+                .cloned()
+        })
+    }
+
+    /// The rectangle of the element relative to the viewport. See
+    /// [bounding_client_rect](../web_api/measure/fn.bounding_client_rect.html).
+    pub fn bounding_client_rect(&self) -> Option<Rect> {
+        self.element()
+            .map(|element| measure::bounding_client_rect(&element))
+    }
+
+    /// The layout width and height of the element. See
+    /// [offset_size](../web_api/measure/fn.offset_size.html).
+    pub fn offset_size(&self) -> Option<(i32, i32)> {
+        self.element().map(|element| measure::offset_size(&element))
+    }
+
+    /// How far the contents of the element are scrolled. See
+    /// [scroll_offset](../web_api/measure/fn.scroll_offset.html).
+    pub fn scroll_offset(&self) -> Option<(i32, i32)> {
+        self.element()
+            .map(|element| measure::scroll_offset(&element))
+    }
+
+    /// Scrolls the element into view, if set. See
+    /// [scroll_into_view](../web_api/scroll/fn.scroll_into_view.html).
+    pub fn scroll_into_view(&self, options: &ScrollIntoViewOptions) {
+        if let Some(element) = self.element() {
+            scroll::scroll_into_view(&element, options);
+        }
+    }
+
+    /// Scrolls the contents of the element to the given offset, if set.
+    pub fn scroll_to(&self, left: f64, top: f64, behavior: ScrollBehavior) {
+        if let Some(element) = self.element() {
+            scroll::scroll_to(&element, left, top, behavior);
+        }
+    }
+
+    /// Scrolls the contents of the element by the given amount, if set.
+    pub fn scroll_by(&self, left: f64, top: f64, behavior: ScrollBehavior) {
+        if let Some(element) = self.element() {
+            scroll::scroll_by(&element, left, top, behavior);
+        }
+    }
+
+    pub(crate) fn set(&self, node: Option<Node>) {
+        *self.0.borrow_mut() = node;
+    }
+}
+
+/// The references are equal only if they are clones of each other, so that a
+/// reference stored as a state is never dirty.
+impl PartialEq for NodeRef {
+    fn eq(&self, other: &NodeRef) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
@@ -0,0 +1,264 @@
+//! Portals, which render their content onto another part of the DOM than the
+//! one they are rendered within.
+//!
+//! The content of a [Portal](struct.Portal.html) is rendered within a
+//! container appended to its target, which is the `<body>` of the document by
+//! default. It is still a part of the markup it is rendered within, as far as
+//! the VDOM is concerned, so that its event handlers are invoked on the
+//! component which rendered it. It is what overlays, like the modals & the
+//! tooltips, which are to escape the `overflow` & `z-index` of their
+//! ancestors, are rendered with.
+//!
+//! Outside of a browser, a portal without a target renders its content in
+//! place. Nor is it rendered on the server, as the markup of the target is
+//! not a part of the app.
+//!
+//! # Example
+//! ```ignore
+//! html! {
+//!     <button>"Save"</button>
+//!     { Portal::new(html!(<div class="toast">"Saved"</div>)) }
+//! }
+//! ```
+
+use crate::{
+    backend::{Node, WebBackend},
+    component::Render,
+    dom::DOMPatch,
+    html::HtmlWriter,
+    vdom::{
+        dump::{self, Dump},
+        keep_alive::KeepAlive,
+        metrics::{Measure, Metrics},
+        vcomponent::{ComponentManager, VComponent},
+        Key, VNode,
+    },
+    MessageSender, Shared,
+};
+use std::{
+    any::{Any, TypeId},
+    fmt, mem,
+};
+use wasm_bindgen::prelude::JsValue;
+use web_sys::window;
+
+/// Renders its content within a container appended to the target.
+pub struct Portal<RCTX> {
+    content: VNode<RCTX>,
+    target: Option<Node>,
+}
+
+impl<RCTX: Render> Portal<RCTX> {
+    /// Creates a portal which renders the content onto the `<body>`.
+    pub fn new(content: VNode<RCTX>) -> Portal<RCTX> {
+        Portal {
+            content,
+            target: None,
+        }
+    }
+
+    /// Renders the content onto the given node instead, like an element
+    /// reserved for the overlays. It is only read on the first render.
+    pub fn target(mut self, target: impl Into<Node>) -> Portal<RCTX> {
+        self.target = Some(target.into());
+        self
+    }
+}
+
+impl<RCTX: Render> From<Portal<RCTX>> for VNode<RCTX> {
+    fn from(portal: Portal<RCTX>) -> VNode<RCTX> {
+        VNode::from(VComponent::managed(Box::new(PortalManager {
+            content: portal.content,
+            target: portal.target,
+            old: None,
+            place: None,
+            is_patched: false,
+        })))
+    }
+}
+
+/// Where the content of a portal is rendered.
+#[derive(Clone)]
+enum Place {
+    /// Within the markup the portal is rendered in.
+    InPlace,
+    /// Within the container appended to the target.
+    Container { target: Node, container: Node },
+}
+
+impl Place {
+    fn new(target: Option<Node>, parent: &Node) -> Result<Place, JsValue> {
+        let target = match target {
+            Some(target) => target,
+            None if parent.backend_node::<WebBackend>().is_some() => {
+                let body = window()
+                    .and_then(|window| window.document())
+                    .and_then(|document| document.body())
+                    .ok_or_else(|| JsValue::from_str("The document does not have a body"))?;
+                Node::new(WebBackend, body.into())
+            }
+            // There is no body to render the content onto.
+            None => return Ok(Place::InPlace),
+        };
+        let container = target.create_element("div")?;
+        target.insert_before(&container, None)?;
+        Ok(Place::Container { target, container })
+    }
+}
+
+/// The vnode of a portal, which takes over the container of the one it is
+/// patched onto.
+struct PortalManager<RCTX> {
+    content: VNode<RCTX>,
+    /// The target the portal was created with, until it is placed.
+    target: Option<Node>,
+    /// The content it is patched onto, until it is walked on.
+    old: Option<VNode<RCTX>>,
+    place: Option<Place>,
+    is_patched: bool,
+}
+
+impl<RCTX: Render> PortalManager<RCTX> {
+    /// The vnode whose nodes are in the DOM.
+    fn rendered(&self) -> &VNode<RCTX> {
+        self.old.as_ref().unwrap_or(&self.content)
+    }
+
+    /// The content, if it is rendered within the markup of the portal.
+    fn in_place(&self) -> Option<&VNode<RCTX>> {
+        match self.place {
+            Some(Place::InPlace) => Some(self.rendered()),
+            _ => None,
+        }
+    }
+}
+
+impl<RCTX: Render> ComponentManager for PortalManager<RCTX> {
+    type RenderContext = RCTX;
+
+    fn render_walk(
+        &mut self,
+        parent: &Node,
+        next: Option<&Node>,
+        render_ctx: Shared<Self::RenderContext>,
+        rx_sender: MessageSender,
+    ) -> Result<(), JsValue> {
+        if self.place.is_none() {
+            self.place = Some(Place::new(self.target.take(), parent)?);
+        }
+        let (parent, next) = match self.place {
+            Some(Place::Container { ref container, .. }) => (container.clone(), None),
+            _ => (parent.clone(), next.cloned()),
+        };
+        if !self.is_patched {
+            let mut old = self.old.take();
+            self.content.patch(
+                old.as_mut(),
+                &parent,
+                next.as_ref(),
+                render_ctx.clone(),
+                rx_sender.clone(),
+            )?;
+            self.is_patched = true;
+        }
+        self.content
+            .render_walk(&parent, next.as_ref(), render_ctx, rx_sender)
+    }
+
+    fn patch(
+        &mut self,
+        old: Option<&mut dyn ComponentManager<RenderContext = Self::RenderContext>>,
+        parent: &Node,
+        _: Option<&Node>,
+        _: Shared<Self::RenderContext>,
+    ) -> Result<(), JsValue> {
+        if let Some(old) = old {
+            let old_portal = old
+                .as_any_mut()
+                .downcast_mut::<PortalManager<RCTX>>()
+                .filter(|old| old.is_patched)
+                .map(|old| {
+                    old.is_patched = false;
+                    (
+                        old.place.take(),
+                        mem::replace(&mut old.content, VNode::None),
+                    )
+                });
+            match old_portal {
+                // The content is patched once walked on, as it requires the
+                // sender.
+                Some((place, old_content)) => {
+                    self.place = place;
+                    self.old = Some(old_content);
+                }
+                None => old.remove(parent)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn reorder(&self, parent: &Node, next: Option<&Node>) -> Result<(), JsValue> {
+        // The container stays where it is within the target.
+        match self.in_place() {
+            Some(content) => content.reorder(parent, next),
+            None => Ok(()),
+        }
+    }
+
+    fn remove(&self, parent: &Node) -> Result<(), JsValue> {
+        match self.place {
+            Some(Place::InPlace) => self.rendered().remove(parent),
+            Some(Place::Container {
+                ref target,
+                ref container,
+            }) => {
+                self.rendered().remove(container)?;
+                target.remove_child(container)
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn node(&self) -> Option<Node> {
+        self.in_place().and_then(VNode::node)
+    }
+
+    fn write_html(&self, _: &mut HtmlWriter<'_>) -> fmt::Result {
+        // The target is not a part of the markup of the app.
+        Ok(())
+    }
+
+    fn measure(&self, depth: usize, metrics: &mut Metrics) {
+        self.content.measure(depth, metrics)
+    }
+
+    fn dump(&self, key: Option<&Key>, depth: usize, out: &mut String) {
+        dump::write_line(out, key, depth, "<Portal>");
+        self.content.dump(None, depth + 1, out);
+    }
+
+    fn root_nodes(&self) -> Vec<Node> {
+        self.in_place().map(VNode::root_nodes).unwrap_or_default()
+    }
+
+    fn component_type(&self) -> TypeId {
+        TypeId::of::<Portal<RCTX>>()
+    }
+
+    fn type_name(&self) -> &'static str {
+        "Portal"
+    }
+
+    fn instance_id(&self) -> Option<u64> {
+        None
+    }
+
+    fn set_keep_alive(&mut self, _: KeepAlive) {
+        // A portal is not a component to be cached, though the ones within it
+        // may be.
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
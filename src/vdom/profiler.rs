@@ -0,0 +1,426 @@
+//! Profilers, which measure how long the subtrees within them take to be
+//! rendered & patched on each flush.
+//!
+//! The time of a flush is measured once for a profiler, however many of the
+//! components within it are rendered, and includes the time of the profilers
+//! within it. The components created within a profiler are measured by it
+//! even when they are rerendered on their own.
+//!
+//! # Example
+//! ```ignore
+//! html! {
+//!     {
+//!         Profiler::new("sidebar", html!(<Sidebar></Sidebar>))
+//!             .on_render(|profile| {
+//!                 if profile.duration > 16.0 {
+//!                     report_slow_render(profile.id, profile.duration);
+//!                 }
+//!             })
+//!             .log_table()
+//!     }
+//! }
+//! ```
+
+use crate::{
+    backend::Node,
+    component::Render,
+    dom::DOMPatch,
+    html::{HtmlWriter, WriteHtml},
+    vdom::{
+        dump::{self, Dump},
+        keep_alive::KeepAlive,
+        metrics::{Measure, Metrics},
+        vcomponent::{ComponentManager, VComponent},
+        Key, VNode,
+    },
+    web_api::timers::now,
+    MessageSender, Shared,
+};
+use js_sys::{Array, Object, Reflect};
+use std::{
+    any::{Any, TypeId},
+    cell::{Cell, RefCell},
+    fmt, mem,
+    rc::Rc,
+};
+use wasm_bindgen::prelude::JsValue;
+use web_sys::console;
+
+thread_local! {
+    /// The profilers being rendered, from the outermost one.
+    static PROFILERS: RefCell<Vec<Profiling>> = const { RefCell::new(vec![]) };
+    /// The profilers measured in the current flush, to be reported once it
+    /// finishes.
+    static MEASURED: RefCell<Vec<Profiling>> = const { RefCell::new(vec![]) };
+}
+
+type OnRenderFn = Rc<dyn Fn(&Profile)>;
+
+/// Measures the time its content takes to be rendered & patched on each
+/// flush, and reports it.
+pub struct Profiler<RCTX> {
+    id: &'static str,
+    content: VNode<RCTX>,
+    on_render: Option<OnRenderFn>,
+    log_table: bool,
+}
+
+/// The time a profiler took in a flush.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+    /// The id the profiler was created with.
+    pub id: &'static str,
+    /// Whether its content was rendered for the first time.
+    pub phase: Phase,
+    /// The milliseconds its content took to be rendered & patched.
+    pub duration: f64,
+}
+
+/// Whether the content of a profiler was rendered for the first time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// It was rendered for the first time.
+    Mount,
+    /// It was rendered again.
+    Update,
+}
+
+impl<RCTX: Render> Profiler<RCTX> {
+    /// Creates a profiler around the content, which is told apart in the
+    /// reports by its id.
+    pub fn new(id: &'static str, content: VNode<RCTX>) -> Profiler<RCTX> {
+        Profiler {
+            id,
+            content,
+            on_render: None,
+            log_table: false,
+        }
+    }
+
+    /// Invokes the callback with the time taken, once each flush in which
+    /// the content was rendered finishes.
+    pub fn on_render(mut self, callback: impl Fn(&Profile) + 'static) -> Profiler<RCTX> {
+        self.on_render = Some(Rc::new(callback));
+        self
+    }
+
+    /// Logs the time taken with `console.table`, along with the other
+    /// profilers which log it, once each flush finishes.
+    pub fn log_table(mut self) -> Profiler<RCTX> {
+        self.log_table = true;
+        self
+    }
+}
+
+impl<RCTX: Render> From<Profiler<RCTX>> for VNode<RCTX> {
+    fn from(profiler: Profiler<RCTX>) -> VNode<RCTX> {
+        VNode::from(VComponent::managed(Box::new(ProfilerManager {
+            profiler: Some(profiler),
+            instance: None,
+        })))
+    }
+}
+
+/// A profiler as seen by the components created within it.
+#[derive(Clone)]
+pub(crate) struct Profiling(Rc<ProfilingInner>);
+
+struct ProfilingInner {
+    id: &'static str,
+    on_render: RefCell<Option<OnRenderFn>>,
+    log_table: Cell<bool>,
+    /// The profiler it is rendered within, which measures it as well.
+    parent: Option<Profiling>,
+    /// How many times it is entered, so that it is measured once.
+    depth: Cell<u32>,
+    started: Cell<f64>,
+    /// The time measured in the current flush.
+    duration: Cell<f64>,
+    is_mounted: Cell<bool>,
+}
+
+impl Profiling {
+    fn start(&self) {
+        let inner = &self.0;
+        if inner.depth.get() == 0 {
+            inner.started.set(now());
+        }
+        inner.depth.set(inner.depth.get() + 1);
+        if let Some(ref parent) = inner.parent {
+            parent.start();
+        }
+    }
+
+    fn stop(&self) {
+        let inner = &self.0;
+        inner.depth.set(inner.depth.get() - 1);
+        if inner.depth.get() == 0 {
+            inner
+                .duration
+                .set(inner.duration.get() + now() - inner.started.get());
+            MEASURED.with(|measured| {
+                let mut measured = measured.borrow_mut();
+                if !measured.iter().any(|other| Rc::ptr_eq(&other.0, &self.0)) {
+                    measured.push(self.clone());
+                }
+            });
+        }
+        if let Some(ref parent) = inner.parent {
+            parent.stop();
+        }
+    }
+
+    /// Takes the profile of the current flush.
+    fn take_profile(&self) -> Profile {
+        let inner = &self.0;
+        let phase = if inner.is_mounted.replace(true) {
+            Phase::Update
+        } else {
+            Phase::Mount
+        };
+        Profile {
+            id: inner.id,
+            phase,
+            duration: inner.duration.replace(0.0),
+        }
+    }
+}
+
+/// Makes the profiler the innermost one being rendered and measures it
+/// along with the ones it is within, until the returned guard is dropped.
+pub(crate) fn enter(profiler: Option<Profiling>) -> Option<ProfilerGuard> {
+    let profiler = profiler?;
+    profiler.start();
+    PROFILERS.with(|profilers| profilers.borrow_mut().push(profiler));
+    Some(ProfilerGuard)
+}
+
+/// The innermost profiler being rendered, if any.
+pub(crate) fn current() -> Option<Profiling> {
+    PROFILERS.with(|profilers| profilers.borrow().last().cloned())
+}
+
+/// Leaves the profiler when dropped, even when unwinding a panic.
+pub(crate) struct ProfilerGuard;
+
+impl Drop for ProfilerGuard {
+    fn drop(&mut self) {
+        if let Some(profiler) = PROFILERS.with(|profilers| profilers.borrow_mut().pop()) {
+            profiler.stop();
+        }
+    }
+}
+
+/// Reports the profilers measured in the flush which just finished.
+pub(crate) fn report() {
+    let measured = MEASURED.with(|measured| mem::take(&mut *measured.borrow_mut()));
+    if measured.is_empty() {
+        return;
+    }
+    let mut logged = vec![];
+    for profiler in measured {
+        let profile = profiler.take_profile();
+        // Cloned out, as the callback may rerender the profiler.
+        let on_render = profiler.0.on_render.borrow().clone();
+        if let Some(on_render) = on_render {
+            on_render(&profile);
+        }
+        if profiler.0.log_table.get() {
+            logged.push(profile);
+        }
+    }
+    if !logged.is_empty() {
+        let rows: Array = logged.iter().map(Profile::to_row).collect();
+        console::table_1(&rows);
+    }
+}
+
+impl Profile {
+    /// A row of the `console.table`.
+    fn to_row(&self) -> JsValue {
+        let row = Object::new();
+        let phase = match self.phase {
+            Phase::Mount => "mount",
+            Phase::Update => "update",
+        };
+        let _ = Reflect::set(&row, &"id".into(), &self.id.into());
+        let _ = Reflect::set(&row, &"phase".into(), &phase.into());
+        let _ = Reflect::set(&row, &"duration".into(), &self.duration.into());
+        row.into()
+    }
+}
+
+/// The vnode of a profiler.
+struct ProfilerManager<RCTX> {
+    /// The profiler, until it is walked on.
+    profiler: Option<Profiler<RCTX>>,
+    instance: Option<ProfilerInstance<RCTX>>,
+}
+
+/// A profiler which is rendered onto the DOM.
+struct ProfilerInstance<RCTX> {
+    profiling: Profiling,
+    content: VNode<RCTX>,
+}
+
+impl<RCTX: Render> ComponentManager for ProfilerManager<RCTX> {
+    type RenderContext = RCTX;
+
+    fn render_walk(
+        &mut self,
+        parent: &Node,
+        next: Option<&Node>,
+        render_ctx: Shared<Self::RenderContext>,
+        rx_sender: MessageSender,
+    ) -> Result<(), JsValue> {
+        let profiler = match self.profiler.take() {
+            Some(profiler) => profiler,
+            None => {
+                // Walked on again without being patched.
+                let instance = self.instance.as_mut().unwrap();
+                let _profiler = enter(Some(instance.profiling.clone()));
+                return instance
+                    .content
+                    .render_walk(parent, next, render_ctx, rx_sender);
+            }
+        };
+        let Profiler {
+            id,
+            mut content,
+            on_render,
+            log_table,
+        } = profiler;
+        let mut instance = match self.instance.take() {
+            Some(instance) => instance,
+            None => ProfilerInstance {
+                profiling: Profiling(Rc::new(ProfilingInner {
+                    id,
+                    on_render: RefCell::new(None),
+                    log_table: Cell::new(false),
+                    parent: current(),
+                    depth: Cell::new(0),
+                    started: Cell::new(0.0),
+                    duration: Cell::new(0.0),
+                    is_mounted: Cell::new(false),
+                })),
+                content: VNode::None,
+            },
+        };
+        *instance.profiling.0.on_render.borrow_mut() = on_render;
+        instance.profiling.0.log_table.set(log_table);
+
+        let _profiler = enter(Some(instance.profiling.clone()));
+        let patched = content.patch(
+            Some(&mut instance.content),
+            parent,
+            next,
+            render_ctx.clone(),
+            rx_sender.clone(),
+        );
+        // Keep whatever got patched, so that it is removed along with it.
+        instance.content = content;
+        let walked = patched.and_then(|()| {
+            instance
+                .content
+                .render_walk(parent, next, render_ctx, rx_sender)
+        });
+        self.instance = Some(instance);
+        walked
+    }
+
+    fn patch(
+        &mut self,
+        old: Option<&mut dyn ComponentManager<RenderContext = Self::RenderContext>>,
+        parent: &Node,
+        _: Option<&Node>,
+        _: Shared<Self::RenderContext>,
+    ) -> Result<(), JsValue> {
+        if let Some(old) = old {
+            let id = self.profiler.as_ref().map(|profiler| profiler.id);
+            let old_instance = old
+                .as_any_mut()
+                .downcast_mut::<ProfilerManager<RCTX>>()
+                .filter(|old| old.instance.as_ref().map(|i| i.profiling.0.id) == id)
+                .and_then(|old| old.instance.take());
+            match old_instance {
+                // The content is patched once walked on.
+                Some(instance) => self.instance = Some(instance),
+                None => old.remove(parent)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn reorder(&self, parent: &Node, next: Option<&Node>) -> Result<(), JsValue> {
+        if let Some(ref instance) = self.instance {
+            instance.content.reorder(parent, next)?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, parent: &Node) -> Result<(), JsValue> {
+        if let Some(ref instance) = self.instance {
+            instance.content.remove(parent)?;
+        }
+        Ok(())
+    }
+
+    fn node(&self) -> Option<Node> {
+        self.instance
+            .as_ref()
+            .and_then(|instance| instance.content.node())
+    }
+
+    fn write_html(&self, w: &mut HtmlWriter<'_>) -> fmt::Result {
+        match self.instance {
+            Some(ref instance) => instance.content.write_html(w),
+            None => self
+                .profiler
+                .as_ref()
+                .map_or(Ok(()), |profiler| profiler.content.write_html(w)),
+        }
+    }
+
+    fn measure(&self, depth: usize, metrics: &mut Metrics) {
+        if let Some(ref instance) = self.instance {
+            instance.content.measure(depth, metrics);
+        }
+    }
+
+    fn dump(&self, key: Option<&Key>, depth: usize, out: &mut String) {
+        let (id, content) = match (&self.instance, &self.profiler) {
+            (_, Some(profiler)) => (profiler.id, &profiler.content),
+            (Some(instance), None) => (instance.profiling.0.id, &instance.content),
+            (None, None) => return,
+        };
+        dump::write_line(out, key, depth, &format!("<Profiler {:?}>", id));
+        content.dump(None, depth + 1, out);
+    }
+
+    fn root_nodes(&self) -> Vec<Node> {
+        self.instance
+            .as_ref()
+            .map(|instance| instance.content.root_nodes())
+            .unwrap_or_default()
+    }
+
+    fn component_type(&self) -> TypeId {
+        TypeId::of::<ProfilerManager<RCTX>>()
+    }
+
+    fn type_name(&self) -> &'static str {
+        "Profiler"
+    }
+
+    fn instance_id(&self) -> Option<u64> {
+        None
+    }
+
+    fn set_keep_alive(&mut self, _: KeepAlive) {
+        // A profiler is not cached, though the components within it may be.
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
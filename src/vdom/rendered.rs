@@ -0,0 +1,180 @@
+//! The markup of a component rendered within another one, e.g. the markup
+//! returned by a render prop of its parent.
+
+use crate::{
+    backend::Node,
+    component::Render,
+    dom::DOMPatch,
+    html::{HtmlWriter, WriteHtml},
+    vdom::{
+        dump::Dump,
+        keep_alive::KeepAlive,
+        metrics::{Measure, Metrics},
+        vcomponent::ComponentManager,
+        Key, VNode,
+    },
+    MessageSender, Shared,
+};
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    fmt,
+    marker::PhantomData,
+    mem,
+    rc::{Rc, Weak},
+};
+use wasm_bindgen::prelude::JsValue;
+
+/// The vnode of the markup of `OUTER` rendered within `RCTX`, which is
+/// patched in the context of `OUTER`, so that its event handlers are invoked
+/// on `OUTER`.
+pub(crate) struct RenderedManager<RCTX, OUTER> {
+    markup: VNode<OUTER>,
+    /// Held weakly, so that the markup does not keep the component which
+    /// rendered it alive.
+    render_ctx: Weak<RefCell<OUTER>>,
+    /// The markup it is patched onto, until it is walked on.
+    old: Option<VNode<OUTER>>,
+    is_patched: bool,
+    _rctx: PhantomData<fn() -> RCTX>,
+}
+
+impl<RCTX, OUTER> RenderedManager<RCTX, OUTER> {
+    pub(crate) fn new(markup: VNode<OUTER>, render_ctx: &Shared<OUTER>) -> Self {
+        RenderedManager {
+            markup,
+            render_ctx: Rc::downgrade(render_ctx),
+            old: None,
+            is_patched: false,
+            _rctx: PhantomData,
+        }
+    }
+}
+
+impl<RCTX: Render, OUTER: Render> ComponentManager for RenderedManager<RCTX, OUTER> {
+    type RenderContext = RCTX;
+
+    fn render_walk(
+        &mut self,
+        parent: &Node,
+        next: Option<&Node>,
+        _: Shared<Self::RenderContext>,
+        rx_sender: MessageSender,
+    ) -> Result<(), JsValue> {
+        // Nothing is rendered once the component which rendered the markup
+        // is destroyed.
+        let render_ctx = match self.render_ctx.upgrade() {
+            Some(render_ctx) => render_ctx,
+            None => {
+                if let Some(old) = self.old.take() {
+                    old.remove(parent)?;
+                }
+                return Ok(());
+            }
+        };
+        if !self.is_patched {
+            let mut old = self.old.take();
+            self.markup.patch(
+                old.as_mut(),
+                parent,
+                next,
+                render_ctx.clone(),
+                rx_sender.clone(),
+            )?;
+            self.is_patched = true;
+        }
+        self.markup.render_walk(parent, next, render_ctx, rx_sender)
+    }
+
+    fn patch(
+        &mut self,
+        old: Option<&mut dyn ComponentManager<RenderContext = Self::RenderContext>>,
+        parent: &Node,
+        _: Option<&Node>,
+        _: Shared<Self::RenderContext>,
+    ) -> Result<(), JsValue> {
+        if let Some(old) = old {
+            let old_markup = old
+                .as_any_mut()
+                .downcast_mut::<RenderedManager<RCTX, OUTER>>()
+                .filter(|old| old.is_patched)
+                .map(|old| {
+                    old.is_patched = false;
+                    mem::replace(&mut old.markup, VNode::None)
+                });
+            match old_markup {
+                // The markup is patched once walked on, as it requires the
+                // sender.
+                Some(old_markup) => self.old = Some(old_markup),
+                None => old.remove(parent)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn reorder(&self, parent: &Node, next: Option<&Node>) -> Result<(), JsValue> {
+        match self.old {
+            Some(ref old) => old.reorder(parent, next),
+            None => self.markup.reorder(parent, next),
+        }
+    }
+
+    fn remove(&self, parent: &Node) -> Result<(), JsValue> {
+        if let Some(ref old) = self.old {
+            old.remove(parent)?;
+        }
+        if self.is_patched {
+            self.markup.remove(parent)?;
+        }
+        Ok(())
+    }
+
+    fn node(&self) -> Option<Node> {
+        match self.old {
+            Some(ref old) => old.node(),
+            None => self.markup.node(),
+        }
+    }
+
+    fn write_html(&self, w: &mut HtmlWriter<'_>) -> fmt::Result {
+        self.markup.write_html(w)
+    }
+
+    fn measure(&self, depth: usize, metrics: &mut Metrics) {
+        self.markup.measure(depth, metrics)
+    }
+
+    fn dump(&self, key: Option<&Key>, depth: usize, out: &mut String) {
+        // It is a part of what the component renders, as far as the tree is
+        // concerned.
+        self.markup.dump(key, depth, out)
+    }
+
+    fn root_nodes(&self) -> Vec<Node> {
+        match self.old {
+            Some(ref old) => old.root_nodes(),
+            None => self.markup.root_nodes(),
+        }
+    }
+
+    fn component_type(&self) -> TypeId {
+        TypeId::of::<RenderedManager<RCTX, OUTER>>()
+    }
+
+    fn type_name(&self) -> &'static str {
+        OUTER::type_name()
+    }
+
+    fn instance_id(&self) -> Option<u64> {
+        None
+    }
+
+    fn set_keep_alive(&mut self, _: KeepAlive) {
+        // The markup is not a component to be cached, though the ones within
+        // it may be.
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -0,0 +1,343 @@
+//! Rendering the markup which is only known at runtime, like the one read
+//! from a config or a CMS, with the values filled into its slots.
+//!
+//! A [StringTemplate](struct.StringTemplate.html) is parsed once from an
+//! HTML-ish string with the `{name}` slots in its texts & attribute values,
+//! and rendered as many times as required with the [Slots](struct.Slots.html)
+//! of the values. A text is always rendered as a text, never as markup, and
+//! a vnode, like a component, can be filled into a slot of a text as well.
+//! The `{{` & `}}` are the literal braces. An attribute holding a URL, like an
+//! `href`, is left out if a slot fills it with one of a scheme other than
+//! `http`, `https` & `mailto`, e.g. a `javascript:` one.
+//!
+//! The template itself is trusted to be rendered as is, so the markup which
+//! is user generated is better rendered with a
+//! [VRaw](../vraw/struct.VRaw.html).
+//!
+//! # Example
+//! ```ignore
+//! let banner = StringTemplate::cached(&config.banner);
+//! html! {
+//!     {
+//!         banner.render(
+//!             Slots::new()
+//!                 .text("name", &self.user.name)
+//!                 .text("count", self.unread)
+//!                 .node("action", html!(<Button label="Open"></Button>)),
+//!         )
+//!     }
+//! }
+//! ```
+
+use crate::{
+    component::Render,
+    errors, html,
+    vdom::{
+        velement::VElement,
+        vlist::VList,
+        vraw::{self, Parsed},
+        vtext::VText,
+        VNode,
+    },
+};
+use indexmap::IndexMap;
+use std::{cell::RefCell, collections::HashMap, fmt::Display, rc::Rc};
+
+/// The number of the templates cached, past which the least recently used
+/// one is evicted.
+const CACHE_CAPACITY: usize = 64;
+
+thread_local! {
+    /// The cached templates, from the least recently used.
+    static CACHE: RefCell<IndexMap<String, Rc<StringTemplate>>> = RefCell::new(IndexMap::new());
+}
+
+/// The markup parsed from a string, with the slots to be filled in.
+#[derive(Debug, PartialEq)]
+pub struct StringTemplate {
+    nodes: Vec<Node>,
+}
+
+/// The values to fill into the slots of a template, by their names.
+pub struct Slots<RCTX> {
+    values: HashMap<String, Value<RCTX>>,
+}
+
+enum Value<RCTX> {
+    Text(String),
+    /// A vnode, until it is rendered in its first slot.
+    Node(Option<VNode<RCTX>>),
+}
+
+#[derive(Debug, PartialEq)]
+enum Node {
+    Text(Vec<Part>),
+    Element {
+        tag: String,
+        attributes: Vec<(String, Vec<Part>)>,
+        children: Vec<Node>,
+    },
+}
+
+/// A part of a text or an attribute value.
+#[derive(Debug, PartialEq)]
+enum Part {
+    Literal(String),
+    Slot(String),
+}
+
+impl StringTemplate {
+    /// Parses the template.
+    pub fn parse(template: &str) -> StringTemplate {
+        StringTemplate {
+            nodes: vraw::parse(template).into_iter().map(node).collect(),
+        }
+    }
+
+    /// Parses the template once, and returns the same one for the same
+    /// string afterwards, for as long as it is among the recently used ones.
+    pub fn cached(template: &str) -> Rc<StringTemplate> {
+        CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            // It is moved to the end, as the most recently used.
+            let parsed = match cache.shift_remove(template) {
+                Some(parsed) => parsed,
+                None => Rc::new(StringTemplate::parse(template)),
+            };
+            if cache.len() >= CACHE_CAPACITY {
+                cache.shift_remove_index(0);
+            }
+            cache.insert(template.to_string(), parsed.clone());
+            parsed
+        })
+    }
+
+    /// The names of the slots, in the order they are written in.
+    pub fn slots(&self) -> Vec<&str> {
+        fn collect<'a>(nodes: &'a [Node], names: &mut Vec<&'a str>) {
+            for node in nodes {
+                let parts: Box<dyn Iterator<Item = &Part>> = match node {
+                    Node::Text(parts) => Box::new(parts.iter()),
+                    Node::Element { attributes, .. } => {
+                        Box::new(attributes.iter().flat_map(|(_, parts)| parts))
+                    }
+                };
+                for part in parts {
+                    if let Part::Slot(name) = part {
+                        if !names.contains(&name.as_str()) {
+                            names.push(name);
+                        }
+                    }
+                }
+                if let Node::Element { children, .. } = node {
+                    collect(children, names);
+                }
+            }
+        }
+        let mut names = vec![];
+        collect(&self.nodes, &mut names);
+        names
+    }
+
+    /// Renders the template with the values filled into its slots. The slots
+    /// without a value are left empty.
+    pub fn render<RCTX: Render>(&self, mut slots: Slots<RCTX>) -> VNode<RCTX> {
+        render_nodes(&self.nodes, &mut slots)
+    }
+}
+
+impl<RCTX> Slots<RCTX> {
+    /// Creates the slots without any value.
+    pub fn new() -> Slots<RCTX> {
+        Slots {
+            values: HashMap::new(),
+        }
+    }
+
+    /// Fills the text into the slots of the name.
+    pub fn text(mut self, name: &str, text: impl Display) -> Slots<RCTX> {
+        self.values
+            .insert(name.to_string(), Value::Text(text.to_string()));
+        self
+    }
+
+    /// Fills the vnode into the first slot of the name within a text. It is
+    /// left out of the attribute values.
+    pub fn node(mut self, name: &str, node: impl Into<VNode<RCTX>>) -> Slots<RCTX> {
+        self.values
+            .insert(name.to_string(), Value::Node(Some(node.into())));
+        self
+    }
+}
+
+impl<RCTX> Default for Slots<RCTX> {
+    fn default() -> Slots<RCTX> {
+        Slots::new()
+    }
+}
+
+fn node(parsed: Parsed) -> Node {
+    match parsed {
+        Parsed::Text(text) => Node::Text(parts(&text)),
+        Parsed::Element(element) => Node::Element {
+            tag: element.tag,
+            attributes: element
+                .attributes
+                .into_iter()
+                .filter(|(name, _)| html::is_attribute_name(name))
+                .map(|(name, value)| (name, parts(&value)))
+                .collect(),
+            children: element.children.into_iter().map(node).collect(),
+        },
+    }
+}
+
+/// Splits the text into the literals & the slots.
+fn parts(text: &str) -> Vec<Part> {
+    let mut parts = vec![];
+    let mut literal = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(['{', '}']) {
+        literal.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            literal.push_str(&rest[..1]);
+            rest = &rest[2..];
+            continue;
+        }
+        if rest.starts_with('}') {
+            literal.push('}');
+            rest = &rest[1..];
+            continue;
+        }
+        let slot = rest[1..]
+            .find('}')
+            .map(|end| &rest[1..=end])
+            .filter(|name| {
+                !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.')
+            });
+        match slot {
+            Some(name) => {
+                if !literal.is_empty() {
+                    parts.push(Part::Literal(std::mem::take(&mut literal)));
+                }
+                parts.push(Part::Slot(name.to_string()));
+                rest = &rest[name.len() + 2..];
+            }
+            None => {
+                literal.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+    literal.push_str(rest);
+    if !literal.is_empty() {
+        parts.push(Part::Literal(literal));
+    }
+    parts
+}
+
+fn render_nodes<RCTX: Render>(nodes: &[Node], slots: &mut Slots<RCTX>) -> VNode<RCTX> {
+    let mut rendered = vec![];
+    for node in nodes {
+        match node {
+            Node::Text(parts) => render_text(parts, slots, &mut rendered),
+            Node::Element {
+                tag,
+                attributes,
+                children,
+            } => {
+                let attributes: Vec<_> = attributes
+                    .iter()
+                    .filter_map(|(name, parts)| {
+                        Some((name.clone(), render_value(name, parts, slots)?))
+                    })
+                    .collect();
+                rendered.push(
+                    VElement::new(
+                        tag.clone(),
+                        vec![],
+                        vec![],
+                        Some(render_nodes(children, slots)),
+                    )
+                    .with_attributes(attributes)
+                    .into(),
+                );
+            }
+        }
+    }
+    match rendered.len() {
+        0 => VNode::None,
+        1 => rendered.remove(0),
+        _ => VList::from(rendered).into(),
+    }
+}
+
+/// Renders the parts of a text, where the adjacent literals & texts make a
+/// single text.
+fn render_text<RCTX: Render>(
+    parts: &[Part],
+    slots: &mut Slots<RCTX>,
+    rendered: &mut Vec<VNode<RCTX>>,
+) {
+    let mut text = String::new();
+    for part in parts {
+        match part {
+            Part::Literal(literal) => text.push_str(literal),
+            Part::Slot(name) => match slots.values.get_mut(name) {
+                Some(Value::Text(value)) => text.push_str(value),
+                Some(Value::Node(node)) => {
+                    if let Some(node) = node.take() {
+                        if !text.is_empty() {
+                            rendered.push(
+                                VText::text(std::mem::take(&mut text)).into(),
+                            );
+                        }
+                        rendered.push(node);
+                    }
+                }
+                None => missing(name),
+            },
+        }
+    }
+    if !text.is_empty() {
+        rendered.push(VText::text(text).into());
+    }
+}
+
+/// Renders the value of an attribute, unless a slot fills a URL of a scheme
+/// not allowed into it.
+fn render_value<RCTX>(name: &str, parts: &[Part], slots: &Slots<RCTX>) -> Option<String> {
+    let mut value = String::new();
+    let mut is_filled = false;
+    for part in parts {
+        match part {
+            Part::Literal(literal) => value.push_str(literal),
+            Part::Slot(slot) => match slots.values.get(slot) {
+                Some(Value::Text(text)) => {
+                    value.push_str(text);
+                    is_filled = true;
+                }
+                Some(Value::Node(_)) => {}
+                None => missing(slot),
+            },
+        }
+    }
+    let is_url = is_filled && vraw::URL_ATTRIBUTES.contains(&name);
+    match vraw::url_scheme(&value) {
+        Some(ref scheme) if is_url && !vraw::SCHEMES.contains(&scheme.as_str()) => None,
+        _ => Some(value),
+    }
+}
+
+fn missing(name: &str) {
+    if cfg!(debug_assertions) {
+        errors::warn(&format!(
+            "ruukh: the slot {{{}}} of the template is not filled",
+            name
+        ));
+    }
+}
@@ -0,0 +1,580 @@
+//! Suspense boundaries, which show a fallback while the components within
+//! them are loading.
+//!
+//! A component rendered within a [Suspense](struct.Suspense.html) suspends
+//! it on a future with
+//! [Component::suspend](../../component/trait.Component.html#method.suspend),
+//! e.g. one which fetches its data. The content of the boundary is held out
+//! of the DOM & the fallback is shown in its place until all of the futures
+//! it is suspended on resolve. A component suspends the innermost boundary it
+//! is created within.
+//!
+//! Both the content & the fallback stay rendered, the one which is not shown
+//! being held out of the DOM, so that the components within them keep their
+//! state when the boundary is suspended again.
+//!
+//! # Example
+//! ```ignore
+//! impl Lifecycle for Profile {
+//!     fn created(&self) {
+//!         let setter = self.state_setter();
+//!         self.suspend(async move {
+//!             let user = fetch_user().await;
+//!             setter.set_state(move |state| state.user = Some(user.clone()));
+//!         });
+//!     }
+//! }
+//!
+//! html! {
+//!     {
+//!         Suspense::new(html!(<Profile></Profile>))
+//!             .fallback(html!(<p>"Loading..."</p>))
+//!             .min_delay(200)
+//!     }
+//! }
+//! ```
+
+use crate::{
+    backend::Node,
+    component::{Render, Status},
+    dom::{Anchor, DOMPatch},
+    html::{HtmlWriter, WriteHtml},
+    streams::{self, Polled, SubscriptionGuard},
+    vdom::{
+        dump::{self, Dump},
+        keep_alive::KeepAlive,
+        metrics::{Measure, Metrics},
+        vcomponent::{ComponentManager, Rerender, VComponent},
+        Key, VNode,
+    },
+    web_api::{set_timeout, Timeout},
+    MessageSender, Shared,
+};
+use std::{
+    any::{Any, TypeId},
+    cell::{Cell, RefCell},
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::{Rc, Weak},
+    task::Context,
+};
+use wasm_bindgen::prelude::JsValue;
+
+thread_local! {
+    /// The boundaries being rendered, from the outermost one.
+    static BOUNDARIES: RefCell<Vec<Boundary>> = const { RefCell::new(vec![]) };
+}
+
+/// Shows a fallback in place of its content while any of the components
+/// within it are suspended.
+pub struct Suspense<RCTX> {
+    content: VNode<RCTX>,
+    fallback: VNode<RCTX>,
+    min_delay: i32,
+}
+
+impl<RCTX: Render> Suspense<RCTX> {
+    /// Creates a boundary around the content, which shows nothing while it
+    /// is suspended.
+    pub fn new(content: VNode<RCTX>) -> Suspense<RCTX> {
+        Suspense {
+            content,
+            fallback: VNode::None,
+            min_delay: 0,
+        }
+    }
+
+    /// Sets the markup shown while the content is suspended.
+    pub fn fallback(mut self, fallback: VNode<RCTX>) -> Suspense<RCTX> {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Shows the fallback only once the content has been suspended for the
+    /// given milliseconds, so that it does not flicker when the content loads
+    /// quickly. Nothing is shown till then.
+    pub fn min_delay(mut self, millis: i32) -> Suspense<RCTX> {
+        self.min_delay = millis;
+        self
+    }
+}
+
+impl<RCTX: Render> From<Suspense<RCTX>> for VNode<RCTX> {
+    fn from(suspense: Suspense<RCTX>) -> VNode<RCTX> {
+        VNode::from(VComponent::managed(Box::new(SuspenseManager {
+            suspense: Some(suspense),
+            instance: None,
+        })))
+    }
+}
+
+/// A boundary as seen by the components created within it, which count the
+/// futures they are suspended on.
+#[derive(Clone)]
+pub(crate) struct Boundary(Rc<BoundaryInner>);
+
+/// The rendered boundary, along with the sender it is rerendered with.
+type RenderedBoundary = (Weak<RefCell<dyn Rerender>>, MessageSender);
+
+struct BoundaryInner {
+    pending: Cell<usize>,
+    /// Whether the fallback is to be shown, once the minimum delay passed.
+    is_fallback_due: Cell<bool>,
+    /// The rendered boundary, which is rerendered once it is suspended or
+    /// resolved.
+    instance: RefCell<Option<RenderedBoundary>>,
+}
+
+impl Boundary {
+    fn new() -> Boundary {
+        Boundary(Rc::new(BoundaryInner {
+            pending: Cell::new(0),
+            is_fallback_due: Cell::new(false),
+            instance: RefCell::new(None),
+        }))
+    }
+
+    fn is_suspended(&self) -> bool {
+        self.0.pending.get() > 0
+    }
+
+    /// Suspends the boundary on another future.
+    pub(crate) fn add(&self) {
+        self.0.pending.set(self.0.pending.get() + 1);
+        if self.0.pending.get() == 1 {
+            self.wake();
+        }
+    }
+
+    /// Resolves one of the futures it is suspended on.
+    pub(crate) fn resolve(&self) {
+        self.0.pending.set(self.0.pending.get() - 1);
+        if self.0.pending.get() == 0 {
+            self.wake();
+        }
+    }
+
+    /// Requests the rendered boundary to show either its content or its
+    /// fallback, on the next flush.
+    fn wake(&self) {
+        if let Some((ref instance, ref rx_sender)) = *self.0.instance.borrow() {
+            rx_sender.do_react(Some(instance.clone()));
+        }
+    }
+}
+
+/// Makes the boundary the innermost one being rendered, until the returned
+/// guard is dropped.
+pub(crate) fn enter(boundary: Option<Boundary>) -> Option<BoundaryGuard> {
+    let boundary = boundary?;
+    BOUNDARIES.with(|boundaries| boundaries.borrow_mut().push(boundary));
+    Some(BoundaryGuard)
+}
+
+/// The innermost boundary being rendered, if any.
+pub(crate) fn current() -> Option<Boundary> {
+    BOUNDARIES.with(|boundaries| boundaries.borrow().last().cloned())
+}
+
+/// Leaves the boundary when dropped, even when unwinding a panic.
+pub(crate) struct BoundaryGuard;
+
+impl Drop for BoundaryGuard {
+    fn drop(&mut self) {
+        BOUNDARIES.with(|boundaries| boundaries.borrow_mut().pop());
+    }
+}
+
+/// Polls the future on the flushes of the app, suspending the boundary the
+/// component was created within until it resolves or the returned guard is
+/// dropped.
+pub(crate) fn suspend<T: 'static>(
+    status: &Shared<Status<T>>,
+    future: impl Future<Output = ()> + 'static,
+) -> SubscriptionGuard {
+    let boundary = status.borrow().boundary();
+    if let Some(ref boundary) = boundary {
+        boundary.add();
+    }
+    streams::register(Rc::new(Pending {
+        future: RefCell::new(Box::pin(future)),
+        boundary: Cell::new(boundary),
+        status: Rc::downgrade(status),
+    }))
+}
+
+/// A future which a boundary is suspended on.
+struct Pending<T> {
+    future: RefCell<Pin<Box<dyn Future<Output = ()>>>>,
+    /// The boundary, until the future is resolved or dropped.
+    boundary: Cell<Option<Boundary>>,
+    status: Weak<RefCell<Status<T>>>,
+}
+
+impl<T> Pending<T> {
+    fn resolve(&self) {
+        if let Some(boundary) = self.boundary.take() {
+            boundary.resolve();
+        }
+    }
+}
+
+impl<T> Polled for Pending<T> {
+    fn request_flush(&self) {
+        if let Some(status) = self.status.upgrade() {
+            // A flush is queued anyway while the state is being set.
+            if let Ok(status) = status.try_borrow() {
+                status.do_react();
+            }
+        }
+    }
+
+    fn poll(&self, cx: &mut Context<'_>) -> bool {
+        let is_ready = self.future.borrow_mut().as_mut().poll(cx).is_ready();
+        if is_ready {
+            self.resolve();
+        }
+        is_ready
+    }
+}
+
+impl<T> Drop for Pending<T> {
+    fn drop(&mut self) {
+        self.resolve();
+    }
+}
+
+/// The vnode of a boundary, which takes over the rendered boundary of the
+/// one it is patched onto.
+struct SuspenseManager<RCTX> {
+    /// The content & the fallback to be rendered, until they are.
+    suspense: Option<Suspense<RCTX>>,
+    instance: Option<Shared<BoundaryInstance<RCTX>>>,
+}
+
+/// A boundary which is rendered onto the DOM.
+struct BoundaryInstance<RCTX> {
+    boundary: Boundary,
+    content: VNode<RCTX>,
+    fallback: VNode<RCTX>,
+    min_delay: i32,
+    /// Holds the content or the fallback out of the DOM, while it is not
+    /// shown.
+    container: Node,
+    is_content_shown: bool,
+    is_fallback_shown: bool,
+    is_suspended: bool,
+    /// Shows the fallback once the minimum delay passes.
+    timeout: Option<Timeout>,
+    anchor: Anchor,
+    render_ctx: Shared<RCTX>,
+}
+
+impl<RCTX: Render> BoundaryInstance<RCTX> {
+    /// The node before which the shown nodes are inserted.
+    fn next(&self) -> Option<Node> {
+        Some(self.anchor.end().clone())
+    }
+
+    /// Where the content or the fallback is rendered, given whether it is
+    /// shown.
+    fn place(&self, is_shown: bool, next: &Option<Node>) -> (Node, Option<Node>) {
+        if is_shown {
+            (self.anchor.parent().clone(), next.clone())
+        } else {
+            (self.container.clone(), None)
+        }
+    }
+
+    /// Patches the newer content & fallback onto the rendered ones.
+    fn update(
+        &mut self,
+        suspense: Suspense<RCTX>,
+        rx_sender: MessageSender,
+    ) -> Result<(), JsValue> {
+        let Suspense {
+            mut content,
+            mut fallback,
+            min_delay,
+        } = suspense;
+        self.min_delay = min_delay;
+        let next = self.next();
+        {
+            let _boundary = enter(Some(self.boundary.clone()));
+            let (parent, next) = self.place(self.is_content_shown, &next);
+            content.patch(
+                Some(&mut self.content),
+                &parent,
+                next.as_ref(),
+                self.render_ctx.clone(),
+                rx_sender.clone(),
+            )?;
+            self.content = content;
+        }
+        let (parent, next) = self.place(self.is_fallback_shown, &next);
+        fallback.patch(
+            Some(&mut self.fallback),
+            &parent,
+            next.as_ref(),
+            self.render_ctx.clone(),
+            rx_sender.clone(),
+        )?;
+        self.fallback = fallback;
+        self.render_walk(rx_sender)
+    }
+
+    /// Walks on the content & the fallback, and then shows either of them.
+    fn render_walk(&mut self, rx_sender: MessageSender) -> Result<(), JsValue> {
+        let next = self.next();
+        {
+            // The components created within the content suspend this
+            // boundary, unlike the ones within the fallback.
+            let _boundary = enter(Some(self.boundary.clone()));
+            let (parent, next) = self.place(self.is_content_shown, &next);
+            self.content.render_walk(
+                &parent,
+                next.as_ref(),
+                self.render_ctx.clone(),
+                rx_sender.clone(),
+            )?;
+        }
+        let (parent, next) = self.place(self.is_fallback_shown, &next);
+        self.fallback
+            .render_walk(&parent, next.as_ref(), self.render_ctx.clone(), rx_sender)?;
+        self.settle()
+    }
+
+    /// Shows the fallback while the boundary is suspended, once due, and the
+    /// content otherwise.
+    fn settle(&mut self) -> Result<(), JsValue> {
+        let parent = self.anchor.parent().clone();
+        let next = self.next();
+        if self.boundary.is_suspended() {
+            if !self.is_suspended {
+                self.is_suspended = true;
+                self.delay_fallback();
+            }
+            if self.is_content_shown {
+                self.content.reorder(&self.container, None)?;
+                self.is_content_shown = false;
+            }
+            if !self.is_fallback_shown && self.boundary.0.is_fallback_due.get() {
+                self.fallback.reorder(&parent, next.as_ref())?;
+                self.is_fallback_shown = true;
+            }
+        } else {
+            self.is_suspended = false;
+            self.timeout = None;
+            if self.is_fallback_shown {
+                self.fallback.reorder(&self.container, None)?;
+                self.is_fallback_shown = false;
+            }
+            if !self.is_content_shown {
+                self.content.reorder(&parent, next.as_ref())?;
+                self.is_content_shown = true;
+            }
+        }
+        Ok(())
+    }
+
+    /// Makes the fallback due once the minimum delay passes.
+    fn delay_fallback(&mut self) {
+        let is_fallback_due = &self.boundary.0.is_fallback_due;
+        if self.min_delay <= 0 {
+            is_fallback_due.set(true);
+            return;
+        }
+        is_fallback_due.set(false);
+        let boundary = self.boundary.clone();
+        self.timeout = set_timeout(self.min_delay, move || {
+            boundary.0.is_fallback_due.set(true);
+            boundary.wake();
+        })
+        .ok();
+        // It is shown right away where there are no timers.
+        if self.timeout.is_none() {
+            is_fallback_due.set(true);
+        }
+    }
+
+    /// The vnode which is shown, if any.
+    fn shown(&self) -> Option<&VNode<RCTX>> {
+        if self.is_content_shown {
+            Some(&self.content)
+        } else if self.is_fallback_shown {
+            Some(&self.fallback)
+        } else {
+            None
+        }
+    }
+}
+
+impl<RCTX: Render> Rerender for BoundaryInstance<RCTX> {
+    fn rerender(&mut self, _: MessageSender) -> Result<(), JsValue> {
+        self.settle()
+    }
+}
+
+impl<RCTX: Render> ComponentManager for SuspenseManager<RCTX> {
+    type RenderContext = RCTX;
+
+    fn render_walk(
+        &mut self,
+        parent: &Node,
+        next: Option<&Node>,
+        render_ctx: Shared<Self::RenderContext>,
+        rx_sender: MessageSender,
+    ) -> Result<(), JsValue> {
+        if let Some(ref instance) = self.instance {
+            let mut instance = instance.borrow_mut();
+            instance.render_ctx = render_ctx;
+            return match self.suspense.take() {
+                Some(suspense) => instance.update(suspense, rx_sender),
+                None => instance.render_walk(rx_sender),
+            };
+        }
+
+        let suspense = self.suspense.take().unwrap();
+        let boundary = Boundary::new();
+        let instance = Rc::new(RefCell::new(BoundaryInstance {
+            boundary: boundary.clone(),
+            content: VNode::None,
+            fallback: VNode::None,
+            min_delay: 0,
+            container: parent.create_element("div")?,
+            is_content_shown: false,
+            is_fallback_shown: false,
+            is_suspended: false,
+            timeout: None,
+            anchor: Anchor::new(parent, next)?,
+            render_ctx,
+        }));
+        instance.borrow_mut().update(suspense, rx_sender.clone())?;
+        let rerender: Shared<dyn Rerender> = instance.clone();
+        *boundary.0.instance.borrow_mut() = Some((Rc::downgrade(&rerender), rx_sender));
+        self.instance = Some(instance);
+        Ok(())
+    }
+
+    fn patch(
+        &mut self,
+        old: Option<&mut dyn ComponentManager<RenderContext = Self::RenderContext>>,
+        parent: &Node,
+        _: Option<&Node>,
+        _: Shared<Self::RenderContext>,
+    ) -> Result<(), JsValue> {
+        if let Some(old) = old {
+            let old_instance = old
+                .as_any_mut()
+                .downcast_mut::<SuspenseManager<RCTX>>()
+                .and_then(|old| old.instance.take());
+            match old_instance {
+                // The content & the fallback are patched once walked on.
+                Some(instance) => self.instance = Some(instance),
+                None => old.remove(parent)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn reorder(&self, parent: &Node, next: Option<&Node>) -> Result<(), JsValue> {
+        if let Some(ref instance) = self.instance {
+            let mut instance = instance.borrow_mut();
+            if let Some(shown) = instance.shown() {
+                shown.reorder(parent, next)?;
+            }
+            instance.anchor.reorder(parent, next)?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, parent: &Node) -> Result<(), JsValue> {
+        if let Some(ref instance) = self.instance {
+            let mut instance = instance.borrow_mut();
+            instance.timeout = None;
+            let content_parent = if instance.is_content_shown {
+                parent
+            } else {
+                &instance.container
+            };
+            instance.content.remove(content_parent)?;
+            let fallback_parent = if instance.is_fallback_shown {
+                parent
+            } else {
+                &instance.container
+            };
+            instance.fallback.remove(fallback_parent)?;
+            instance.anchor.remove()?;
+        }
+        Ok(())
+    }
+
+    fn node(&self) -> Option<Node> {
+        self.instance.as_ref().and_then(|instance| {
+            let instance = instance.borrow();
+            instance
+                .shown()
+                .and_then(VNode::node)
+                .or_else(|| Some(instance.anchor.end().clone()))
+        })
+    }
+
+    fn write_html(&self, w: &mut HtmlWriter<'_>) -> fmt::Result {
+        if let Some(ref instance) = self.instance {
+            if let Some(shown) = instance.borrow().shown() {
+                shown.write_html(w)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn measure(&self, depth: usize, metrics: &mut Metrics) {
+        if let Some(ref instance) = self.instance {
+            if let Some(shown) = instance.borrow().shown() {
+                shown.measure(depth, metrics);
+            }
+        }
+    }
+
+    fn dump(&self, key: Option<&Key>, depth: usize, out: &mut String) {
+        dump::write_line(out, key, depth, "<Suspense>");
+        if let Some(ref instance) = self.instance {
+            if let Some(shown) = instance.borrow().shown() {
+                shown.dump(None, depth + 1, out);
+            }
+        }
+    }
+
+    fn root_nodes(&self) -> Vec<Node> {
+        self.instance
+            .as_ref()
+            .map(|instance| {
+                let instance = instance.borrow();
+                let mut nodes = instance.shown().map(VNode::root_nodes).unwrap_or_default();
+                nodes.push(instance.anchor.end().clone());
+                nodes
+            })
+            .unwrap_or_default()
+    }
+
+    fn component_type(&self) -> TypeId {
+        TypeId::of::<Suspense<RCTX>>()
+    }
+
+    fn type_name(&self) -> &'static str {
+        "Suspense"
+    }
+
+    fn instance_id(&self) -> Option<u64> {
+        None
+    }
+
+    fn set_keep_alive(&mut self, _: KeepAlive) {
+        // A boundary is not a component to be cached.
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -0,0 +1,61 @@
+//! Cloning the static subtrees from a template, instead of creating each of
+//! their nodes.
+//!
+//! The template of a static subtree is a clone of its first render, which is
+//! kept for each backend that can clone its nodes. `html!` marks the subtrees
+//! made of the literals only, so that there are as many templates as such
+//! subtrees written in the app.
+
+use crate::{backend::Node, stats};
+use std::{
+    any::TypeId,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+};
+
+thread_local! {
+    static TEMPLATES: RefCell<HashMap<(TypeId, &'static str), Node>> =
+        RefCell::new(HashMap::new());
+    /// Whether a template is being built, so that the static subtrees within
+    /// it are not kept on their own.
+    static BUILDING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Clones the template of the static subtree for the backend of the parent,
+/// if there is one.
+pub(crate) fn instantiate(markup: &'static str, parent: &Node) -> Option<Node> {
+    if BUILDING.with(Cell::get) {
+        return None;
+    }
+    let template = TEMPLATES.with(|templates| {
+        templates
+            .borrow()
+            .get(&(parent.backend_type(), markup))
+            .cloned()
+    })?;
+    let node = template.clone_node()?;
+    stats::record(|stats| stats.templates_cloned += 1);
+    Some(node)
+}
+
+/// Creates the static subtree with `create` and keeps a clone of it as its
+/// template, unless it is within another one being built.
+pub(crate) fn build<T>(
+    markup: &'static str,
+    create: impl FnOnce() -> Result<Node, T>,
+) -> Result<Node, T> {
+    if BUILDING.with(|building| building.replace(true)) {
+        return create();
+    }
+    let node = create();
+    BUILDING.with(|building| building.set(false));
+    let node = node?;
+    if let Some(template) = node.clone_node() {
+        TEMPLATES.with(|templates| {
+            templates
+                .borrow_mut()
+                .insert((node.backend_type(), markup), template)
+        });
+    }
+    Ok(node)
+}
@@ -1,14 +1,33 @@
 //! Component representation in a VDOM.
 
 use crate::{
+    backend::Node,
     component::{FromEventProps, Render, Status},
-    dom::DOMPatch,
-    vdom::{Shared, VNode},
+    devtools,
+    dom::{Anchor, DOMPatch},
+    errors, hot, i18n,
+    html::{HtmlWriter, WriteHtml},
+    islands, prepare, rehydration, styles, time_travel,
+    vdom::{
+        dump::{self, Dump},
+        keep_alive::{Cached, KeepAlive},
+        lazy::LazyManager,
+        metrics::{Measure, Metrics},
+        profiler::{self, Profiling},
+        rendered::RenderedManager,
+        suspense, Key, Shared, VNode,
+    },
     MessageSender,
 };
-use std::{any::Any, cell::RefCell, rc::Rc};
+use std::{
+    any::{Any, TypeId},
+    cell::{Cell, RefCell},
+    fmt::{self, Display, Formatter},
+    future::Future,
+    mem,
+    rc::Rc,
+};
 use wasm_bindgen::prelude::JsValue;
-use web_sys::Node;
 
 /// The representation of a component in a Virtual DOM.
 pub struct VComponent<RCTX>(Box<dyn ComponentManager<RenderContext = RCTX>>);
@@ -24,16 +43,150 @@ impl<RCTX: Render> VComponent<RCTX> {
     {
         VComponent(Box::new(ComponentWrapper::<COMP, RCTX>::new(props, events)))
     }
+
+    /// Creates a component whose markup is loaded on its first render, e.g.
+    /// once the chunk of a route is fetched. Nothing is rendered until then
+    /// and the innermost [Suspense](../suspense/struct.Suspense.html) it is
+    /// rendered within shows its fallback meanwhile.
+    ///
+    /// The markup is loaded only once, so it is rendered as is on the later
+    /// renders of the parent.
+    ///
+    /// # Example
+    /// ```ignore
+    /// html! {
+    ///     {
+    ///         Suspense::new(html! {
+    ///             {
+    ///                 VComponent::lazy(|| async {
+    ///                     load_chunk("settings").await;
+    ///                     html!(<Settings></Settings>)
+    ///                 })
+    ///             }
+    ///         })
+    ///         .fallback(html!(<p>"Loading..."</p>))
+    ///     }
+    /// }
+    /// ```
+    pub fn lazy<F, Fut>(load: F) -> VComponent<RCTX>
+    where
+        F: FnOnce() -> Fut + 'static,
+        Fut: Future<Output = VNode<RCTX>> + 'static,
+    {
+        VComponent(Box::new(LazyManager::new(load)))
+    }
+
+    /// Renders the markup of another component within this one, in the
+    /// context of the other, so that its event handlers are invoked on it.
+    /// It is what a render prop, i.e. an event declared to return `Markup`,
+    /// returns.
+    ///
+    /// # Example
+    /// ```ignore
+    /// #[component]
+    /// #[derive(Lifecycle)]
+    /// #[events(
+    ///     fn render_item(&self, item: &Item) -> Markup;
+    /// )]
+    /// struct List {
+    ///     items: Vec<Item>,
+    /// }
+    ///
+    /// impl Render for List {
+    ///     fn render(&self) -> Markup<Self> {
+    ///         let items: Vec<_> = self.items.iter().map(|item| self.render_item(item)).collect();
+    ///         html!(<ul>{ items }</ul>)
+    ///     }
+    /// }
+    ///
+    /// // Within the render of the parent.
+    /// html! {
+    ///     <List items={items} @render-item={Self::render_item}></List>
+    /// }
+    /// ```
+    pub fn rendered_in<OUTER: Render>(
+        markup: VNode<OUTER>,
+        render_ctx: &Shared<OUTER>,
+    ) -> VComponent<RCTX> {
+        VComponent(Box::new(RenderedManager::<RCTX, OUTER>::new(
+            markup, render_ctx,
+        )))
+    }
+
+    /// Keeps the component alive in the cache when it is removed, to be
+    /// re-attached later.
+    pub fn keep_alive(mut self, cache: &KeepAlive) -> Self {
+        self.0.set_keep_alive(cache.clone());
+        self
+    }
+
+    /// Wraps a manager of its own, like a suspense boundary.
+    pub(crate) fn managed(manager: Box<dyn ComponentManager<RenderContext = RCTX>>) -> Self {
+        VComponent(manager)
+    }
+
+    /// The topmost nodes rendered by the component.
+    pub(crate) fn root_nodes(&self) -> Vec<Node> {
+        self.0.root_nodes()
+    }
+
+    /// The type of the component, as compared with `TypeId::of::<COMP>()`.
+    pub fn component_type(&self) -> TypeId {
+        self.0.component_type()
+    }
+
+    /// The name of the component, as given by `Component::type_name`.
+    pub fn type_name(&self) -> &'static str {
+        self.0.type_name()
+    }
+
+    /// The id of the instance of the component, once it is rendered. Each
+    /// instance created gets a larger id than the ones before it, which is
+    /// kept for as long as it is alive.
+    pub fn instance_id(&self) -> Option<u64> {
+        self.0.instance_id()
+    }
 }
 
 pub(crate) struct ComponentWrapper<COMP: Render, RCTX>
 where
     COMP::Events: FromEventProps<RCTX>,
 {
-    component: Option<Shared<COMP>>,
+    instance: Option<Shared<Instance<COMP>>>,
     props: Option<COMP::Props>,
     events: Option<<COMP::Events as FromEventProps<RCTX>>::From>,
-    cached_render: Option<VNode<COMP>>,
+    keep_alive: Option<KeepAlive>,
+    /// The index of the component, when rendered on the server or rehydrated.
+    index: Option<usize>,
+    /// Whether it was patched with the same props as the ones it has.
+    is_props_same: bool,
+}
+
+/// A component which is rendered onto the DOM along with where it is
+/// rendered, so that it can be rerendered on its own.
+struct Instance<COMP: Render> {
+    id: u64,
+    component: Shared<COMP>,
+    cached_render: VNode<COMP>,
+    anchor: Anchor,
+    /// The innermost profiler it was created within, which measures its
+    /// rerenders.
+    profiler: Option<Profiling>,
+}
+
+thread_local! {
+    static NEXT_INSTANCE_ID: Cell<u64> = const { Cell::new(1) };
+}
+
+/// Takes the id of an instance being created.
+fn next_instance_id() -> u64 {
+    NEXT_INSTANCE_ID.with(|next| next.replace(next.get() + 1))
+}
+
+/// Trait to rerender a dirty component without walking from the root.
+pub(crate) trait Rerender {
+    /// Rerenders the component if it is dirty, along with its descendants.
+    fn rerender(&mut self, rx_sender: MessageSender) -> Result<(), JsValue>;
 }
 
 impl<COMP: Render, RCTX: Render> ComponentWrapper<COMP, RCTX>
@@ -45,14 +198,161 @@ where
         events: <COMP::Events as FromEventProps<RCTX>>::From,
     ) -> ComponentWrapper<COMP, RCTX> {
         ComponentWrapper {
-            component: None,
+            instance: None,
             props: Some(props),
             events: Some(events),
-            cached_render: None,
+            keep_alive: None,
+            index: None,
+            is_props_same: false,
         }
     }
 }
 
+/// A component along with the detached node which holds its nodes, while it
+/// is kept alive.
+struct CachedInstance<COMP: Render> {
+    instance: Shared<Instance<COMP>>,
+    container: Node,
+}
+
+impl<COMP: Render> Cached for CachedInstance<COMP> {
+    fn destroy(&self) {
+        self.instance
+            .borrow()
+            .destroy(&self.container)
+            .expect("Could not destroy the cached component");
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+impl<COMP: Render> Instance<COMP> {
+    /// Whether the component is required to be rerendered.
+    fn is_dirty(&self) -> bool {
+        self.component
+            .borrow()
+            .status()
+            .map(|s| {
+                let s = s.borrow();
+                s.is_state_dirty() || s.is_props_dirty() || s.is_render_dirty()
+            })
+            .unwrap_or(false)
+    }
+
+    /// Walks on the component where it is anchored.
+    fn render_walk(&mut self, rx_sender: MessageSender) -> Result<(), JsValue> {
+        let _guard = errors::enter::<COMP>();
+        let (parent, end) = (self.anchor.parent().clone(), self.anchor.end().clone());
+        let (parent, next) = (&parent, Some(&end));
+        let comp = &self.component;
+        // The components created within it suspend the same boundary.
+        let _boundary = suspense::enter(comp.borrow().status().and_then(|s| s.borrow().boundary()));
+        let _profiler = profiler::enter(self.profiler.clone());
+
+        let state_changed = comp
+            .borrow()
+            .status()
+            .map(|s| s.borrow().is_state_dirty())
+            .unwrap_or(false);
+        let state_changed = if state_changed {
+            comp.borrow()
+                .status()
+                .unwrap()
+                .borrow_mut()
+                .set_state_dirty(false);
+            comp.borrow_mut().refresh_state()
+        } else {
+            false
+        };
+
+        let props_changed = comp
+            .borrow()
+            .status()
+            .map(|s| s.borrow().is_props_dirty())
+            .unwrap_or(false);
+        if props_changed {
+            comp.borrow()
+                .status()
+                .unwrap()
+                .borrow_mut()
+                .set_props_dirty(false);
+        }
+
+        let render_forced = comp
+            .borrow()
+            .status()
+            .map(|s| s.borrow().is_render_dirty())
+            .unwrap_or(false);
+        if render_forced {
+            comp.borrow()
+                .status()
+                .unwrap()
+                .borrow_mut()
+                .set_render_dirty(false);
+        }
+
+        if state_changed || props_changed || render_forced {
+            let mut rerender = {
+                let comp = comp.borrow();
+                let _translating = i18n::enter(comp.status());
+                styles::scope_roots(devtools::render(&*comp))
+            };
+            let mut cached_render = mem::replace(&mut self.cached_render, VNode::None);
+            let patched = rerender.patch(
+                Some(&mut cached_render),
+                parent,
+                next,
+                comp.clone(),
+                rx_sender.clone(),
+            );
+            // Keep whatever got patched, so that the component can recover.
+            self.cached_render = rerender;
+            patched.or_else(|error| comp.borrow().on_render_error(error))?;
+            devtools::highlight(&self.cached_render);
+        }
+
+        if state_changed {
+            let changed_watchers = comp
+                .borrow()
+                .status()
+                .map(|s| s.borrow_mut().changed_watchers())
+                .unwrap_or_default();
+            for callback in changed_watchers {
+                callback();
+            }
+        }
+
+        self.cached_render
+            .render_walk(parent, next, self.component.clone(), rx_sender)
+            .or_else(|error| self.component.borrow().on_render_error(error))
+    }
+
+    /// Removes the rendered component from the DOM and destroys it.
+    fn destroy(&self, parent: &Node) -> Result<(), JsValue> {
+        self.cached_render.remove(parent)?;
+        self.anchor.remove()?;
+        let comp = self.component.borrow();
+        comp.destroyed();
+        if let Some(status) = comp.status() {
+            let scope = status.borrow().scope().clone();
+            scope.clear();
+        }
+        Ok(())
+    }
+}
+
+impl<COMP: Render> Rerender for Instance<COMP> {
+    fn rerender(&mut self, rx_sender: MessageSender) -> Result<(), JsValue> {
+        // It may have already been rerendered along with its ancestor.
+        if !self.is_dirty() {
+            return Ok(());
+        }
+        self.render_walk(rx_sender)
+    }
+}
+
 impl<RCTX: Render> DOMPatch for VComponent<RCTX> {
     type RenderContext = RCTX;
     type Node = Node;
@@ -87,7 +387,7 @@ impl<RCTX: Render> DOMPatch for VComponent<RCTX> {
         self.0.remove(parent)
     }
 
-    fn node(&self) -> Option<&Node> {
+    fn node(&self) -> Option<Node> {
         self.0.node()
     }
 }
@@ -115,7 +415,23 @@ pub(crate) trait ComponentManager: 'static {
 
     fn remove(&self, parent: &Node) -> Result<(), JsValue>;
 
-    fn node(&self) -> Option<&Node>;
+    fn node(&self) -> Option<Node>;
+
+    fn write_html(&self, w: &mut HtmlWriter<'_>) -> fmt::Result;
+
+    fn measure(&self, depth: usize, metrics: &mut Metrics);
+
+    fn dump(&self, key: Option<&Key>, depth: usize, out: &mut String);
+
+    fn root_nodes(&self) -> Vec<Node>;
+
+    fn component_type(&self) -> TypeId;
+
+    fn type_name(&self) -> &'static str;
+
+    fn instance_id(&self) -> Option<u64>;
+
+    fn set_keep_alive(&mut self, cache: KeepAlive);
 
     fn as_any_mut(&mut self) -> &mut dyn Any;
 }
@@ -133,80 +449,81 @@ where
         render_ctx: Shared<Self::RenderContext>,
         rx_sender: MessageSender,
     ) -> Result<(), JsValue> {
-        if self.component.is_none() {
-            let props = self.props.take().unwrap();
-            let events = self.events.take().unwrap();
-            let instance = COMP::init(
-                props,
-                FromEventProps::from(events, render_ctx),
-                Status::new(COMP::State::default(), rx_sender.clone()),
-            );
-            instance.created();
-            let mut initial_render = instance.render();
-            let shared_instance = Rc::new(RefCell::new(instance));
-            initial_render.patch(
-                None,
-                parent,
-                next,
-                shared_instance.clone(),
-                rx_sender.clone(),
-            )?;
-            shared_instance.borrow().mounted();
-            self.component = Some(shared_instance);
-            self.cached_render = Some(initial_render);
-        } else {
-            let comp = self.component.as_ref().unwrap();
-
-            let state_changed = comp
-                .borrow()
-                .status()
-                .map(|s| s.borrow().is_state_dirty())
-                .unwrap_or(false);
-            let state_changed = if state_changed {
-                comp.borrow()
-                    .status()
-                    .unwrap()
-                    .borrow_mut()
-                    .set_state_dirty(false);
-                comp.borrow_mut().refresh_state()
-            } else {
-                false
-            };
-
-            let props_changed = comp
-                .borrow()
-                .status()
-                .map(|s| s.borrow().is_props_dirty())
-                .unwrap_or(false);
-            if props_changed {
-                comp.borrow()
-                    .status()
-                    .unwrap()
-                    .borrow_mut()
-                    .set_props_dirty(false);
+        if let Some(ref instance) = self.instance {
+            let mut instance = instance.borrow_mut();
+            if self.is_props_same && !instance.is_dirty() {
+                // Nothing it rendered has changed, except for the dirty
+                // descendants which are rerendered on their own.
+                return Ok(());
             }
+            return instance.render_walk(rx_sender);
+        }
+        let _guard = errors::enter::<COMP>();
+
+        let cached = self
+            .keep_alive
+            .as_ref()
+            .and_then(|cache| cache.take::<COMP>());
+        if let Some(cached) = cached {
+            let cached = cached
+                .into_any()
+                .downcast::<CachedInstance<COMP>>()
+                .expect("A cached component is of the type it is cached with");
+            self.instance = Some(cached.instance);
+            return self.activate(parent, next, render_ctx, rx_sender);
+        }
 
-            if state_changed || props_changed {
-                let mut rerender = comp.borrow().render();
-                let mut cached_render = self.cached_render.take();
-                rerender.patch(
-                    cached_render.as_mut(),
-                    parent,
-                    next,
-                    comp.clone(),
-                    rx_sender.clone(),
-                )?;
-                self.cached_render = Some(rerender);
+        let props = self.props.take().unwrap();
+        let events = self.events.take().unwrap();
+        let mut component = COMP::init(
+            props,
+            FromEventProps::from(events, render_ctx),
+            Status::new(COMP::State::default(), rx_sender.clone()),
+        );
+        self.index = rehydration::next_index();
+        if let Some(state) = self.index.and_then(rehydration::state_of::<COMP>) {
+            component.rehydrate(state);
+            component.refresh_state();
+            // It is to be rendered with the state anyway.
+            if let Some(status) = component.status() {
+                status.borrow_mut().set_state_dirty(false);
             }
         }
-        if let Some(ref mut cached) = self.cached_render {
-            cached.render_walk(
-                parent,
-                next,
-                self.component.as_ref().unwrap().clone(),
-                rx_sender,
-            )?;
+        component.created();
+        prepare::collect(&component);
+        let mut initial_render = {
+            let _translating = i18n::enter(component.status());
+            styles::scope_roots(devtools::render(&component))
+        };
+        let component = Rc::new(RefCell::new(component));
+        let id = next_instance_id();
+        hot::track(&component);
+        time_travel::track(id, &component);
+        initial_render
+            .patch(None, parent, next, component.clone(), rx_sender.clone())
+            .and_then(|()| {
+                initial_render.render_walk(parent, next, component.clone(), rx_sender.clone())
+            })
+            .or_else(|error| component.borrow().on_render_error(error))?;
+
+        let instance = Rc::new(RefCell::new(Instance {
+            id,
+            component: component.clone(),
+            cached_render: initial_render,
+            anchor: Anchor::new(parent, next)?,
+            profiler: profiler::current(),
+        }));
+        if let Some(status) = component.borrow().status() {
+            let instance: Shared<dyn Rerender> = instance.clone();
+            let mut status = status.borrow_mut();
+            status.set_instance(Rc::downgrade(&instance));
+            // Queue the state changes made before the component was rendered.
+            if status.is_state_dirty() {
+                status.do_react();
+            }
         }
+        self.instance = Some(instance);
+        component.borrow().mounted();
         Ok(())
     }
 
@@ -217,27 +534,32 @@ where
         _: Option<&Node>,
         render_ctx: Shared<Self::RenderContext>,
     ) -> Result<(), JsValue> {
+        let _guard = errors::enter::<COMP>();
         if let Some(old) = old {
-            let is_same = match old
+            let old_instance = old
                 .as_any_mut()
                 .downcast_mut::<ComponentWrapper<COMP, RCTX>>()
-            {
-                Some(old) => {
-                    let comp = old.component.take().unwrap();
+                // The old one is not rendered if its parent failed to be patched.
+                .and_then(|old| old.instance.take());
+            let is_same = match old_instance {
+                Some(instance) => {
+                    // Reuse the older instance by passing in the newer props.
                     let props = self.props.take().unwrap();
                     let events = self.events.take().unwrap();
 
-                    // Reuse the older component by passing in the newer props.
+                    let comp = instance.borrow().component.clone();
+                    self.is_props_same = comp.borrow().is_props_same(&props);
+                    if !self.is_props_same {
+                        comp.borrow().before_update(&props);
+                    }
+                    // The events are updated regardless.
                     let old_props = comp
                         .borrow_mut()
                         .update(props, FromEventProps::from(events, render_ctx));
                     if let Some(old_props) = old_props {
                         comp.borrow().updated(old_props);
                     }
-                    self.component = Some(comp);
-
-                    // Reuse the cached render too to do patches on.
-                    self.cached_render = old.cached_render.take();
+                    self.instance = Some(instance);
 
                     true
                 }
@@ -252,23 +574,102 @@ where
     }
 
     fn reorder(&self, parent: &Node, next: Option<&Node>) -> Result<(), JsValue> {
-        if let Some(ref cached_render) = self.cached_render {
-            cached_render.reorder(parent, next)?;
+        if let Some(ref instance) = self.instance {
+            let mut instance = instance.borrow_mut();
+            instance.cached_render.reorder(parent, next)?;
+            instance.anchor.reorder(parent, next)?;
         }
         Ok(())
     }
 
     fn remove(&self, parent: &Node) -> Result<(), JsValue> {
-        if let Some(ref cached_render) = self.cached_render {
-            cached_render.remove(parent)?;
-            let comp = self.component.as_ref().unwrap();
-            comp.borrow().destroyed();
+        if let Some(ref instance) = self.instance {
+            if let Some(ref cache) = self.keep_alive {
+                return Self::deactivate(instance, parent, cache);
+            }
+
+            let comp = instance.borrow().component.clone();
+            if !comp.borrow().before_destroy() {
+                if let Some(status) = comp.borrow().status() {
+                    // Keep the instance alive until the removal is proceeded.
+                    let instance = instance.clone();
+                    let parent = parent.clone();
+                    status
+                        .borrow()
+                        .removal()
+                        .delay(Box::new(move || instance.borrow().destroy(&parent)));
+                    return Ok(());
+                }
+            }
+            instance.borrow().destroy(parent)?;
+        }
+        Ok(())
+    }
+
+    fn node(&self) -> Option<Node> {
+        // The end marker is the first node of a component which rendered
+        // nothing.
+        self.instance.as_ref().and_then(|instance| {
+            let instance = instance.borrow();
+            instance
+                .cached_render
+                .node()
+                .or_else(|| Some(instance.anchor.end().clone()))
+        })
+    }
+
+    fn write_html(&self, w: &mut HtmlWriter<'_>) -> fmt::Result {
+        if let Some(ref instance) = self.instance {
+            let instance = instance.borrow();
+            if let Some(index) = self.index {
+                rehydration::dehydrate(index, &*instance.component.borrow());
+            }
+            islands::write_html::<COMP>(w, self.index, |w| instance.cached_render.write_html(w))?;
         }
         Ok(())
     }
 
-    fn node(&self) -> Option<&Node> {
-        self.cached_render.as_ref().and_then(|inner| inner.node())
+    fn measure(&self, depth: usize, metrics: &mut Metrics) {
+        metrics.components += 1;
+        if let Some(ref instance) = self.instance {
+            instance.borrow().cached_render.measure(depth, metrics);
+        }
+    }
+
+    fn dump(&self, key: Option<&Key>, depth: usize, out: &mut String) {
+        let line = format!("<{}>", COMP::type_name());
+        dump::write_line(out, key, depth, &line);
+        if let Some(ref instance) = self.instance {
+            instance.borrow().cached_render.dump(None, depth + 1, out);
+        }
+    }
+
+    fn root_nodes(&self) -> Vec<Node> {
+        self.instance
+            .as_ref()
+            .map(|instance| {
+                let instance = instance.borrow();
+                let mut nodes = instance.cached_render.root_nodes();
+                nodes.push(instance.anchor.end().clone());
+                nodes
+            })
+            .unwrap_or_default()
+    }
+
+    fn component_type(&self) -> TypeId {
+        TypeId::of::<COMP>()
+    }
+
+    fn type_name(&self) -> &'static str {
+        COMP::type_name()
+    }
+
+    fn instance_id(&self) -> Option<u64> {
+        self.instance.as_ref().map(|instance| instance.borrow().id)
+    }
+
+    fn set_keep_alive(&mut self, cache: KeepAlive) {
+        self.keep_alive = Some(cache);
     }
 
     fn as_any_mut(&mut self) -> &mut dyn Any {
@@ -276,6 +677,90 @@ where
     }
 }
 
+impl<COMP: Render, RCTX: Render> ComponentWrapper<COMP, RCTX>
+where
+    COMP::Events: FromEventProps<RCTX>,
+{
+    /// Re-attaches the component taken out of the cache, updated with the
+    /// newer props.
+    fn activate(
+        &mut self,
+        parent: &Node,
+        next: Option<&Node>,
+        render_ctx: Shared<RCTX>,
+        rx_sender: MessageSender,
+    ) -> Result<(), JsValue> {
+        let instance = self.instance.as_ref().unwrap();
+        let props = self.props.take().unwrap();
+        let events = self.events.take().unwrap();
+
+        let comp = instance.borrow().component.clone();
+        comp.borrow().before_update(&props);
+        let old_props = comp
+            .borrow_mut()
+            .update(props, FromEventProps::from(events, render_ctx));
+        if let Some(old_props) = old_props {
+            comp.borrow().updated(old_props);
+        }
+
+        {
+            let mut instance = instance.borrow_mut();
+            instance.cached_render.reorder(parent, next)?;
+            instance.anchor.reorder(parent, next)?;
+            instance.render_walk(rx_sender)?;
+        }
+        comp.borrow().activated();
+        Ok(())
+    }
+
+    /// Detaches the nodes of the component and keeps it in the cache.
+    fn deactivate(
+        instance: &Shared<Instance<COMP>>,
+        parent: &Node,
+        cache: &KeepAlive,
+    ) -> Result<(), JsValue> {
+        let comp = instance.borrow().component.clone();
+        comp.borrow().deactivated();
+
+        // Any element would do to hold onto the nodes out of the DOM.
+        let container = parent.create_element("div")?;
+        {
+            let mut instance = instance.borrow_mut();
+            instance.cached_render.reorder(&container, None)?;
+            instance.anchor.reorder(&container, None)?;
+        }
+        cache.insert::<COMP>(Box::new(CachedInstance {
+            instance: instance.clone(),
+            container,
+        }));
+        Ok(())
+    }
+}
+
+impl<RCTX: Render> WriteHtml for VComponent<RCTX> {
+    fn write_html(&self, w: &mut HtmlWriter<'_>) -> fmt::Result {
+        self.0.write_html(w)
+    }
+}
+
+impl<RCTX: Render> Measure for VComponent<RCTX> {
+    fn measure(&self, depth: usize, metrics: &mut Metrics) {
+        self.0.measure(depth, metrics)
+    }
+}
+
+impl<RCTX: Render> Dump for VComponent<RCTX> {
+    fn dump(&self, key: Option<&Key>, depth: usize, out: &mut String) {
+        self.0.dump(key, depth, out)
+    }
+}
+
+impl<RCTX: Render> Display for VComponent<RCTX> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write_html(&mut HtmlWriter::display(f))
+    }
+}
+
 impl<RCTX> From<VComponent<RCTX>> for VNode<RCTX> {
     fn from(comp: VComponent<RCTX>) -> VNode<RCTX> {
         VNode::Component(comp)
@@ -288,7 +773,7 @@ pub mod test {
     use crate::{
         component::*,
         prelude::*,
-        vdom::{test::container, velement::*, vtext::*, VNode},
+        vdom::{test::container, velement::*, vlist::VList, vtext::*, VNode},
         Shared,
     };
     use wasm_bindgen_test::*;
@@ -343,7 +828,7 @@ pub mod test {
                 "button",
                 vec![Attribute::new("disabled", self.disabled.to_string())],
                 vec![],
-                VNode::from(VText::text("Click")),
+                Some(VText::text("Click")),
             ))
         }
     }
@@ -354,7 +839,7 @@ pub mod test {
         let div = container();
         vcomp
             .render_walk(
-                div.as_ref(),
+                &div.clone().into(),
                 None,
                 root_render_ctx(),
                 crate::message_sender(),
@@ -363,7 +848,7 @@ pub mod test {
 
         assert_eq!(
             div.inner_html(),
-            r#"<button disabled="false">Click</button>"#
+            r#"<button disabled="false">Click</button><!---->"#
         );
     }
 
@@ -373,7 +858,7 @@ pub mod test {
         let div = container();
         vcomp
             .render_walk(
-                div.as_ref(),
+                &div.clone().into(),
                 None,
                 root_render_ctx(),
                 crate::message_sender(),
@@ -382,14 +867,14 @@ pub mod test {
 
         assert_eq!(
             div.inner_html(),
-            r#"<button disabled="false">Click</button>"#
+            r#"<button disabled="false">Click</button><!---->"#
         );
 
         let mut patched = VComponent::new::<Button>(ButtonProps { disabled: true }, ());
         patched
             .patch(
                 Some(&mut vcomp),
-                div.as_ref(),
+                &div.clone().into(),
                 None,
                 root_render_ctx(),
                 crate::message_sender(),
@@ -397,7 +882,105 @@ pub mod test {
             .unwrap();
         patched
             .render_walk(
-                div.as_ref(),
+                &div.clone().into(),
+                None,
+                root_render_ctx(),
+                crate::message_sender(),
+            )
+            .expect("To patch div");
+
+        assert_eq!(
+            div.inner_html(),
+            r#"<button disabled="true">Click</button><!---->"#
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn should_identify_the_instances_of_the_components() {
+        let div = container();
+        let mut first = VComponent::new::<Button>(ButtonProps { disabled: false }, ());
+        assert_eq!(first.type_name(), "Button");
+        assert_eq!(first.instance_id(), None);
+        first
+            .render_walk(
+                &div.clone().into(),
+                None,
+                root_render_ctx(),
+                crate::message_sender(),
+            )
+            .unwrap();
+        let first_id = first.instance_id().unwrap();
+
+        let mut second = VComponent::new::<Button>(ButtonProps { disabled: false }, ());
+        second
+            .render_walk(
+                &div.clone().into(),
+                None,
+                root_render_ctx(),
+                crate::message_sender(),
+            )
+            .unwrap();
+        assert!(second.instance_id().unwrap() > first_id);
+
+        let mut patched = VComponent::new::<Button>(ButtonProps { disabled: true }, ());
+        patched
+            .patch(
+                Some(&mut first),
+                &div.clone().into(),
+                None,
+                root_render_ctx(),
+                crate::message_sender(),
+            )
+            .unwrap();
+        assert_eq!(patched.instance_id(), Some(first_id));
+    }
+
+    #[wasm_bindgen_test]
+    fn should_move_the_keyed_components_along_with_their_end_markers() {
+        let buttons = |keys: [(&str, bool); 2]| {
+            let mut list = VList::new();
+            for (key, disabled) in keys.iter() {
+                let disabled = *disabled;
+                list.push_keyed(*key, VComponent::new::<Button>(ButtonProps { disabled }, ()));
+            }
+            list
+        };
+        let mut list = buttons([("a", false), ("b", true)]);
+        let div = container();
+        list.patch(
+            None,
+            &div.clone().into(),
+            None,
+            root_render_ctx(),
+            crate::message_sender(),
+        )
+        .expect("To patch div");
+        list.render_walk(
+            &div.clone().into(),
+            None,
+            root_render_ctx(),
+            crate::message_sender(),
+        )
+        .expect("To patch div");
+
+        assert_eq!(
+            div.inner_html(),
+            r#"<button disabled="false">Click</button><!----><button disabled="true">Click</button><!---->"#
+        );
+
+        let mut moved = buttons([("b", true), ("a", false)]);
+        moved
+            .patch(
+                Some(&mut list),
+                &div.clone().into(),
+                None,
+                root_render_ctx(),
+                crate::message_sender(),
+            )
+            .expect("To patch div");
+        moved
+            .render_walk(
+                &div.clone().into(),
                 None,
                 root_render_ctx(),
                 crate::message_sender(),
@@ -406,7 +989,7 @@ pub mod test {
 
         assert_eq!(
             div.inner_html(),
-            r#"<button disabled="true">Click</button>"#
+            r#"<button disabled="true">Click</button><!----><button disabled="false">Click</button><!---->"#
         );
     }
 }
@@ -1,15 +1,37 @@
 //! Element representation in a VDOM.
 
-use crate::{component::Render, dom::DOMPatch, vdom::VNode, MessageSender, Shared};
+use crate::{
+    backend::{Listener, Node},
+    component::Render,
+    dom::DOMPatch,
+    errors,
+    html::{self, HtmlWriter, WriteHtml},
+    transition::Transition,
+    vdom::{
+        dump::{self, Dump},
+        metrics::{Measure, Metrics},
+        nesting, template,
+        vlist::KeyedVNodes,
+        Key, NodeRef, VNode,
+    },
+    MessageSender, Shared,
+};
 use indexmap::IndexMap;
-use std::{borrow::Cow, cell::RefCell, rc::Rc};
-use wasm_bindgen::{prelude::*, JsCast};
-use web_sys::{window, Element, Event, EventTarget, Node};
+use std::{
+    any::Any,
+    borrow::Cow,
+    cell::RefCell,
+    fmt::{self, Display, Formatter},
+    mem,
+    rc::{Rc, Weak},
+};
+use wasm_bindgen::prelude::*;
+use web_sys::Event;
 
 /// The representation of an element in virtual DOM.
 pub struct VElement<RCTX> {
     /// The tag of the element. Eg: h, p, div, ...
-    tag: &'static str,
+    tag: Cow<'static, str>,
     /// The attributes of the given element
     attributes: Attributes,
     /// Event listeners to the DOM events
@@ -17,11 +39,21 @@ pub struct VElement<RCTX> {
     /// The child node of the given element
     child: Box<VNode<RCTX>>,
     /// Element reference to the DOM
-    node: Option<Element>,
+    node: Option<Node>,
+    /// The reference which is set with the node of the element
+    node_ref: Option<NodeRef>,
+    /// The transition played when the element is inserted or removed
+    transition: Option<Transition>,
+    /// Whether the element is focused once it is created
+    autofocus: bool,
+    /// The markup of the static subtree, whose nodes are cloned from a
+    /// template
+    template: Option<&'static str>,
 }
 
 /// A list of attributes.
-struct Attributes(IndexMap<&'static str, AttributeValue>);
+#[derive(Clone)]
+struct Attributes(IndexMap<Cow<'static, str>, AttributeValue>);
 
 /// The key, value pair of the attributes on an element.
 pub struct Attribute {
@@ -31,33 +63,76 @@ pub struct Attribute {
     value: AttributeValue,
 }
 
-/// Either a string or a bool
+/// Either a string or a bool. The numbers are converted into a string, whereas
+/// a false bool leaves out the attribute.
+#[derive(Clone, Debug, PartialEq)]
 pub enum AttributeValue {
     /// A string attribute value
     String(String),
     /// A boolean attribute value
     Bool(bool),
-    /// An optional attribute value
+    /// An attribute set from a `None`, which is left out of the element and
+    /// removed from it if set earlier
     None,
 }
 
 struct EventListeners<RCTX>(Vec<Box<dyn EventManager<RenderContext = RCTX>>>);
 
+thread_local! {
+    /// The render context of the event listener being invoked.
+    static LISTENER_CONTEXT: RefCell<Option<Rc<dyn Any>>> = RefCell::new(None);
+    /// The element to be focused once the current patch is flushed.
+    static PENDING_FOCUS: RefCell<Option<Node>> = const { RefCell::new(None) };
+}
+
+type ListenerFn<RCTX> = Rc<dyn Fn(&RCTX, Event)>;
+
 /// Event listener to be invoked on a DOM event.
 pub struct EventListener<RCTX> {
     type_: &'static str,
-    listener: Option<Box<dyn Fn(&RCTX, Event)>>,
-    dom_listener: Option<Closure<dyn Fn(Event)>>,
+    listener: ListenerFn<RCTX>,
+    dom_listener: Option<DomListener<RCTX>>,
+}
+
+/// A listener attached onto the node, which invokes the handler of the latest
+/// patch, so that it is attached only once for as long as the event is
+/// listened to.
+struct DomListener<RCTX> {
+    listener: Listener,
+    handler: Rc<RefCell<Handler<RCTX>>>,
+}
+
+/// The handler of an event along with the render context it is invoked with.
+/// The render context is held weakly, so that a listener left attached on a
+/// node does not keep a destroyed component alive.
+struct Handler<RCTX> {
+    listener: ListenerFn<RCTX>,
+    render_ctx: Weak<RefCell<RCTX>>,
 }
 
 impl<RCTX: Render> VElement<RCTX> {
-    /// Create a VElement.
-    pub fn new(
-        tag: &'static str,
+    /// Create a VElement with its children, which may be any nodes, `Option`s
+    /// of them or `(key, node)` pairs. The children of a void element, like an
+    /// `<input>`, are ignored, as it cannot have any.
+    pub fn new<C: Into<KeyedVNodes<RCTX>>>(
+        tag: impl Into<Cow<'static, str>>,
         attributes: Vec<Attribute>,
         event_listeners: Vec<EventListener<RCTX>>,
-        child: VNode<RCTX>,
+        children: impl IntoIterator<Item = C>,
     ) -> VElement<RCTX> {
+        let tag = tag.into();
+        let child = KeyedVNodes::into_child(children);
+        let child = if html::is_void(&tag) && !child.is_none() {
+            if cfg!(debug_assertions) {
+                errors::warn(&format!(
+                    "ruukh: the children of the void element <{}> are ignored",
+                    tag
+                ));
+            }
+            VNode::None
+        } else {
+            child
+        };
         VElement {
             tag,
             attributes: Attributes::from(attributes),
@@ -74,17 +149,21 @@ impl<RCTX: Render> VElement<RCTX> {
             ),
             child: Box::new(child),
             node: None,
+            node_ref: None,
+            transition: None,
+            autofocus: false,
+            template: None,
         }
     }
 
     /// Create a VElement without a child.
     pub fn childless(
-        tag: &'static str,
+        tag: impl Into<Cow<'static, str>>,
         attributes: Vec<Attribute>,
         event_listeners: Vec<EventListener<RCTX>>,
     ) -> VElement<RCTX> {
         VElement {
-            tag,
+            tag: tag.into(),
             attributes: Attributes::from(attributes),
             event_listeners: EventListeners(
                 event_listeners
@@ -99,8 +178,101 @@ impl<RCTX: Render> VElement<RCTX> {
             ),
             child: Box::new(VNode::None),
             node: None,
+            node_ref: None,
+            transition: None,
+            autofocus: false,
+            template: None,
         }
     }
+
+    /// Sets the reference with the node of the element once it is patched.
+    pub fn with_node_ref(mut self, node_ref: &NodeRef) -> VElement<RCTX> {
+        self.node_ref = Some(node_ref.clone());
+        self
+    }
+
+    /// Plays the transition when the element is inserted or removed.
+    pub fn with_transition(mut self, transition: &Transition) -> VElement<RCTX> {
+        self.transition = Some(transition.clone());
+        self
+    }
+
+    /// Focuses the element once the patch in which it is created is flushed.
+    ///
+    /// Unlike the `autofocus` attribute, which the browser only honours when
+    /// the document is loaded, it works for the elements rendered later on,
+    /// like a dialog being opened. If multiple elements are created with it
+    /// in the same patch, the last one gets the focus.
+    pub fn autofocus(mut self) -> VElement<RCTX> {
+        self.autofocus = true;
+        self
+    }
+
+    /// Marks the element as a static subtree, which has no listeners, refs
+    /// nor components within, told apart by its `markup`. Its nodes are then
+    /// cloned from a template kept from its first render, instead of being
+    /// created one by one. `html!` marks the subtrees made of the literals
+    /// only.
+    pub fn with_template(mut self, markup: &'static str) -> VElement<RCTX> {
+        self.template = Some(markup);
+        self
+    }
+
+    /// Adds the class onto the element, along with the ones it already has.
+    pub fn with_class(mut self, class: &str) -> VElement<RCTX> {
+        // The markup no longer tells the subtree apart.
+        self.template = None;
+        let classes = match self.attributes.0.get("class") {
+            Some(AttributeValue::String(classes)) if !classes.is_empty() => {
+                format!("{} {}", classes, class)
+            }
+            _ => class.to_string(),
+        };
+        self.attributes
+            .0
+            .insert("class".into(), AttributeValue::String(classes));
+        self
+    }
+
+    /// Sets the attributes over the ones declared on the element, e.g. the
+    /// ones a wrapper component forwards onto its root element.
+    ///
+    /// An attribute already declared keeps its place, whereas the new ones
+    /// are placed after the declared ones sorted by their keys, so that the
+    /// element is rendered the same even if they come out of a `HashMap`. The
+    /// ones not named validly, e.g. with a space or a quote, are left out.
+    pub fn with_attributes<K, V>(
+        mut self,
+        attributes: impl IntoIterator<Item = (K, V)>,
+    ) -> VElement<RCTX>
+    where
+        K: Into<Cow<'static, str>>,
+        V: Into<AttributeValue>,
+    {
+        self.template = None;
+        let mut added = vec![];
+        for (key, value) in attributes {
+            let key = key.into();
+            if !html::is_attribute_name(&key) {
+                if cfg!(debug_assertions) {
+                    errors::warn(&format!(
+                        "ruukh: the attribute `{}` spread on <{}> is not a valid name and is left out",
+                        key, self.tag
+                    ));
+                }
+                continue;
+            }
+            if self.attributes.0.contains_key(&key) {
+                self.attributes.0.insert(key, value.into());
+            } else {
+                added.push((key, value.into()));
+            }
+        }
+        // A stable sort keeps the last value of a key repeated in the spread.
+        added.sort_by(|(a, _), (b, _)| a.cmp(b));
+        self.attributes.0.extend(added);
+        self
+    }
 }
 
 impl Attribute {
@@ -118,12 +290,83 @@ impl<RCTX> EventListener<RCTX> {
     pub fn new(type_: &'static str, listener: Box<dyn Fn(&RCTX, Event)>) -> EventListener<RCTX> {
         EventListener {
             type_,
-            listener: Some(listener),
+            listener: listener.into(),
             dom_listener: None,
         }
     }
 }
 
+impl<RCTX: Render> VElement<RCTX> {
+    /// Clones the element along with its attributes, listeners & children,
+    /// which is created anew when patched.
+    ///
+    /// Returns `None` if a component is within the element. See
+    /// [VNode::try_clone](../enum.VNode.html#method.try_clone).
+    pub fn try_clone(&self) -> Option<Self> {
+        Some(VElement {
+            tag: self.tag.clone(),
+            attributes: self.attributes.clone(),
+            event_listeners: EventListeners(
+                self.event_listeners
+                    .0
+                    .iter()
+                    .map(|listener| listener.clone_box())
+                    .collect(),
+            ),
+            child: Box::new(self.child.try_clone()?),
+            node: None,
+            node_ref: self.node_ref.clone(),
+            transition: self.transition.clone(),
+            autofocus: self.autofocus,
+            template: self.template,
+        })
+    }
+}
+
+impl<RCTX> VElement<RCTX> {
+    /// The tag of the element.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// The value of the attribute, if it is set.
+    pub fn attribute(&self, key: &str) -> Option<&AttributeValue> {
+        self.attributes.0.get(key)
+    }
+
+    /// The attributes of the element along with their values, in the order
+    /// they were set in.
+    pub fn attributes(&self) -> impl Iterator<Item = (&str, &AttributeValue)> {
+        self.attributes
+            .0
+            .iter()
+            .map(|(key, value)| (key.as_ref(), value))
+    }
+
+    /// The child of the element.
+    pub fn child(&self) -> &VNode<RCTX> {
+        &self.child
+    }
+
+    /// The child of the element, to be changed before it is rendered.
+    pub fn child_mut(&mut self) -> &mut VNode<RCTX> {
+        // The markup no longer tells the subtree apart.
+        self.template = None;
+        &mut self.child
+    }
+
+    /// Replaces the child of the element with the one mapped from it.
+    pub(crate) fn map_child(
+        mut self,
+        f: impl FnOnce(VNode<RCTX>) -> VNode<RCTX>,
+    ) -> VElement<RCTX> {
+        self.template = None;
+        let child = mem::replace(&mut *self.child, VNode::None);
+        *self.child = f(child);
+        self
+    }
+}
+
 impl<RCTX> From<VElement<RCTX>> for VNode<RCTX> {
     fn from(el: VElement<RCTX>) -> VNode<RCTX> {
         VNode::Element(el)
@@ -131,6 +374,34 @@ impl<RCTX> From<VElement<RCTX>> for VNode<RCTX> {
 }
 
 impl<RCTX: Render> VElement<RCTX> {
+    /// Makes the element draggable.
+    pub fn draggable(self) -> VElement<RCTX> {
+        self.with_attributes(vec![("draggable", "true")])
+    }
+
+    /// Makes the element accept the drops, by preventing the default of the
+    /// `dragenter` & `dragover` events on it. The drop is to be handled on a
+    /// `drop` event.
+    pub fn drop_target(mut self) -> VElement<RCTX> {
+        for type_ in &["dragenter", "dragover"] {
+            let listener = EventListener::new(
+                type_,
+                Box::new(|_: &RCTX, event: Event| event.prevent_default()),
+            );
+            self.event_listeners.0.push(Box::new(listener));
+        }
+        self
+    }
+
+    /// Whether the content of the element is edited by the user in place.
+    fn is_editable(&self) -> bool {
+        match self.attributes.0.get("contenteditable") {
+            Some(AttributeValue::String(value)) => value != "false",
+            Some(AttributeValue::Bool(editable)) => *editable,
+            _ => false,
+        }
+    }
+
     fn patch_new(
         &mut self,
         parent: &Node,
@@ -138,11 +409,45 @@ impl<RCTX: Render> VElement<RCTX> {
         render_ctx: Shared<RCTX>,
         rx_sender: MessageSender,
     ) -> Result<(), JsValue> {
-        let el = window()
-            .unwrap()
-            .document()
-            .unwrap()
-            .create_element(&self.tag)?;
+        let template = self
+            .template
+            .and_then(|markup| template::instantiate(markup, parent));
+        let el = match template {
+            Some(el) => {
+                let mut child = el.first_child();
+                self.child.adopt(&mut child);
+                el
+            }
+            None => match self.template {
+                Some(markup) => template::build(markup, || {
+                    self.create(parent, render_ctx, rx_sender)
+                })?,
+                None => self.create(parent, render_ctx, rx_sender)?,
+            },
+        };
+        parent.insert_before(&el, next)?;
+        if let Some(ref transition) = self.transition {
+            transition.enter(&el)?;
+        }
+        if let Some(ref node_ref) = self.node_ref {
+            node_ref.set(Some(el.clone()));
+        }
+        if self.autofocus {
+            PENDING_FOCUS.with(|pending| *pending.borrow_mut() = Some(el.clone()));
+        }
+        self.node = Some(el);
+        Ok(())
+    }
+
+    /// Creates the element along with its descendants, yet to be inserted.
+    fn create(
+        &mut self,
+        parent: &Node,
+        render_ctx: Shared<RCTX>,
+        rx_sender: MessageSender,
+    ) -> Result<Node, JsValue> {
+        nesting::check(&self.tag);
+        let el = parent.create_element(&self.tag)?;
         self.attributes.patch(
             None,
             &el,
@@ -152,11 +457,19 @@ impl<RCTX: Render> VElement<RCTX> {
         )?;
         self.event_listeners
             .patch(None, &el, None, render_ctx.clone(), rx_sender.clone())?;
-        self.child
-            .patch(None, el.as_ref(), None, render_ctx, rx_sender)?;
-        parent.insert_before(el.as_ref(), next)?;
+        {
+            let _nesting = nesting::enter(&self.tag);
+            self.child.patch(None, &el, None, render_ctx, rx_sender)?;
+        }
+        Ok(el)
+    }
+
+    /// Takes the node cloned from a template as its own, along with the ones
+    /// of its descendants.
+    pub(crate) fn adopt(&mut self, el: Node) {
+        let mut child = el.first_child();
+        self.child.adopt(&mut child);
         self.node = Some(el);
-        Ok(())
     }
 }
 
@@ -171,12 +484,15 @@ impl<RCTX: Render> DOMPatch for VElement<RCTX> {
         render_ctx: Shared<Self::RenderContext>,
         rx_sender: MessageSender,
     ) -> Result<(), JsValue> {
-        let node = self
-            .node
-            .as_ref()
-            .expect("The element itself must be patched before rendering the child");
-        self.child
-            .render_walk(node.as_ref(), None, render_ctx, rx_sender)
+        // The element is missing if it failed to be patched.
+        match self.node {
+            Some(ref node) => {
+                // The components created within are checked against it.
+                let _nesting = nesting::enter(&self.tag);
+                self.child.render_walk(node, None, render_ctx, rx_sender)
+            }
+            None => Ok(()),
+        }
     }
 
     fn patch(
@@ -188,33 +504,49 @@ impl<RCTX: Render> DOMPatch for VElement<RCTX> {
         rx_sender: MessageSender,
     ) -> Result<(), JsValue> {
         if let Some(old) = old {
-            if self.tag == old.tag {
-                let old_el = old
-                    .node
-                    .as_ref()
-                    .expect("The old node is expected to be attached to the DOM");
-                self.attributes.patch(
-                    Some(&mut old.attributes),
-                    &old_el,
-                    None,
-                    Rc::new(RefCell::new(())),
-                    rx_sender.clone(),
-                )?;
+            // The old element is missing if it failed to be patched.
+            let old_el = old.node.as_ref().filter(|_| self.tag == old.tag);
+            if let Some(old_el) = old_el {
+                // The markup of a template holds its attributes, so they are
+                // the same as long as the markup is.
+                if self.template.is_none() || self.template != old.template {
+                    self.attributes.patch(
+                        Some(&mut old.attributes),
+                        old_el,
+                        None,
+                        Rc::new(RefCell::new(())),
+                        rx_sender.clone(),
+                    )?;
+                }
                 self.event_listeners.patch(
                     Some(&mut old.event_listeners),
-                    &old_el,
+                    old_el,
                     None,
                     render_ctx.clone(),
                     rx_sender.clone(),
                 )?;
+                let _nesting = nesting::enter(&self.tag);
+                // The edits are left be while the content stays the same, as
+                // nothing is changed by the patch then.
+                if self.is_editable() && !self.child.is_same_content(&old.child) {
+                    restore_content(&old.child, old_el)?;
+                }
                 self.child.patch(
                     Some(&mut *old.child),
-                    old_el.as_ref(),
+                    old_el,
                     None,
                     render_ctx.clone(),
                     rx_sender,
                 )?;
 
+                if self.node_ref != old.node_ref {
+                    if let Some(ref old_ref) = old.node_ref {
+                        old_ref.set(None);
+                    }
+                }
+                if let Some(ref node_ref) = self.node_ref {
+                    node_ref.set(Some(old_el.clone()));
+                }
                 self.node = Some(old_el.clone());
                 Ok(())
             } else {
@@ -227,35 +559,46 @@ impl<RCTX: Render> DOMPatch for VElement<RCTX> {
     }
 
     fn reorder(&self, parent: &Node, next: Option<&Node>) -> Result<(), JsValue> {
-        let el = self.node.as_ref().unwrap();
-        parent.insert_before(el.as_ref(), next)?;
+        if let Some(ref el) = self.node {
+            parent.move_before(el, next)?;
+        }
         Ok(())
     }
 
     fn remove(&self, parent: &Node) -> Result<(), JsValue> {
-        let el = self
-            .node
-            .as_ref()
-            .expect("The old node is expected to be attached to the DOM");
-        self.child.remove(el.as_ref())?;
-        self.attributes.remove(&el)?;
-        parent.remove_child(el.as_ref())?;
+        // There is nothing to remove if it failed to be patched.
+        let el = match self.node {
+            Some(ref el) => el,
+            None => return Ok(()),
+        };
+        if let Some(ref transition) = self.transition {
+            transition.leave(parent, el)?;
+        }
+        if self.is_editable() {
+            restore_content(&self.child, el)?;
+        }
+        self.child.remove(el)?;
+        self.attributes.remove(el)?;
+        parent.remove_child(el)?;
+        if let Some(ref node_ref) = self.node_ref {
+            node_ref.set(None);
+        }
         Ok(())
     }
 
-    fn node(&self) -> Option<&Node> {
-        self.node.as_ref().map(|el| el.as_ref())
+    fn node(&self) -> Option<Node> {
+        self.node.clone()
     }
 }
 
 impl DOMPatch for Attributes {
     type RenderContext = ();
-    type Node = Element;
+    type Node = Node;
 
     fn render_walk(
         &mut self,
-        _: &Element,
-        _: Option<&Element>,
+        _: &Node,
+        _: Option<&Node>,
         _: Shared<Self::RenderContext>,
         _: MessageSender,
     ) -> Result<(), JsValue> {
@@ -265,8 +608,8 @@ impl DOMPatch for Attributes {
     fn patch(
         &mut self,
         mut old: Option<&mut Self>,
-        parent: &Element,
-        next: Option<&Element>,
+        parent: &Node,
+        next: Option<&Node>,
         _: Shared<Self::RenderContext>,
         _: MessageSender,
     ) -> Result<(), JsValue> {
@@ -310,26 +653,26 @@ impl DOMPatch for Attributes {
         unreachable!("Cannot reorder Attributes");
     }
 
-    fn remove(&self, parent: &Element) -> Result<(), JsValue> {
+    fn remove(&self, parent: &Node) -> Result<(), JsValue> {
         for (k, _) in self.0.iter() {
             parent.remove_attribute(&k)?;
         }
         Ok(())
     }
 
-    fn node(&self) -> Option<&Node> {
+    fn node(&self) -> Option<Node> {
         unreachable!("Attributes have no nodes");
     }
 }
 
 impl<RCTX: Render> DOMPatch for EventListeners<RCTX> {
     type RenderContext = RCTX;
-    type Node = Element;
+    type Node = Node;
 
     fn render_walk(
         &mut self,
-        _: &Element,
-        _: Option<&Element>,
+        _: &Node,
+        _: Option<&Node>,
         _: Shared<Self::RenderContext>,
         _: MessageSender,
     ) -> Result<(), JsValue> {
@@ -339,16 +682,28 @@ impl<RCTX: Render> DOMPatch for EventListeners<RCTX> {
     fn patch(
         &mut self,
         old: Option<&mut Self>,
-        parent: &Element,
-        _: Option<&Element>,
+        parent: &Node,
+        _: Option<&Node>,
         render_ctx: Shared<Self::RenderContext>,
         _: MessageSender,
     ) -> Result<(), JsValue> {
-        if let Some(old) = old {
-            old.remove(parent)?;
-        }
+        let mut old = old.map(|old| &mut old.0);
         for listener in self.0.iter_mut() {
-            listener.start_listening(parent.as_ref(), render_ctx.clone())?;
+            // Take over a listener of the same event, attached earlier.
+            let attached = old.as_mut().and_then(|old| {
+                old.iter_mut()
+                    .filter(|old| old.type_() == listener.type_())
+                    .find_map(|old| old.take_dom_listener())
+            });
+            match attached {
+                Some(attached) => listener.listen_with(attached, render_ctx.clone()),
+                None => listener.start_listening(parent, render_ctx.clone())?,
+            }
+        }
+        if let Some(old) = old {
+            for listener in old.iter() {
+                listener.stop_listening(parent)?;
+            }
         }
         Ok(())
     }
@@ -357,14 +712,14 @@ impl<RCTX: Render> DOMPatch for EventListeners<RCTX> {
         unreachable!("Cannot reorder EventListeners");
     }
 
-    fn remove(&self, parent: &Element) -> Result<(), JsValue> {
+    fn remove(&self, parent: &Node) -> Result<(), JsValue> {
         for listener in self.0.iter() {
-            listener.stop_listening(parent.as_ref())?;
+            listener.stop_listening(parent)?;
         }
         Ok(())
     }
 
-    fn node(&self) -> Option<&Node> {
+    fn node(&self) -> Option<Node> {
         unreachable!("EventListeners have no nodes");
     }
 }
@@ -372,44 +727,182 @@ impl<RCTX: Render> DOMPatch for EventListeners<RCTX> {
 trait EventManager {
     type RenderContext;
 
+    fn type_(&self) -> &'static str;
+
+    fn clone_box(&self) -> Box<dyn EventManager<RenderContext = Self::RenderContext>>;
+
     fn start_listening(
         &mut self,
-        parent: &EventTarget,
+        parent: &Node,
         render_ctx: Shared<Self::RenderContext>,
     ) -> Result<(), JsValue>;
 
-    fn stop_listening(&self, parent: &EventTarget) -> Result<(), JsValue>;
+    /// Listens onto the listener attached earlier for the same event, which
+    /// now invokes this one instead.
+    fn listen_with(
+        &mut self,
+        attached: DomListener<Self::RenderContext>,
+        render_ctx: Shared<Self::RenderContext>,
+    );
+
+    fn take_dom_listener(&mut self) -> Option<DomListener<Self::RenderContext>>;
+
+    fn stop_listening(&self, parent: &Node) -> Result<(), JsValue>;
 }
 
 impl<RCTX: Render> EventManager for EventListener<RCTX> {
     type RenderContext = RCTX;
 
+    fn type_(&self) -> &'static str {
+        self.type_
+    }
+
+    fn clone_box(&self) -> Box<dyn EventManager<RenderContext = RCTX>> {
+        Box::new(EventListener {
+            type_: self.type_,
+            listener: self.listener.clone(),
+            dom_listener: None,
+        })
+    }
+
     fn start_listening(
         &mut self,
-        parent: &EventTarget,
+        parent: &Node,
         render_ctx: Shared<Self::RenderContext>,
     ) -> Result<(), JsValue> {
-        let listener = self.listener.take().unwrap();
-        let js_closure: Closure<dyn Fn(Event)> = Closure::wrap(Box::new(move |event| {
-            listener(&*render_ctx.borrow(), event)
+        let handler = Rc::new(RefCell::new(Handler {
+            listener: self.listener.clone(),
+            render_ctx: Rc::downgrade(&render_ctx),
         }));
-        parent
-            .add_event_listener_with_callback(&self.type_, js_closure.as_ref().unchecked_ref())?;
-        self.dom_listener = Some(js_closure);
+        let latest = handler.clone();
+        let listener = parent.add_event_listener(
+            &self.type_,
+            Box::new(move |event| {
+                // The handler may be replaced by a patch while it is invoked.
+                let (listener, render_ctx) = {
+                    let latest = latest.borrow();
+                    (latest.listener.clone(), latest.render_ctx.upgrade())
+                };
+                let render_ctx = match render_ctx {
+                    Some(render_ctx) => render_ctx,
+                    None => return,
+                };
+                with_listener_context(&render_ctx, || listener(&*render_ctx.borrow(), event))
+            }),
+        )?;
+        self.dom_listener = Some(DomListener { listener, handler });
         Ok(())
     }
 
-    fn stop_listening(&self, parent: &EventTarget) -> Result<(), JsValue> {
+    fn listen_with(&mut self, attached: DomListener<RCTX>, render_ctx: Shared<RCTX>) {
+        *attached.handler.borrow_mut() = Handler {
+            listener: self.listener.clone(),
+            render_ctx: Rc::downgrade(&render_ctx),
+        };
+        self.dom_listener = Some(attached);
+    }
+
+    fn take_dom_listener(&mut self) -> Option<DomListener<RCTX>> {
+        self.dom_listener.take()
+    }
+
+    fn stop_listening(&self, parent: &Node) -> Result<(), JsValue> {
         if let Some(ref dom_listener) = self.dom_listener {
-            parent.remove_event_listener_with_callback(
-                &self.type_,
-                dom_listener.as_ref().unchecked_ref(),
-            )?;
+            parent.remove_event_listener(&self.type_, &dom_listener.listener)?;
         }
         Ok(())
     }
 }
 
+/// Puts back the nodes of the content of an editable element, in case they
+/// were replaced by the edits of the user, so that they can be patched or
+/// removed.
+fn restore_content<RCTX: Render>(child: &VNode<RCTX>, el: &Node) -> Result<(), JsValue> {
+    el.set_text_content("")?;
+    child.reorder(el, None)
+}
+
+/// Focuses the element created with `autofocus` during the patch that was just
+/// flushed, if any.
+pub(crate) fn focus_pending() -> Result<(), JsValue> {
+    match PENDING_FOCUS.with(|pending| pending.borrow_mut().take()) {
+        Some(el) => el.focus(),
+        None => Ok(()),
+    }
+}
+
+/// Invokes a listener, while making its render context available to the
+/// listeners which defer the invocation of the handler they wrap.
+pub(crate) fn with_listener_context<RCTX: 'static>(render_ctx: &Shared<RCTX>, f: impl FnOnce()) {
+    let render_ctx: Rc<dyn Any> = render_ctx.clone();
+    let outer = LISTENER_CONTEXT.with(|ctx| ctx.replace(Some(render_ctx)));
+    f();
+    LISTENER_CONTEXT.with(|ctx| *ctx.borrow_mut() = outer);
+}
+
+/// Gets the render context of the event listener being invoked.
+pub(crate) fn listener_context<RCTX: 'static>() -> Option<Shared<RCTX>> {
+    let render_ctx = LISTENER_CONTEXT.with(|ctx| ctx.borrow().clone())?;
+    render_ctx.downcast().ok()
+}
+
+impl<RCTX: Render> WriteHtml for VElement<RCTX> {
+    fn write_html(&self, w: &mut HtmlWriter<'_>) -> fmt::Result {
+        let attributes = self
+            .attributes
+            .0
+            .iter()
+            .filter_map(|(key, value)| match value {
+                AttributeValue::String(val) => Some((&**key, Some(val.as_str()))),
+                AttributeValue::Bool(true) => Some((&**key, None)),
+                AttributeValue::Bool(false) | AttributeValue::None => None,
+            })
+            // The browser honours it when the rendered document is loaded.
+            .chain(if self.autofocus && !self.attributes.0.contains_key("autofocus") {
+                Some(("autofocus", None))
+            } else {
+                None
+            });
+        let events = self.event_listeners.0.iter().map(|listener| listener.type_());
+        w.open_tag(&self.tag, attributes, events)?;
+        if !html::is_void(&self.tag) {
+            self.child.write_html(w)?;
+        }
+        w.close_tag(&self.tag)
+    }
+}
+
+impl<RCTX: Render> Measure for VElement<RCTX> {
+    fn measure(&self, depth: usize, metrics: &mut Metrics) {
+        metrics.add_node(depth);
+        metrics.listeners += self.event_listeners.0.len();
+        self.child.measure(depth + 1, metrics);
+    }
+}
+
+impl<RCTX: Render> Dump for VElement<RCTX> {
+    fn dump(&self, key: Option<&Key>, depth: usize, out: &mut String) {
+        let mut line = self.tag.to_string();
+        for (attr_key, value) in self.attributes() {
+            match value {
+                AttributeValue::String(value) => {
+                    line.push_str(&format!(" {}={:?}", attr_key, value))
+                }
+                AttributeValue::Bool(true) => line.push_str(&format!(" {}", attr_key)),
+                AttributeValue::Bool(false) | AttributeValue::None => {}
+            }
+        }
+        dump::write_line(out, key, depth, &line);
+        self.child.dump(None, depth + 1, out);
+    }
+}
+
+impl<RCTX: Render> Display for VElement<RCTX> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write_html(&mut HtmlWriter::display(f))
+    }
+}
+
 impl From<bool> for AttributeValue {
     fn from(val: bool) -> AttributeValue {
         AttributeValue::Bool(val)
@@ -434,6 +927,28 @@ impl<'a> From<Cow<'a, str>> for AttributeValue {
     }
 }
 
+impl<'a> From<&'a String> for AttributeValue {
+    fn from(val: &'a String) -> AttributeValue {
+        AttributeValue::String(val.clone())
+    }
+}
+
+macro_rules! impl_with_to_string {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for AttributeValue {
+                fn from(val: $t) -> AttributeValue {
+                    AttributeValue::String(val.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_with_to_string!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, char
+);
+
 impl<'a, T: Into<AttributeValue>> From<Option<T>> for AttributeValue {
     fn from(val: Option<T>) -> AttributeValue {
         match val {
@@ -445,7 +960,7 @@ impl<'a, T: Into<AttributeValue>> From<Option<T>> for AttributeValue {
 
 impl From<Vec<Attribute>> for Attributes {
     fn from(val: Vec<Attribute>) -> Attributes {
-        let attrs = val.into_iter().map(|attr| (attr.key, attr.value)).collect();
+        let attrs = val.into_iter().map(|attr| (attr.key.into(), attr.value)).collect();
         Attributes(attrs)
     }
 }
@@ -453,7 +968,10 @@ impl From<Vec<Attribute>> for Attributes {
 #[cfg(test)]
 pub mod test {
     use super::*;
-    use crate::{component::root_render_ctx, vdom::test::container};
+    use crate::{
+        component::root_render_ctx,
+        vdom::{test::container, VText},
+    };
     use wasm_bindgen_test::*;
 
     #[wasm_bindgen_test]
@@ -463,7 +981,7 @@ pub mod test {
         button_el
             .patch(
                 None,
-                div.as_ref(),
+                &div.clone().into(),
                 None,
                 root_render_ctx(),
                 crate::message_sender(),
@@ -473,6 +991,30 @@ pub mod test {
         assert_eq!(div.inner_html(), "<button></button>");
     }
 
+    #[wasm_bindgen_test]
+    fn should_ignore_the_children_of_void_element() {
+        let mut input_el = VElement::new(
+            "input",
+            vec![Attribute::new("name", "email")],
+            vec![],
+            Some(VText::text("Email")),
+        );
+        assert_eq!(input_el.to_string(), r#"<input name="email">"#);
+
+        let div = container();
+        input_el
+            .patch(
+                None,
+                &div.clone().into(),
+                None,
+                root_render_ctx(),
+                crate::message_sender(),
+            )
+            .expect("To patch div");
+
+        assert_eq!(div.inner_html(), r#"<input name="email">"#);
+    }
+
     #[wasm_bindgen_test]
     fn should_patch_container_with_button_with_attrs() {
         let mut button_el = VElement::childless(
@@ -487,7 +1029,7 @@ pub mod test {
         button_el
             .patch(
                 None,
-                div.as_ref(),
+                &div.clone().into(),
                 None,
                 root_render_ctx(),
                 crate::message_sender(),
@@ -506,7 +1048,7 @@ pub mod test {
             "div",
             vec![],
             vec![],
-            VNode::from(VElement::childless(
+            Some(VElement::childless(
                 "a",
                 vec![Attribute::new("href", "http://www.rust-lang.org/")],
                 vec![],
@@ -516,7 +1058,7 @@ pub mod test {
         div_el
             .patch(
                 None,
-                div.as_ref(),
+                &div.clone().into(),
                 None,
                 root_render_ctx(),
                 crate::message_sender(),
@@ -536,7 +1078,7 @@ pub mod test {
         div_el
             .patch(
                 None,
-                div.as_ref(),
+                &div.clone().into(),
                 None,
                 root_render_ctx(),
                 crate::message_sender(),
@@ -549,7 +1091,7 @@ pub mod test {
         button_el
             .patch(
                 Some(&mut div_el),
-                div.as_ref(),
+                &div.clone().into(),
                 None,
                 root_render_ctx(),
                 crate::message_sender(),
@@ -567,7 +1109,7 @@ pub mod test {
         div_el
             .patch(
                 None,
-                div.as_ref(),
+                &div.clone().into(),
                 None,
                 root_render_ctx(),
                 crate::message_sender(),
@@ -587,7 +1129,7 @@ pub mod test {
         div_diff
             .patch(
                 Some(&mut div_el),
-                div.as_ref(),
+                &div.clone().into(),
                 None,
                 root_render_ctx(),
                 crate::message_sender(),
@@ -599,4 +1141,54 @@ pub mod test {
             r#"<div class="bg-white txt-black" id="main"></div>"#
         )
     }
+
+    #[wasm_bindgen_test]
+    fn should_invoke_the_latest_listener_of_the_event() {
+        type Listener = Box<dyn Fn(&(), Event)>;
+
+        thread_local! {
+            static CLICKS: RefCell<Vec<&'static str>> = const { RefCell::new(vec![]) };
+        }
+        let clicked = |name: &'static str| -> Listener {
+            Box::new(move |_, _| CLICKS.with(|clicks| clicks.borrow_mut().push(name)))
+        };
+
+        let mut old_el = VElement::childless(
+            "button",
+            vec![],
+            vec![EventListener::new("click", clicked("old"))],
+        );
+        let div = container();
+        old_el
+            .patch(
+                None,
+                &div.clone().into(),
+                None,
+                root_render_ctx(),
+                crate::message_sender(),
+            )
+            .expect("To patch div");
+
+        let mut new_el = VElement::childless(
+            "button",
+            vec![],
+            vec![EventListener::new("click", clicked("new"))],
+        );
+        new_el
+            .patch(
+                Some(&mut old_el),
+                &div.clone().into(),
+                None,
+                root_render_ctx(),
+                crate::message_sender(),
+            )
+            .expect("To patch div");
+
+        let button = div.first_element_child().unwrap();
+        button
+            .dispatch_event(&Event::new("click").unwrap())
+            .unwrap();
+
+        assert_eq!(CLICKS.with(|clicks| clicks.borrow().clone()), vec!["new"]);
+    }
 }
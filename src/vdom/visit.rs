@@ -0,0 +1,95 @@
+//! Walking through a VDOM tree, e.g. to check or collect its elements, and
+//! mapping its elements.
+
+use crate::{
+    component::Render,
+    vdom::{vcomponent::VComponent, velement::VElement, vlist::VList, vtext::VText, VNode},
+};
+
+/// The nodes visited by [VNode::walk](enum.VNode.html#method.walk), in the
+/// document order. Only the ones of interest need to be implemented.
+///
+/// # Example
+/// ```ignore
+/// struct Links(Vec<String>);
+///
+/// impl<RCTX> Visitor<RCTX> for Links {
+///     fn visit_element(&mut self, el: &VElement<RCTX>) {
+///         if let Some(AttributeValue::String(href)) = el.attribute("href") {
+///             self.0.push(href.clone());
+///         }
+///     }
+/// }
+/// ```
+pub trait Visitor<RCTX> {
+    /// Visits an element, before its child.
+    fn visit_element(&mut self, _el: &VElement<RCTX>) {}
+
+    /// Leaves an element, after its child was walked through.
+    fn leave_element(&mut self, _el: &VElement<RCTX>) {}
+
+    /// Visits a text or a comment.
+    fn visit_text(&mut self, _text: &VText<RCTX>) {}
+
+    /// Visits a list, before its items.
+    fn visit_list(&mut self, _list: &VList<RCTX>) {}
+
+    /// Visits a component. What it renders is not walked through, as it is
+    /// markup of another component.
+    fn visit_component(&mut self, _comp: &VComponent<RCTX>) {}
+}
+
+impl<RCTX: Render> VNode<RCTX> {
+    /// Walks through the tree in the document order, passing each of its
+    /// nodes to the visitor.
+    pub fn walk(&self, visitor: &mut impl Visitor<RCTX>) {
+        match self {
+            VNode::Element(el) => {
+                visitor.visit_element(el);
+                el.child().walk(visitor);
+                visitor.leave_element(el);
+            }
+            VNode::Text(txt) => visitor.visit_text(txt),
+            VNode::List(li) => {
+                visitor.visit_list(li);
+                for (_, item) in li.items() {
+                    item.walk(visitor);
+                }
+            }
+            VNode::Component(comp) => visitor.visit_component(comp),
+            VNode::None => {}
+        }
+    }
+}
+
+impl<RCTX: Render> VNode<RCTX> {
+    /// Rebuilds the tree with each of its elements mapped by `f`, e.g. to add
+    /// a class onto the links or to rewrite their URLs. An element is mapped
+    /// before its child, which is then taken from the mapped one. The
+    /// components are left as they are.
+    ///
+    /// The static markup within is no longer hoisted, as `f` may map it
+    /// differently on each render.
+    ///
+    /// # Example
+    /// ```ignore
+    /// markup.map_elements(|el| match el.tag() {
+    ///     "a" => el.with_class("link"),
+    ///     _ => el,
+    /// })
+    /// ```
+    pub fn map_elements(self, f: impl Fn(VElement<RCTX>) -> VElement<RCTX>) -> VNode<RCTX> {
+        map_elements(self, &f)
+    }
+}
+
+fn map_elements<RCTX: Render>(
+    vnode: VNode<RCTX>,
+    f: &dyn Fn(VElement<RCTX>) -> VElement<RCTX>,
+) -> VNode<RCTX> {
+    match vnode {
+        VNode::Element(el) => VNode::Element(f(el).map_child(|child| map_elements(child, f))),
+        VNode::List(li) => VNode::List(li.unhoisted().map(|item| map_elements(item, f))),
+        vnode => vnode,
+    }
+}
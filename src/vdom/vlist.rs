@@ -1,19 +1,79 @@
 //! Representation of a list of nodes in VDOM.
 
 use crate::{
+    backend::Node,
     component::Render,
+    devtools,
     dom::DOMPatch,
-    vdom::{Key, VNode},
+    errors,
+    html::{HtmlWriter, WriteHtml},
+    transition::Motion,
+    vdom::{
+        dump::Dump,
+        metrics::{Measure, Metrics},
+        nesting, Key, VNode,
+    },
     MessageSender, Shared,
 };
 use fnv::FnvBuildHasher;
 use indexmap::IndexMap;
-use std::collections::HashSet;
+use std::{
+    any::TypeId,
+    collections::HashSet,
+    fmt::{self, Display, Formatter},
+    iter, mem,
+};
 use wasm_bindgen::prelude::JsValue;
-use web_sys::Node;
 
-/// The representation of a list of vnodes in the vtree.
-pub struct VList<RCTX>(IndexMap<Key, VNode<RCTX>, FnvBuildHasher>);
+/// The representation of a list of vnodes in the vtree, along with how its
+/// items are animated when moved, how they were keyed and the static markup
+/// they were hoisted from, if so.
+pub struct VList<RCTX> {
+    items: IndexMap<Key, VNode<RCTX>, FnvBuildHasher>,
+    motion: Option<Motion>,
+    keys: Keys,
+    hoisted_from: Option<TypeId>,
+}
+
+/// A child of an element, i.e. a vnode along with its key, if given. Any
+/// `Into<VNode>`, an `Option` of one & a `(key, node)` pair are converted into
+/// it.
+pub struct KeyedVNodes<RCTX> {
+    key: Option<Key>,
+    node: VNode<RCTX>,
+}
+
+/// How the items of a list are keyed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Keys {
+    /// By the keys given to them.
+    Given,
+    /// By their position in the markup they are written in.
+    Written,
+    /// By their position in a `Vec` built in an expression, e.g. from an
+    /// iterator.
+    Collected,
+}
+
+impl<RCTX: Render> VList<RCTX> {
+    /// Clones the items along with their keys.
+    ///
+    /// Returns `None` if a component is within the list. See
+    /// [VNode::try_clone](../enum.VNode.html#method.try_clone).
+    pub fn try_clone(&self) -> Option<Self> {
+        let items = self
+            .items
+            .iter()
+            .map(|(key, vnode)| Some((key.clone(), vnode.try_clone()?)))
+            .collect::<Option<_>>()?;
+        Some(VList {
+            items,
+            motion: self.motion.clone(),
+            keys: self.keys,
+            hoisted_from: self.hoisted_from,
+        })
+    }
+}
 
 impl<RCTX> From<VList<RCTX>> for VNode<RCTX> {
     fn from(list: VList<RCTX>) -> VNode<RCTX> {
@@ -21,24 +81,332 @@ impl<RCTX> From<VList<RCTX>> for VNode<RCTX> {
     }
 }
 
+impl<RCTX> KeyedVNodes<RCTX> {
+    /// The node along with its key.
+    pub fn keyed(key: impl Into<Key>, node: impl Into<VNode<RCTX>>) -> KeyedVNodes<RCTX> {
+        KeyedVNodes {
+            key: Some(key.into()),
+            node: node.into(),
+        }
+    }
+
+    /// The node keyed by its position.
+    pub fn unkeyed(node: impl Into<VNode<RCTX>>) -> KeyedVNodes<RCTX> {
+        KeyedVNodes {
+            key: None,
+            node: node.into(),
+        }
+    }
+
+    /// The key given to the node, if any.
+    pub fn key(&self) -> Option<&Key> {
+        self.key.as_ref()
+    }
+
+    /// The node.
+    pub fn vnode(&self) -> &VNode<RCTX> {
+        &self.node
+    }
+
+    /// The node, to be modified in place.
+    pub fn vnode_mut(&mut self) -> &mut VNode<RCTX> {
+        &mut self.node
+    }
+
+    /// Splits it into the key, if given, and the node.
+    pub fn into_parts(self) -> (Option<Key>, VNode<RCTX>) {
+        (self.key, self.node)
+    }
+
+    /// The vnode of the children of an element, which is a list unless there
+    /// is only an unkeyed one. The unkeyed ones are keyed by their position.
+    pub(crate) fn into_child<C: Into<KeyedVNodes<RCTX>>>(
+        children: impl IntoIterator<Item = C>,
+    ) -> VNode<RCTX> {
+        let mut children = children.into_iter().map(Into::into).peekable();
+        let first = match children.next() {
+            Some(first) => first,
+            None => return VNode::None,
+        };
+        if first.key.is_none() && children.peek().is_none() {
+            return first.node;
+        }
+        iter::once(first)
+            .chain(children)
+            .fold(VList::new(), VList::with_child)
+            .collected()
+            .into()
+    }
+}
+
+impl<RCTX, N: Into<VNode<RCTX>>> From<N> for KeyedVNodes<RCTX> {
+    fn from(node: N) -> KeyedVNodes<RCTX> {
+        KeyedVNodes::unkeyed(node)
+    }
+}
+
+/// A `None` is kept in place, so that the children after it are not moved
+/// once it is `Some`.
+impl<RCTX, N: Into<VNode<RCTX>>> From<Option<N>> for KeyedVNodes<RCTX> {
+    fn from(node: Option<N>) -> KeyedVNodes<RCTX> {
+        KeyedVNodes::unkeyed(node.map_or(VNode::None, Into::into))
+    }
+}
+
+impl<RCTX, K: Into<Key>, N: Into<VNode<RCTX>>> From<(K, N)> for KeyedVNodes<RCTX> {
+    fn from((key, node): (K, N)) -> KeyedVNodes<RCTX> {
+        KeyedVNodes::keyed(key, node)
+    }
+}
+
+impl<RCTX> Default for VList<RCTX> {
+    fn default() -> VList<RCTX> {
+        VList::new()
+    }
+}
+
 impl<RCTX> From<Vec<VNode<RCTX>>> for VList<RCTX> {
     fn from(children: Vec<VNode<RCTX>>) -> Self {
-        VList(
-            children
+        VList {
+            items: children
                 .into_iter()
                 .enumerate()
                 .map(|(k, v)| (Key::new(k as u32), v))
                 .collect(),
-        )
+            motion: None,
+            keys: Keys::Written,
+            hoisted_from: None,
+        }
     }
 }
 
 impl<RCTX> From<IndexMap<Key, VNode<RCTX>, FnvBuildHasher>> for VList<RCTX> {
     fn from(map: IndexMap<Key, VNode<RCTX>, FnvBuildHasher>) -> Self {
-        VList(map)
+        VList {
+            items: map,
+            motion: None,
+            keys: Keys::Given,
+            hoisted_from: None,
+        }
+    }
+}
+
+impl<RCTX> VList<RCTX> {
+    /// Creates an empty list, to push the items onto. See the `vlist!` macro.
+    pub fn new() -> VList<RCTX> {
+        VList {
+            items: IndexMap::default(),
+            motion: None,
+            keys: Keys::Written,
+            hoisted_from: None,
+        }
+    }
+
+    /// Appends the node, keyed by its position.
+    pub fn push(&mut self, node: impl Into<VNode<RCTX>>) {
+        let key = Key::new(self.items.len() as u32);
+        self.insert(key, node.into());
+    }
+
+    /// Appends the node with the key, so that the list is keyed by the given
+    /// keys.
+    pub fn push_keyed(&mut self, key: impl Into<Key>, node: impl Into<VNode<RCTX>>) {
+        self.keys = Keys::Given;
+        self.insert(key.into(), node.into());
+    }
+
+    /// Appends the nodes of the iterator, keyed by their position. As their
+    /// number may differ between the renders, they are best keyed, e.g. with
+    /// `push_keyed`.
+    pub fn extend<N: Into<VNode<RCTX>>>(&mut self, nodes: impl IntoIterator<Item = N>) {
+        if self.keys == Keys::Written {
+            self.keys = Keys::Collected;
+        }
+        for node in nodes {
+            self.push(node);
+        }
+    }
+
+    fn with_child(mut self, child: KeyedVNodes<RCTX>) -> VList<RCTX> {
+        match child.key {
+            Some(key) => self.push_keyed(key, child.node),
+            None => self.push(child.node),
+        }
+        self
+    }
+
+    fn insert(&mut self, key: Key, node: VNode<RCTX>) {
+        if self.items.insert(key.clone(), node).is_some() && cfg!(debug_assertions) {
+            errors::warn(&format!(
+                "ruukh: the item of the key `{}` is replaced by another of the same key",
+                key
+            ));
+        }
+    }
+
+    /// The number of the items.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether there are no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The items along with their keys, in order. The items which were not
+    /// given a key are keyed by their position.
+    pub fn items(&self) -> impl Iterator<Item = (&Key, &VNode<RCTX>)> {
+        self.items.iter()
+    }
+
+    /// The items along with their keys, to be changed before they are
+    /// rendered.
+    pub fn items_mut(&mut self) -> impl Iterator<Item = (&Key, &mut VNode<RCTX>)> {
+        self.items.iter_mut()
+    }
+
+    /// Takes the items out of the list along with their keys.
+    pub fn into_items(self) -> impl Iterator<Item = (Key, VNode<RCTX>)> {
+        self.items.into_iter()
+    }
+
+    /// Maps each of the vnodes in the list, keeping their keys.
+    pub(crate) fn map(self, f: impl Fn(VNode<RCTX>) -> VNode<RCTX>) -> VList<RCTX> {
+        VList {
+            items: self.items.into_iter().map(|(k, v)| (k, f(v))).collect(),
+            ..self
+        }
+    }
+
+    /// The topmost nodes of the items.
+    pub(crate) fn root_nodes(&self) -> Vec<Node>
+    where
+        RCTX: Render,
+    {
+        self.items.values().flat_map(VNode::root_nodes).collect()
+    }
+
+    /// Takes the nodes cloned from a template as the ones of the items, in
+    /// order.
+    pub(crate) fn adopt(&mut self, next: &mut Option<Node>)
+    where
+        RCTX: Render,
+    {
+        for item in self.items.values_mut() {
+            item.adopt(next);
+        }
+    }
+
+    /// Marks the list as a clone of the static markup hoisted under the key,
+    /// so that it is not patched onto another clone of it.
+    pub(crate) fn hoisted(node: VNode<RCTX>, key: TypeId) -> VList<RCTX> {
+        let mut list = VList::from(vec![node]);
+        list.hoisted_from = Some(key);
+        list
+    }
+
+    /// Marks the list as built in an expression, whose items are not keyed
+    /// unless given the keys.
+    pub(crate) fn collected(mut self) -> VList<RCTX> {
+        if self.keys == Keys::Written {
+            self.keys = Keys::Collected;
+        }
+        self
+    }
+
+    /// Unmarks the list as hoisted, once its items are no longer the same as
+    /// the static markup.
+    pub(crate) fn unhoisted(mut self) -> VList<RCTX> {
+        self.hoisted_from = None;
+        self
+    }
+
+    /// The key of the static markup the list was hoisted from, if so.
+    pub(crate) fn hoisted_from(&self) -> Option<TypeId> {
+        self.hoisted_from
+    }
+
+    /// Animates the items whose position changed after a patch.
+    pub(crate) fn with_motion(mut self, motion: Motion) -> VList<RCTX> {
+        self.motion = Some(motion);
+        self
     }
 }
 
+impl<RCTX: Render> VList<RCTX> {
+    /// Patches the items onto the old ones of the same type in order, rather
+    /// than by their position. So, an item inserted or removed does not
+    /// recreate the ones after it.
+    fn patch_unkeyed(
+        &mut self,
+        old: &mut Self,
+        parent: &Node,
+        mut next: Option<Node>,
+        render_ctx: Shared<RCTX>,
+        rx_sender: MessageSender,
+    ) -> Result<(), JsValue> {
+        // Match each item with the next old one of the same type, if any.
+        let mut matches = Vec::with_capacity(self.items.len());
+        let mut is_matched = vec![false; old.items.len()];
+        let mut from = 0;
+        for vnode in self.items.values() {
+            let found = old
+                .items
+                .values()
+                .skip(from)
+                .position(|old| vnode.is_same_type(old))
+                .map(|offset| from + offset);
+            if let Some(index) = found {
+                is_matched[index] = true;
+                from = index + 1;
+            }
+            matches.push(found);
+        }
+
+        // As the matched ones are in order, none of them need to be moved.
+        for (vnode, found) in self.items.values_mut().zip(matches.iter()).rev() {
+            let old_vnode = found
+                .and_then(|index| old.items.get_index_mut(index))
+                .map(|(_, old_vnode)| old_vnode);
+            vnode.patch(
+                old_vnode,
+                parent,
+                next.as_ref(),
+                render_ctx.clone(),
+                rx_sender.clone(),
+            )?;
+            next = vnode.node().or(next);
+        }
+
+        for (vnode, is_matched) in old.items.values().zip(is_matched) {
+            if !is_matched {
+                vnode.remove(parent)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Warns of a list of the unkeyed items built in an expression, if enabled
+/// with `devtools::warn_unkeyed_lists`.
+fn warn_unkeyed(len: usize) {
+    if !devtools::warns_unkeyed_lists() {
+        return;
+    }
+    let parent = match nesting::parent() {
+        Some(tag) => format!("<{}>", tag),
+        None => "the root".to_string(),
+    };
+    errors::warn(&format!(
+        "ruukh: a list of {} items without the keys is rendered within {} in {}, \
+         so they are mixed up once reordered; give each of them a `key`",
+        len,
+        parent,
+        errors::component_path()
+    ));
+}
+
 impl<RCTX: Render> DOMPatch for VList<RCTX> {
     type RenderContext = RCTX;
     type Node = Node;
@@ -50,10 +418,14 @@ impl<RCTX: Render> DOMPatch for VList<RCTX> {
         render_ctx: Shared<Self::RenderContext>,
         rx_sender: MessageSender,
     ) -> Result<(), JsValue> {
-        let mut next = next;
-        for (_, vnode) in self.0.iter_mut().rev() {
-            vnode.render_walk(parent, next, render_ctx.clone(), rx_sender.clone())?;
-            next = vnode.node();
+        // A static markup has no components to walk on.
+        if self.hoisted_from.is_some() {
+            return Ok(());
+        }
+        let mut next = next.cloned();
+        for (_, vnode) in self.items.iter_mut().rev() {
+            vnode.render_walk(parent, next.as_ref(), render_ctx.clone(), rx_sender.clone())?;
+            next = vnode.node().or(next);
         }
         Ok(())
     }
@@ -66,44 +438,85 @@ impl<RCTX: Render> DOMPatch for VList<RCTX> {
         render_ctx: Shared<Self::RenderContext>,
         rx_sender: MessageSender,
     ) -> Result<(), JsValue> {
-        let mut next = next;
+        let mut next = next.cloned();
         if let Some(old) = old {
+            // The same static markup is left as it was rendered.
+            if self.hoisted_from.is_some() && self.hoisted_from == old.hoisted_from {
+                mem::swap(&mut self.items, &mut old.items);
+                return Ok(());
+            }
+            if self.keys != Keys::Given && old.keys != Keys::Given && self.motion.is_none() {
+                return self.patch_unkeyed(old, parent, next, render_ctx, rx_sender);
+            }
+
+            // Record where the items were before they are patched, to be moved
+            // from there.
+            let flips = match self.motion {
+                Some(ref motion) => self
+                    .items
+                    .keys()
+                    .filter_map(|key| motion.first(&old.items.get(key)?.node()?))
+                    .collect(),
+                None => vec![],
+            };
+
             // Collect the keys of alive nodes from old vlist.
             let mut alive_keys = HashSet::with_hasher(FnvBuildHasher::default());
 
-            for (index, (key, vnode)) in self.0.iter_mut().enumerate().rev() {
+            for (index, (key, vnode)) in self.items.iter_mut().enumerate().rev() {
                 // Patch the old vnode if found.
-                if let Some((old_index, _, old)) = old.0.get_full_mut(key) {
+                if let Some((old_index, _, old)) = old.items.get_full_mut(key) {
                     vnode.patch(
                         Some(old),
                         parent,
-                        next,
+                        next.as_ref(),
                         render_ctx.clone(),
                         rx_sender.clone(),
                     )?;
 
                     // If the order changed, update it in the DOM.
                     if index != old_index {
-                        vnode.reorder(parent, next)?;
+                        vnode.reorder(parent, next.as_ref())?;
                     }
 
                     alive_keys.insert(key);
                 } else {
-                    vnode.patch(None, parent, next, render_ctx.clone(), rx_sender.clone())?;
+                    vnode.patch(
+                        None,
+                        parent,
+                        next.as_ref(),
+                        render_ctx.clone(),
+                        rx_sender.clone(),
+                    )?;
                 }
 
                 next = vnode.node().or(next);
             }
 
             // Remove all the remaining ones.
-            for (key, vnode) in old.0.iter() {
+            for (key, vnode) in old.items.iter() {
                 if !alive_keys.contains(key) {
                     vnode.remove(parent)?;
                 }
             }
+
+            if let Some(ref motion) = self.motion {
+                for flip in flips {
+                    motion.play(flip);
+                }
+            }
         } else {
-            for (_, vnode) in self.0.iter_mut().rev() {
-                vnode.patch(None, parent, next, render_ctx.clone(), rx_sender.clone())?;
+            if self.keys == Keys::Collected && self.items.len() > 1 {
+                warn_unkeyed(self.items.len());
+            }
+            for (_, vnode) in self.items.iter_mut().rev() {
+                vnode.patch(
+                    None,
+                    parent,
+                    next.as_ref(),
+                    render_ctx.clone(),
+                    rx_sender.clone(),
+                )?;
                 next = vnode.node().or(next);
             }
         }
@@ -111,24 +524,97 @@ impl<RCTX: Render> DOMPatch for VList<RCTX> {
     }
 
     fn reorder(&self, parent: &Node, next: Option<&Node>) -> Result<(), JsValue> {
-        for (_, node) in self.0.iter() {
+        for (_, node) in self.items.iter() {
             node.reorder(parent, next)?;
         }
         Ok(())
     }
 
     fn remove(&self, parent: &Self::Node) -> Result<(), JsValue> {
-        for (_, vnode) in self.0.iter() {
+        for (_, vnode) in self.items.iter() {
             vnode.remove(parent)?;
         }
         Ok(())
     }
 
-    fn node(&self) -> Option<&Node> {
-        self.0.get_index(0).and_then(|(_, first)| first.node())
+    fn node(&self) -> Option<Node> {
+        self.items.get_index(0).and_then(|(_, first)| first.node())
+    }
+}
+
+impl<RCTX: Render> WriteHtml for VList<RCTX> {
+    fn write_html(&self, w: &mut HtmlWriter<'_>) -> fmt::Result {
+        for (_, vnode) in self.items.iter() {
+            vnode.write_html(w)?;
+        }
+        Ok(())
+    }
+}
+
+impl<RCTX: Render> Measure for VList<RCTX> {
+    fn measure(&self, depth: usize, metrics: &mut Metrics) {
+        for (_, vnode) in self.items.iter() {
+            vnode.measure(depth, metrics);
+        }
+    }
+}
+
+impl<RCTX: Render> Dump for VList<RCTX> {
+    fn dump(&self, key: Option<&Key>, depth: usize, out: &mut String) {
+        // A list is not a node of its own, so the key it is given is the one
+        // of its first item.
+        let mut key = key;
+        for (item_key, vnode) in self.items.iter() {
+            let item_key = match self.keys {
+                Keys::Given => Some(item_key),
+                Keys::Written | Keys::Collected => key.take(),
+            };
+            vnode.dump(item_key, depth, out);
+        }
+    }
+}
+
+impl<RCTX: Render> Display for VList<RCTX> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write_html(&mut HtmlWriter::display(f))
     }
 }
 
+/// Builds a `VList` out of the items, which are either:
+/// * a node, i.e. anything which is `Into<VNode>`, keyed by its position,
+/// * a `key => node`, keyed by the key, or
+/// * a `..nodes` of an iterable, whose nodes are appended in place.
+///
+/// # Example
+/// ```ignore
+/// vlist![
+///     html!(<h2>"Todos"</h2>),
+///     ..self.todos.iter().map(|todo| todo.title.as_str()),
+///     "footer" => Footer::new(),
+/// ]
+/// ```
+#[macro_export]
+macro_rules! vlist {
+    (@push $list:ident;) => {};
+    (@push $list:ident; ..$nodes:expr $(, $($rest:tt)*)?) => {
+        $list.extend($nodes);
+        $crate::vlist!(@push $list; $($($rest)*)?);
+    };
+    (@push $list:ident; $key:expr => $node:expr $(, $($rest:tt)*)?) => {
+        $list.push_keyed($key, $node);
+        $crate::vlist!(@push $list; $($($rest)*)?);
+    };
+    (@push $list:ident; $node:expr $(, $($rest:tt)*)?) => {
+        $list.push($node);
+        $crate::vlist!(@push $list; $($($rest)*)?);
+    };
+    ($($items:tt)*) => {{
+        let mut list = $crate::vdom::vlist::VList::new();
+        $crate::vlist!(@push list; $($items)*);
+        list
+    }};
+}
+
 #[cfg(test)]
 pub mod test {
     use super::*;
@@ -147,7 +633,7 @@ pub mod test {
         let div = container();
         list.patch(
             None,
-            div.as_ref(),
+            &div.clone().into(),
             None,
             root_render_ctx(),
             crate::message_sender(),
@@ -166,7 +652,7 @@ pub mod test {
         let div = container();
         list.patch(
             None,
-            div.as_ref(),
+            &div.clone().into(),
             None,
             root_render_ctx(),
             crate::message_sender(),
@@ -183,7 +669,7 @@ pub mod test {
         new_list
             .patch(
                 Some(&mut list),
-                div.as_ref(),
+                &div.clone().into(),
                 None,
                 root_render_ctx(),
                 crate::message_sender(),
@@ -192,4 +678,80 @@ pub mod test {
 
         assert_eq!(div.inner_html(), "<div></div>Hello World!How are you?");
     }
+
+    #[wasm_bindgen_test]
+    fn should_patch_the_unkeyed_items_of_the_same_type() {
+        let mut list = VList::from(vec![
+            VNode::from(VElement::childless("div", vec![], vec![])),
+            VNode::from(VElement::childless("span", vec![], vec![])),
+        ]);
+        let div = container();
+        list.patch(
+            None,
+            &div.clone().into(),
+            None,
+            root_render_ctx(),
+            crate::message_sender(),
+        )
+        .expect("To patch div");
+        let old_span = div.last_child().unwrap();
+
+        let mut new_list = VList::from(vec![
+            VNode::from(VElement::childless("p", vec![], vec![])),
+            VNode::from(VElement::childless("span", vec![], vec![])),
+        ]);
+        new_list
+            .patch(
+                Some(&mut list),
+                &div.clone().into(),
+                None,
+                root_render_ctx(),
+                crate::message_sender(),
+            )
+            .expect("To patch div");
+
+        assert_eq!(div.inner_html(), "<p></p><span></span>");
+        assert!(div.last_child().unwrap().is_same_node(Some(&old_span)));
+    }
+
+    #[wasm_bindgen_test]
+    fn should_move_the_keyed_nested_lists_along_with_their_items() {
+        let nested = |keys: [&str; 2]| {
+            let mut list = VList::new();
+            for key in keys.iter() {
+                let items = if *key == "a" {
+                    vec![VNode::from(VText::text("A1")), VNode::from(VText::text("A2"))]
+                } else {
+                    vec![VNode::from(VElement::childless("hr", vec![], vec![]))]
+                };
+                list.push_keyed(*key, VList::from(items));
+            }
+            list
+        };
+        let mut list = nested(["a", "b"]);
+        let div = container();
+        list.patch(
+            None,
+            &div.clone().into(),
+            None,
+            root_render_ctx(),
+            crate::message_sender(),
+        )
+        .expect("To patch div");
+
+        assert_eq!(div.inner_html(), "A1A2<hr>");
+
+        let mut moved = nested(["b", "a"]);
+        moved
+            .patch(
+                Some(&mut list),
+                &div.clone().into(),
+                None,
+                root_render_ctx(),
+                crate::message_sender(),
+            )
+            .expect("To patch div");
+
+        assert_eq!(div.inner_html(), "<hr>A1A2");
+    }
 }
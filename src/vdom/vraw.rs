@@ -0,0 +1,579 @@
+//! Rendering the HTML which is only known at runtime, like the user
+//! generated content, safely.
+//!
+//! The HTML of a [VRaw](struct.VRaw.html) is parsed & sanitized against the
+//! allow-list of a [Sanitizer](struct.Sanitizer.html), and then rendered as
+//! the vnodes it is made of, on any backend & on the server alike. The tags
+//! which are not allowed are unwrapped, keeping their text, except for the
+//! ones like `<script>` & `<style>` which are dropped along with their
+//! content. The attributes which are not allowed, like the `on*` handlers,
+//! are dropped, and so are the URLs of the schemes other than `http`,
+//! `https` & `mailto`, like `javascript:`.
+//!
+//! # Example
+//! ```ignore
+//! html! {
+//!     <article class="comment">
+//!         { VRaw::sanitized(&comment.body) }
+//!     </article>
+//! }
+//! ```
+
+use crate::{
+    component::Render,
+    html,
+    vdom::{velement::VElement, vlist::VList, vtext::VText, VNode},
+};
+use std::collections::{HashMap, HashSet};
+
+/// The tags allowed by default, which format the text.
+const TAGS: &[&str] = &[
+    "a",
+    "abbr",
+    "b",
+    "blockquote",
+    "br",
+    "caption",
+    "cite",
+    "code",
+    "col",
+    "colgroup",
+    "dd",
+    "del",
+    "details",
+    "dfn",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "figcaption",
+    "figure",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "i",
+    "img",
+    "ins",
+    "kbd",
+    "li",
+    "mark",
+    "ol",
+    "p",
+    "pre",
+    "q",
+    "s",
+    "samp",
+    "small",
+    "span",
+    "strong",
+    "sub",
+    "summary",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "time",
+    "tr",
+    "u",
+    "ul",
+    "var",
+];
+
+/// The attributes allowed by default, by the tags they are allowed on, where
+/// `*` is any tag.
+const ATTRIBUTES: &[(&str, &[&str])] = &[
+    ("*", &["title", "lang", "dir"]),
+    ("a", &["href"]),
+    ("img", &["src", "alt", "width", "height"]),
+    ("blockquote", &["cite"]),
+    ("q", &["cite"]),
+    ("del", &["cite", "datetime"]),
+    ("ins", &["cite", "datetime"]),
+    ("time", &["datetime"]),
+    ("ol", &["start", "reversed"]),
+    ("li", &["value"]),
+    ("td", &["colspan", "rowspan"]),
+    ("th", &["colspan", "rowspan", "scope"]),
+    ("col", &["span"]),
+    ("colgroup", &["span"]),
+    ("details", &["open"]),
+];
+
+/// The schemes of the URLs allowed by default.
+pub(crate) const SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// The attributes which hold a URL.
+pub(crate) const URL_ATTRIBUTES: &[&str] =
+    &["href", "src", "cite", "action", "formaction", "poster"];
+
+/// The tags which are dropped along with their content, unless allowed.
+const DROPPED_TAGS: &[&str] = &[
+    "script", "style", "template", "iframe", "object", "embed", "noscript", "textarea", "select",
+    "title", "head", "svg", "math", "xmp", "noembed", "noframes",
+];
+
+/// The tags whose content is not parsed as HTML.
+const RAW_TEXT_TAGS: &[&str] = &[
+    "script", "style", "textarea", "title", "iframe", "xmp", "noembed", "noframes",
+];
+
+/// The depth of the elements past which their tags are dropped & their
+/// content is kept within the innermost one, so that a hostile markup nested
+/// too deep does not overflow the stack while it is walked on.
+const MAX_DEPTH: usize = 128;
+
+/// The HTML which is rendered once sanitized.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VRaw {
+    nodes: Vec<RawNode>,
+}
+
+/// The tags, attributes & the URL schemes allowed to be rendered.
+#[derive(Clone, Debug)]
+pub struct Sanitizer {
+    tags: HashSet<&'static str>,
+    /// The allowed attributes by the tags, where `*` is any tag.
+    attributes: HashMap<&'static str, HashSet<&'static str>>,
+    schemes: HashSet<String>,
+}
+
+/// A sanitized node.
+#[derive(Clone, Debug, PartialEq)]
+enum RawNode {
+    Text(String),
+    Element {
+        tag: &'static str,
+        attributes: Vec<(&'static str, String)>,
+        children: Vec<RawNode>,
+    },
+}
+
+/// A node as it is parsed.
+#[derive(Debug)]
+pub(crate) enum Parsed {
+    Text(String),
+    Element(ParsedElement),
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ParsedElement {
+    pub(crate) tag: String,
+    pub(crate) attributes: Vec<(String, String)>,
+    pub(crate) children: Vec<Parsed>,
+}
+
+impl VRaw {
+    /// Parses & sanitizes the HTML with the default allow-list of the
+    /// [Sanitizer](struct.Sanitizer.html).
+    pub fn sanitized(html: &str) -> VRaw {
+        VRaw::sanitized_with(html, &Sanitizer::new())
+    }
+
+    /// Parses & sanitizes the HTML with the allow-list of the sanitizer.
+    pub fn sanitized_with(html: &str, sanitizer: &Sanitizer) -> VRaw {
+        VRaw {
+            nodes: sanitizer.sanitize(parse(html)),
+        }
+    }
+
+    /// Whether nothing is left to be rendered.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+impl<RCTX: Render> From<VRaw> for VNode<RCTX> {
+    fn from(raw: VRaw) -> VNode<RCTX> {
+        to_vnode(raw.nodes)
+    }
+}
+
+fn to_vnode<RCTX: Render>(mut nodes: Vec<RawNode>) -> VNode<RCTX> {
+    match nodes.len() {
+        0 => VNode::None,
+        1 => match nodes.remove(0) {
+            RawNode::Text(text) => VText::text(text).into(),
+            RawNode::Element {
+                tag,
+                attributes,
+                children,
+            } => VElement::new(tag, vec![], vec![], Some(to_vnode(children)))
+                .with_attributes(attributes)
+                .into(),
+        },
+        _ => VList::from(
+            nodes
+                .into_iter()
+                .map(|node| to_vnode(vec![node]))
+                .collect::<Vec<_>>(),
+        )
+        .into(),
+    }
+}
+
+impl Default for Sanitizer {
+    fn default() -> Sanitizer {
+        let mut sanitizer = Sanitizer {
+            tags: HashSet::new(),
+            attributes: HashMap::new(),
+            schemes: HashSet::new(),
+        }
+        .allow_tags(TAGS)
+        .allow_schemes(SCHEMES);
+        for (tag, attributes) in ATTRIBUTES {
+            sanitizer = sanitizer.allow_attributes(tag, attributes);
+        }
+        sanitizer
+    }
+}
+
+impl Sanitizer {
+    /// Creates a sanitizer with the default allow-list, which is fit for the
+    /// formatted text like the comments & the posts.
+    pub fn new() -> Sanitizer {
+        Sanitizer::default()
+    }
+
+    /// Allows the tags as well. The `<script>` & the `<style>` are best left
+    /// out.
+    pub fn allow_tags(mut self, tags: &[&'static str]) -> Sanitizer {
+        self.tags.extend(tags);
+        self
+    }
+
+    /// Allows the attributes on the tag as well, or on any tag if it is `*`.
+    /// The URLs within them are checked if they are among `href`, `src`,
+    /// `cite`, `action`, `formaction` & `poster`.
+    pub fn allow_attributes(mut self, tag: &'static str, attributes: &[&'static str]) -> Sanitizer {
+        self.attributes
+            .entry(tag)
+            .or_default()
+            .extend(attributes);
+        self
+    }
+
+    /// Allows the URLs of the schemes as well, like `tel`.
+    pub fn allow_schemes(mut self, schemes: &[&str]) -> Sanitizer {
+        self.schemes
+            .extend(schemes.iter().map(|scheme| scheme.to_ascii_lowercase()));
+        self
+    }
+
+    fn sanitize(&self, nodes: Vec<Parsed>) -> Vec<RawNode> {
+        let mut sanitized = vec![];
+        for node in nodes {
+            let element = match node {
+                Parsed::Text(text) => {
+                    append(&mut sanitized, RawNode::Text(text));
+                    continue;
+                }
+                Parsed::Element(element) => element,
+            };
+            match self.tags.get(element.tag.as_str()) {
+                Some(&tag) => sanitized.push(RawNode::Element {
+                    tag,
+                    attributes: element
+                        .attributes
+                        .into_iter()
+                        .filter_map(|(name, value)| {
+                            let name = self.allowed_attribute(tag, &name)?;
+                            if URL_ATTRIBUTES.contains(&name) && !self.is_url_allowed(&value) {
+                                return None;
+                            }
+                            Some((name, value))
+                        })
+                        .collect(),
+                    children: self.sanitize(element.children),
+                }),
+                None if DROPPED_TAGS.contains(&element.tag.as_str()) => {}
+                // The text within is still of interest.
+                None => {
+                    for node in self.sanitize(element.children) {
+                        append(&mut sanitized, node);
+                    }
+                }
+            }
+        }
+        sanitized
+    }
+
+    fn allowed_attribute(&self, tag: &str, name: &str) -> Option<&'static str> {
+        [tag, "*"].iter().find_map(|tag| {
+            self.attributes
+                .get(*tag)
+                .and_then(|attributes| attributes.get(name))
+                .cloned()
+        })
+    }
+
+    /// Whether the URL is relative or of an allowed scheme.
+    pub(crate) fn is_url_allowed(&self, url: &str) -> bool {
+        match url_scheme(url) {
+            Some(scheme) => self.schemes.contains(&scheme),
+            None => true,
+        }
+    }
+}
+
+/// The scheme of the URL in lowercase, unless it is relative. The whitespaces
+/// & the control characters, which the browsers ignore within a scheme, are
+/// ignored as well.
+pub(crate) fn url_scheme(url: &str) -> Option<String> {
+    let url: String = url
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect();
+    match url.find([':', '/', '?', '#']) {
+        Some(end) if url[end..].starts_with(':') => Some(url[..end].to_ascii_lowercase()),
+        _ => None,
+    }
+}
+
+/// Appends the node, merging the adjacent texts, like the ones around an
+/// unwrapped element.
+fn append(nodes: &mut Vec<RawNode>, node: RawNode) {
+    match (nodes.last_mut(), node) {
+        (Some(RawNode::Text(text)), RawNode::Text(more)) => text.push_str(&more),
+        (_, node) => nodes.push(node),
+    }
+}
+
+/// Parses the HTML leniently, the way a browser would for the most part. The
+/// comments, doctypes & processing instructions are dropped, the elements
+/// left open are closed at the end, and the ones nested past `MAX_DEPTH` are
+/// flattened into their content.
+pub(crate) fn parse(html: &str) -> Vec<Parsed> {
+    // The root is at the bottom of the open elements.
+    let mut open = vec![ParsedElement::default()];
+    // The tags of the elements opened past the depth, which are dropped.
+    let mut flattened: Vec<String> = vec![];
+    let mut rest = html;
+    while !rest.is_empty() {
+        if rest.starts_with("<!--") {
+            rest = rest[4..].find("-->").map_or("", |end| &rest[4 + end + 3..]);
+        } else if rest.starts_with("</") {
+            rest = match tag_name(&rest[2..]) {
+                Some((tag, after)) => {
+                    if flattened.last() == Some(&tag) {
+                        flattened.pop();
+                    } else {
+                        flattened.clear();
+                        close(&mut open, &tag);
+                    }
+                    skip_tag(after)
+                }
+                // A bogus comment, like `</ >`.
+                None => skip_tag(&rest[2..]),
+            };
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = skip_tag(&rest[2..]);
+        } else if let Some((tag, after)) = rest.strip_prefix('<').and_then(tag_name) {
+            let (attributes, after) = parse_attributes(after);
+            let mut element = ParsedElement {
+                tag,
+                attributes,
+                children: vec![],
+            };
+            rest = after;
+            if RAW_TEXT_TAGS.contains(&element.tag.as_str()) {
+                let (text, after) = raw_text(rest, &element.tag);
+                let text = match element.tag.as_str() {
+                    "textarea" | "title" => decode(text),
+                    _ => text.to_string(),
+                };
+                element.children.push(Parsed::Text(text));
+                push(&mut open, Parsed::Element(element));
+                rest = after;
+            } else if html::is_void(&element.tag) {
+                push(&mut open, Parsed::Element(element));
+            } else if open.len() > MAX_DEPTH {
+                flattened.push(element.tag);
+            } else {
+                open.push(element);
+            }
+        } else {
+            // A `<` which does not start a tag is a text.
+            let first = rest.chars().next().map_or(1, char::len_utf8);
+            let end = rest[first..]
+                .find('<')
+                .map_or(rest.len(), |end| end + first);
+            push(&mut open, Parsed::Text(decode(&rest[..end])));
+            rest = &rest[end..];
+        }
+    }
+    while open.len() > 1 {
+        let element = open.pop().unwrap();
+        push(&mut open, Parsed::Element(element));
+    }
+    open.pop().unwrap().children
+}
+
+/// Appends the node into the innermost open element, merging the adjacent
+/// texts.
+fn push(open: &mut [ParsedElement], node: Parsed) {
+    let children = &mut open.last_mut().unwrap().children;
+    match (children.last_mut(), node) {
+        (Some(Parsed::Text(text)), Parsed::Text(more)) => text.push_str(&more),
+        (_, node) => children.push(node),
+    }
+}
+
+/// Closes the innermost open element of the tag, along with the ones opened
+/// within it. A closing tag without an open element is ignored.
+fn close(open: &mut Vec<ParsedElement>, tag: &str) {
+    if let Some(index) = open.iter().skip(1).rposition(|element| element.tag == tag) {
+        while open.len() > index + 1 {
+            let element = open.pop().unwrap();
+            push(open, Parsed::Element(element));
+        }
+    }
+}
+
+/// The lowercased name of the tag which starts the input, if it does.
+fn tag_name(input: &str) -> Option<(String, &str)> {
+    if !input.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let end = input
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .unwrap_or(input.len());
+    Some((input[..end].to_ascii_lowercase(), &input[end..]))
+}
+
+/// Skips past the end of the tag.
+fn skip_tag(input: &str) -> &str {
+    input.find('>').map_or("", |end| &input[end + 1..])
+}
+
+/// Parses the attributes until the end of the tag.
+fn parse_attributes(mut input: &str) -> (Vec<(String, String)>, &str) {
+    let mut attributes: Vec<(String, String)> = vec![];
+    loop {
+        input = input.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        if input.is_empty() {
+            return (attributes, input);
+        }
+        if let Some(after) = input.strip_prefix('>') {
+            return (attributes, after);
+        }
+        // The first character is a part of the name, even if it is a `=`.
+        let first = input.chars().next().map_or(1, char::len_utf8);
+        let end = input[first..]
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '/' || c == '>')
+            .map_or(input.len(), |end| end + first);
+        let name = input[..end].to_ascii_lowercase();
+        input = input[end..].trim_start();
+        let mut value = String::new();
+        if input.starts_with('=') {
+            input = input[1..].trim_start();
+            let (raw, after) = match input.chars().next() {
+                Some(quote @ '"') | Some(quote @ '\'') => {
+                    let end = input[1..].find(quote).map_or(input.len(), |end| end + 1);
+                    (&input[1..end], input.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = input
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .unwrap_or(input.len());
+                    (&input[..end], &input[end..])
+                }
+            };
+            value = decode(raw);
+            input = after;
+        }
+        // The first one of the repeated attributes wins.
+        if !attributes.iter().any(|(existing, _)| *existing == name) {
+            attributes.push((name, value));
+        }
+    }
+}
+
+/// The text of a raw text element up to its closing tag, and the input after
+/// it.
+fn raw_text<'a>(input: &'a str, tag: &str) -> (&'a str, &'a str) {
+    let closing = format!("</{}", tag);
+    let lowercased = input.to_ascii_lowercase();
+    match lowercased.find(&closing) {
+        Some(end) => (&input[..end], skip_tag(&input[end..])),
+        None => (input, ""),
+    }
+}
+
+/// Decodes the character references of a text or an attribute value. The
+/// ones which are not known are left as is.
+fn decode(input: &str) -> String {
+    if !input.contains('&') {
+        return input.to_string();
+    }
+    let mut decoded = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let reference = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 32)
+            .and_then(|end| Some((character(&rest[1..end + 1])?, end + 2)));
+        match reference {
+            Some((c, len)) => {
+                decoded.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// The character of the reference, like `amp` or `#x27`.
+pub(crate) fn character(reference: &str) -> Option<char> {
+    if let Some(hex) = reference
+        .strip_prefix("#x")
+        .or_else(|| reference.strip_prefix("#X"))
+    {
+        return u32::from_str_radix(hex, 16)
+            .ok()
+            .and_then(std::char::from_u32);
+    }
+    if let Some(decimal) = reference.strip_prefix('#') {
+        return decimal.parse().ok().and_then(std::char::from_u32);
+    }
+    let c = match reference {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "hellip" => '…',
+        "mdash" => '—',
+        "ndash" => '–',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "middot" => '·',
+        "bull" => '•',
+        "times" => '×',
+        "deg" => '°',
+        "euro" => '€',
+        _ => return None,
+    };
+    Some(c)
+}
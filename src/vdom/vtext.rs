@@ -1,9 +1,19 @@
 //! Representation of text/comment in virtual dom tree.
 
-use crate::{component::Render, dom::DOMPatch, vdom::VNode, MessageSender, Shared};
-use std::marker::PhantomData;
+use crate::{
+    backend::Node,
+    component::Render,
+    dom::DOMPatch,
+    html::{HtmlWriter, WriteHtml},
+    vdom::{
+        dump::{self, Dump},
+        metrics::{Measure, Metrics},
+        Key, VNode,
+    },
+    MessageSender, Shared,
+};
+use std::{fmt::{self, Display, Formatter}, marker::PhantomData};
 use wasm_bindgen::prelude::JsValue;
-use web_sys::{window, Node};
 
 /// The representation of text/comment in virtual dom tree.
 pub struct VText<RCTX> {
@@ -40,21 +50,26 @@ impl<RCTX> VText<RCTX> {
 }
 
 impl<RCTX> VText<RCTX> {
+    /// The content of the text or the comment.
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// Whether it is a comment.
+    pub fn is_comment(&self) -> bool {
+        self.is_comment
+    }
+
+    /// Takes the node cloned from a template as its own.
+    pub(crate) fn adopt(&mut self, node: Node) {
+        self.node = Some(node);
+    }
+
     fn patch_new(&mut self, parent: &Node, next: Option<&Node>) -> Result<(), JsValue> {
-        let node: Node = if self.is_comment {
-            window()
-                .unwrap()
-                .document()
-                .unwrap()
-                .create_comment(&self.content)
-                .into()
+        let node = if self.is_comment {
+            parent.create_comment(&self.content)?
         } else {
-            window()
-                .unwrap()
-                .document()
-                .unwrap()
-                .create_text_node(&self.content)
-                .into()
+            parent.create_text_node(&self.content)?
         };
         parent.insert_before(&node, next)?;
         self.node = Some(node);
@@ -62,6 +77,18 @@ impl<RCTX> VText<RCTX> {
     }
 }
 
+/// Clones the content, which is created anew when patched.
+impl<RCTX> Clone for VText<RCTX> {
+    fn clone(&self) -> Self {
+        VText {
+            content: self.content.clone(),
+            is_comment: self.is_comment,
+            node: None,
+            _phantom: PhantomData,
+        }
+    }
+}
+
 impl<RCTX> From<VText<RCTX>> for VNode<RCTX> {
     fn from(text: VText<RCTX>) -> VNode<RCTX> {
         VNode::Text(text)
@@ -91,13 +118,11 @@ impl<RCTX: Render> DOMPatch for VText<RCTX> {
         _: MessageSender,
     ) -> Result<(), JsValue> {
         if let Some(old) = old {
-            if self.is_comment == old.is_comment {
-                let old_node = old
-                    .node
-                    .as_ref()
-                    .expect("The old node is expected to be attached to the DOM");
+            // The old node is missing if it failed to be patched.
+            let old_node = old.node.as_ref().filter(|_| self.is_comment == old.is_comment);
+            if let Some(old_node) = old_node {
                 if self.content != old.content {
-                    old_node.set_text_content(Some(&self.content));
+                    old_node.set_text_content(&self.content)?;
                 }
                 self.node = Some(old_node.clone());
                 Ok(())
@@ -111,22 +136,55 @@ impl<RCTX: Render> DOMPatch for VText<RCTX> {
     }
 
     fn reorder(&self, parent: &Node, next: Option<&Node>) -> Result<(), JsValue> {
-        let node = self.node.as_ref().unwrap();
-        parent.insert_before(node, next)?;
+        if let Some(ref node) = self.node {
+            parent.move_before(node, next)?;
+        }
         Ok(())
     }
 
     fn remove(&self, parent: &Node) -> Result<(), JsValue> {
-        parent.remove_child(
-            self.node
-                .as_ref()
-                .expect("The old node is expected to be attached to the DOM"),
-        )?;
+        // There is nothing to remove if it failed to be patched.
+        if let Some(ref node) = self.node {
+            parent.remove_child(node)?;
+        }
         Ok(())
     }
 
-    fn node(&self) -> Option<&Node> {
-        self.node.as_ref()
+    fn node(&self) -> Option<Node> {
+        self.node.clone()
+    }
+}
+
+impl<RCTX> WriteHtml for VText<RCTX> {
+    fn write_html(&self, w: &mut HtmlWriter<'_>) -> fmt::Result {
+        if self.is_comment {
+            w.comment(&self.content)
+        } else {
+            w.text(&self.content)
+        }
+    }
+}
+
+impl<RCTX> Measure for VText<RCTX> {
+    fn measure(&self, depth: usize, metrics: &mut Metrics) {
+        metrics.add_node(depth);
+    }
+}
+
+impl<RCTX> Dump for VText<RCTX> {
+    fn dump(&self, key: Option<&Key>, depth: usize, out: &mut String) {
+        let line = if self.is_comment {
+            format!("<!--{}-->", self.content)
+        } else {
+            format!("{:?}", self.content)
+        };
+        dump::write_line(out, key, depth, &line);
+    }
+}
+
+impl<RCTX> Display for VText<RCTX> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write_html(&mut HtmlWriter::display(f))
     }
 }
 
@@ -143,7 +201,7 @@ pub mod test {
         vtext
             .patch(
                 None,
-                div.as_ref(),
+                &div.clone().into(),
                 None,
                 root_render_ctx(),
                 crate::message_sender(),
@@ -160,7 +218,7 @@ pub mod test {
         vtext
             .patch(
                 None,
-                div.as_ref(),
+                &div.clone().into(),
                 None,
                 root_render_ctx(),
                 crate::message_sender(),
@@ -173,7 +231,7 @@ pub mod test {
         updated
             .patch(
                 Some(&mut vtext),
-                div.as_ref(),
+                &div.clone().into(),
                 None,
                 root_render_ctx(),
                 crate::message_sender(),
@@ -190,7 +248,7 @@ pub mod test {
         comment
             .patch(
                 None,
-                div.as_ref(),
+                &div.clone().into(),
                 None,
                 root_render_ctx(),
                 crate::message_sender(),
@@ -207,7 +265,7 @@ pub mod test {
         comment
             .patch(
                 None,
-                div.as_ref(),
+                &div.clone().into(),
                 None,
                 root_render_ctx(),
                 crate::message_sender(),
@@ -219,7 +277,7 @@ pub mod test {
         let mut text = VText::text("This is a text");
         text.patch(
             Some(&mut comment),
-            div.as_ref(),
+            &div.clone().into(),
             None,
             root_render_ctx(),
             crate::message_sender(),
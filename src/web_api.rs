@@ -0,0 +1,36 @@
+//! Rust friendly bindings to the Web APIs commonly required by the apps.
+//!
+//! These are thin wrappers over `web-sys` which take care of getting hold of
+//! the window and of the cleanup of the callbacks passed to the browser.
+
+pub use self::{
+    animation::{request_animation_frame, AnimationFrame, AnimationLoop},
+    events::{listen, on_click_outside, EventListenerHandle},
+    intersection::{on_visible, IntersectionObserver},
+    mutation::MutationObserver,
+    storage::{Storage, StoredItem},
+    timers::{debounce, set_interval, set_timeout, throttle, Interval, Timeout},
+    websocket::{Message, WebSocket},
+};
+
+mod animation;
+pub mod clipboard;
+pub mod drag;
+pub mod editing;
+pub mod elements;
+pub mod files;
+pub mod flip;
+mod events;
+pub mod history;
+mod intersection;
+pub mod intl;
+pub mod keyboard;
+pub mod location;
+pub mod measure;
+pub mod media;
+mod mutation;
+pub mod pointer;
+pub mod scroll;
+mod storage;
+pub(crate) mod timers;
+mod websocket;
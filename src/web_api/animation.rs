@@ -0,0 +1,110 @@
+//! The animation frames which are invoked before the browser repaints.
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::window;
+
+/// A handle to an animation frame requested via
+/// [request_animation_frame](fn.request_animation_frame.html). The request is
+/// cancelled when the handle is dropped.
+#[must_use = "The animation frame is cancelled as soon as the handle is dropped"]
+pub struct AnimationFrame {
+    id: i32,
+    _closure: Closure<dyn FnMut(f64)>,
+}
+
+/// Invokes the handler once before the next repaint with the timestamp of the
+/// frame in milliseconds.
+pub fn request_animation_frame(
+    handler: impl FnOnce(f64) + 'static,
+) -> Result<AnimationFrame, JsValue> {
+    let mut handler = Some(handler);
+    let closure: Closure<dyn FnMut(f64)> = Closure::wrap(Box::new(move |timestamp| {
+        if let Some(handler) = handler.take() {
+            handler(timestamp);
+        }
+    }));
+    let id = window()
+        .unwrap()
+        .request_animation_frame(closure.as_ref().unchecked_ref())?;
+    Ok(AnimationFrame {
+        id,
+        _closure: closure,
+    })
+}
+
+impl AnimationFrame {
+    /// Cancels the request for the animation frame. Same as dropping it.
+    pub fn cancel(self) {}
+}
+
+impl Drop for AnimationFrame {
+    fn drop(&mut self) {
+        // Cancelling a frame which has already been invoked is a no-op.
+        let _ = window().unwrap().cancel_animation_frame(self.id);
+    }
+}
+
+type FrameClosure = Closure<dyn FnMut(f64)>;
+
+/// Invokes a handler on every animation frame until it is dropped.
+///
+/// # Example
+/// ```ignore
+/// fn mounted(&self) {
+///     let setter = self.state_setter();
+///     let animation = AnimationLoop::start(move |timestamp| {
+///         setter.set_state(|state| state.angle = timestamp / 10.0 % 360.0);
+///     })
+///     .unwrap();
+///     // Stop animating once the component is destroyed.
+///     self.scope().keep(animation);
+/// }
+/// ```
+#[must_use = "The animation loop is stopped as soon as it is dropped"]
+pub struct AnimationLoop {
+    id: Rc<Cell<i32>>,
+    closure: Rc<RefCell<Option<FrameClosure>>>,
+}
+
+impl AnimationLoop {
+    /// Starts invoking the handler with the timestamp of every frame in
+    /// milliseconds.
+    pub fn start(mut handler: impl FnMut(f64) + 'static) -> Result<AnimationLoop, JsValue> {
+        let id = Rc::new(Cell::new(0));
+        let closure = Rc::new(RefCell::new(None));
+
+        let next_id = id.clone();
+        let next_closure = closure.clone();
+        *closure.borrow_mut() = Some(Closure::wrap(Box::new(move |timestamp| {
+            handler(timestamp);
+            // Request the next frame, unless the loop is stopped.
+            if let Some(ref closure) = *next_closure.borrow() {
+                next_id.set(request_frame(closure).expect("Could not request a frame"));
+            }
+        }) as Box<dyn FnMut(f64)>));
+
+        id.set(request_frame(closure.borrow().as_ref().unwrap())?);
+        Ok(AnimationLoop { id, closure })
+    }
+
+    /// Stops the loop. Same as dropping it.
+    pub fn stop(self) {}
+}
+
+fn request_frame(closure: &Closure<dyn FnMut(f64)>) -> Result<i32, JsValue> {
+    window()
+        .unwrap()
+        .request_animation_frame(closure.as_ref().unchecked_ref())
+}
+
+impl Drop for AnimationLoop {
+    fn drop(&mut self) {
+        let _ = window().unwrap().cancel_animation_frame(self.id.get());
+        // The closure refers to itself, so it has to be dropped explicitly.
+        self.closure.borrow_mut().take();
+    }
+}
@@ -0,0 +1,53 @@
+//! The system clipboard and the `copy`, `cut` & `paste` events.
+//!
+//! # Example
+//! ```ignore
+//! fn on_click(&self, _: Event) {
+//!     let url = self.url.clone();
+//!     wasm_bindgen_futures::spawn_local(async move {
+//!         clipboard::write_text(&url).await.unwrap();
+//!     });
+//! }
+//! ```
+
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{window, Clipboard, ClipboardEvent, Event};
+
+fn clipboard() -> Clipboard {
+    window().unwrap().navigator().clipboard()
+}
+
+/// Reads the text in the clipboard. The browser may ask the user for the
+/// permission to do so.
+pub async fn read_text() -> Result<String, JsValue> {
+    let text = JsFuture::from(clipboard().read_text()).await?;
+    Ok(text.as_string().unwrap_or_default())
+}
+
+/// Writes the text into the clipboard.
+pub async fn write_text(text: &str) -> Result<(), JsValue> {
+    JsFuture::from(clipboard().write_text(text)).await?;
+    Ok(())
+}
+
+/// Gets the text being pasted from a `paste` event.
+pub fn pasted_text(event: &Event) -> Option<String> {
+    event
+        .dyn_ref::<ClipboardEvent>()?
+        .clipboard_data()?
+        .get_data("text/plain")
+        .ok()
+}
+
+/// Replaces the text being copied in a `copy` or `cut` event, instead of the
+/// selected text.
+pub fn set_copied_text(event: &Event, text: &str) -> Result<(), JsValue> {
+    let data = event
+        .dyn_ref::<ClipboardEvent>()
+        .and_then(|event| event.clipboard_data())
+        .ok_or_else(|| JsValue::from_str("Not a copy or cut event"))?;
+    data.set_data("text/plain", text)?;
+    event.prevent_default();
+    Ok(())
+}
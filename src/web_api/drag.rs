@@ -0,0 +1,57 @@
+//! The data carried along by the `dragstart`, `dragover` & `drop` events.
+//!
+//! An element is made draggable with a `draggable={"true"}` attribute, and a
+//! drop target by preventing the default of its `dragover` event. The
+//! `draggable` & `drop_target` methods of a `VElement` do so too.
+//!
+//! # Example
+//! ```ignore
+//! fn on_drag_start(&self, event: Event) {
+//!     drag::set_data(&event, "text/plain", &self.id.to_string()).unwrap();
+//!     drag::set_effect_allowed(&event, "move");
+//! }
+//!
+//! fn on_drop(&self, event: Event) {
+//!     event.prevent_default();
+//!     let id = drag::data(&event, "text/plain");
+//! }
+//! ```
+
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{DragEvent, Event};
+
+pub use web_sys::DataTransfer;
+
+/// Gets the data transfer of a drag event.
+pub fn data_transfer(event: &Event) -> Option<DataTransfer> {
+    event.dyn_ref::<DragEvent>()?.data_transfer()
+}
+
+/// Sets the data of the given format, e.g. `text/plain`, being dragged in a
+/// `dragstart` event.
+pub fn set_data(event: &Event, format: &str, data: &str) -> Result<(), JsValue> {
+    data_transfer(event)
+        .ok_or_else(|| JsValue::from_str("Not a drag event"))?
+        .set_data(format, data)
+}
+
+/// Gets the data of the given format being dropped in a `drop` event.
+pub fn data(event: &Event, format: &str) -> Option<String> {
+    data_transfer(event)?.get_data(format).ok()
+}
+
+/// Sets the effects allowed on the data being dragged, e.g. `copy`, `move` or
+/// `copyMove`.
+pub fn set_effect_allowed(event: &Event, effect: &str) {
+    if let Some(data) = data_transfer(event) {
+        data.set_effect_allowed(effect);
+    }
+}
+
+/// Sets the effect shown to the user while dragging over a drop target, e.g.
+/// `copy` or `move`.
+pub fn set_drop_effect(event: &Event, effect: &str) {
+    if let Some(data) = data_transfer(event) {
+        data.set_drop_effect(effect);
+    }
+}
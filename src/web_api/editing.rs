@@ -0,0 +1,52 @@
+//! The `contenteditable` elements and the `input` & composition events fired
+//! while editing them.
+//!
+//! The content of an editable element is left as edited by the user, until the
+//! content it renders changes. While an input method composes a text, the
+//! `input` events are better ignored until the `compositionend`.
+//!
+//! # Example
+//! ```ignore
+//! fn on_input(&self, event: Event) {
+//!     if editing::is_composing(&event) {
+//!         return;
+//!     }
+//!     let text = editing::editable_text(&event).unwrap_or_default();
+//!     self.set_state(|state| state.text = text);
+//! }
+//! ```
+
+use wasm_bindgen::JsCast;
+use web_sys::{CompositionEvent, Event, HtmlElement, InputEvent};
+
+/// Gets the text inserted by an `input` event, if any.
+pub fn input_data(event: &Event) -> Option<String> {
+    event.dyn_ref::<InputEvent>()?.data()
+}
+
+/// Gets the kind of edit of an `input` event, e.g. `insertText` or
+/// `deleteContentBackward`.
+pub fn input_type(event: &Event) -> Option<String> {
+    event.dyn_ref::<InputEvent>().map(InputEvent::input_type)
+}
+
+/// Whether an `input` event is fired while an input method is composing the
+/// text.
+pub fn is_composing(event: &Event) -> bool {
+    event
+        .dyn_ref::<InputEvent>()
+        .is_some_and(InputEvent::is_composing)
+}
+
+/// Gets the text being composed by a `compositionstart`,
+/// `compositionupdate` or `compositionend` event.
+pub fn composition_data(event: &Event) -> Option<String> {
+    event.dyn_ref::<CompositionEvent>()?.data()
+}
+
+/// Gets the text of the editable element listening to the event, as rendered
+/// to the user.
+pub fn editable_text(event: &Event) -> Option<String> {
+    let el = event.current_target()?.dyn_into::<HtmlElement>().ok()?;
+    Some(el.inner_text())
+}
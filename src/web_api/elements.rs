@@ -0,0 +1,161 @@
+//! Finding & creating the elements and changing their classes & properties.
+//!
+//! # Example
+//! ```ignore
+//! if let Some(menu) = elements::query_selector("nav .menu")? {
+//!     elements::class_list(&menu).toggle("open")?;
+//! }
+//! ```
+
+use js_sys::Reflect;
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{window, Document, DomTokenList, Element, Node, NodeList};
+
+/// The namespace of the SVG elements.
+pub const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+/// The namespace of the MathML elements.
+pub const MATHML_NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+fn document() -> Document {
+    window().unwrap().document().unwrap()
+}
+
+/// The classes of an element.
+pub struct ClassList(DomTokenList);
+
+/// Gets the classes of the element.
+pub fn class_list(element: &Element) -> ClassList {
+    ClassList(element.class_list())
+}
+
+impl ClassList {
+    /// Adds the class, unless it is there already.
+    pub fn add(&self, class: &str) -> Result<(), JsValue> {
+        self.0.add_1(class)
+    }
+
+    /// Removes the class, if it is there.
+    pub fn remove(&self, class: &str) -> Result<(), JsValue> {
+        self.0.remove_1(class)
+    }
+
+    /// Removes the class if it is there, otherwise adds it. Returns whether
+    /// it is there now.
+    pub fn toggle(&self, class: &str) -> Result<bool, JsValue> {
+        self.0.toggle(class)
+    }
+
+    /// Whether the class is there.
+    pub fn contains(&self, class: &str) -> bool {
+        self.0.contains(class)
+    }
+}
+
+/// Finds the first element in the document which matches the CSS selector.
+pub fn query_selector(selector: &str) -> Result<Option<Element>, JsValue> {
+    document().query_selector(selector)
+}
+
+/// Finds all the elements in the document which match the CSS selector, in
+/// the document order.
+pub fn query_selector_all(selector: &str) -> Result<Vec<Element>, JsValue> {
+    Ok(elements_of(&document().query_selector_all(selector)?))
+}
+
+/// Finds the first descendant of the element which matches the CSS selector.
+pub fn query_selector_within(
+    element: &Element,
+    selector: &str,
+) -> Result<Option<Element>, JsValue> {
+    element.query_selector(selector)
+}
+
+/// Finds all the descendants of the element which match the CSS selector, in
+/// the document order.
+pub fn query_selector_all_within(
+    element: &Element,
+    selector: &str,
+) -> Result<Vec<Element>, JsValue> {
+    Ok(elements_of(&element.query_selector_all(selector)?))
+}
+
+fn elements_of(nodes: &NodeList) -> Vec<Element> {
+    (0..nodes.length())
+        .filter_map(|index| nodes.get(index)?.dyn_into::<Element>().ok())
+        .collect()
+}
+
+/// Creates an element of the namespace, like an SVG one within
+/// [SVG_NAMESPACE](constant.SVG_NAMESPACE.html), which is not an HTML element
+/// otherwise.
+pub fn create_element_ns(namespace: &str, tag: &str) -> Result<Element, JsValue> {
+    document().create_element_ns(Some(namespace), tag)
+}
+
+/// Sets the property of the node, like the `srcObject` of a video or the
+/// props of a custom element, which have no attribute to be set with.
+pub fn set_property(node: &Node, name: &str, value: &JsValue) -> Result<(), JsValue> {
+    if Reflect::set(node, &JsValue::from_str(name), value)? {
+        Ok(())
+    } else {
+        Err(JsValue::from_str(&format!(
+            "The property `{}` is read-only",
+            name
+        )))
+    }
+}
+
+/// Gets the property of the node, which is `undefined` if there is none.
+pub fn get_property(node: &Node, name: &str) -> Result<JsValue, JsValue> {
+    Reflect::get(node, &JsValue::from_str(name))
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn should_toggle_the_classes() {
+        let element = document().create_element("div").unwrap();
+        let classes = class_list(&element);
+        classes.add("menu").unwrap();
+        assert!(classes.toggle("open").unwrap());
+        assert!(classes.contains("open"));
+        assert!(!classes.toggle("open").unwrap());
+        classes.remove("menu").unwrap();
+        assert!(!classes.contains("menu"));
+    }
+
+    #[wasm_bindgen_test]
+    fn should_query_the_descendants_of_an_element() {
+        let element = document().create_element("div").unwrap();
+        element.set_inner_html(r#"<p class="item">A</p><span><p class="item">B</p></span>"#);
+        let items = query_selector_all_within(&element, ".item").unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].text_content(), Some("B".to_string()));
+        assert!(query_selector_within(&element, "ul").unwrap().is_none());
+    }
+
+    #[wasm_bindgen_test]
+    fn should_set_and_get_the_properties() {
+        let input = document().create_element("input").unwrap();
+        set_property(&input, "value", &JsValue::from_str("typed")).unwrap();
+        assert_eq!(
+            get_property(&input, "value").unwrap().as_string(),
+            Some("typed".to_string())
+        );
+        assert_eq!(input.get_attribute("value"), None);
+
+        set_property(&input, "items", &JsValue::from_f64(3.0)).unwrap();
+        assert_eq!(get_property(&input, "items").unwrap().as_f64(), Some(3.0));
+        assert!(get_property(&input, "missing").unwrap().is_undefined());
+    }
+
+    #[wasm_bindgen_test]
+    fn should_create_an_svg_element() {
+        let circle = create_element_ns(SVG_NAMESPACE, "circle").unwrap();
+        assert_eq!(circle.namespace_uri(), Some(SVG_NAMESPACE.to_string()));
+        assert_eq!(circle.tag_name(), "circle");
+    }
+}
@@ -0,0 +1,143 @@
+//! Listening to the DOM events outside of the VDOM.
+
+use crate::vdom::NodeRef;
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{window, Event, EventTarget, Node};
+
+/// A handle to an event listener attached via [listen](fn.listen.html).
+///
+/// The listener is detached when the handle is dropped. If it is required to
+/// listen for the lifetime of the program, `forget` it instead.
+pub struct EventListenerHandle {
+    target: EventTarget,
+    type_: &'static str,
+    closure: Option<Closure<dyn Fn(Event)>>,
+}
+
+/// Attaches a listener to the `target` which is invoked when an event of the
+/// given type occurs.
+///
+/// # Example
+/// ```ignore
+/// let handle = listen(window().unwrap().as_ref(), "resize", |_| {
+///     // Do something on resize.
+/// })?;
+/// ```
+pub fn listen(
+    target: &EventTarget,
+    type_: &'static str,
+    handler: impl Fn(Event) + 'static,
+) -> Result<EventListenerHandle, JsValue> {
+    let closure: Closure<dyn Fn(Event)> = Closure::wrap(Box::new(handler));
+    target.add_event_listener_with_callback(type_, closure.as_ref().unchecked_ref())?;
+    Ok(EventListenerHandle {
+        target: target.clone(),
+        type_,
+        closure: Some(closure),
+    })
+}
+
+impl EventListenerHandle {
+    /// Keeps on listening for the lifetime of the program.
+    pub fn forget(mut self) {
+        if let Some(closure) = self.closure.take() {
+            closure.forget();
+        }
+    }
+}
+
+impl Drop for EventListenerHandle {
+    fn drop(&mut self) {
+        if let Some(ref closure) = self.closure {
+            // The listener may only fail to detach if the target is gone.
+            let _ = self
+                .target
+                .remove_event_listener_with_callback(self.type_, closure.as_ref().unchecked_ref());
+        }
+    }
+}
+
+/// Invokes the handler when the document is clicked outside of the
+/// referenced element, e.g. to close a dropdown or a popover. It listens for
+/// as long as the returned handle is kept, usually in the scope of the
+/// component.
+///
+/// Nothing is invoked while the reference is not set, nor for a click on a
+/// node which the click itself removed from the document, like an option of
+/// the dropdown rendered anew.
+///
+/// # Example
+/// ```ignore
+/// fn mounted(&self) {
+///     let setter = self.state_setter();
+///     let listener = on_click_outside(&self.menu, move |_| {
+///         setter.set_state(|state| state.is_open = false);
+///     })
+///     .unwrap();
+///     self.scope().keep(listener);
+/// }
+/// ```
+pub fn on_click_outside(
+    node_ref: &NodeRef,
+    handler: impl Fn(Event) + 'static,
+) -> Result<EventListenerHandle, JsValue> {
+    let node_ref = node_ref.clone();
+    let document = window().unwrap().document().unwrap();
+    listen(document.as_ref(), "click", move |event: Event| {
+        let element = match node_ref.element() {
+            Some(element) => element,
+            None => return,
+        };
+        let target = match event
+            .target()
+            .and_then(|target| target.dyn_into::<Node>().ok())
+        {
+            Some(target) => target,
+            None => return,
+        };
+        if target.is_connected() && !element.contains(Some(&target)) {
+            handler(event);
+        }
+    })
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::backend::{Node, WebBackend};
+    use std::{cell::Cell, rc::Rc};
+    use wasm_bindgen_test::*;
+    use web_sys::HtmlElement;
+
+    #[wasm_bindgen_test]
+    fn should_invoke_the_handler_on_a_click_outside() {
+        let document = window().unwrap().document().unwrap();
+        let body = document.body().unwrap();
+        let menu = document.create_element("div").unwrap();
+        let item = document.create_element("button").unwrap();
+        let other = document.create_element("button").unwrap();
+        menu.append_child(&item).unwrap();
+        body.append_child(&menu).unwrap();
+        body.append_child(&other).unwrap();
+        let node_ref = NodeRef::new();
+        node_ref.set(Some(Node::new(WebBackend, menu.clone().into())));
+
+        let clicks = Rc::new(Cell::new(0));
+        let handle = {
+            let clicks = clicks.clone();
+            on_click_outside(&node_ref, move |_| clicks.set(clicks.get() + 1)).unwrap()
+        };
+        let click = |element: &web_sys::Element| element.unchecked_ref::<HtmlElement>().click();
+
+        click(&item);
+        assert_eq!(clicks.get(), 0);
+        click(&other);
+        assert_eq!(clicks.get(), 1);
+        drop(handle);
+        click(&other);
+        assert_eq!(clicks.get(), 1);
+
+        body.remove_child(&menu).unwrap();
+        body.remove_child(&other).unwrap();
+    }
+}
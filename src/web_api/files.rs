@@ -0,0 +1,46 @@
+//! The files selected with an `<input type="file">` and reading their
+//! content.
+//!
+//! # Example
+//! ```ignore
+//! fn on_change(&self, event: Event) {
+//!     for file in files::selected_files(&event) {
+//!         wasm_bindgen_futures::spawn_local(async move {
+//!             let text = files::read_text(&file).await.unwrap();
+//!             // Upload or preview the text.
+//!         });
+//!     }
+//! }
+//! ```
+
+use js_sys::Uint8Array;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Event, HtmlInputElement};
+
+pub use web_sys::{File, FileList};
+
+/// Gets the files selected by the `<input type="file">` of a `change` or an
+/// `input` event.
+pub fn selected_files(event: &Event) -> Vec<File> {
+    let list = event
+        .target()
+        .and_then(|target| target.dyn_into::<HtmlInputElement>().ok())
+        .and_then(|input| input.files());
+    match list {
+        Some(list) => (0..list.length()).filter_map(|i| list.get(i)).collect(),
+        None => vec![],
+    }
+}
+
+/// Reads the content of the file as a UTF-8 text.
+pub async fn read_text(file: &File) -> Result<String, JsValue> {
+    let text = JsFuture::from(file.text()).await?;
+    Ok(text.as_string().unwrap_or_default())
+}
+
+/// Reads the content of the file as bytes.
+pub async fn read_bytes(file: &File) -> Result<Vec<u8>, JsValue> {
+    let buffer = JsFuture::from(file.array_buffer()).await?;
+    Ok(Uint8Array::new(&buffer).to_vec())
+}
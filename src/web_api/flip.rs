@@ -0,0 +1,86 @@
+//! Animating the layout changes of the elements with the FLIP technique.
+//!
+//! The First position of an element is recorded before the DOM is changed.
+//! Once changed, its Last position is measured and a transform Inverting the
+//! difference is applied, so that it looks as if it never moved. Then the
+//! transform is transitioned away to Play the move.
+
+use crate::web_api::measure::{self, Rect};
+use wasm_bindgen::JsCast;
+use web_sys::{CssStyleDeclaration, Element, HtmlElement};
+
+/// The first position of an element, recorded to animate its move.
+///
+/// # Example
+/// ```ignore
+/// let flip = Flip::first(&element);
+/// // Change the DOM, e.g. move the element elsewhere.
+/// flip.play("transform 300ms ease-out");
+/// ```
+pub struct Flip {
+    element: Element,
+    first: Rect,
+}
+
+impl Flip {
+    /// Records the position of the element before it is moved.
+    pub fn first(element: &Element) -> Flip {
+        Flip {
+            element: element.clone(),
+            first: measure::bounding_client_rect(element),
+        }
+    }
+
+    /// The element being moved.
+    pub fn element(&self) -> &Element {
+        &self.element
+    }
+
+    /// Animates the element from its first position to where it is now, with
+    /// the given CSS transition, e.g. `transform 300ms ease-out`. Returns
+    /// whether the element had moved at all.
+    pub fn play(&self, transition: &str) -> bool {
+        let style = match self.invert() {
+            Some(style) => style,
+            None => return false,
+        };
+        let _ = style.set_property("transition", transition);
+        let _ = style.remove_property("transform");
+        true
+    }
+
+    /// Animates the element from its first position to where it is now, with
+    /// the transition of the given class. The class is to be removed once
+    /// the transition ends. Returns whether the element had moved at all.
+    pub fn play_with_class(&self, class: &str) -> bool {
+        let style = match self.invert() {
+            Some(style) => style,
+            None => return false,
+        };
+        let _ = self.element.class_list().add_1(class);
+        let _ = style.remove_property("transition");
+        let _ = style.remove_property("transform");
+        true
+    }
+
+    /// Puts the element back at its first position without transitioning.
+    fn invert(&self) -> Option<CssStyleDeclaration> {
+        // It is not moved but gone, if it is no longer in the document.
+        if !self.element.is_connected() {
+            return None;
+        }
+        let last = measure::bounding_client_rect(&self.element);
+        let (dx, dy) = (self.first.left - last.left, self.first.top - last.top);
+        if dx == 0.0 && dy == 0.0 {
+            return None;
+        }
+        let style = self.element.dyn_ref::<HtmlElement>()?.style();
+
+        let _ = style.set_property("transition", "none");
+        let _ = style.set_property("transform", &format!("translate({}px, {}px)", dx, dy));
+        // Read the layout, so that the inverted transform is applied before
+        // it is transitioned away.
+        measure::offset_size(&self.element);
+        Some(style)
+    }
+}
@@ -0,0 +1,61 @@
+//! The session history of the browser window.
+
+use crate::web_api::{listen, EventListenerHandle};
+use wasm_bindgen::prelude::*;
+use web_sys::{window, History};
+
+fn history() -> Result<History, JsValue> {
+    window().unwrap().history()
+}
+
+/// Pushes the URL into the session history, without loading it.
+pub fn push_state(url: &str) -> Result<(), JsValue> {
+    history()?.push_state_with_url(&JsValue::null(), "", Some(url))
+}
+
+/// Replaces the current entry of the session history with the URL, without
+/// loading it.
+pub fn replace_state(url: &str) -> Result<(), JsValue> {
+    history()?.replace_state_with_url(&JsValue::null(), "", Some(url))
+}
+
+/// Goes back to the previous entry in the session history.
+pub fn back() -> Result<(), JsValue> {
+    history()?.back()
+}
+
+/// Goes forward to the next entry in the session history.
+pub fn forward() -> Result<(), JsValue> {
+    history()?.forward()
+}
+
+/// Listens to the `popstate` event, which occurs when the active entry of the
+/// session history changes by going back/forward.
+///
+/// # Example
+/// ```ignore
+/// let handle = on_pop_state(|| {
+///     // Match the routes with the current location.
+/// })?;
+/// ```
+pub fn on_pop_state(handler: impl Fn() + 'static) -> Result<EventListenerHandle, JsValue> {
+    listen(window().unwrap().as_ref(), "popstate", move |_| handler())
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::web_api::location;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn should_push_and_replace_state() {
+        push_state("/pushed?query=1#hash").unwrap();
+        assert_eq!(location::pathname().unwrap(), "/pushed");
+        assert_eq!(location::search().unwrap(), "?query=1");
+        assert_eq!(location::hash().unwrap(), "#hash");
+
+        replace_state("/replaced").unwrap();
+        assert_eq!(location::pathname().unwrap(), "/replaced");
+    }
+}
@@ -0,0 +1,109 @@
+//! Observing the intersection of the elements with the viewport.
+
+use crate::vdom::NodeRef;
+use js_sys::Array;
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{Element, IntersectionObserverEntry, IntersectionObserverInit};
+
+/// Observes when the elements enter or leave the viewport. It stops observing
+/// when dropped.
+pub struct IntersectionObserver {
+    observer: web_sys::IntersectionObserver,
+    _closure: Closure<dyn Fn(Array)>,
+}
+
+impl IntersectionObserver {
+    /// Creates an observer which invokes the handler with the entries of the
+    /// observed elements whose intersection with the viewport changed.
+    pub fn new(
+        handler: impl Fn(Vec<IntersectionObserverEntry>) + 'static,
+    ) -> Result<IntersectionObserver, JsValue> {
+        let closure = entries_closure(handler);
+        let observer = web_sys::IntersectionObserver::new(closure.as_ref().unchecked_ref())?;
+        Ok(IntersectionObserver {
+            observer,
+            _closure: closure,
+        })
+    }
+
+    /// Creates an observer whose viewport is grown by the margin, like
+    /// `"200px"`, so that the elements intersect it before they are scrolled
+    /// into view.
+    pub fn with_root_margin(
+        root_margin: &str,
+        handler: impl Fn(Vec<IntersectionObserverEntry>) + 'static,
+    ) -> Result<IntersectionObserver, JsValue> {
+        let closure = entries_closure(handler);
+        let options = IntersectionObserverInit::new();
+        options.set_root_margin(root_margin);
+        let observer = web_sys::IntersectionObserver::new_with_options(
+            closure.as_ref().unchecked_ref(),
+            &options,
+        )?;
+        Ok(IntersectionObserver {
+            observer,
+            _closure: closure,
+        })
+    }
+
+    /// Starts observing the element.
+    pub fn observe(&self, element: &Element) {
+        self.observer.observe(element);
+    }
+
+    /// Stops observing the element.
+    pub fn unobserve(&self, element: &Element) {
+        self.observer.unobserve(element);
+    }
+}
+
+impl Drop for IntersectionObserver {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
+
+/// Wraps the handler to be invoked with the entries by the observer.
+fn entries_closure(
+    handler: impl Fn(Vec<IntersectionObserverEntry>) + 'static,
+) -> Closure<dyn Fn(Array)> {
+    Closure::wrap(Box::new(move |entries: Array| {
+        handler(
+            entries
+                .iter()
+                .map(|entry| entry.unchecked_into::<IntersectionObserverEntry>())
+                .collect(),
+        )
+    }))
+}
+
+/// Invokes the handler every time the referenced element enters the viewport.
+/// It is to be called once the reference is set, i.e. after `mounted`.
+///
+/// # Example
+/// ```ignore
+/// fn mounted(&self) {
+///     let setter = self.state_setter();
+///     let observer = on_visible(&self.sentinel, move || {
+///         setter.set_state(|state| state.page += 1);
+///     })
+///     .unwrap();
+///     // Stop observing once the component is destroyed.
+///     self.scope().keep(observer);
+/// }
+/// ```
+pub fn on_visible(
+    node_ref: &NodeRef,
+    handler: impl Fn() + 'static,
+) -> Result<IntersectionObserver, JsValue> {
+    let element = node_ref
+        .element()
+        .ok_or_else(|| JsValue::from_str("The reference is not set with an element"))?;
+    let observer = IntersectionObserver::new(move |entries| {
+        if entries.iter().any(|entry| entry.is_intersecting()) {
+            handler();
+        }
+    })?;
+    observer.observe(&element);
+    Ok(observer)
+}
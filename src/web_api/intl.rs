@@ -0,0 +1,489 @@
+//! Formatting the numbers & the dates in the locale of the user, with the
+//! `Intl` API of the browser.
+//!
+//! A formatter is created with the options built from
+//! [NumberFormatOptions](struct.NumberFormatOptions.html) or
+//! [DateTimeFormatOptions](struct.DateTimeFormatOptions.html), for a locale
+//! like `de-DE`, or for the locale of the browser when `None`. It is best
+//! created once & kept, as creating one is costly compared to formatting.
+//!
+//! # Example
+//! ```ignore
+//! let price = NumberFormat::new(Some("de-DE"), &NumberFormatOptions::new().currency("EUR"))?;
+//! assert_eq!(price.format(1234.5), "1.234,50 €");
+//!
+//! let date = DateTimeFormat::new(
+//!     Some("en-US"),
+//!     &DateTimeFormatOptions::new().date_style(DateTimeStyle::Long),
+//! )?;
+//! assert_eq!(date.format(&Date::new(&"2019-03-14".into())), "March 14, 2019");
+//! ```
+
+use js_sys::{Array, Date, Function, Object, Reflect};
+use wasm_bindgen::{prelude::JsValue, JsCast};
+
+/// Formats the numbers, like the prices, the percentages & the distances.
+#[derive(Clone, Debug)]
+pub struct NumberFormat {
+    /// The `format` of the formatter, which is bound to it.
+    format: Function,
+}
+
+/// Formats the dates & the times.
+#[derive(Clone, Debug)]
+pub struct DateTimeFormat {
+    /// The `format` of the formatter, which is bound to it.
+    format: Function,
+}
+
+/// The options a number is formatted with. The ones which are not set are
+/// left to the defaults of the locale.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NumberFormatOptions {
+    options: Options,
+}
+
+/// The options a date is formatted with. Only the date is formatted if no
+/// part of it is set.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DateTimeFormatOptions {
+    options: Options,
+}
+
+/// How a currency is shown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurrencyDisplay {
+    /// Like `€` or `US$`.
+    Symbol,
+    /// Like `$`.
+    NarrowSymbol,
+    /// Like `EUR`.
+    Code,
+    /// Like `euros`.
+    Name,
+}
+
+/// How long the name of a unit or a part of a date is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextWidth {
+    /// Like `kilometers` or `Thursday`.
+    Long,
+    /// Like `km` or `Thu`.
+    Short,
+    /// Like `km` or `T`.
+    Narrow,
+}
+
+/// How a number is written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Notation {
+    /// Like `1,234`.
+    Standard,
+    /// Like `1.234E3`.
+    Scientific,
+    /// Like `1.234E3`, with the exponent in the multiples of three.
+    Engineering,
+    /// Like `1.2K`.
+    Compact,
+}
+
+/// When the sign of a number is shown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignDisplay {
+    /// Only for the negative numbers.
+    Auto,
+    /// For all the numbers.
+    Always,
+    /// For all the numbers but zero.
+    ExceptZero,
+    /// Never.
+    Never,
+}
+
+/// A predefined length of a date or a time, which is written the way the
+/// locale prefers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateTimeStyle {
+    /// Like `Thursday, March 14, 2019`.
+    Full,
+    /// Like `March 14, 2019`.
+    Long,
+    /// Like `Mar 14, 2019`.
+    Medium,
+    /// Like `3/14/19`.
+    Short,
+}
+
+/// How a numeric part of a date is written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Digits {
+    /// Like `3`.
+    Numeric,
+    /// Like `03`.
+    TwoDigit,
+}
+
+/// How the month of a date is written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MonthFormat {
+    /// Like `3`.
+    Numeric,
+    /// Like `03`.
+    TwoDigit,
+    /// Like `March`.
+    Long,
+    /// Like `Mar`.
+    Short,
+    /// Like `M`.
+    Narrow,
+}
+
+/// The options as they are set, to be converted to a JS object once a
+/// formatter is created.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Options(Vec<(&'static str, Value)>);
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Text(String),
+    Number(f64),
+    Bool(bool),
+}
+
+impl Options {
+    fn set(&mut self, name: &'static str, value: Value) {
+        self.0.retain(|(set, _)| *set != name);
+        self.0.push((name, value));
+    }
+
+    fn to_object(&self) -> Result<Object, JsValue> {
+        let object = Object::new();
+        for (name, value) in self.0.iter() {
+            let value = match value {
+                Value::Text(text) => JsValue::from_str(text),
+                Value::Number(number) => JsValue::from_f64(*number),
+                Value::Bool(boolean) => JsValue::from_bool(*boolean),
+            };
+            Reflect::set(&object, &JsValue::from_str(name), &value)?;
+        }
+        Ok(object)
+    }
+}
+
+impl NumberFormatOptions {
+    /// Creates the options with the defaults of the locale.
+    pub fn new() -> NumberFormatOptions {
+        NumberFormatOptions::default()
+    }
+
+    fn set(mut self, name: &'static str, value: Value) -> NumberFormatOptions {
+        self.options.set(name, value);
+        self
+    }
+
+    /// Formats the numbers as an amount of the currency, by its ISO 4217
+    /// code like `EUR`.
+    pub fn currency(self, code: &str) -> NumberFormatOptions {
+        self.set("style", Value::Text("currency".to_string()))
+            .set("currency", Value::Text(code.to_string()))
+    }
+
+    /// Sets how the currency is shown. It is a `Symbol` by default.
+    pub fn currency_display(self, display: CurrencyDisplay) -> NumberFormatOptions {
+        let display = match display {
+            CurrencyDisplay::Symbol => "symbol",
+            CurrencyDisplay::NarrowSymbol => "narrowSymbol",
+            CurrencyDisplay::Code => "code",
+            CurrencyDisplay::Name => "name",
+        };
+        self.set("currencyDisplay", Value::Text(display.to_string()))
+    }
+
+    /// Formats the numbers as percentages, i.e. `0.25` as `25%`.
+    pub fn percent(self) -> NumberFormatOptions {
+        self.set("style", Value::Text("percent".to_string()))
+    }
+
+    /// Formats the numbers as a measure of the unit, like `kilometer` or
+    /// `megabyte-per-second`.
+    pub fn unit(self, unit: &str) -> NumberFormatOptions {
+        self.set("style", Value::Text("unit".to_string()))
+            .set("unit", Value::Text(unit.to_string()))
+    }
+
+    /// Sets how long the name of the unit is. It is `Short` by default.
+    pub fn unit_display(self, width: TextWidth) -> NumberFormatOptions {
+        self.set("unitDisplay", Value::Text(width.as_str().to_string()))
+    }
+
+    /// Sets how the numbers are written.
+    pub fn notation(self, notation: Notation) -> NumberFormatOptions {
+        let notation = match notation {
+            Notation::Standard => "standard",
+            Notation::Scientific => "scientific",
+            Notation::Engineering => "engineering",
+            Notation::Compact => "compact",
+        };
+        self.set("notation", Value::Text(notation.to_string()))
+    }
+
+    /// Sets when the sign of the numbers is shown.
+    pub fn sign_display(self, display: SignDisplay) -> NumberFormatOptions {
+        let display = match display {
+            SignDisplay::Auto => "auto",
+            SignDisplay::Always => "always",
+            SignDisplay::ExceptZero => "exceptZero",
+            SignDisplay::Never => "never",
+        };
+        self.set("signDisplay", Value::Text(display.to_string()))
+    }
+
+    /// Sets whether the thousands are separated, like `1,234`. They are by
+    /// default.
+    pub fn grouping(self, grouping: bool) -> NumberFormatOptions {
+        self.set("useGrouping", Value::Bool(grouping))
+    }
+
+    /// Pads the integer part with the zeros up to the digits.
+    pub fn minimum_integer_digits(self, digits: u8) -> NumberFormatOptions {
+        self.set("minimumIntegerDigits", Value::Number(digits.into()))
+    }
+
+    /// Rounds or pads the fraction to between the digits, like `(2, 2)` for
+    /// the cents.
+    pub fn fraction_digits(self, minimum: u8, maximum: u8) -> NumberFormatOptions {
+        self.set("minimumFractionDigits", Value::Number(minimum.into()))
+            .set("maximumFractionDigits", Value::Number(maximum.into()))
+    }
+
+    /// Rounds the numbers to the significant digits.
+    pub fn maximum_significant_digits(self, digits: u8) -> NumberFormatOptions {
+        self.set("maximumSignificantDigits", Value::Number(digits.into()))
+    }
+}
+
+impl DateTimeFormatOptions {
+    /// Creates the options which format only the date with the defaults of
+    /// the locale.
+    pub fn new() -> DateTimeFormatOptions {
+        DateTimeFormatOptions::default()
+    }
+
+    fn set(mut self, name: &'static str, value: Value) -> DateTimeFormatOptions {
+        self.options.set(name, value);
+        self
+    }
+
+    /// Formats the date in the style. It cannot be combined with the
+    /// individual parts.
+    pub fn date_style(self, style: DateTimeStyle) -> DateTimeFormatOptions {
+        self.set("dateStyle", Value::Text(style.as_str().to_string()))
+    }
+
+    /// Formats the time in the style. It cannot be combined with the
+    /// individual parts.
+    pub fn time_style(self, style: DateTimeStyle) -> DateTimeFormatOptions {
+        self.set("timeStyle", Value::Text(style.as_str().to_string()))
+    }
+
+    /// Shows the day of the week.
+    pub fn weekday(self, width: TextWidth) -> DateTimeFormatOptions {
+        self.set("weekday", Value::Text(width.as_str().to_string()))
+    }
+
+    /// Shows the year.
+    pub fn year(self, digits: Digits) -> DateTimeFormatOptions {
+        self.set("year", Value::Text(digits.as_str().to_string()))
+    }
+
+    /// Shows the month.
+    pub fn month(self, format: MonthFormat) -> DateTimeFormatOptions {
+        let format = match format {
+            MonthFormat::Numeric => "numeric",
+            MonthFormat::TwoDigit => "2-digit",
+            MonthFormat::Long => "long",
+            MonthFormat::Short => "short",
+            MonthFormat::Narrow => "narrow",
+        };
+        self.set("month", Value::Text(format.to_string()))
+    }
+
+    /// Shows the day of the month.
+    pub fn day(self, digits: Digits) -> DateTimeFormatOptions {
+        self.set("day", Value::Text(digits.as_str().to_string()))
+    }
+
+    /// Shows the hour.
+    pub fn hour(self, digits: Digits) -> DateTimeFormatOptions {
+        self.set("hour", Value::Text(digits.as_str().to_string()))
+    }
+
+    /// Shows the minute.
+    pub fn minute(self, digits: Digits) -> DateTimeFormatOptions {
+        self.set("minute", Value::Text(digits.as_str().to_string()))
+    }
+
+    /// Shows the second.
+    pub fn second(self, digits: Digits) -> DateTimeFormatOptions {
+        self.set("second", Value::Text(digits.as_str().to_string()))
+    }
+
+    /// Sets whether the hours are shown in the 12-hour clock, instead of the
+    /// preference of the locale.
+    pub fn hour12(self, hour12: bool) -> DateTimeFormatOptions {
+        self.set("hour12", Value::Bool(hour12))
+    }
+
+    /// Shows the dates in the IANA time zone, like `Asia/Kathmandu` or `UTC`,
+    /// instead of the one of the user.
+    pub fn time_zone(self, time_zone: &str) -> DateTimeFormatOptions {
+        self.set("timeZone", Value::Text(time_zone.to_string()))
+    }
+
+    /// Shows the name of the time zone.
+    pub fn time_zone_name(self, width: TextWidth) -> DateTimeFormatOptions {
+        self.set("timeZoneName", Value::Text(width.as_str().to_string()))
+    }
+}
+
+impl TextWidth {
+    fn as_str(self) -> &'static str {
+        match self {
+            TextWidth::Long => "long",
+            TextWidth::Short => "short",
+            TextWidth::Narrow => "narrow",
+        }
+    }
+}
+
+impl DateTimeStyle {
+    fn as_str(self) -> &'static str {
+        match self {
+            DateTimeStyle::Full => "full",
+            DateTimeStyle::Long => "long",
+            DateTimeStyle::Medium => "medium",
+            DateTimeStyle::Short => "short",
+        }
+    }
+}
+
+impl Digits {
+    fn as_str(self) -> &'static str {
+        match self {
+            Digits::Numeric => "numeric",
+            Digits::TwoDigit => "2-digit",
+        }
+    }
+}
+
+/// Creates the `Intl` formatter of the name, returning its bound `format`.
+/// It fails if the locale or the options are invalid, or if the browser does
+/// not support them.
+fn create_formatter(
+    name: &str,
+    locale: Option<&str>,
+    options: &Options,
+) -> Result<Function, JsValue> {
+    let intl = Reflect::get(&js_sys::global(), &JsValue::from_str("Intl"))?;
+    let constructor: Function = Reflect::get(&intl, &JsValue::from_str(name))?.dyn_into()?;
+    let locale = locale.map_or(JsValue::UNDEFINED, JsValue::from_str);
+    let options = options.to_object()?;
+    let formatter = Reflect::construct(&constructor, &Array::of2(&locale, &options))?;
+    Reflect::get(&formatter, &JsValue::from_str("format"))?.dyn_into()
+}
+
+/// Invokes the `format` with the value, which returns a string unless the
+/// value is out of range.
+fn format(format: &Function, value: &JsValue) -> String {
+    format
+        .call1(&JsValue::UNDEFINED, value)
+        .ok()
+        .and_then(|formatted| formatted.as_string())
+        .unwrap_or_default()
+}
+
+impl NumberFormat {
+    /// Creates a formatter of the numbers in the locale, or the one of the
+    /// browser if `None`.
+    pub fn new(
+        locale: Option<&str>,
+        options: &NumberFormatOptions,
+    ) -> Result<NumberFormat, JsValue> {
+        Ok(NumberFormat {
+            format: create_formatter("NumberFormat", locale, &options.options)?,
+        })
+    }
+
+    /// Formats the number.
+    pub fn format(&self, number: f64) -> String {
+        format(&self.format, &JsValue::from_f64(number))
+    }
+}
+
+impl DateTimeFormat {
+    /// Creates a formatter of the dates in the locale, or the one of the
+    /// browser if `None`.
+    pub fn new(
+        locale: Option<&str>,
+        options: &DateTimeFormatOptions,
+    ) -> Result<DateTimeFormat, JsValue> {
+        Ok(DateTimeFormat {
+            format: create_formatter("DateTimeFormat", locale, &options.options)?,
+        })
+    }
+
+    /// Formats the date. An invalid date is formatted as empty.
+    pub fn format(&self, date: &Date) -> String {
+        format(&self.format, date.as_ref())
+    }
+
+    /// Formats the date at the milliseconds since the UNIX epoch, as given by
+    /// `Date.now()`.
+    pub fn format_timestamp(&self, millis: f64) -> String {
+        self.format(&Date::new(&JsValue::from_f64(millis)))
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn should_format_the_numbers_in_the_locale() {
+        let plain = NumberFormat::new(Some("en-US"), &NumberFormatOptions::new()).unwrap();
+        assert_eq!(plain.format(1234.5), "1,234.5");
+
+        let price = NumberFormat::new(
+            Some("en-US"),
+            &NumberFormatOptions::new()
+                .currency("USD")
+                .fraction_digits(2, 2),
+        )
+        .unwrap();
+        assert_eq!(price.format(1234.5), "$1,234.50");
+
+        let share =
+            NumberFormat::new(Some("en-US"), &NumberFormatOptions::new().percent()).unwrap();
+        assert_eq!(share.format(0.25), "25%");
+
+        assert!(
+            NumberFormat::new(Some("en-US"), &NumberFormatOptions::new().currency("")).is_err()
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn should_format_the_dates_in_the_locale() {
+        let date = DateTimeFormat::new(
+            Some("en-US"),
+            &DateTimeFormatOptions::new()
+                .year(Digits::Numeric)
+                .month(MonthFormat::Long)
+                .day(Digits::Numeric)
+                .time_zone("UTC"),
+        )
+        .unwrap();
+        // 2019-03-14T00:00:00Z
+        assert_eq!(date.format_timestamp(1_552_521_600_000.0), "March 14, 2019");
+    }
+}
@@ -0,0 +1,383 @@
+//! Matching the keyboard shortcuts on the `keydown` & `keyup` listeners, and
+//! the app-wide shortcuts registered with [Shortcuts](struct.Shortcuts.html).
+//!
+//! # Example
+//! ```ignore
+//! html! {
+//!     <textarea
+//!         @keydown={keyboard::on_key("Ctrl+Enter", Self::submit)}
+//!     ></textarea>
+//! }
+//! ```
+
+use crate::{
+    vdom::NodeRef,
+    web_api::events::{listen, EventListenerHandle},
+};
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::{prelude::JsValue, JsCast};
+use web_sys::{window, Element, Event, HtmlElement, KeyboardEvent};
+
+thread_local! {
+    static SHORTCUTS: RefCell<Registry> = RefCell::new(Registry::default());
+}
+
+/// A key along with the modifiers to be held down with it.
+///
+/// It is built with the methods or parsed from a shortcut like
+/// `Ctrl+Shift+S`, where the modifiers are `Ctrl`, `Shift`, `Alt` & `Meta`.
+/// The key is either the character it types or the name of the key, like
+/// `Enter` or `ArrowUp`, compared regardless of the case.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KeyMatch {
+    key: String,
+    ctrl: bool,
+    shift: bool,
+    alt: bool,
+    meta: bool,
+}
+
+impl KeyMatch {
+    /// Matches the key when pressed without any modifiers.
+    pub fn new(key: &str) -> KeyMatch {
+        KeyMatch {
+            key: key.to_string(),
+            ..KeyMatch::default()
+        }
+    }
+
+    /// Also requires the `Ctrl` key to be held down.
+    pub fn ctrl(mut self) -> KeyMatch {
+        self.ctrl = true;
+        self
+    }
+
+    /// Also requires the `Shift` key to be held down.
+    pub fn shift(mut self) -> KeyMatch {
+        self.shift = true;
+        self
+    }
+
+    /// Also requires the `Alt` key to be held down.
+    pub fn alt(mut self) -> KeyMatch {
+        self.alt = true;
+        self
+    }
+
+    /// Also requires the `Meta` key, i.e. `⌘` or `⊞`, to be held down.
+    pub fn meta(mut self) -> KeyMatch {
+        self.meta = true;
+        self
+    }
+
+    /// Whether any of `Ctrl`, `Alt` or `Meta` is required, unlike the keys
+    /// which type into a field.
+    fn has_command_modifier(&self) -> bool {
+        self.ctrl || self.alt || self.meta
+    }
+
+    /// Whether the keyboard event is of the key, with exactly the modifiers
+    /// required held down.
+    pub fn matches(&self, event: &Event) -> bool {
+        let event = match event.dyn_ref::<KeyboardEvent>() {
+            Some(event) => event,
+            None => return false,
+        };
+        event.key().eq_ignore_ascii_case(&self.key)
+            && event.ctrl_key() == self.ctrl
+            && event.shift_key() == self.shift
+            && event.alt_key() == self.alt
+            && event.meta_key() == self.meta
+    }
+}
+
+impl<'a> From<&'a str> for KeyMatch {
+    fn from(shortcut: &'a str) -> KeyMatch {
+        // The `+` key itself is written as `Ctrl++`.
+        let (modifiers, key) = if let Some(modifiers) = shortcut.strip_suffix("++") {
+            (modifiers, "+")
+        } else {
+            match shortcut.rfind('+') {
+                Some(i) if i + 1 < shortcut.len() => (&shortcut[..i], &shortcut[i + 1..]),
+                _ => ("", shortcut),
+            }
+        };
+        modifiers
+            .split('+')
+            .filter(|modifier| !modifier.is_empty())
+            .fold(KeyMatch::new(key), |keys, modifier| {
+                match modifier.to_ascii_lowercase().as_str() {
+                    "ctrl" | "control" => keys.ctrl(),
+                    "shift" => keys.shift(),
+                    "alt" => keys.alt(),
+                    "meta" | "cmd" => keys.meta(),
+                    _ => panic!("Unknown modifier `{}` in `{}`", modifier, shortcut),
+                }
+            })
+    }
+}
+
+/// Wraps an event handler to only be invoked when the keyboard event matches
+/// the keys.
+pub fn on_key<RCTX>(
+    keys: impl Into<KeyMatch>,
+    handler: impl Fn(&RCTX, Event),
+) -> impl Fn(&RCTX, Event) {
+    let keys = keys.into();
+    move |ctx: &RCTX, event: Event| {
+        if keys.matches(&event) {
+            handler(ctx, event);
+        }
+    }
+}
+
+/// The keyboard shortcuts of the app, which are dispatched from a single
+/// `keydown` listener on the document.
+///
+/// A shortcut is registered either for the whole document or within the
+/// element of a reference, in which case it is dispatched only while the
+/// focus is within that element. Of the shortcuts registered for the same
+/// keys, the one of the innermost element wins over the ones around it & the
+/// document-wide ones, and the one registered last wins among the ones of the
+/// same element. So, a shortcut within a dialog shadows the one of the page
+/// behind it.
+///
+/// A shortcut without `Ctrl`, `Alt` or `Meta` is not dispatched while typing
+/// into a field. The event of a dispatched shortcut is prevented from its
+/// default action.
+///
+/// The shortcut is registered until the returned handle is dropped, which is
+/// usually kept in the scope of the component, so that it is registered for
+/// as long as the component is mounted.
+///
+/// # Example
+/// ```ignore
+/// fn mounted(&self) {
+///     let setter = self.state_setter();
+///     let save = Shortcuts::register("Ctrl+S", move |_| {
+///         setter.set_state(|state| state.is_saving = true);
+///     })
+///     .unwrap();
+///     self.scope().keep(save);
+/// }
+/// ```
+pub struct Shortcuts;
+
+/// A registered shortcut, which is unregistered once dropped.
+#[must_use = "The shortcut is unregistered once dropped"]
+pub struct Shortcut {
+    id: u64,
+}
+
+#[derive(Default)]
+struct Registry {
+    shortcuts: Vec<Registered>,
+    next_id: u64,
+    /// The listener on the document, while there are any shortcuts.
+    listener: Option<EventListenerHandle>,
+}
+
+struct Registered {
+    id: u64,
+    keys: KeyMatch,
+    within: Option<NodeRef>,
+    handler: Rc<dyn Fn(Event)>,
+}
+
+impl Shortcuts {
+    /// Registers a shortcut for the whole document.
+    pub fn register(
+        keys: impl Into<KeyMatch>,
+        handler: impl Fn(Event) + 'static,
+    ) -> Result<Shortcut, JsValue> {
+        add(keys.into(), None, Rc::new(handler))
+    }
+
+    /// Registers a shortcut which is dispatched only while the focus is
+    /// within the referenced element.
+    pub fn register_within(
+        node_ref: &NodeRef,
+        keys: impl Into<KeyMatch>,
+        handler: impl Fn(Event) + 'static,
+    ) -> Result<Shortcut, JsValue> {
+        add(keys.into(), Some(node_ref.clone()), Rc::new(handler))
+    }
+}
+
+fn add(
+    keys: KeyMatch,
+    within: Option<NodeRef>,
+    handler: Rc<dyn Fn(Event)>,
+) -> Result<Shortcut, JsValue> {
+    SHORTCUTS.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        if registry.listener.is_none() {
+            let document = window().unwrap().document().unwrap();
+            registry.listener = Some(listen(document.as_ref(), "keydown", dispatch)?);
+        }
+        let id = registry.next_id;
+        registry.next_id += 1;
+        registry.shortcuts.push(Registered {
+            id,
+            keys,
+            within,
+            handler,
+        });
+        Ok(Shortcut { id })
+    })
+}
+
+impl Drop for Shortcut {
+    fn drop(&mut self) {
+        // Dropped without a registry while the thread is torn down.
+        let _ = SHORTCUTS.try_with(|registry| {
+            let mut registry = registry.borrow_mut();
+            registry.shortcuts.retain(|shortcut| shortcut.id != self.id);
+            if registry.shortcuts.is_empty() {
+                registry.listener = None;
+            }
+        });
+    }
+}
+
+/// Invokes the handler of the shortcut which wins for the event.
+fn dispatch(event: Event) {
+    let target = event
+        .target()
+        .and_then(|target| target.dyn_into::<Element>().ok());
+    let is_typing = target.as_ref().is_some_and(is_editable);
+    let handler = SHORTCUTS.with(|registry| {
+        registry
+            .borrow()
+            .shortcuts
+            .iter()
+            .filter(|shortcut| shortcut.keys.matches(&event))
+            .filter(|shortcut| !is_typing || shortcut.keys.has_command_modifier())
+            .filter_map(|shortcut| {
+                let depth = match shortcut.within {
+                    Some(ref node_ref) => {
+                        let element = node_ref.element()?;
+                        if !element.contains(target.as_ref().map(AsRef::as_ref)) {
+                            return None;
+                        }
+                        depth(&element) + 1
+                    }
+                    None => 0,
+                };
+                Some(((depth, shortcut.id), shortcut.handler.clone()))
+            })
+            .max_by_key(|(rank, _)| *rank)
+            .map(|(_, handler)| handler)
+    });
+    // The registry is not borrowed while invoked, so that the handler may
+    // register or drop the shortcuts.
+    if let Some(handler) = handler {
+        event.prevent_default();
+        handler(event);
+    }
+}
+
+/// The number of the ancestors of the element.
+fn depth(element: &Element) -> usize {
+    let mut depth = 0;
+    let mut parent = element.parent_element();
+    while let Some(element) = parent {
+        depth += 1;
+        parent = element.parent_element();
+    }
+    depth
+}
+
+/// Whether the element takes the typed text.
+fn is_editable(element: &Element) -> bool {
+    match element.tag_name().to_ascii_lowercase().as_str() {
+        "input" | "textarea" | "select" => true,
+        _ => element
+            .dyn_ref::<HtmlElement>()
+            .is_some_and(HtmlElement::is_content_editable),
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::backend::{Node, WebBackend};
+    use wasm_bindgen_test::*;
+    use web_sys::KeyboardEventInit;
+
+    fn keydown(key: &str, ctrl: bool) -> KeyboardEvent {
+        let init = KeyboardEventInit::new();
+        init.set_key(key);
+        init.set_ctrl_key(ctrl);
+        init.set_bubbles(true);
+        init.set_cancelable(true);
+        KeyboardEvent::new_with_keyboard_event_init_dict("keydown", &init).unwrap()
+    }
+
+    fn register(
+        fired: &Rc<RefCell<Vec<&'static str>>>,
+        within: Option<&NodeRef>,
+        keys: &str,
+        name: &'static str,
+    ) -> Shortcut {
+        let fired = fired.clone();
+        let handler = move |_| fired.borrow_mut().push(name);
+        match within {
+            Some(node_ref) => Shortcuts::register_within(node_ref, keys, handler),
+            None => Shortcuts::register(keys, handler),
+        }
+        .unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn should_parse_the_shortcuts() {
+        assert_eq!(KeyMatch::from("Enter"), KeyMatch::new("Enter"));
+        assert_eq!(
+            KeyMatch::from("Ctrl+Shift+S"),
+            KeyMatch::new("S").ctrl().shift()
+        );
+        assert_eq!(KeyMatch::from("Meta++"), KeyMatch::new("+").meta());
+        assert_eq!(KeyMatch::from("+"), KeyMatch::new("+"));
+    }
+
+    #[wasm_bindgen_test]
+    fn should_dispatch_the_shortcut_of_the_innermost_scope() {
+        let document = window().unwrap().document().unwrap();
+        let dialog = document.create_element("div").unwrap();
+        let button = document.create_element("button").unwrap();
+        dialog.append_child(&button).unwrap();
+        document.body().unwrap().append_child(&dialog).unwrap();
+        let node_ref = NodeRef::new();
+        node_ref.set(Some(Node::new(WebBackend, dialog.clone().into())));
+
+        let fired = Rc::new(RefCell::new(vec![]));
+        let _page = register(&fired, None, "Ctrl+S", "page");
+        let within = register(&fired, Some(&node_ref), "Ctrl+S", "dialog");
+
+        button.dispatch_event(&keydown("s", true)).unwrap();
+        document.dispatch_event(&keydown("s", true)).unwrap();
+        drop(within);
+        button.dispatch_event(&keydown("s", true)).unwrap();
+        assert_eq!(*fired.borrow(), vec!["dialog", "page", "page"]);
+
+        document.body().unwrap().remove_child(&dialog).unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    fn should_not_dispatch_the_unmodified_shortcuts_while_typing() {
+        let document = window().unwrap().document().unwrap();
+        let input = document.create_element("input").unwrap();
+        document.body().unwrap().append_child(&input).unwrap();
+
+        let fired = Rc::new(RefCell::new(vec![]));
+        let _help = register(&fired, None, "?", "help");
+        let _find = register(&fired, None, "Ctrl+F", "find");
+
+        input.dispatch_event(&keydown("?", false)).unwrap();
+        input.dispatch_event(&keydown("f", true)).unwrap();
+        document.dispatch_event(&keydown("?", false)).unwrap();
+        assert_eq!(*fired.borrow(), vec!["find", "help"]);
+
+        document.body().unwrap().remove_child(&input).unwrap();
+    }
+}
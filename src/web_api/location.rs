@@ -0,0 +1,23 @@
+//! The location of the document in the browser window.
+
+use wasm_bindgen::prelude::*;
+use web_sys::{window, Location};
+
+fn location() -> Location {
+    window().unwrap().location()
+}
+
+/// The path of the URL, e.g. `/users/1`.
+pub fn pathname() -> Result<String, JsValue> {
+    location().pathname()
+}
+
+/// The query string of the URL along with the leading `?`, e.g. `?page=1`.
+pub fn search() -> Result<String, JsValue> {
+    location().search()
+}
+
+/// The fragment of the URL along with the leading `#`, e.g. `#top`.
+pub fn hash() -> Result<String, JsValue> {
+    location().hash()
+}
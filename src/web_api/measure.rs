@@ -0,0 +1,55 @@
+//! Measuring the layout of the elements.
+
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement};
+
+/// A rectangle in CSS pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    /// The distance of the left edge from the origin.
+    pub left: f64,
+    /// The distance of the top edge from the origin.
+    pub top: f64,
+    /// The width of the rectangle.
+    pub width: f64,
+    /// The height of the rectangle.
+    pub height: f64,
+}
+
+impl Rect {
+    /// The distance of the right edge from the origin.
+    pub fn right(&self) -> f64 {
+        self.left + self.width
+    }
+
+    /// The distance of the bottom edge from the origin.
+    pub fn bottom(&self) -> f64 {
+        self.top + self.height
+    }
+}
+
+/// The rectangle of the element relative to the viewport, including its
+/// padding and border.
+pub fn bounding_client_rect(element: &Element) -> Rect {
+    let rect = element.get_bounding_client_rect();
+    Rect {
+        left: rect.x(),
+        top: rect.y(),
+        width: rect.width(),
+        height: rect.height(),
+    }
+}
+
+/// The layout width and height of the element, rounded to integers. It is
+/// `(0, 0)` for the elements which are not HTML elements, like SVG.
+pub fn offset_size(element: &Element) -> (i32, i32) {
+    element
+        .dyn_ref::<HtmlElement>()
+        .map(|element| (element.offset_width(), element.offset_height()))
+        .unwrap_or((0, 0))
+}
+
+/// How far the contents of the element are scrolled, as `(left, top)`.
+pub fn scroll_offset(element: &Element) -> (i32, i32) {
+    (element.scroll_left(), element.scroll_top())
+}
@@ -0,0 +1,128 @@
+//! Matching the media queries, like the breakpoints of a responsive layout.
+//!
+//! A component reads whether a [MediaQuery](struct.MediaQuery.html) matches
+//! & `subscribe`s to it, so that it is rerendered when the query starts or
+//! stops matching, e.g. when the window is resized across a breakpoint. The
+//! query is better created once in `created` than on every render, as the
+//! browser parses it each time.
+//!
+//! Outside of a browser, e.g. in a headless app, a query never matches.
+//!
+//! # Example
+//! ```ignore
+//! const WIDE: &str = "(min-width: 800px)";
+//!
+//! impl Lifecycle for Layout {
+//!     fn created(&self) {
+//!         let query = MediaQuery::new(WIDE);
+//!         self.set_state(|state| state.wide = query.matches());
+//!         let setter = self.state_setter();
+//!         if let Ok(handle) = query.on_change(move |wide| {
+//!             setter.set_state(|state| state.wide = wide);
+//!         }) {
+//!             self.scope().keep(handle);
+//!         }
+//!     }
+//! }
+//!
+//! impl Render for Layout {
+//!     fn render(&self) -> Markup<Self> {
+//!         if self.wide {
+//!             html!(<Sidebar></Sidebar><Content></Content>)
+//!         } else {
+//!             html!(<Drawer></Drawer><Content></Content>)
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::{
+    component::{status_of, Component},
+    web_api::events::{listen, EventListenerHandle},
+};
+use std::rc::Rc;
+use wasm_bindgen::prelude::JsValue;
+use web_sys::{window, MediaQueryList};
+
+/// A media query, as matched against the document with `window.matchMedia`.
+#[derive(Clone)]
+pub struct MediaQuery {
+    /// The list, unless the browser failed to parse the query.
+    list: Option<MediaQueryList>,
+}
+
+impl MediaQuery {
+    /// Creates a media query, like `(prefers-reduced-motion: reduce)`. A
+    /// query which the browser does not understand, or which is created
+    /// outside of a browser, never matches.
+    pub fn new(query: &str) -> MediaQuery {
+        if !cfg!(target_arch = "wasm32") {
+            return MediaQuery { list: None };
+        }
+        MediaQuery {
+            list: window()
+                .and_then(|window| window.match_media(query).ok())
+                .and_then(|list| list),
+        }
+    }
+
+    /// Whether the query matches the document currently.
+    pub fn matches(&self) -> bool {
+        self.list.as_ref().is_some_and(MediaQueryList::matches)
+    }
+
+    /// The query, as serialized by the browser.
+    pub fn media(&self) -> String {
+        self.list
+            .as_ref()
+            .map_or_else(|| "not all".to_string(), MediaQueryList::media)
+    }
+
+    /// Invokes the handler with whether the query matches, every time it
+    /// starts or stops matching. It listens for as long as the returned
+    /// handle is kept. A query which never matches fails to be listened to.
+    pub fn on_change(
+        &self,
+        handler: impl Fn(bool) + 'static,
+    ) -> Result<EventListenerHandle, JsValue> {
+        let list = match self.list.clone() {
+            Some(list) => list,
+            None if cfg!(target_arch = "wasm32") => {
+                return Err(JsValue::from_str("The media query is not understood"))
+            }
+            // The error can only be created on wasm.
+            None => return Err(JsValue::NULL),
+        };
+        listen(list.clone().as_ref(), "change", move |_| {
+            handler(list.matches())
+        })
+    }
+
+    /// Rerenders the component every time the query starts or stops matching,
+    /// until it is destroyed.
+    pub fn subscribe<COMP: Component>(&self, component: &COMP) {
+        let status = Rc::downgrade(status_of(component));
+        // A query which is not understood never changes.
+        if let Ok(listener) = self.on_change(move |_| {
+            if let Some(status) = status.upgrade() {
+                status.borrow_mut().force_update();
+            }
+        }) {
+            component.scope().keep(listener);
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn should_match_the_media_queries() {
+        assert!(MediaQuery::new("all").matches());
+        assert!(!MediaQuery::new("not all").matches());
+        assert!(!MediaQuery::new("(min-width: 100000px)").matches());
+        assert_eq!(MediaQuery::new("screen").media(), "screen");
+    }
+}
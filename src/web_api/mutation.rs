@@ -0,0 +1,67 @@
+//! Observing the changes made to the DOM.
+
+use js_sys::Array;
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{MutationObserverInit, MutationRecord, Node};
+
+/// Observes the changes made to the DOM nodes. It stops observing when
+/// dropped.
+///
+/// It is an escape hatch for the apps which let third-party scripts change the
+/// DOM managed by Ruukh. Keep in mind that the changes made by Ruukh itself
+/// are observed as well.
+pub struct MutationObserver {
+    observer: web_sys::MutationObserver,
+    _closure: Closure<dyn Fn(Array)>,
+}
+
+impl MutationObserver {
+    /// Creates an observer which invokes the handler with the records of the
+    /// changes made to the observed nodes.
+    pub fn new(
+        handler: impl Fn(Vec<MutationRecord>) + 'static,
+    ) -> Result<MutationObserver, JsValue> {
+        let closure: Closure<dyn Fn(Array)> = Closure::wrap(Box::new(move |records: Array| {
+            handler(into_records(&records))
+        }));
+        let observer = web_sys::MutationObserver::new(closure.as_ref().unchecked_ref())?;
+        Ok(MutationObserver {
+            observer,
+            _closure: closure,
+        })
+    }
+
+    /// Starts observing the node for the changes given in the options.
+    pub fn observe(&self, node: &Node, options: &MutationObserverInit) -> Result<(), JsValue> {
+        self.observer.observe_with_options(node, options)
+    }
+
+    /// Starts observing all the changes made to the node and its descendants
+    /// i.e. the children, the attributes and the texts.
+    pub fn observe_subtree(&self, node: &Node) -> Result<(), JsValue> {
+        let options = MutationObserverInit::new();
+        options.set_child_list(true);
+        options.set_attributes(true);
+        options.set_character_data(true);
+        options.set_subtree(true);
+        self.observe(node, &options)
+    }
+
+    /// Takes the records of the changes which are not yet handled.
+    pub fn take_records(&self) -> Vec<MutationRecord> {
+        into_records(&self.observer.take_records())
+    }
+}
+
+fn into_records(records: &Array) -> Vec<MutationRecord> {
+    records
+        .iter()
+        .map(|record| record.unchecked_into::<MutationRecord>())
+        .collect()
+}
+
+impl Drop for MutationObserver {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
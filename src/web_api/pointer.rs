@@ -0,0 +1,117 @@
+//! The pointer & touch events, for the components driven by gestures like the
+//! sliders, the swipes or the drawing canvases.
+//!
+//! # Example
+//! ```ignore
+//! fn on_pointer_down(&self, event: Event) {
+//!     pointer::capture(&event).unwrap();
+//!     if let Some(pointer) = pointer::pointer(&event) {
+//!         self.set_state(|state| state.start = (pointer.client_x, pointer.client_y));
+//!     }
+//! }
+//! ```
+
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{Element, Event, PointerEvent, TouchEvent, TouchList};
+
+/// The pointer which fired a pointer event.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pointer {
+    /// Identifies the pointer across the events, e.g. one of the fingers.
+    pub id: i32,
+    /// Either `mouse`, `pen` or `touch`.
+    pub kind: String,
+    /// Whether it is the primary pointer of its kind.
+    pub is_primary: bool,
+    /// The horizontal position relative to the viewport.
+    pub client_x: i32,
+    /// The vertical position relative to the viewport.
+    pub client_y: i32,
+    /// The horizontal position relative to the element.
+    pub offset_x: i32,
+    /// The vertical position relative to the element.
+    pub offset_y: i32,
+    /// The pressure from 0 to 1, which is 0.5 for the pointers which do not
+    /// sense it while pressed.
+    pub pressure: f32,
+}
+
+/// A point of contact on a touch surface.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Touch {
+    /// Identifies the touch across the events, as long as it is in contact.
+    pub id: i32,
+    /// The horizontal position relative to the viewport.
+    pub client_x: i32,
+    /// The vertical position relative to the viewport.
+    pub client_y: i32,
+    /// The horizontal position relative to the document.
+    pub page_x: i32,
+    /// The vertical position relative to the document.
+    pub page_y: i32,
+}
+
+/// Gets the pointer of a pointer event like `pointerdown` or `pointermove`.
+pub fn pointer(event: &Event) -> Option<Pointer> {
+    let event = event.dyn_ref::<PointerEvent>()?;
+    Some(Pointer {
+        id: event.pointer_id(),
+        kind: event.pointer_type(),
+        is_primary: event.is_primary(),
+        client_x: event.client_x(),
+        client_y: event.client_y(),
+        offset_x: event.offset_x(),
+        offset_y: event.offset_y(),
+        pressure: event.pressure(),
+    })
+}
+
+/// Routes the later events of the pointer to the element listening to the
+/// event, even when the pointer moves out of it, until it is released.
+pub fn capture(event: &Event) -> Result<(), JsValue> {
+    let pointer = event
+        .dyn_ref::<PointerEvent>()
+        .ok_or_else(|| JsValue::from_str("Not a pointer event"))?;
+    match event
+        .current_target()
+        .and_then(|target| target.dyn_into::<Element>().ok())
+    {
+        Some(el) => el.set_pointer_capture(pointer.pointer_id()),
+        None => Ok(()),
+    }
+}
+
+/// Gets all the touches on the surface of a touch event.
+pub fn touches(event: &Event) -> Vec<Touch> {
+    event
+        .dyn_ref::<TouchEvent>()
+        .map_or(vec![], |event| to_vec(&event.touches()))
+}
+
+/// Gets the touches which started on the element of a touch event.
+pub fn target_touches(event: &Event) -> Vec<Touch> {
+    event
+        .dyn_ref::<TouchEvent>()
+        .map_or(vec![], |event| to_vec(&event.target_touches()))
+}
+
+/// Gets the touches which changed in a touch event, e.g. the ones lifted in a
+/// `touchend`.
+pub fn changed_touches(event: &Event) -> Vec<Touch> {
+    event
+        .dyn_ref::<TouchEvent>()
+        .map_or(vec![], |event| to_vec(&event.changed_touches()))
+}
+
+fn to_vec(list: &TouchList) -> Vec<Touch> {
+    (0..list.length())
+        .filter_map(|i| list.get(i))
+        .map(|touch| Touch {
+            id: touch.identifier(),
+            client_x: touch.client_x(),
+            client_y: touch.client_y(),
+            page_x: touch.page_x(),
+            page_y: touch.page_y(),
+        })
+        .collect()
+}
@@ -0,0 +1,133 @@
+//! Scrolling the window and the elements programmatically.
+
+use std::cell::RefCell;
+use web_sys::{window, Element, ScrollToOptions};
+
+pub use web_sys::{ScrollBehavior, ScrollLogicalPosition};
+
+thread_local! {
+    /// The number of the scroll locks held, along with the `overflow` of the
+    /// body to be restored once all of them are released.
+    static LOCKS: RefCell<(usize, String)> = const { RefCell::new((0, String::new())) };
+}
+
+/// How an element is scrolled into view.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrollIntoViewOptions {
+    /// Whether to scroll smoothly or jump instantly.
+    pub behavior: ScrollBehavior,
+    /// The vertical alignment of the element in the view.
+    pub block: ScrollLogicalPosition,
+    /// The horizontal alignment of the element in the view.
+    pub inline: ScrollLogicalPosition,
+}
+
+impl Default for ScrollIntoViewOptions {
+    /// The defaults of the browser i.e. jump to align the element to the top.
+    fn default() -> ScrollIntoViewOptions {
+        ScrollIntoViewOptions {
+            behavior: ScrollBehavior::Auto,
+            block: ScrollLogicalPosition::Start,
+            inline: ScrollLogicalPosition::Nearest,
+        }
+    }
+}
+
+/// Scrolls the ancestors of the element, so that it is visible.
+pub fn scroll_into_view(element: &Element, options: &ScrollIntoViewOptions) {
+    let web_options = web_sys::ScrollIntoViewOptions::new();
+    web_options.set_behavior(options.behavior);
+    web_options.set_block(options.block);
+    web_options.set_inline(options.inline);
+    element.scroll_into_view_with_scroll_into_view_options(&web_options);
+}
+
+/// Scrolls the element with the id into view, e.g. to the anchor in the URL
+/// after a navigation. Returns whether such an element exists.
+pub fn scroll_to_id(id: &str, options: &ScrollIntoViewOptions) -> bool {
+    let element = window().unwrap().document().unwrap().get_element_by_id(id);
+    match element {
+        Some(element) => {
+            scroll_into_view(&element, options);
+            true
+        }
+        None => false,
+    }
+}
+
+fn scroll_to_options(left: f64, top: f64, behavior: ScrollBehavior) -> ScrollToOptions {
+    let options = ScrollToOptions::new();
+    options.set_left(left);
+    options.set_top(top);
+    options.set_behavior(behavior);
+    options
+}
+
+/// Scrolls the contents of the element to the given offset.
+pub fn scroll_to(element: &Element, left: f64, top: f64, behavior: ScrollBehavior) {
+    element.scroll_to_with_scroll_to_options(&scroll_to_options(left, top, behavior));
+}
+
+/// Scrolls the contents of the element by the given amount.
+pub fn scroll_by(element: &Element, left: f64, top: f64, behavior: ScrollBehavior) {
+    element.scroll_by_with_scroll_to_options(&scroll_to_options(left, top, behavior));
+}
+
+/// Scrolls the window to the given offset.
+pub fn scroll_window_to(left: f64, top: f64, behavior: ScrollBehavior) {
+    window()
+        .unwrap()
+        .scroll_to_with_scroll_to_options(&scroll_to_options(left, top, behavior));
+}
+
+/// Scrolls the window by the given amount.
+pub fn scroll_window_by(left: f64, top: f64, behavior: ScrollBehavior) {
+    window()
+        .unwrap()
+        .scroll_by_with_scroll_to_options(&scroll_to_options(left, top, behavior));
+}
+
+/// Keeps the page from being scrolled, until dropped, e.g. while a modal is
+/// open. The locks may be nested, the page being scrollable again once all of
+/// them are released.
+pub struct ScrollLock(());
+
+/// Locks the scrolling of the page by hiding the overflow of the `<body>`.
+///
+/// # Example
+/// ```ignore
+/// fn mounted(&self) {
+///     self.scope().keep(scroll::lock_scroll());
+/// }
+/// ```
+pub fn lock_scroll() -> ScrollLock {
+    LOCKS.with(|locks| {
+        let mut locks = locks.borrow_mut();
+        if locks.0 == 0 {
+            if let Some(style) = body_style() {
+                locks.1 = style.get_property_value("overflow").unwrap_or_default();
+                let _ = style.set_property("overflow", "hidden");
+            }
+        }
+        locks.0 += 1;
+    });
+    ScrollLock(())
+}
+
+impl Drop for ScrollLock {
+    fn drop(&mut self) {
+        LOCKS.with(|locks| {
+            let mut locks = locks.borrow_mut();
+            locks.0 -= 1;
+            if locks.0 == 0 {
+                if let Some(style) = body_style() {
+                    let _ = style.set_property("overflow", &locks.1);
+                }
+            }
+        });
+    }
+}
+
+fn body_style() -> Option<web_sys::CssStyleDeclaration> {
+    window()?.document()?.body().map(|body| body.style())
+}
@@ -0,0 +1,134 @@
+//! The Web Storage i.e. `localStorage` and `sessionStorage`.
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::marker::PhantomData;
+use wasm_bindgen::prelude::*;
+use web_sys::window;
+
+/// A key-value store of strings which is either persisted across the browser
+/// sessions (local) or only for the current one (session).
+#[derive(Clone)]
+pub struct Storage(web_sys::Storage);
+
+impl Storage {
+    /// The `localStorage` which persists across the browser sessions.
+    pub fn local() -> Result<Storage, JsValue> {
+        window()
+            .unwrap()
+            .local_storage()?
+            .map(Storage)
+            .ok_or_else(|| JsValue::from_str("localStorage is not available"))
+    }
+
+    /// The `sessionStorage` which is cleared when the page session ends.
+    pub fn session() -> Result<Storage, JsValue> {
+        window()
+            .unwrap()
+            .session_storage()?
+            .map(Storage)
+            .ok_or_else(|| JsValue::from_str("sessionStorage is not available"))
+    }
+
+    /// Gets the value stored for the key, if any.
+    pub fn get_item(&self, key: &str) -> Result<Option<String>, JsValue> {
+        self.0.get_item(key)
+    }
+
+    /// Stores the value for the key, replacing the previous one.
+    pub fn set_item(&self, key: &str, value: &str) -> Result<(), JsValue> {
+        self.0.set_item(key, value)
+    }
+
+    /// Removes the value stored for the key.
+    pub fn remove_item(&self, key: &str) -> Result<(), JsValue> {
+        self.0.remove_item(key)
+    }
+
+    /// Removes all the values in the storage.
+    pub fn clear(&self) -> Result<(), JsValue> {
+        self.0.clear()
+    }
+}
+
+/// An item in a [Storage](struct.Storage.html) whose value is of type `T`. The
+/// value is stored as JSON.
+///
+/// # Example
+/// ```ignore
+/// let drafts = StoredItem::<Vec<String>>::new(Storage::local()?, "drafts");
+/// drafts.set(&vec!["Hello".to_string()])?;
+/// assert_eq!(drafts.get()?, Some(vec!["Hello".to_string()]));
+/// ```
+pub struct StoredItem<T> {
+    storage: Storage,
+    key: String,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<T: Serialize + DeserializeOwned> StoredItem<T> {
+    /// Creates an item stored for the key in the storage.
+    pub fn new(storage: Storage, key: &str) -> StoredItem<T> {
+        StoredItem {
+            storage,
+            key: key.to_string(),
+            _value: PhantomData,
+        }
+    }
+
+    /// Gets the stored value, if any. A value which cannot be deserialized as
+    /// `T` is an error.
+    pub fn get(&self) -> Result<Option<T>, JsValue> {
+        match self.storage.get_item(&self.key)? {
+            Some(json) => serde_json::from_str(&json)
+                .map(Some)
+                .map_err(|err| JsValue::from_str(&err.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    /// Stores the value, replacing the previous one.
+    pub fn set(&self, value: &T) -> Result<(), JsValue> {
+        let json =
+            serde_json::to_string(value).map_err(|err| JsValue::from_str(&err.to_string()))?;
+        self.storage.set_item(&self.key, &json)
+    }
+
+    /// Removes the stored value.
+    pub fn remove(&self) -> Result<(), JsValue> {
+        self.storage.remove_item(&self.key)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn should_set_get_and_remove_an_item() {
+        let storage = Storage::session().unwrap();
+        storage.set_item("key", "value").unwrap();
+        assert_eq!(storage.get_item("key").unwrap(), Some("value".to_string()));
+
+        storage.remove_item("key").unwrap();
+        assert_eq!(storage.get_item("key").unwrap(), None);
+    }
+
+    #[wasm_bindgen_test]
+    fn should_store_typed_values_as_json() {
+        let storage = Storage::session().unwrap();
+        let item = StoredItem::<Vec<u32>>::new(storage.clone(), "numbers");
+        item.set(&vec![1, 2, 3]).unwrap();
+        assert_eq!(
+            storage.get_item("numbers").unwrap(),
+            Some("[1,2,3]".to_string())
+        );
+        assert_eq!(item.get().unwrap(), Some(vec![1, 2, 3]));
+
+        storage.set_item("numbers", "not json").unwrap();
+        assert!(item.get().is_err());
+
+        storage.clear().unwrap();
+        assert_eq!(item.get().unwrap(), None);
+    }
+}
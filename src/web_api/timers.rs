@@ -0,0 +1,357 @@
+//! The timers which invoke a callback after a delay or at an interval.
+//!
+//! In a browser, the timers are set on the window. Elsewhere, e.g. when
+//! rendered onto a terminal, they are queued and fired by
+//! [HeadlessApp::flush](../struct.HeadlessApp.html#method.flush) once due.
+
+use crate::{
+    component::Render,
+    vdom::velement::{listener_context, with_listener_context},
+};
+use std::{
+    any::TypeId,
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+use wasm_bindgen::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::JsCast;
+#[cfg(target_arch = "wasm32")]
+use web_sys::window;
+use web_sys::Event;
+
+/// A handle to a timeout set via [set_timeout](fn.set_timeout.html). The
+/// timeout is cancelled when the handle is dropped.
+#[must_use = "The timeout is cancelled as soon as the handle is dropped"]
+pub struct Timeout {
+    id: i32,
+    handler: Option<Handler>,
+}
+
+/// A handle to an interval set via [set_interval](fn.set_interval.html). The
+/// interval is cancelled when the handle is dropped.
+#[must_use = "The interval is cancelled as soon as the handle is dropped"]
+pub struct Interval {
+    id: i32,
+    handler: Option<Handler>,
+}
+
+/// Invokes the handler once after the given milliseconds.
+pub fn set_timeout(millis: i32, handler: impl FnOnce() + 'static) -> Result<Timeout, JsValue> {
+    let handler = Cell::new(Some(handler));
+    let (id, handler) = schedule(
+        millis,
+        false,
+        Box::new(move || {
+            if let Some(handler) = handler.take() {
+                handler();
+            }
+        }),
+    )?;
+    Ok(Timeout {
+        id,
+        handler: Some(handler),
+    })
+}
+
+/// Invokes the handler repeatedly every given milliseconds.
+///
+/// # Example
+/// ```ignore
+/// fn mounted(&self) {
+///     let setter = self.state_setter();
+///     let interval = set_interval(1000, move || {
+///         setter.set_state(|state| state.seconds += 1);
+///     })
+///     .unwrap();
+///     // Stop the interval once the component is destroyed.
+///     self.scope().keep(interval);
+/// }
+/// ```
+pub fn set_interval(millis: i32, handler: impl Fn() + 'static) -> Result<Interval, JsValue> {
+    let (id, handler) = schedule(millis, true, Box::new(handler))?;
+    Ok(Interval {
+        id,
+        handler: Some(handler),
+    })
+}
+
+impl Timeout {
+    /// Lets the timeout fire even though the handle is dropped.
+    pub fn forget(mut self) {
+        if let Some(handler) = self.handler.take() {
+            handler.forget();
+        }
+    }
+}
+
+impl Interval {
+    /// Keeps the interval going for the lifetime of the program.
+    pub fn forget(mut self) {
+        if let Some(handler) = self.handler.take() {
+            handler.forget();
+        }
+    }
+}
+
+impl Drop for Timeout {
+    fn drop(&mut self) {
+        if self.handler.is_some() {
+            cancel(self.id, false);
+        }
+    }
+}
+
+impl Drop for Interval {
+    fn drop(&mut self) {
+        if self.handler.is_some() {
+            cancel(self.id, true);
+        }
+    }
+}
+
+/// The handler of a timer, which is kept alive until the timer is cancelled.
+#[cfg(target_arch = "wasm32")]
+type Handler = Closure<dyn Fn()>;
+
+/// Sets a timer on the window, which fires once or repeatedly.
+#[cfg(target_arch = "wasm32")]
+fn schedule(millis: i32, repeat: bool, handler: Box<dyn Fn()>) -> Result<(i32, Handler), JsValue> {
+    let closure: Closure<dyn Fn()> = Closure::wrap(handler);
+    let window = window().unwrap();
+    let callback = closure.as_ref().unchecked_ref();
+    let id = if repeat {
+        window.set_interval_with_callback_and_timeout_and_arguments_0(callback, millis)?
+    } else {
+        window.set_timeout_with_callback_and_timeout_and_arguments_0(callback, millis)?
+    };
+    Ok((id, closure))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn cancel(id: i32, repeat: bool) {
+    let window = window().unwrap();
+    if repeat {
+        window.clear_interval_with_handle(id);
+    } else {
+        window.clear_timeout_with_handle(id);
+    }
+}
+
+/// The time in milliseconds, from the clock of the page in the browser or a
+/// monotonic clock elsewhere, e.g. when rendered onto a terminal.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now() -> f64 {
+    window()
+        .and_then(|window| window.performance())
+        .map(|performance| performance.now())
+        .unwrap_or_else(js_sys::Date::now)
+}
+
+/// Outside of a browser, the handler is queued along with the timer, so
+/// there is nothing else to keep alive.
+#[cfg(not(target_arch = "wasm32"))]
+struct Handler;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Handler {
+    fn forget(self) {}
+}
+
+/// A timer queued outside of a browser.
+#[cfg(not(target_arch = "wasm32"))]
+struct QueuedTimer {
+    id: i32,
+    due: f64,
+    /// The milliseconds to fire it again after, if it is an interval.
+    every: Option<f64>,
+    handler: Rc<dyn Fn()>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+thread_local! {
+    static TIMERS: RefCell<Vec<QueuedTimer>> = const { RefCell::new(vec![]) };
+    static NEXT_ID: Cell<i32> = const { Cell::new(1) };
+}
+
+/// Queues a timer, which fires once or repeatedly.
+#[cfg(not(target_arch = "wasm32"))]
+fn schedule(millis: i32, repeat: bool, handler: Box<dyn Fn()>) -> Result<(i32, Handler), JsValue> {
+    let id = NEXT_ID.with(|next| next.replace(next.get() + 1));
+    let millis = f64::from(millis.max(0));
+    TIMERS.with(|timers| {
+        timers.borrow_mut().push(QueuedTimer {
+            id,
+            due: now() + millis,
+            every: if repeat { Some(millis) } else { None },
+            handler: handler.into(),
+        })
+    });
+    Ok((id, Handler))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn cancel(id: i32, _: bool) {
+    TIMERS.with(|timers| timers.borrow_mut().retain(|timer| timer.id != id));
+}
+
+/// Fires the queued timers which are due, the earliest first. An interval is
+/// fired at most once per call.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn fire_due() {
+    let time = now();
+    let mut fired = vec![];
+    loop {
+        let handler = TIMERS.with(|timers| {
+            let mut timers = timers.borrow_mut();
+            let index = timers
+                .iter()
+                .enumerate()
+                .filter(|(_, timer)| timer.due <= time && !fired.contains(&timer.id))
+                .min_by(|(_, a), (_, b)| a.due.partial_cmp(&b.due).unwrap())
+                .map(|(index, _)| index)?;
+            let timer = &mut timers[index];
+            fired.push(timer.id);
+            let handler = timer.handler.clone();
+            match timer.every {
+                Some(every) => timer.due = time + every,
+                None => {
+                    timers.remove(index);
+                }
+            }
+            Some(handler)
+        });
+        // Invoke it outside of the borrow, so that it can set or cancel the
+        // timers.
+        match handler {
+            Some(handler) => handler(),
+            None => break,
+        }
+    }
+}
+
+/// The time in milliseconds, from the clock of the page in the browser or a
+/// monotonic clock elsewhere, e.g. when rendered onto a terminal.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn now() -> f64 {
+    use std::time::Instant;
+
+    thread_local! {
+        static EPOCH: Instant = Instant::now();
+    }
+    EPOCH.with(|epoch| epoch.elapsed().as_secs_f64() * 1000.0)
+}
+
+/// Wraps an event listener to only be invoked once the events stop firing for
+/// the given milliseconds, with the last of the events. Like searching as the
+/// user types.
+///
+/// The pending invocation is kept in the scope of the component, so it
+/// survives the rerenders and is cancelled once the component is destroyed.
+/// It is shared by the listeners which debounce the same handler. A component
+/// implemented by hand without a status has no scope, so its pending
+/// invocation is cancelled along with the listener.
+///
+/// # Example
+/// ```ignore
+/// html! {
+///     <input @input={debounce(300, Self::on_search)}>
+/// }
+/// ```
+pub fn debounce<RCTX: Render, H>(millis: i32, handler: H) -> impl Fn(&RCTX, Event)
+where
+    H: Fn(&RCTX, Event) + 'static,
+{
+    let handler = Rc::new(handler);
+    let fallback = Rc::new(Debounced::default());
+    move |ctx: &RCTX, event: Event| {
+        let debounced = timer_state::<RCTX, H, _>(ctx, &fallback);
+        let handler = handler.clone();
+        // Replacing the pending timeout cancels it.
+        *debounced.pending.borrow_mut() = defer::<RCTX>(millis, move |ctx| handler(ctx, event));
+    }
+}
+
+/// Wraps an event listener to be invoked at most once in the given
+/// milliseconds. An event fired in between is handled once the milliseconds
+/// pass, with the last of such events. Like tracking the scroll position.
+///
+/// Like with [debounce](fn.debounce.html), the time of the last invocation
+/// and the pending one are kept in the scope of the component.
+///
+/// # Example
+/// ```ignore
+/// html! {
+///     <div @scroll={throttle(100, Self::on_scroll)}></div>
+/// }
+/// ```
+pub fn throttle<RCTX: Render, H>(millis: i32, handler: H) -> impl Fn(&RCTX, Event)
+where
+    H: Fn(&RCTX, Event) + 'static,
+{
+    let handler = Rc::new(handler);
+    let fallback = Rc::new(Throttled::default());
+    move |ctx: &RCTX, event: Event| {
+        let throttled = timer_state::<RCTX, H, _>(ctx, &fallback);
+        let wait = throttled.last_invoked.get() + f64::from(millis) - now();
+        if wait <= 0.0 {
+            throttled.pending.borrow_mut().take();
+            throttled.last_invoked.set(now());
+            handler(ctx, event);
+            return;
+        }
+        // Held weakly, as the state holds the pending timeout itself.
+        let (handler, throttled_ref) = (handler.clone(), Rc::downgrade(&throttled));
+        *throttled.pending.borrow_mut() = defer::<RCTX>(wait.ceil() as i32, move |ctx| {
+            if let Some(throttled) = throttled_ref.upgrade() {
+                throttled.last_invoked.set(now());
+            }
+            handler(ctx, event);
+        });
+    }
+}
+
+/// The state of a debounced handler.
+#[derive(Default)]
+struct Debounced {
+    pending: RefCell<Option<Timeout>>,
+}
+
+/// The state of a throttled handler.
+struct Throttled {
+    last_invoked: Cell<f64>,
+    pending: RefCell<Option<Timeout>>,
+}
+
+impl Default for Throttled {
+    fn default() -> Throttled {
+        Throttled {
+            last_invoked: Cell::new(f64::NEG_INFINITY),
+            pending: RefCell::new(None),
+        }
+    }
+}
+
+/// Gets the state of the handler `H` kept in the scope of the component, or
+/// the `fallback` if the component has no scope.
+fn timer_state<RCTX: Render, H: 'static, T: Default + 'static>(
+    ctx: &RCTX,
+    fallback: &Rc<T>,
+) -> Rc<T> {
+    match ctx.status() {
+        Some(status) => status.borrow().scope().keyed(TypeId::of::<H>()),
+        None => fallback.clone(),
+    }
+}
+
+/// Invokes the handler after the given milliseconds with the render context
+/// of the event listener being invoked now, unless it is gone by then.
+fn defer<RCTX: Render>(millis: i32, handler: impl FnOnce(&RCTX) + 'static) -> Option<Timeout> {
+    let render_ctx = Rc::downgrade(&listener_context::<RCTX>()?);
+    set_timeout(millis, move || {
+        if let Some(render_ctx) = render_ctx.upgrade() {
+            with_listener_context(&render_ctx, || handler(&*render_ctx.borrow()));
+        }
+    })
+    .ok()
+}
@@ -0,0 +1,142 @@
+//! The WebSocket client.
+
+use js_sys::{ArrayBuffer, Uint8Array};
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{BinaryType, CloseEvent, Event, MessageEvent};
+
+/// A message received or sent over a [WebSocket](struct.WebSocket.html).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    /// A text message.
+    Text(String),
+    /// A binary message.
+    Binary(Vec<u8>),
+}
+
+/// A connection to a WebSocket server.
+///
+/// The clones of it share the same connection, which is closed when the last
+/// one is dropped. Keep it in the [Scope](../component/struct.Scope.html) of a
+/// component for it to be closed once the component is destroyed.
+///
+/// # Example
+/// ```ignore
+/// fn mounted(&self) {
+///     let setter = self.state_setter();
+///     let socket = WebSocket::connect("wss://example.com/chat").unwrap();
+///     socket.on_message(move |message| {
+///         if let Message::Text(text) = message {
+///             setter.set_state(|state| state.messages.push(text.clone()));
+///         }
+///     });
+///     self.scope().keep(socket);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct WebSocket(Rc<Connection>);
+
+struct Connection {
+    socket: web_sys::WebSocket,
+    callbacks: RefCell<Callbacks>,
+}
+
+/// The callbacks are stored so that they live as long as the connection.
+#[derive(Default)]
+struct Callbacks {
+    on_open: Option<Closure<dyn Fn(Event)>>,
+    on_message: Option<Closure<dyn Fn(MessageEvent)>>,
+    on_close: Option<Closure<dyn Fn(CloseEvent)>>,
+    on_error: Option<Closure<dyn Fn(Event)>>,
+}
+
+impl WebSocket {
+    /// Opens a connection to the server at the URL.
+    pub fn connect(url: &str) -> Result<WebSocket, JsValue> {
+        let socket = web_sys::WebSocket::new(url)?;
+        socket.set_binary_type(BinaryType::Arraybuffer);
+        Ok(WebSocket(Rc::new(Connection {
+            socket,
+            callbacks: RefCell::new(Callbacks::default()),
+        })))
+    }
+
+    /// Invokes the handler when the connection is opened.
+    pub fn on_open(&self, handler: impl Fn() + 'static) {
+        let closure: Closure<dyn Fn(Event)> = Closure::wrap(Box::new(move |_| handler()));
+        self.0
+            .socket
+            .set_onopen(Some(closure.as_ref().unchecked_ref()));
+        self.0.callbacks.borrow_mut().on_open = Some(closure);
+    }
+
+    /// Invokes the handler with every message received.
+    pub fn on_message(&self, handler: impl Fn(Message) + 'static) {
+        let closure: Closure<dyn Fn(MessageEvent)> =
+            Closure::wrap(Box::new(move |event: MessageEvent| {
+                let data = event.data();
+                let message = match data.as_string() {
+                    Some(text) => Message::Text(text),
+                    None => match data.dyn_into::<ArrayBuffer>() {
+                        Ok(buffer) => Message::Binary(Uint8Array::new(&buffer).to_vec()),
+                        Err(_) => return,
+                    },
+                };
+                handler(message);
+            }));
+        self.0
+            .socket
+            .set_onmessage(Some(closure.as_ref().unchecked_ref()));
+        self.0.callbacks.borrow_mut().on_message = Some(closure);
+    }
+
+    /// Invokes the handler when the connection is closed.
+    pub fn on_close(&self, handler: impl Fn(CloseEvent) + 'static) {
+        let closure: Closure<dyn Fn(CloseEvent)> = Closure::wrap(Box::new(handler));
+        self.0
+            .socket
+            .set_onclose(Some(closure.as_ref().unchecked_ref()));
+        self.0.callbacks.borrow_mut().on_close = Some(closure);
+    }
+
+    /// Invokes the handler when the connection fails.
+    pub fn on_error(&self, handler: impl Fn(Event) + 'static) {
+        let closure: Closure<dyn Fn(Event)> = Closure::wrap(Box::new(handler));
+        self.0
+            .socket
+            .set_onerror(Some(closure.as_ref().unchecked_ref()));
+        self.0.callbacks.borrow_mut().on_error = Some(closure);
+    }
+
+    /// Sends the message to the server.
+    pub fn send(&self, message: &Message) -> Result<(), JsValue> {
+        match message {
+            Message::Text(text) => self.0.socket.send_with_str(text),
+            Message::Binary(bytes) => self.0.socket.send_with_u8_array(bytes),
+        }
+    }
+
+    /// Closes the connection. The callbacks are not invoked anymore.
+    pub fn close(&self) -> Result<(), JsValue> {
+        self.0.detach();
+        self.0.socket.close()
+    }
+}
+
+impl Connection {
+    fn detach(&self) {
+        self.socket.set_onopen(None);
+        self.socket.set_onmessage(None);
+        self.socket.set_onclose(None);
+        self.socket.set_onerror(None);
+        *self.callbacks.borrow_mut() = Callbacks::default();
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.detach();
+        // Closing an already closed connection is a no-op.
+        let _ = self.socket.close();
+    }
+}
@@ -0,0 +1,154 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::{bus::EventBus, prelude::*};
+use std::cell::RefCell;
+
+struct Added(&'static str);
+
+#[test]
+fn should_deliver_the_published_events_to_the_siblings_on_flush() {
+    thread_local! {
+        static ADD: RefCell<Option<Box<dyn Fn()>>> = RefCell::new(None);
+    }
+
+    #[component]
+    struct Button {
+        label: &'static str,
+    }
+
+    impl Lifecycle for Button {
+        fn mounted(&self) {
+            let label = self.label;
+            ADD.with(|add| {
+                *add.borrow_mut() = Some(Box::new(move || {
+                    EventBus::<Added>::global().publish(Added(label));
+                }))
+            });
+        }
+    }
+
+    impl Render for Button {
+        fn render(&self) -> Markup<Self> {
+            html!({ self.label })
+        }
+    }
+
+    #[component]
+    struct Log {
+        #[state]
+        items: Vec<&'static str>,
+    }
+
+    impl Lifecycle for Log {
+        fn created(&self) {
+            let setter = self.state_setter();
+            EventBus::<Added>::global().subscribe(self, move |Added(item)| {
+                setter.set_state(|state| state.items.push(item));
+            });
+        }
+    }
+
+    impl Render for Log {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <p>{ self.items.join(", ") }</p>
+            }
+        }
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <Button label={"Apple"}></Button>
+                <Log></Log>
+            }
+        }
+    }
+
+    let add = || ADD.with(|add| add.borrow().as_ref().unwrap()());
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "Apple");
+
+    add();
+    add();
+    // Nothing is delivered until the flush.
+    assert_eq!(root.to_string(), "Apple");
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "Apple\nApple, Apple");
+}
+
+#[test]
+fn should_not_deliver_to_the_destroyed_components() {
+    thread_local! {
+        static SHOW: RefCell<Option<Box<dyn Fn(bool)>>> = RefCell::new(None);
+        static RECEIVED: RefCell<Vec<&'static str>> = RefCell::new(vec![]);
+    }
+
+    #[component]
+    struct Listener {
+        // Only a component with a state or props can subscribe.
+        #[state]
+        count: i32,
+    }
+
+    impl Lifecycle for Listener {
+        fn created(&self) {
+            EventBus::<Added>::global().subscribe(self, |Added(item)| {
+                RECEIVED.with(|received| received.borrow_mut().push(item));
+            });
+        }
+    }
+
+    impl Render for Listener {
+        fn render(&self) -> Markup<Self> {
+            html!()
+        }
+    }
+
+    #[component]
+    struct MainApp {
+        #[state(default = true)]
+        show: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            let setter = self.state_setter();
+            SHOW.with(|show| {
+                *show.borrow_mut() = Some(Box::new(move |value| {
+                    setter.set_state(|state| state.show = value);
+                }))
+            });
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            if self.show {
+                html! {
+                    <Listener></Listener>
+                }
+            } else {
+                html!()
+            }
+        }
+    }
+
+    let (_, mut app) = common::mount::<MainApp>();
+    EventBus::<Added>::global().publish(Added("Apple"));
+    app.flush().unwrap();
+    RECEIVED.with(|received| assert_eq!(*received.borrow(), vec!["Apple"]));
+
+    SHOW.with(|show| show.borrow().as_ref().unwrap()(false));
+    app.flush().unwrap();
+    EventBus::<Added>::global().publish(Added("Banana"));
+    app.flush().unwrap();
+    RECEIVED.with(|received| assert_eq!(*received.borrow(), vec!["Apple"]));
+}
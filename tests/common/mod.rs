@@ -0,0 +1,13 @@
+//! The fixture shared by the tests which mount an app on a terminal.
+
+use ruukh::{backend::TuiNode, prelude::*, HeadlessApp};
+
+/// Mounts the `COMP` app onto a new terminal root, returned along with the
+/// app so that the output is read after every flush.
+pub fn mount<COMP: Render<Props = (), Events = ()>>() -> (TuiNode, HeadlessApp<COMP>) {
+    let root = TuiNode::root();
+    let app = App::<COMP>::new()
+        .mount_headless(root.clone().into())
+        .unwrap();
+    (root, app)
+}
@@ -1,5 +1,7 @@
 #![feature(proc_macro_hygiene, decl_macro)]
 
+mod common;
+
 use ruukh::prelude::*;
 
 #[test]
@@ -166,3 +168,165 @@ fn should_build_a_component_with_default_option_props() {
     assert_eq!(props.prop_a, Some(false));
     assert_eq!(props.prop_b, Some(3));
 }
+
+#[test]
+fn should_not_clone_the_markup_with_a_component() {
+    #[component]
+    #[derive(Lifecycle)]
+    struct Icon;
+
+    impl Render for Icon {
+        fn render(&self) -> Markup<Self> {
+            html!("★")
+        }
+    }
+
+    let markup: Markup<()> = html! {
+        <button><Icon></Icon>"Star"</button>
+    };
+
+    assert!(markup.try_clone().is_none());
+}
+
+#[test]
+fn should_not_handle_the_events_once_the_render_context_is_destroyed() {
+    use ruukh::component::FromEventProps;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[component]
+    #[events(
+        fn save(&self, num: i32);
+    )]
+    struct Button;
+
+    let saved = Rc::new(RefCell::new(vec![]));
+    let render_ctx = Rc::new(RefCell::new(()));
+    let events = <ButtonEvent as FromEventProps<()>>::from(
+        ButtonEventProps {
+            save: Box::new({
+                let saved = saved.clone();
+                move |_: &(), num| saved.borrow_mut().push(num)
+            }),
+        },
+        render_ctx.clone(),
+    );
+    (events.save)(1);
+
+    drop(render_ctx);
+    (events.save)(2);
+    assert_eq!(*saved.borrow(), vec![1]);
+}
+
+#[test]
+#[should_panic(expected = "The component handling the event is already destroyed")]
+fn should_panic_on_the_events_with_a_value_once_the_render_context_is_destroyed() {
+    use ruukh::component::FromEventProps;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[component]
+    #[events(
+        fn click(&self) -> i32;
+    )]
+    struct Button;
+
+    let render_ctx = Rc::new(RefCell::new(()));
+    let events = <ButtonEvent as FromEventProps<()>>::from(
+        ButtonEventProps {
+            click: Box::new(|_: &()| 5),
+        },
+        render_ctx.clone(),
+    );
+    assert_eq!((events.click)(), 5);
+
+    drop(render_ctx);
+    (events.click)();
+}
+
+#[test]
+fn should_drop_the_removed_components_along_with_their_event_handlers() {
+    use std::cell::RefCell;
+    use web_sys::Event;
+
+    thread_local! {
+        static DROPPED: RefCell<Vec<&'static str>> = RefCell::new(vec![]);
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    #[events(
+        fn done(&self, event: Event);
+    )]
+    struct Child;
+
+    impl Drop for Child {
+        fn drop(&mut self) {
+            DROPPED.with(|dropped| dropped.borrow_mut().push("Child"));
+        }
+    }
+
+    impl Render for Child {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <button @click={Self::done}>"Done"</button>
+            }
+        }
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct Parent;
+
+    impl Parent {
+        fn on_done(&self, _: Event) {}
+    }
+
+    impl Drop for Parent {
+        fn drop(&mut self) {
+            DROPPED.with(|dropped| dropped.borrow_mut().push("Parent"));
+        }
+    }
+
+    impl Render for Parent {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <Child @done={Self::on_done}></Child>
+            }
+        }
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        hide_parent: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| {
+                state.hide_parent = true;
+            });
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            if self.hide_parent {
+                html!()
+            } else {
+                html! {
+                    <Parent></Parent>
+                }
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "Done");
+    assert!(DROPPED.with(|dropped| dropped.borrow().is_empty()));
+
+    app.flush().unwrap();
+
+    let mut dropped = DROPPED.with(|dropped| dropped.borrow().clone());
+    dropped.sort();
+    assert_eq!(dropped, vec!["Child", "Parent"]);
+}
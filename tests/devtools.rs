@@ -0,0 +1,99 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::{devtools, prelude::*};
+use std::cell::Cell;
+
+#[test]
+fn should_render_the_pure_components_as_is() {
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <ul>
+                    <li key={1}>"Pure"</li>
+                </ul>
+            }
+        }
+    }
+
+    devtools::check_purity(true);
+    let (root, _) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "- Pure");
+}
+
+#[test]
+#[should_panic(expected = "<MainApp> rendered a different tree when rendered twice")]
+fn should_panic_once_a_component_renders_a_different_tree() {
+    thread_local! {
+        static RENDERS: Cell<u32> = Cell::new(0);
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            RENDERS.with(|renders| renders.set(renders.get() + 1));
+            html! {
+                <p>"Rendered "{ RENDERS.with(Cell::get) }" times"</p>
+            }
+        }
+    }
+
+    devtools::check_purity(true);
+    common::mount::<MainApp>();
+}
+
+#[test]
+fn should_dump_the_rendered_tree_with_the_components() {
+    #[component]
+    #[derive(Lifecycle)]
+    struct Todo {
+        #[prop]
+        title: String,
+    }
+
+    impl Render for Todo {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <li class={"todo"}>{ &self.title }</li>
+            }
+        }
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            let todos: Vec<_> = vec![(1, "Write"), (2, "Review")]
+                .into_iter()
+                .map(|(id, title)| html! { <Todo key={id} title={title.to_string()}></Todo> })
+                .collect();
+            html! {
+                <ul hidden={false}>{ todos }</ul>
+            }
+        }
+    }
+
+    let (_, app) = common::mount::<MainApp>();
+
+    assert_eq!(
+        app.dump_tree(),
+        "<MainApp>\n\
+         \x20 ul\n\
+         \x20   #1 <Todo>\n\
+         \x20     li class=\"todo\"\n\
+         \x20       \"Write\"\n\
+         \x20   #2 <Todo>\n\
+         \x20     li class=\"todo\"\n\
+         \x20       \"Review\"\n"
+    );
+}
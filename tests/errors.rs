@@ -0,0 +1,239 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+use ruukh::{
+    backend::{Backend, Node, TuiBackend, TuiNode},
+    errors::{self, ErrorReport},
+    prelude::*,
+};
+use std::{
+    cell::{Cell, RefCell},
+    panic::{self, AssertUnwindSafe},
+};
+use wasm_bindgen::prelude::JsValue;
+use web_sys::Event;
+
+#[test]
+fn should_report_the_panics_along_with_the_components() {
+    thread_local! {
+        static REPORTS: RefCell<Vec<ErrorReport>> = RefCell::new(vec![]);
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct Broken;
+
+    impl Render for Broken {
+        fn render(&self) -> Markup<Self> {
+            panic!("Could not render");
+        }
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <div><Broken></Broken></div>
+            }
+        }
+    }
+
+    errors::install_error_reporting_with(|report| {
+        REPORTS.with(|reports| reports.borrow_mut().push(report.clone()));
+    });
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        App::<MainApp>::new().mount_headless(TuiNode::root().into())
+    }));
+    let _ = panic::take_hook();
+
+    assert!(result.is_err());
+    let reports = REPORTS.with(|reports| reports.borrow().clone());
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].message, "Could not render");
+    assert!(reports[0]
+        .location
+        .as_ref()
+        .unwrap()
+        .starts_with("tests/errors.rs:"));
+    assert_eq!(reports[0].components, vec!["MainApp", "Broken"]);
+    assert!(reports[0].to_string().ends_with(" in <MainApp> <Broken>"));
+}
+
+/// Lays out like the `TuiBackend`, but cannot create a `canvas`.
+#[derive(Clone)]
+struct NoCanvasBackend;
+
+impl Backend for NoCanvasBackend {
+    type Node = TuiNode;
+    type Listener = ();
+
+    fn create_element(&self, tag: &str) -> Result<TuiNode, JsValue> {
+        if tag == "canvas" {
+            return Err(JsValue::NULL);
+        }
+        TuiBackend.create_element(tag)
+    }
+
+    fn create_text_node(&self, content: &str) -> Result<TuiNode, JsValue> {
+        TuiBackend.create_text_node(content)
+    }
+
+    fn create_comment(&self, content: &str) -> Result<TuiNode, JsValue> {
+        TuiBackend.create_comment(content)
+    }
+
+    fn set_text_content(&self, node: &TuiNode, content: &str) -> Result<(), JsValue> {
+        TuiBackend.set_text_content(node, content)
+    }
+
+    fn set_attribute(&self, node: &TuiNode, key: &str, value: &str) -> Result<(), JsValue> {
+        TuiBackend.set_attribute(node, key, value)
+    }
+
+    fn remove_attribute(&self, node: &TuiNode, key: &str) -> Result<(), JsValue> {
+        TuiBackend.remove_attribute(node, key)
+    }
+
+    fn insert_before(
+        &self,
+        parent: &TuiNode,
+        child: &TuiNode,
+        next: Option<&TuiNode>,
+    ) -> Result<(), JsValue> {
+        TuiBackend.insert_before(parent, child, next)
+    }
+
+    fn remove_child(&self, parent: &TuiNode, child: &TuiNode) -> Result<(), JsValue> {
+        TuiBackend.remove_child(parent, child)
+    }
+
+    fn add_event_listener(
+        &self,
+        node: &TuiNode,
+        type_: &str,
+        listener: Box<dyn Fn(Event)>,
+    ) -> Result<(), JsValue> {
+        TuiBackend.add_event_listener(node, type_, listener)
+    }
+
+    fn remove_event_listener(
+        &self,
+        node: &TuiNode,
+        type_: &str,
+        listener: &(),
+    ) -> Result<(), JsValue> {
+        TuiBackend.remove_event_listener(node, type_, listener)
+    }
+}
+
+#[test]
+fn should_show_the_crash_screen_once_the_app_fails_to_render() {
+    thread_local! {
+        static REPORTS: RefCell<Vec<ErrorReport>> = RefCell::new(vec![]);
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <h1>"Drawing"</h1>
+                <div><canvas></canvas></div>
+            }
+        }
+    }
+
+    let root = TuiNode::root();
+    let mut app = App::<MainApp>::new()
+        .on_error(|report| {
+            REPORTS.with(|reports| reports.borrow_mut().push(report.clone()));
+        })
+        .crash_screen(|report| html!(<p>"Crashed: "{ report.message.clone() }</p>))
+        .mount_headless(Node::new(NoCanvasBackend, root.clone()))
+        .unwrap();
+    assert_eq!(root.to_string(), "Crashed: The backend failed");
+    assert!(!app.flush().unwrap());
+
+    let reports = REPORTS.with(|reports| reports.borrow().clone());
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].message, "The backend failed");
+}
+
+#[test]
+fn should_remount_the_app_once_it_fails_to_render() {
+    thread_local! {
+        static ATTEMPTS: Cell<u32> = Cell::new(0);
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            ATTEMPTS.with(|attempts| attempts.set(attempts.get() + 1));
+            if ATTEMPTS.with(Cell::get) == 1 {
+                html!(<div><canvas></canvas></div>)
+            } else {
+                html!(<p>"Remounted"</p>)
+            }
+        }
+    }
+
+    let root = TuiNode::root();
+    App::<MainApp>::new()
+        .remount_on_error()
+        .crash_screen(|_| html!(<p>"Crashed"</p>))
+        .mount_headless(Node::new(NoCanvasBackend, root.clone()))
+        .unwrap();
+    assert_eq!(root.to_string(), "Remounted");
+    assert_eq!(ATTEMPTS.with(Cell::get), 2);
+}
+
+#[test]
+fn should_fail_to_mount_without_recovering_from_the_errors() {
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html!(<canvas></canvas>)
+        }
+    }
+
+    let result = App::<MainApp>::new().mount_headless(Node::new(NoCanvasBackend, TuiNode::root()));
+    assert!(result.is_err());
+}
+
+#[test]
+fn should_keep_the_other_nodes_of_the_parent_once_the_app_crashes() {
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <h1>"Drawing"</h1>
+                <div><canvas></canvas></div>
+            }
+        }
+    }
+
+    let root = TuiNode::root();
+    let header = TuiBackend.create_element("header").unwrap();
+    TuiBackend
+        .set_text_content(&header, "Rendered on the server")
+        .unwrap();
+    TuiBackend.insert_before(&root, &header, None).unwrap();
+    App::<MainApp>::new()
+        .crash_screen(|_| html!(<p>"Crashed"</p>))
+        .mount_headless(Node::new(NoCanvasBackend, root.clone()))
+        .unwrap();
+    assert_eq!(root.to_string(), "Rendered on the server\nCrashed");
+}
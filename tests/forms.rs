@@ -0,0 +1,87 @@
+use ruukh::forms::{self, Form, FromForm};
+
+#[derive(Debug, PartialEq)]
+struct Signup {
+    email: String,
+    age: u8,
+}
+
+impl FromForm for Signup {
+    fn from_form(form: &Form<Self>) -> Result<Self, (&'static str, String)> {
+        Ok(Signup {
+            email: form.value("email").to_string(),
+            age: form
+                .value("age")
+                .parse()
+                .map_err(|_| ("age", "The age is to be a number.".to_string()))?,
+        })
+    }
+}
+
+fn signup_form() -> Form<Signup> {
+    Form::new()
+        .field("email", "")
+        .validate("email", forms::required())
+        .validate("email", |value: &str| {
+            if value.contains('@') {
+                Ok(())
+            } else {
+                Err("The email is invalid.".to_string())
+            }
+        })
+        .field("age", "18")
+}
+
+#[test]
+fn should_show_the_errors_once_touched() {
+    let mut form = signup_form();
+    assert!(!form.is_valid());
+    assert_eq!(form.error("email"), None);
+
+    form.touch("email");
+    assert_eq!(form.error("email"), Some("This field is required."));
+
+    form.set_value("email", "me");
+    assert!(form.is_dirty("email"));
+    assert_eq!(form.error("email"), Some("The email is invalid."));
+
+    form.set_value("email", "me@example.com");
+    assert_eq!(form.error("email"), None);
+    assert!(form.is_valid());
+}
+
+#[test]
+fn should_submit_only_a_valid_form() {
+    let mut form = signup_form();
+    assert_eq!(form.try_submit(), None);
+    assert!(form.is_submitted());
+    assert_eq!(form.error("email"), Some("This field is required."));
+
+    form.set_value("email", "me@example.com");
+    form.set_value("age", "old");
+    assert_eq!(form.try_submit(), None);
+    assert_eq!(form.error("age"), Some("The age is to be a number."));
+
+    form.set_value("age", "30");
+    assert_eq!(
+        form.try_submit(),
+        Some(Signup {
+            email: "me@example.com".to_string(),
+            age: 30
+        })
+    );
+}
+
+#[test]
+fn should_reset_to_the_initial_values() {
+    let mut form = signup_form();
+    form.set_value("email", "me@example.com");
+    form.touch("email");
+    let edited = form.clone();
+
+    form.reset();
+    assert_eq!(form.value("email"), "");
+    assert!(!form.is_touched("email"));
+    assert!(!form.is_dirty("email"));
+    assert!(form != edited);
+}
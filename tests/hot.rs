@@ -0,0 +1,45 @@
+#![cfg(feature = "hot-reload")]
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::{hot, prelude::*};
+
+#[test]
+fn should_rerender_with_the_replaced_render_keeping_the_state() {
+    #[component]
+    struct Counter {
+        #[state]
+        count: i32,
+    }
+
+    impl Lifecycle for Counter {
+        fn mounted(&self) {
+            self.set_state(|state| state.count = 2);
+        }
+    }
+
+    impl Render for Counter {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <p>"Count: "{ self.count }</p>
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<Counter>();
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "Count: 2");
+
+    hot::replace_render::<Counter>(|counter| {
+        html! {
+            <p>"Clicked "{ counter.count }" times"</p>
+        }
+    });
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "Clicked 2 times");
+
+    hot::restore_render::<Counter>();
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "Count: 2");
+}
@@ -1,6 +1,13 @@
 #![feature(proc_macro_hygiene)]
 
-use ruukh::prelude::*;
+use ruukh::{
+    prelude::*,
+    vdom::{
+        velement::{AttributeValue, VElement},
+        vtext::VText,
+        Metrics, Visitor,
+    },
+};
 use web_sys::Event;
 
 #[test]
@@ -62,3 +69,312 @@ fn should_expand_element_with_attributes() {
         </button>
     };
 }
+
+#[test]
+fn should_display_escaped_html() {
+    let markup: Markup<()> = html! {
+        <div title={r#"Say "Hi""#}>"<b>Tom & Jerry</b>"</div>
+    };
+
+    assert_eq!(
+        markup.to_string(),
+        r#"<div title="Say &quot;Hi&quot;">&lt;b&gt;Tom &amp; Jerry&lt;/b&gt;</div>"#
+    );
+}
+
+#[test]
+fn should_display_void_elements_without_closing_tag() {
+    let markup: Markup<()> = html! {
+        "Name:"<br><input name={"name"}>
+    };
+
+    assert_eq!(markup.to_string(), r#"Name:<br><input name="name">"#);
+}
+
+#[test]
+fn should_display_pretty_html() {
+    let markup: Markup<()> = html! {
+        <div class={"card"}>
+            <span>"Hello"</span>
+            <p></p>
+            <br>
+        </div>
+        "Bye"
+    };
+
+    assert_eq!(
+        format!("{:#}", markup),
+        r#"<div class="card">
+  <span>
+    Hello
+  </span>
+  <p></p>
+  <br>
+</div>
+Bye"#
+    );
+}
+
+#[test]
+fn should_build_the_class_list_from_conditional_parts() {
+    let (size, is_active, is_disabled): (Option<&str>, _, _) = (Some("btn-large"), true, false);
+    let markup: Markup<()> = html! {
+        <button class={classes!["btn", size, ("active", is_active), ("disabled", is_disabled)]}>
+        </button>
+        <span class={classes![None::<&str>, ("hidden", false)]}></span>
+    };
+
+    assert_eq!(
+        markup.to_string(),
+        r#"<button class="btn btn-large active"></button><span></span>"#
+    );
+}
+
+#[test]
+fn should_serialize_the_typed_attribute_values() {
+    let label = String::from("Amount");
+    let markup: Markup<()> = html! {
+        <input aria-label={&label} min={0} max={100u8} step={0.5} tabindex={-1} required={true} readonly={false}>
+    };
+
+    assert_eq!(
+        markup.to_string(),
+        r#"<input aria-label="Amount" min="0" max="100" step="0.5" tabindex="-1" required>"#
+    );
+}
+
+#[test]
+fn should_spread_the_attributes_over_the_declared_ones() {
+    use ruukh::vdom::velement::AttributeValue;
+    use std::collections::BTreeMap;
+
+    let mut attributes = BTreeMap::<String, AttributeValue>::new();
+    attributes.insert("aria-label".to_string(), "Close".into());
+    attributes.insert("title".to_string(), "Close the dialog".into());
+    let markup: Markup<()> = html! {
+        <button name={"close"} title={"x"} ..{attributes.clone()}></button>
+        <input ..{vec![("hidden", true)]}>
+    };
+
+    assert_eq!(
+        markup.to_string(),
+        r#"<button name="close" title="Close the dialog" aria-label="Close"></button><input hidden>"#
+    );
+}
+
+#[test]
+fn should_render_the_spread_attributes_in_the_same_order() {
+    use std::collections::HashMap;
+
+    let attributes = ["title", "data-id", "aria-label", "role", "lang"]
+        .iter()
+        .map(|key| (*key, key.to_string()))
+        .collect::<HashMap<_, _>>();
+    let markup: Markup<()> = html! {
+        <div id={"card"} role={"region"} ..{attributes}></div>
+    };
+
+    assert_eq!(
+        markup.to_string(),
+        r#"<div id="card" role="role" aria-label="aria-label" data-id="data-id" lang="lang" title="title"></div>"#
+    );
+}
+
+#[test]
+fn should_clone_the_markup_to_render_it_in_multiple_places() {
+    let icon: Markup<()> = html! {
+        <span class={"icon"} @click={on_click}>"★"</span>
+    };
+    let markup: Markup<()> = html! {
+        <button>{ icon.try_clone().unwrap() }"Star"</button>
+        <button>{ icon }"Unstar"</button>
+    };
+
+    assert_eq!(
+        markup.to_string(),
+        r#"<button><span class="icon">★</span>Star</button><button><span class="icon">★</span>Unstar</button>"#
+    );
+}
+
+#[test]
+fn should_measure_the_markup() {
+    let items = (0..3)
+        .map(|i| html! { <li @click={on_click}>{ i }</li> })
+        .collect::<Vec<Markup<()>>>();
+    let markup: Markup<()> = html! {
+        <h1>"Items"</h1>
+        <ul @mouseover={on_click}>{ items }</ul>
+    };
+
+    assert_eq!(
+        markup.metrics(),
+        Metrics {
+            nodes: 9,
+            depth: 3,
+            listeners: 4,
+            components: 0,
+        }
+    );
+}
+
+#[test]
+fn should_convert_the_plain_values_into_texts() {
+    use ruukh::vdom::VNode;
+
+    let children: Vec<VNode<()>> = vec![
+        "Total: ".into(),
+        String::from("$").into(),
+        42u64.into(),
+        '.'.into(),
+        5.into(),
+    ];
+    let markup: Markup<()> = html! {
+        <p>{ children }" "{ -1.5 }{ true }</p>
+    };
+
+    assert_eq!(markup.to_string(), "<p>Total: $42.5 -1.5true</p>");
+}
+
+#[test]
+fn should_expand_element_with_key_matched_listener() {
+    use ruukh::web_api::keyboard::{on_key, KeyMatch};
+
+    let _: Markup<()> = html! {
+        <textarea
+            @keydown={on_key("Ctrl+Enter", on_click)}
+            @keyup={on_key(KeyMatch::new("Escape").shift(), on_click)}
+        ></textarea>
+    };
+}
+
+#[test]
+fn should_inspect_the_markup() {
+    let items = vec!["Home", "About"];
+    let markup: Markup<()> = html! {
+        <ul class={"menu"}>
+            { items.iter().map(|item| html! { <li>{ *item }</li> }).collect::<Vec<_>>() }
+        </ul>
+    };
+
+    let ul = markup.as_element().unwrap();
+    assert_eq!(ul.tag(), "ul");
+    assert_eq!(
+        ul.attribute("class"),
+        Some(&AttributeValue::String("menu".to_string()))
+    );
+    let items = ul.child().as_list().unwrap();
+    assert_eq!(items.len(), 2);
+    let texts: Vec<_> = items
+        .items()
+        .map(|(_, li)| {
+            li.as_element()
+                .unwrap()
+                .child()
+                .as_text()
+                .unwrap()
+                .content()
+        })
+        .collect();
+    assert_eq!(texts, vec!["Home", "About"]);
+}
+
+#[test]
+fn should_keep_the_static_markup_an_element() {
+    let markup: Markup<()> = html! {
+        <ul class={"menu"}>
+            <li>"Home"</li>
+            <li>"About"</li>
+        </ul>
+    };
+
+    // Only the children of the element are hoisted.
+    assert_eq!(markup.as_element().unwrap().tag(), "ul");
+    assert_eq!(
+        markup.to_string(),
+        r#"<ul class="menu"><li>Home</li><li>About</li></ul>"#
+    );
+
+    // Too few to be hoisted at all.
+    let markup: Markup<()> = html!(<p>"Hi"</p>);
+    let p = markup.as_element().unwrap();
+    assert_eq!(p.child().as_text().unwrap().content(), "Hi");
+}
+
+#[test]
+fn should_walk_through_the_markup() {
+    struct Links {
+        hrefs: Vec<String>,
+        texts: usize,
+        depth: usize,
+    }
+
+    impl<RCTX> Visitor<RCTX> for Links {
+        fn visit_element(&mut self, el: &VElement<RCTX>) {
+            if let Some(AttributeValue::String(href)) = el.attribute("href") {
+                self.hrefs.push(format!("{}@{}", href, self.depth));
+            }
+            self.depth += 1;
+        }
+
+        fn leave_element(&mut self, _: &VElement<RCTX>) {
+            self.depth -= 1;
+        }
+
+        fn visit_text(&mut self, _: &VText<RCTX>) {
+            self.texts += 1;
+        }
+    }
+
+    let page = "/about";
+    let markup: Markup<()> = html! {
+        <nav>
+            <a href={"/"}>"Home"</a>
+            <p><a href={page}>{ page }</a></p>
+        </nav>
+        "Footer"
+    };
+    let mut links = Links {
+        hrefs: vec![],
+        texts: 0,
+        depth: 0,
+    };
+    markup.walk(&mut links);
+
+    assert_eq!(links.hrefs, vec!["/@1", "/about@2"]);
+    assert_eq!(links.texts, 3);
+    assert_eq!(links.depth, 0);
+}
+
+#[test]
+fn should_map_the_elements_of_the_markup() {
+    let name = "Docs";
+    let markup: Markup<()> = html! {
+        <nav>
+            <a href={"https://example.com"}>"Home"</a>
+            <p><a href={"docs"} class={"active"}>{ name }</a></p>
+        </nav>
+    };
+
+    let markup = markup.map_elements(|el| {
+        if el.tag() != "a" {
+            return el;
+        }
+        let href = match el.attribute("href") {
+            Some(AttributeValue::String(href)) if !href.contains("://") => {
+                Some(format!("/base/{}", href))
+            }
+            _ => None,
+        };
+        let el = el.with_class("link");
+        match href {
+            Some(href) => el.with_attributes(vec![("href", href)]),
+            None => el,
+        }
+    });
+
+    assert_eq!(
+        markup.to_string(),
+        "<nav><a href=\"https://example.com\" class=\"link\">Home</a>\
+         <p><a class=\"active link\" href=\"/base/docs\">Docs</a></p></nav>"
+    );
+}
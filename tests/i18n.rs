@@ -0,0 +1,98 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::{
+    i18n::{self, Catalog, Plural},
+    prelude::*,
+};
+
+fn catalog() -> Catalog {
+    Catalog::new()
+        .add(
+            "en",
+            &[
+                ("greeting", "Hello, {name}!"),
+                ("inbox.one", "{count} message"),
+                ("inbox.other", "{count} messages"),
+            ],
+        )
+        .add(
+            "ru",
+            &[
+                ("inbox.one", "{count} сообщение"),
+                ("inbox.few", "{count} сообщения"),
+                ("inbox.many", "{count} сообщений"),
+            ],
+        )
+        .fallback("en")
+}
+
+#[test]
+fn should_translate_and_pluralize_the_messages() {
+    i18n::load(catalog(), "en-GB");
+    let inbox = |count: u32| i18n::translate("inbox", &[("count", count.into())]);
+
+    assert_eq!(
+        i18n::translate("greeting", &[("name", "Ruukh".into())]),
+        "Hello, Ruukh!"
+    );
+    assert_eq!(inbox(1), "1 message");
+    assert_eq!(inbox(5), "5 messages");
+    assert_eq!(i18n::translate("missing", &[]), "missing");
+
+    i18n::set_locale("ru");
+    assert_eq!(inbox(21), "21 сообщение");
+    assert_eq!(inbox(3), "3 сообщения");
+    assert_eq!(inbox(11), "11 сообщений");
+    // It falls back onto the messages in English.
+    assert_eq!(
+        i18n::translate("greeting", &[("name", "Ruukh".into())]),
+        "Hello, Ruukh!"
+    );
+
+    assert_eq!(i18n::plural("fr", 0.0), Plural::One);
+    assert_eq!(i18n::plural("ar", 2.0), Plural::Two);
+    assert_eq!(i18n::plural("ja", 1.0), Plural::Other);
+}
+
+#[test]
+fn should_rerender_the_translated_nodes_once_the_locale_is_switched() {
+    #[component]
+    struct Inbox {
+        // A state, so that it can be rerendered.
+        #[state(default = 2)]
+        unread: u32,
+    }
+
+    impl Lifecycle for Inbox {}
+
+    impl Render for Inbox {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                { t!("inbox", count = self.unread) }
+            }
+        }
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <Inbox></Inbox>
+            }
+        }
+    }
+
+    i18n::load(catalog(), "en");
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "2 messages");
+
+    i18n::set_locale("ru");
+    assert_eq!(i18n::locale(), "ru");
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "2 сообщения");
+}
@@ -0,0 +1,99 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::prelude::*;
+
+#[test]
+fn should_keep_the_removed_component_alive() {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static HOOKS: RefCell<Vec<&'static str>> = RefCell::new(vec![]);
+        static HIDE: RefCell<Option<Box<dyn Fn(bool)>>> = RefCell::new(None);
+    }
+
+    fn hook(name: &'static str) {
+        HOOKS.with(|hooks| hooks.borrow_mut().push(name));
+    }
+
+    #[component]
+    struct Counter {
+        #[state]
+        count: i32,
+    }
+
+    impl Lifecycle for Counter {
+        fn created(&self) {
+            hook("created");
+        }
+
+        fn mounted(&self) {
+            self.set_state(|state| state.count += 1);
+        }
+
+        fn activated(&self) {
+            hook("activated");
+        }
+
+        fn deactivated(&self) {
+            hook("deactivated");
+        }
+
+        fn destroyed(&self) {
+            hook("destroyed");
+        }
+    }
+
+    impl Render for Counter {
+        fn render(&self) -> Markup<Self> {
+            html!({ self.count })
+        }
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        hidden: bool,
+        #[state]
+        cache: KeepAlive,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            let setter = self.state_setter();
+            HIDE.with(|hide| {
+                *hide.borrow_mut() = Some(Box::new(move |hidden| {
+                    setter.set_state(|state| state.hidden = hidden);
+                }))
+            });
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            if self.hidden {
+                html!("Hidden")
+            } else {
+                html!(<Counter keep_alive={&self.cache}></Counter>)
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "1");
+
+    let hide = |hidden| HIDE.with(|hide| hide.borrow().as_ref().unwrap()(hidden));
+    hide(true);
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "Hidden");
+
+    hide(false);
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "1");
+    assert_eq!(
+        HOOKS.with(|hooks| hooks.borrow().clone()),
+        vec!["created", "deactivated", "activated"]
+    );
+}
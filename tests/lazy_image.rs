@@ -0,0 +1,37 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::prelude::*;
+
+#[test]
+fn should_swap_in_the_source_of_a_lazy_image() {
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                {
+                    LazyImage::new("/photo.jpg")
+                        .alt("A photo")
+                        .placeholder("/photo.thumb.jpg")
+                }
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    let image = root.children().remove(0);
+    assert_eq!(image.tag().as_ref().map(String::as_str), Some("img"));
+    assert_eq!(image.attribute("alt").unwrap(), "A photo");
+    assert_eq!(image.attribute("src").unwrap(), "/photo.thumb.jpg");
+    assert_eq!(image.attribute("data-state").unwrap(), "placeholder");
+
+    // There is no viewport outside of a browser, so it is loaded right away.
+    assert!(app.flush().unwrap());
+    let image = root.children().remove(0);
+    assert_eq!(image.attribute("src").unwrap(), "/photo.jpg");
+    assert_eq!(image.attribute("data-state").unwrap(), "loading");
+}
@@ -1,4 +1,12 @@
-use ruukh::{component::Status, prelude::*};
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::{
+    backend::{TuiBackend, TuiNode},
+    component::Status,
+    prelude::*,
+};
 use std::{cell::RefCell, rc::Rc};
 
 #[test]
@@ -30,3 +38,242 @@ fn should_impl_lifecycle() {
 
     Button.created();
 }
+
+#[test]
+fn should_invoke_before_update_prior_to_the_patch() {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static SNAPSHOTS: RefCell<Vec<(String, i32)>> = RefCell::new(vec![]);
+    }
+
+    #[component]
+    struct Child {
+        #[prop]
+        count: i32,
+        #[state]
+        root: NodeRef,
+    }
+
+    impl Lifecycle for Child {
+        fn before_update(&self, new_props: &ChildProps) {
+            let node = self.root.get().unwrap();
+            let text = node.backend_node::<TuiBackend>().unwrap().to_string();
+            SNAPSHOTS.with(|snapshots| snapshots.borrow_mut().push((text, new_props.count)));
+        }
+    }
+
+    impl Render for Child {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <div ref={&self.root}>{ self.count }</div>
+            }
+        }
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        count: i32,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| {
+                state.count = 3;
+            });
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <Child count={self.count}></Child>
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    app.flush().unwrap();
+
+    assert_eq!(root.to_string(), "3");
+    assert_eq!(
+        SNAPSHOTS.with(|snapshots| snapshots.borrow().clone()),
+        vec![("0".to_string(), 3)]
+    );
+}
+
+#[test]
+fn should_delay_the_removal_until_proceeded() {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static REMOVAL: RefCell<Option<ruukh::component::Removal>> = RefCell::new(None);
+    }
+
+    #[component]
+    struct Child {
+        #[state]
+        leaving: bool,
+    }
+
+    impl Lifecycle for Child {
+        fn before_destroy(&self) -> bool {
+            REMOVAL.with(|removal| *removal.borrow_mut() = Some(self.removal()));
+            self.set_state(|state| state.leaving = true);
+            false
+        }
+    }
+
+    impl Render for Child {
+        fn render(&self) -> Markup<Self> {
+            if self.leaving {
+                html!("Leaving")
+            } else {
+                html!("Child")
+            }
+        }
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        hide_child: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| {
+                state.hide_child = true;
+            });
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            if self.hide_child {
+                html!()
+            } else {
+                html!(<Child></Child>)
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "Child");
+
+    app.flush().unwrap();
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "Leaving");
+
+    let removal = REMOVAL.with(|removal| removal.borrow_mut().take()).unwrap();
+    assert!(removal.is_delayed());
+    removal.proceed().unwrap();
+    assert!(!removal.is_delayed());
+    assert_eq!(root.to_string(), "");
+}
+
+#[test]
+fn should_let_the_component_recover_from_a_render_error() {
+    use ruukh::backend::{Backend, Node};
+    use wasm_bindgen::prelude::JsValue;
+    use web_sys::Event;
+
+    /// Lays out like the `TuiBackend`, but cannot create a `canvas`.
+    #[derive(Clone)]
+    struct NoCanvasBackend;
+
+    impl Backend for NoCanvasBackend {
+        type Node = TuiNode;
+        type Listener = ();
+
+        fn create_element(&self, tag: &str) -> Result<TuiNode, JsValue> {
+            if tag == "canvas" {
+                return Err(JsValue::NULL);
+            }
+            TuiBackend.create_element(tag)
+        }
+
+        fn create_text_node(&self, content: &str) -> Result<TuiNode, JsValue> {
+            TuiBackend.create_text_node(content)
+        }
+
+        fn create_comment(&self, content: &str) -> Result<TuiNode, JsValue> {
+            TuiBackend.create_comment(content)
+        }
+
+        fn set_text_content(&self, node: &TuiNode, content: &str) -> Result<(), JsValue> {
+            TuiBackend.set_text_content(node, content)
+        }
+
+        fn set_attribute(&self, node: &TuiNode, key: &str, value: &str) -> Result<(), JsValue> {
+            TuiBackend.set_attribute(node, key, value)
+        }
+
+        fn remove_attribute(&self, node: &TuiNode, key: &str) -> Result<(), JsValue> {
+            TuiBackend.remove_attribute(node, key)
+        }
+
+        fn insert_before(
+            &self,
+            parent: &TuiNode,
+            child: &TuiNode,
+            next: Option<&TuiNode>,
+        ) -> Result<(), JsValue> {
+            TuiBackend.insert_before(parent, child, next)
+        }
+
+        fn remove_child(&self, parent: &TuiNode, child: &TuiNode) -> Result<(), JsValue> {
+            TuiBackend.remove_child(parent, child)
+        }
+
+        fn add_event_listener(
+            &self,
+            node: &TuiNode,
+            type_: &str,
+            listener: Box<dyn Fn(Event)>,
+        ) -> Result<(), JsValue> {
+            TuiBackend.add_event_listener(node, type_, listener)
+        }
+
+        fn remove_event_listener(
+            &self,
+            node: &TuiNode,
+            type_: &str,
+            listener: &(),
+        ) -> Result<(), JsValue> {
+            TuiBackend.remove_event_listener(node, type_, listener)
+        }
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        fallback: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn on_render_error(&self, _: JsValue) -> Result<(), JsValue> {
+            self.set_state(|state| state.fallback = true);
+            Ok(())
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            if self.fallback {
+                html!(<p>"Fallback"</p>)
+            } else {
+                html!(<div><canvas></canvas></div>)
+            }
+        }
+    }
+
+    let root = TuiNode::root();
+    let mut app = App::<MainApp>::new()
+        .mount_headless(Node::new(NoCanvasBackend, root.clone()))
+        .unwrap();
+
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "Fallback");
+}
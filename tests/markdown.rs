@@ -0,0 +1,70 @@
+#![cfg(feature = "markdown")]
+#![feature(proc_macro_hygiene, decl_macro)]
+
+use ruukh::{markdown::Markdown, prelude::*};
+
+#[component]
+#[derive(Lifecycle)]
+struct MainApp;
+
+impl Render for MainApp {
+    fn render(&self) -> Markup<Self> {
+        html!()
+    }
+}
+
+fn render(source: &str) -> String {
+    Markup::<MainApp>::from(Markdown::new(source)).to_string()
+}
+
+#[test]
+fn should_render_the_blocks() {
+    assert_eq!(
+        render(
+            "# Title #\n\nSome *text*\nwrapped.\n\n> Quoted\n\n---\n\n```rust\nlet a = 1 < 2;\n```"
+        ),
+        "<h1>Title</h1><p>Some <em>text</em>\nwrapped.</p><blockquote><p>Quoted</p></blockquote>\
+         <hr><pre><code class=\"language-rust\">let a = 1 &lt; 2;</code></pre>"
+    );
+}
+
+#[test]
+fn should_render_the_tight_and_loose_lists() {
+    assert_eq!(
+        render("- One\n- Two\n  - Nested\n\n3. Three\n\n4. Four"),
+        "<ul><li>One</li><li>Two<ul><li>Nested</li></ul></li></ul>\
+         <ol start=\"3\"><li><p>Three</p></li><li><p>Four</p></li></ol>"
+    );
+}
+
+#[test]
+fn should_render_the_inlines() {
+    assert_eq!(
+        render(
+            "**Bold** _em_ ~~gone~~ `a*b` [link](https://ruukh.rs \"Ruukh\") \
+             ![logo](/logo.png) <https://x.io> \\*not\\* &amp;  \nnext"
+        ),
+        "<p><strong>Bold</strong> <em>em</em> <del>gone</del> <code>a*b</code> \
+         <a href=\"https://ruukh.rs\" title=\"Ruukh\">link</a> <img src=\"/logo.png\" alt=\"logo\"> \
+         <a href=\"https://x.io\">https://x.io</a> *not* &amp;<br>next</p>"
+    );
+}
+
+#[test]
+fn should_leave_out_the_unsafe_urls_and_the_html() {
+    assert_eq!(
+        render("[click](javascript:alert(1)) <b>bold</b>"),
+        "<p>click &lt;b&gt;bold&lt;/b&gt;</p>"
+    );
+}
+
+#[test]
+fn should_parse_the_text_nested_too_deep_as_a_text() {
+    let depth = 100_000;
+    let quoted = render(&format!("{} Deep", ">".repeat(depth)));
+    assert_eq!(quoted.matches("<blockquote>").count(), 32);
+    assert!(quoted.contains("<p>&gt;&gt;"));
+
+    let linked = render(&format!("{}a{}", "[".repeat(depth), "](/a)".repeat(depth)));
+    assert_eq!(linked.matches("<a href=\"/a\">").count(), 32);
+}
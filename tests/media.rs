@@ -0,0 +1,47 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::{prelude::*, web_api::media::MediaQuery};
+
+#[test]
+fn should_never_match_the_media_queries_outside_of_a_browser() {
+    #[component]
+    struct Layout {
+        #[state]
+        wide: bool,
+    }
+
+    impl Lifecycle for Layout {
+        fn created(&self) {
+            let query = MediaQuery::new("all");
+            query.subscribe(self);
+            self.set_state(|state| state.wide = query.matches());
+        }
+    }
+
+    impl Render for Layout {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                { if self.wide { "wide" } else { "narrow" } }
+            }
+        }
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <Layout></Layout>
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "narrow");
+    assert_eq!(MediaQuery::new("all").media(), "not all");
+}
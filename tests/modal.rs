@@ -0,0 +1,133 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::{backend::TuiNode, prelude::*};
+use std::cell::RefCell;
+
+#[test]
+fn should_render_the_content_of_a_portal_onto_its_target() {
+    thread_local! {
+        static TARGET: TuiNode = TuiNode::root();
+        static SET_COUNT: RefCell<Option<Box<dyn Fn(Option<i32>)>>> = RefCell::new(None);
+    }
+
+    #[component]
+    struct MainApp {
+        #[state(default = Some(1))]
+        count: Option<i32>,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            let setter = self.state_setter();
+            SET_COUNT.with(|set| {
+                *set.borrow_mut() = Some(Box::new(move |count| {
+                    setter.set_state(move |state| state.count = count)
+                }))
+            });
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            let toast = match self.count {
+                Some(count) => Portal::new(html!(<p>"Saved "{ count }</p>))
+                    .target(TARGET.with(Clone::clone))
+                    .into(),
+                None => Markup::None,
+            };
+            html! {
+                <p>"App"</p>
+                { toast }
+            }
+        }
+    }
+
+    let set_count = |count| SET_COUNT.with(|set| set.borrow().as_ref().unwrap()(count));
+    let target = || TARGET.with(|target| target.to_string());
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "App");
+    assert_eq!(target(), "Saved 1");
+
+    set_count(Some(2));
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "App");
+    assert_eq!(target(), "Saved 2");
+
+    set_count(None);
+    app.flush().unwrap();
+    assert_eq!(target(), "");
+    assert!(TARGET.with(|target| target.children().is_empty()));
+}
+
+#[test]
+fn should_focus_a_modal_once_opened() {
+    thread_local! {
+        static SET_OPEN: RefCell<Option<Box<dyn Fn(bool)>>> = RefCell::new(None);
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        is_open: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            let setter = self.state_setter();
+            SET_OPEN.with(|set| {
+                *set.borrow_mut() = Some(Box::new(move |is_open| {
+                    setter.set_state(move |state| state.is_open = is_open)
+                }))
+            });
+        }
+    }
+
+    impl MainApp {
+        fn close(&self) {
+            self.set_state(|state| state.is_open = false);
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            let modal = if self.is_open {
+                Modal::new(html!(<p>"Delete the file?"</p>))
+                    .label("Delete the file")
+                    .on_close(Self::close)
+                    .into()
+            } else {
+                Markup::None
+            };
+            html! {
+                <p>"App"</p>
+                { modal }
+            }
+        }
+    }
+
+    let set_open = |is_open| SET_OPEN.with(|set| set.borrow().as_ref().unwrap()(is_open));
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "App");
+
+    // It is rendered in place outside of a browser.
+    set_open(true);
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "App\nDelete the file?");
+    let dialog = root.children().remove(1);
+    assert_eq!(dialog.attribute("role").unwrap(), "dialog");
+    assert_eq!(dialog.attribute("aria-label").unwrap(), "Delete the file");
+    assert!(dialog.is_focused());
+
+    // The open modal is patched on the later renders.
+    set_open(true);
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "App\nDelete the file?");
+
+    set_open(false);
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "App");
+}
@@ -0,0 +1,100 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::{
+    prelude::*,
+    vdom::profiler::{Phase, Profile},
+};
+use std::cell::RefCell;
+
+#[test]
+fn should_report_the_flushes_which_render_the_content_of_a_profiler() {
+    thread_local! {
+        static PROFILES: RefCell<Vec<Profile>> = RefCell::new(vec![]);
+        static INCREMENT: RefCell<Vec<(&'static str, Box<dyn Fn()>)>> = RefCell::new(vec![]);
+    }
+
+    #[component]
+    struct Counter {
+        name: &'static str,
+        #[state]
+        count: i32,
+    }
+
+    impl Lifecycle for Counter {
+        fn mounted(&self) {
+            let setter = self.state_setter();
+            let increment = move || setter.set_state(|state| state.count += 1);
+            INCREMENT.with(|increments| {
+                increments
+                    .borrow_mut()
+                    .push((self.name, Box::new(increment)))
+            });
+        }
+    }
+
+    impl Render for Counter {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <p>{ self.count }</p>
+            }
+        }
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                {
+                    Profiler::new("counter", html!(<Counter name={"inner"}></Counter>)).on_render(|profile| {
+                        PROFILES.with(|profiles| profiles.borrow_mut().push(profile.clone()))
+                    })
+                }
+                <Counter name={"outer"}></Counter>
+            }
+        }
+    }
+
+    let increment = |name: &str| {
+        INCREMENT.with(|increments| {
+            let increments = increments.borrow();
+            let (_, increment) = increments.iter().find(|(n, _)| *n == name).unwrap();
+            increment()
+        })
+    };
+    let phases = || {
+        PROFILES.with(|profiles| {
+            profiles
+                .borrow()
+                .iter()
+                .map(|profile| (profile.id, profile.phase))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "0\n0");
+    assert_eq!(phases(), vec![("counter", Phase::Mount)]);
+
+    // The counter within the profiler is rerendered on its own.
+    increment("inner");
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "1\n0");
+    assert_eq!(
+        phases(),
+        vec![("counter", Phase::Mount), ("counter", Phase::Update)]
+    );
+
+    // Nothing within the profiler is rendered.
+    increment("outer");
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "1\n1");
+    assert_eq!(phases().len(), 2);
+
+    let profiles = PROFILES.with(|profiles| profiles.borrow().clone());
+    assert!(profiles.iter().all(|profile| profile.duration >= 0.0));
+}
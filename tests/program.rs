@@ -0,0 +1,93 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::{
+    component::Scope,
+    prelude::*,
+    program::{self, Program, ProgramComponent, Sender, View},
+};
+use std::cell::RefCell;
+
+struct Counter;
+
+#[derive(Clone)]
+enum Message {
+    Increment,
+    Reset,
+}
+
+thread_local! {
+    static SENDER: RefCell<Option<Sender<Counter>>> = RefCell::new(None);
+}
+
+impl Program for Counter {
+    type Model = i32;
+    type Message = Message;
+
+    fn init() -> i32 {
+        5
+    }
+
+    fn update(count: &mut i32, message: Message) {
+        match message {
+            Message::Increment => *count += 1,
+            Message::Reset => *count = 0,
+        }
+    }
+
+    fn view(count: &i32) -> View<Self> {
+        html! {
+            <button @click={program::send(Message::Increment)}>{ *count }</button>
+        }
+    }
+
+    fn subscriptions(sender: Sender<Self>, _: &Scope) {
+        SENDER.with(|s| *s.borrow_mut() = Some(sender));
+    }
+}
+
+fn send(message: Message) {
+    SENDER.with(|sender| sender.borrow().as_ref().unwrap().send(message));
+}
+
+#[test]
+fn should_view_the_model_updated_by_the_messages() {
+    let (root, mut app) = common::mount::<ProgramComponent<Counter>>();
+    assert_eq!(root.to_string(), "5");
+
+    send(Message::Increment);
+    send(Message::Increment);
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "7");
+
+    send(Message::Reset);
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "0");
+}
+
+#[test]
+fn should_render_a_program_within_a_component() {
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <p>"Count: "{ program::embed::<Counter, _>() }</p>
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "Count: 5");
+
+    send(Message::Increment);
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "Count: 6");
+    assert_eq!(
+        app.dump_tree(),
+        "<MainApp>\n  p\n    \"Count: \"\n    <Counter>\n      button\n        \"6\"\n"
+    );
+}
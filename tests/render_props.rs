@@ -0,0 +1,114 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::prelude::*;
+
+#[test]
+fn should_render_the_render_props_in_the_context_of_the_parent() {
+    #[derive(Clone, PartialEq)]
+    struct Item {
+        name: &'static str,
+        done: bool,
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    #[events(
+        fn render_item(&self, item: &Item) -> Markup;
+
+        #[optional]
+        fn render_empty(&self) -> Markup;
+    )]
+    struct List {
+        items: Vec<Item>,
+    }
+
+    impl Render for List {
+        fn render(&self) -> Markup<Self> {
+            if self.items.is_empty() {
+                return self.render_empty().unwrap_or(Markup::None);
+            }
+            let items: Vec<_> = self.items.iter().map(|item| self.render_item(item)).collect();
+            html! {
+                <ul>{ items }</ul>
+            }
+        }
+    }
+
+    #[component]
+    struct MainApp {
+        #[state(default = "?")]
+        marker: &'static str,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| state.marker = "x");
+        }
+    }
+
+    impl MainApp {
+        fn render_item(&self, item: &Item) -> Markup<Self> {
+            let marker = if item.done { self.marker } else { " " };
+            html! {
+                <li>{ marker }{ item.name }</li>
+            }
+        }
+
+        fn render_empty(&self) -> Markup<Self> {
+            html!(<p>"Nothing to do"</p>)
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            let items = vec![
+                Item {
+                    name: "Write",
+                    done: true,
+                },
+                Item {
+                    name: "Test",
+                    done: false,
+                },
+            ];
+            html! {
+                <List items={items} @render-item={Self::render_item}></List>
+                <List items={vec![]} @render-item={Self::render_item} @render-empty={Self::render_empty}></List>
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "- ?Write\n-  Test\nNothing to do");
+
+    // The items are rendered again with the state of the parent.
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "- xWrite\n-  Test\nNothing to do");
+}
+
+#[test]
+fn should_render_nothing_of_the_markup_whose_context_is_destroyed() {
+    use ruukh::vdom::vcomponent::VComponent;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            let render_ctx = Rc::new(RefCell::new(()));
+            let markup = VComponent::rendered_in(html!(<p>"Gone"</p>), &render_ctx);
+            drop(render_ctx);
+            html! {
+                <div>"Kept"</div>
+                { Markup::from(markup) }
+            }
+        }
+    }
+
+    let (root, _) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "Kept");
+}
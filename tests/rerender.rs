@@ -0,0 +1,291 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::prelude::*;
+
+#[test]
+fn should_rerender_only_the_dirty_component() {
+    use std::cell::Cell;
+
+    thread_local! {
+        static PARENT_RENDERS: Cell<usize> = Cell::new(0);
+    }
+
+    #[component]
+    struct Child {
+        #[state]
+        count: i32,
+    }
+
+    impl Lifecycle for Child {
+        fn mounted(&self) {
+            self.set_state(|state| {
+                state.count += 1;
+            });
+        }
+    }
+
+    impl Render for Child {
+        fn render(&self) -> Markup<Self> {
+            html!({ self.count })
+        }
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            PARENT_RENDERS.with(|renders| renders.set(renders.get() + 1));
+            html! {
+                <div>"Count: "<Child></Child></div>
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "Count: 0");
+
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "Count: 1");
+    assert_eq!(PARENT_RENDERS.with(|renders| renders.get()), 1);
+}
+
+#[test]
+fn should_rerender_on_force_update() {
+    use std::cell::Cell;
+
+    thread_local! {
+        static TICKS: Cell<i32> = Cell::new(0);
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        unused: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            TICKS.with(|ticks| ticks.set(10));
+            self.force_update();
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html!({ TICKS.with(|ticks| ticks.get()) })
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "0");
+
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "10");
+}
+
+#[test]
+fn should_not_update_the_component_with_the_same_props() {
+    use std::cell::{Cell, RefCell};
+
+    thread_local! {
+        static RENDERS: Cell<usize> = Cell::new(0);
+        static BEFORE_UPDATES: RefCell<Vec<i32>> = RefCell::new(vec![]);
+    }
+
+    #[derive(Lifecycle)]
+    #[component]
+    struct GrandChild;
+
+    impl Render for GrandChild {
+        fn render(&self) -> Markup<Self> {
+            RENDERS.with(|renders| renders.set(renders.get() + 1));
+            html! {
+                "!"
+            }
+        }
+    }
+
+    #[component]
+    struct Child {
+        #[prop]
+        count: i32,
+    }
+
+    impl Lifecycle for Child {
+        fn before_update(&self, new_props: &ChildProps) {
+            BEFORE_UPDATES.with(|updates| updates.borrow_mut().push(new_props.count));
+        }
+    }
+
+    impl Render for Child {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <div>{ self.count }<GrandChild></GrandChild></div>
+            }
+        }
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        tick: i32,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| {
+                state.tick = 1;
+            });
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <p>{ self.tick }</p>
+                <Child count={self.tick / 2}></Child>
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    app.flush().unwrap();
+
+    assert_eq!(root.to_string(), "1\n0!");
+    assert_eq!(RENDERS.with(Cell::get), 1);
+    assert!(BEFORE_UPDATES.with(|updates| updates.borrow().is_empty()));
+}
+
+#[test]
+fn should_place_the_nodes_of_a_component_after_its_sibling_was_replaced() {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static TOGGLES: RefCell<Vec<Box<dyn Fn()>>> = RefCell::new(vec![]);
+    }
+
+    #[component]
+    struct Item {
+        name: &'static str,
+        #[state]
+        toggled: bool,
+    }
+
+    impl Lifecycle for Item {
+        fn mounted(&self) {
+            let setter = self.state_setter();
+            TOGGLES.with(|toggles| {
+                toggles.borrow_mut().push(Box::new(move || {
+                    setter.set_state(|state| state.toggled = true);
+                }))
+            });
+        }
+    }
+
+    impl Render for Item {
+        fn render(&self) -> Markup<Self> {
+            // Replaces the element, as the tag changes.
+            if self.toggled {
+                html!(<div>{ self.name }"!"</div>)
+            } else {
+                html!(<p>{ self.name }</p>)
+            }
+        }
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <Item name={"First"}></Item>
+                <Item name={"Second"}></Item>
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "First\nSecond");
+
+    // The items are created from the last, so the second is toggled first.
+    let toggle = |index: usize| TOGGLES.with(|toggles| toggles.borrow()[index]());
+    toggle(0);
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "First\nSecond!");
+
+    toggle(1);
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "First!\nSecond!");
+}
+
+#[test]
+fn should_place_the_nodes_of_a_component_which_rendered_nothing() {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static TOGGLES: RefCell<Vec<Box<dyn Fn()>>> = RefCell::new(vec![]);
+    }
+
+    #[component]
+    struct Item {
+        name: &'static str,
+        #[state]
+        toggled: bool,
+    }
+
+    impl Lifecycle for Item {
+        fn mounted(&self) {
+            let setter = self.state_setter();
+            TOGGLES.with(|toggles| {
+                toggles.borrow_mut().push(Box::new(move || {
+                    setter.set_state(|state| state.toggled = true);
+                }))
+            });
+        }
+    }
+
+    impl Render for Item {
+        fn render(&self) -> Markup<Self> {
+            match (self.name, self.toggled) {
+                ("Badge", false) => html!(),
+                ("Badge", true) => html!(<p>"New"</p>),
+                // Replaces the element, as the tag changes.
+                (name, true) => html!(<div>{ name }"!"</div>),
+                (name, false) => html!(<p>{ name }</p>),
+            }
+        }
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <Item name={"First"}></Item>
+                <Item name={"Badge"}></Item>
+                <Item name={"Second"}></Item>
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "First\nSecond");
+
+    // The node which followed the badge is replaced before it shows up.
+    let toggle = |index: usize| TOGGLES.with(|toggles| toggles.borrow()[index]());
+    toggle(0);
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "First\nSecond!");
+
+    toggle(1);
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "First\nNew\nSecond!");
+}
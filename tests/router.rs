@@ -0,0 +1,13 @@
+use ruukh::{prelude::*, router::link, vdom::velement::Attribute};
+
+#[test]
+fn should_render_link_as_anchor() {
+    let markup: Markup<()> = link(
+        "/about",
+        vec![Attribute::new("class", "nav")],
+        "About".into(),
+    )
+    .into();
+
+    assert_eq!(markup.to_string(), r#"<a class="nav" href="/about">About</a>"#);
+}
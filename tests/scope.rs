@@ -0,0 +1,200 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::prelude::*;
+
+#[test]
+fn should_drop_the_scope_of_a_destroyed_component() {
+    use std::cell::Cell;
+
+    thread_local! {
+        static DROPPED: Cell<bool> = Cell::new(false);
+    }
+
+    struct Guard;
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            DROPPED.with(|dropped| dropped.set(true));
+        }
+    }
+
+    #[component]
+    struct Child {
+        #[state]
+        ticks: i32,
+    }
+
+    impl Lifecycle for Child {
+        fn mounted(&self) {
+            self.scope().keep(Guard);
+        }
+    }
+
+    impl Render for Child {
+        fn render(&self) -> Markup<Self> {
+            html!("Child")
+        }
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        hide_child: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| {
+                state.hide_child = true;
+            });
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            if self.hide_child {
+                html!()
+            } else {
+                html!(<Child></Child>)
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "Child");
+    assert!(!DROPPED.with(|dropped| dropped.get()));
+
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "");
+    assert!(DROPPED.with(|dropped| dropped.get()));
+}
+
+#[test]
+fn should_scope_a_component_without_any_props_or_state() {
+    use std::cell::Cell;
+
+    thread_local! {
+        static DROPPED: Cell<bool> = Cell::new(false);
+    }
+
+    struct Guard;
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            DROPPED.with(|dropped| dropped.set(true));
+        }
+    }
+
+    #[component]
+    struct Child;
+
+    impl Lifecycle for Child {
+        fn mounted(&self) {
+            self.scope().keep(Guard);
+        }
+    }
+
+    impl Render for Child {
+        fn render(&self) -> Markup<Self> {
+            html!("Child")
+        }
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        hide_child: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| {
+                state.hide_child = true;
+            });
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            if self.hide_child {
+                html!()
+            } else {
+                html!(<Child></Child>)
+            }
+        }
+    }
+
+    let (_, mut app) = common::mount::<MainApp>();
+    assert!(!DROPPED.with(|dropped| dropped.get()));
+
+    app.flush().unwrap();
+    assert!(DROPPED.with(|dropped| dropped.get()));
+}
+
+#[test]
+fn should_clean_up_the_scope_of_a_destroyed_component_in_reverse() {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static CLEANUPS: RefCell<Vec<&'static str>> = RefCell::new(vec![]);
+    }
+
+    #[component]
+    struct Child {
+        #[state]
+        ticks: i32,
+    }
+
+    impl Lifecycle for Child {
+        fn mounted(&self) {
+            let scope = self.scope();
+            scope.on_clear(|| CLEANUPS.with(|cleanups| cleanups.borrow_mut().push("listener")));
+            scope.on_clear(|| CLEANUPS.with(|cleanups| cleanups.borrow_mut().push("timer")));
+        }
+
+        fn destroyed(&self) {
+            CLEANUPS.with(|cleanups| cleanups.borrow_mut().push("destroyed"));
+        }
+    }
+
+    impl Render for Child {
+        fn render(&self) -> Markup<Self> {
+            html!("Child")
+        }
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        hide_child: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| {
+                state.hide_child = true;
+            });
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            if self.hide_child {
+                html!()
+            } else {
+                html!(<Child></Child>)
+            }
+        }
+    }
+
+    let (_, mut app) = common::mount::<MainApp>();
+    assert!(CLEANUPS.with(|cleanups| cleanups.borrow().is_empty()));
+
+    app.flush().unwrap();
+    assert_eq!(
+        CLEANUPS.with(|cleanups| cleanups.borrow().clone()),
+        vec!["destroyed", "timer", "listener"]
+    );
+}
@@ -0,0 +1,497 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+use ruukh::prelude::*;
+use web_sys::Event;
+
+#[component]
+#[derive(Lifecycle)]
+struct MainApp;
+
+impl Render for MainApp {
+    fn render(&self) -> Markup<Self> {
+        html! {
+            <div class={"greeting"}>"Hello "{ "World!" }</div>
+            <button disabled={true} hidden={false} @click={Self::on_click}>"Click"</button>
+        }
+    }
+}
+
+impl MainApp {
+    fn on_click(&self, _: Event) {}
+}
+
+#[test]
+fn should_render_to_string_with_hydration_markers() {
+    let html = App::<MainApp>::new().render_to_string().unwrap();
+
+    assert_eq!(
+        html,
+        r#"<div class="greeting">Hello <!---->World!</div><button disabled data-ruukh-events="click">Click</button>"#
+    );
+}
+
+#[test]
+fn should_render_to_static_markup() {
+    let html = App::<MainApp>::new().render_to_static_markup().unwrap();
+
+    assert_eq!(
+        html,
+        r#"<div class="greeting">Hello World!</div><button disabled>Click</button>"#
+    );
+}
+
+#[test]
+fn should_render_the_head_tags_set_by_the_components() {
+    use ruukh::head::{Head, HeadTag};
+
+    #[component]
+    struct Page {
+        #[prop]
+        title: &'static str,
+    }
+
+    impl Lifecycle for Page {
+        fn created(&self) {
+            let head = Head::new(vec![
+                HeadTag::title(self.title),
+                HeadTag::meta("description", "A \"quoted\" page"),
+            ]);
+            self.scope().keep(head);
+        }
+    }
+
+    impl Render for Page {
+        fn render(&self) -> Markup<Self> {
+            html!(<h1>{ self.title }</h1>)
+        }
+    }
+
+    #[component]
+    struct Layout {
+        #[state]
+        unused: bool,
+    }
+
+    impl Lifecycle for Layout {
+        fn created(&self) {
+            self.scope().keep(Head::new(vec![HeadTag::title("Site")]));
+        }
+    }
+
+    impl Render for Layout {
+        fn render(&self) -> Markup<Self> {
+            html!(<Page title={"About"}></Page>)
+        }
+    }
+
+    let (head, body) = App::<Layout>::new().render_with_head().unwrap();
+    assert_eq!(
+        head,
+        r#"<title>About</title><meta name="description" content="A &quot;quoted&quot; page">"#
+    );
+    assert_eq!(body, "<h1>About</h1>");
+
+    // The heads are dropped along with the app.
+    let (head, _) = App::<MainApp>::new().render_with_head().unwrap();
+    assert_eq!(head, "");
+}
+
+#[test]
+fn should_render_the_scoped_styles_of_the_components() {
+    #[component]
+    #[derive(Lifecycle)]
+    struct Card;
+
+    impl Render for Card {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <div class={"card"}><p>"Text"</p></div>
+                <span></span>
+            }
+        }
+
+        fn style() -> Option<&'static str> {
+            Some("& { padding: 1em; } p > b { color: red; }")
+        }
+    }
+
+    let (head, body) = App::<Card>::new().render_with_head().unwrap();
+    let scope = body
+        .split('"')
+        .nth(1)
+        .and_then(|class| class.split(' ').nth(1))
+        .unwrap()
+        .to_string();
+    assert!(scope.starts_with("ruukh-"));
+    assert_eq!(
+        body,
+        format!(
+            r#"<div class="card {0}"><p>Text</p></div><span class="{0}"></span>"#,
+            scope
+        )
+    );
+    assert_eq!(
+        head,
+        format!(
+            r#"<style data-ruukh-scope="{0}">.{0}{{padding: 1em;}}.{0} p > b{{color: red;}}</style>"#,
+            scope
+        )
+    );
+}
+
+#[test]
+fn should_render_the_draggables_and_the_drop_targets() {
+    use ruukh::vdom::{velement::VElement, VNode};
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct Board;
+
+    impl Render for Board {
+        fn render(&self) -> Markup<Self> {
+            let card = VElement::childless("li", vec![], vec![]).draggable();
+            let column =
+                VElement::new("ul", vec![], vec![], Some(VNode::Element(card))).drop_target();
+            VNode::Element(column)
+        }
+    }
+
+    let html = App::<Board>::new().render_to_string().unwrap();
+    assert_eq!(
+        html,
+        r#"<ul data-ruukh-events="dragenter dragover"><li draggable="true"></li></ul>"#
+    );
+}
+
+#[test]
+fn should_render_the_debounced_and_throttled_listeners() {
+    use ruukh::web_api::{debounce, throttle};
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct Search;
+
+    impl Render for Search {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <div @scroll={throttle(100, Self::on_scroll)}>
+                    <input @input={debounce(300, Self::on_search)}>
+                </div>
+            }
+        }
+    }
+
+    impl Search {
+        fn on_search(&self, _: Event) {}
+
+        fn on_scroll(&self, _: Event) {}
+    }
+
+    let html = App::<Search>::new().render_to_string().unwrap();
+    assert_eq!(
+        html,
+        r#"<div data-ruukh-events="scroll"><input data-ruukh-events="input"></div>"#
+    );
+}
+
+#[test]
+fn should_render_the_choices_with_the_selected_ones() {
+    use ruukh::forms::{self, Choice};
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Size {
+        Small,
+        Large,
+    }
+
+    impl Default for Size {
+        fn default() -> Size {
+            Size::Small
+        }
+    }
+
+    impl Choice for Size {
+        fn choices() -> Vec<Size> {
+            vec![Size::Small, Size::Large]
+        }
+
+        fn value(&self) -> &'static str {
+            match self {
+                Size::Small => "small",
+                Size::Large => "large",
+            }
+        }
+
+        fn label(&self) -> String {
+            match self {
+                Size::Small => "Small".to_string(),
+                Size::Large => "Large".to_string(),
+            }
+        }
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct Order {
+        #[state(default = Size::Large)]
+        size: Size,
+    }
+
+    impl Render for Order {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                { forms::select(self.size, Self::on_size) }
+                { forms::select_multiple(&[Size::Small, Size::Large], |_: &Self, _| {}) }
+                { forms::radio_group("size", self.size, Self::on_size) }
+            }
+        }
+    }
+
+    impl Order {
+        fn on_size(&self, size: Size) {
+            self.set_state(|state| state.size = size);
+        }
+    }
+
+    let html = App::<Order>::new().render_to_static_markup().unwrap();
+    assert_eq!(
+        html,
+        concat!(
+            r#"<select><option value="small">Small</option><option value="large" selected>Large</option></select>"#,
+            r#"<select multiple><option value="small" selected>Small</option><option value="large" selected>Large</option></select>"#,
+            r#"<label><input type="radio" name="size" value="small">Small</label>"#,
+            r#"<label><input type="radio" name="size" value="large" checked>Large</label>"#,
+        )
+    );
+    assert_eq!(Size::from_value("large"), Some(Size::Large));
+}
+
+#[test]
+fn should_render_the_autofocus_attribute() {
+    use ruukh::vdom::{velement::VElement, VNode};
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct Search;
+
+    impl Render for Search {
+        fn render(&self) -> Markup<Self> {
+            VNode::Element(VElement::childless("input", vec![], vec![]).autofocus())
+        }
+    }
+
+    let html = App::<Search>::new().render_to_static_markup().unwrap();
+    assert_eq!(html, "<input autofocus>");
+}
+
+#[test]
+fn should_render_the_static_parts_and_the_islands() {
+    use ruukh::islands::{Hydration, Load};
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct Article;
+
+    impl Render for Article {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <p @click={Self::on_click}>"Hello "{ "World!" }</p>
+            }
+        }
+
+        fn hydration() -> Hydration {
+            Hydration::Static
+        }
+    }
+
+    impl Article {
+        fn on_click(&self, _: Event) {}
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct Likes;
+
+    impl Render for Likes {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <button @click={Self::on_click}>"Like"</button>
+            }
+        }
+
+        fn hydration() -> Hydration {
+            Hydration::Island(Load::Visible)
+        }
+    }
+
+    impl Likes {
+        fn on_click(&self, _: Event) {}
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct Page;
+
+    impl Render for Page {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <Article></Article>
+                <Likes></Likes>
+            }
+        }
+    }
+
+    let html = App::<Page>::new().render_to_string().unwrap();
+    assert!(html.starts_with(r#"<p>Hello World!</p><ruukh-island data-ruukh-island=""#));
+    assert!(html.ends_with(
+        r#"" data-ruukh-load="visible" data-ruukh-index="1" style="display: contents"><button data-ruukh-events="click">Like</button></ruukh-island>"#
+    ));
+
+    let html = App::<Page>::new().render_to_static_markup().unwrap();
+    assert_eq!(html, "<p>Hello World!</p><button>Like</button>");
+}
+
+#[test]
+fn should_render_the_dehydrated_states_as_a_payload() {
+    use serde_json::Value;
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct Posts {
+        #[state(default = vec!["Hello".to_string(), "</script>".to_string()])]
+        titles: Vec<String>,
+    }
+
+    impl Render for Posts {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <p>{ self.titles.len() }</p>
+            }
+        }
+
+        fn dehydrate(&self) -> Option<Value> {
+            serde_json::to_value(&self.titles).ok()
+        }
+    }
+
+    let html = App::<Posts>::new().render_to_string().unwrap();
+    let payload = html
+        .trim_start_matches(r#"<p>2</p><script type="application/json" id="ruukh-state">"#)
+        .trim_end_matches("</script>");
+    assert!(!payload.contains("</"));
+    let payload: Value = serde_json::from_str(payload).unwrap();
+    assert_eq!(payload["0"][1], serde_json::json!(["Hello", "</script>"]));
+
+    let html = App::<Posts>::new().render_to_static_markup().unwrap();
+    assert_eq!(html, "<p>2</p>");
+}
+
+#[test]
+fn should_render_once_the_data_of_the_components_is_prepared() {
+    use std::{
+        future::Future,
+        pin::Pin,
+        task::{Context, Poll, Waker},
+    };
+
+    /// A fetch which completes when polled the second time.
+    struct Fetch(bool);
+
+    impl Future for Fetch {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                Poll::Ready(())
+            } else {
+                self.0 = true;
+                Poll::Pending
+            }
+        }
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct Post {
+        #[prop]
+        id: u32,
+        #[state]
+        title: String,
+    }
+
+    impl Render for Post {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <li>{ &self.title }</li>
+            }
+        }
+
+        fn prepare(&self) -> Option<Pin<Box<dyn Future<Output = ()>>>> {
+            let setter = self.state_setter();
+            let title = format!("Post {}", self.id);
+            Some(Box::pin(async move {
+                Fetch(false).await;
+                setter.set_state(|state| state.title = title.clone());
+            }))
+        }
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct Posts {
+        #[state]
+        ids: Vec<u32>,
+    }
+
+    impl Render for Posts {
+        fn render(&self) -> Markup<Self> {
+            let posts = self
+                .ids
+                .iter()
+                .map(|id| html! { <Post id={*id}></Post> })
+                .collect::<Vec<_>>();
+            html! {
+                <ul>{ posts }</ul>
+            }
+        }
+
+        fn prepare(&self) -> Option<Pin<Box<dyn Future<Output = ()>>>> {
+            let setter = self.state_setter();
+            Some(Box::pin(async move {
+                Fetch(false).await;
+                setter.set_state(|state| state.ids = vec![1, 2]);
+            }))
+        }
+    }
+
+    let mut render = Box::pin(App::<Posts>::new().render_to_string_async());
+    let mut cx = Context::from_waker(Waker::noop());
+    let html = loop {
+        if let Poll::Ready(html) = render.as_mut().poll(&mut cx) {
+            break html.unwrap();
+        }
+    };
+    assert_eq!(html, "<ul><li>Post 1</li><li>Post 2</li></ul>");
+}
+
+#[test]
+fn should_leave_out_the_spread_attributes_of_invalid_names() {
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            let attributes = vec![
+                ("onload=alert(1) x", "y"),
+                ("title\"><script>alert(1)</script>", "y"),
+                ("data-id", "7"),
+            ];
+            html!(<img ..{attributes}>)
+        }
+    }
+
+    let html = App::<MainApp>::new().render_to_static_markup().unwrap();
+
+    assert_eq!(html, r#"<img data-id="7">"#);
+}
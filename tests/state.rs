@@ -0,0 +1,159 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::prelude::*;
+
+#[test]
+fn should_invoke_the_watchers_when_the_state_changes() {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static QUERIES: RefCell<Vec<String>> = RefCell::new(vec![]);
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        query: String,
+    }
+
+    impl Lifecycle for MainApp {
+        fn created(&self) {
+            self.watch(
+                |state| state.query.clone(),
+                |query| QUERIES.with(|queries| queries.borrow_mut().push(query.clone())),
+            );
+        }
+
+        fn mounted(&self) {
+            self.set_state(|state| {
+                state.query = "rust".to_string();
+            });
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html!({ &self.query })
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert!(QUERIES.with(|queries| queries.borrow().is_empty()));
+
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "rust");
+    assert_eq!(QUERIES.with(|queries| queries.borrow().clone()), vec!["rust"]);
+}
+
+#[test]
+fn should_mutate_the_state_by_dispatching_messages() {
+    #[component]
+    struct MainApp {
+        #[state]
+        count: i32,
+    }
+
+    enum Message {
+        Add(i32),
+        Reset,
+    }
+
+    impl Reducer for MainApp {
+        type Message = Message;
+
+        fn reduce(state: &mut MainAppState, message: Message) {
+            match message {
+                Message::Add(value) => state.count += value,
+                Message::Reset => state.count = 0,
+            }
+        }
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.dispatch(Message::Add(5));
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html!({ self.count })
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "5");
+
+    let mut state = MainAppState::default();
+    MainApp::reduce(&mut state, Message::Add(2));
+    MainApp::reduce(&mut state, Message::Reset);
+    assert_eq!(state.count, 0);
+}
+
+#[test]
+fn should_route_the_messages_emitted_by_a_child_to_the_reducer() {
+    enum CounterMessage {
+        Add(i32),
+        Reset,
+    }
+
+    #[component]
+    #[events(
+        emits = CounterMessage;
+    )]
+    struct Counter {
+        add: i32,
+    }
+
+    impl Lifecycle for Counter {
+        fn mounted(&self) {
+            self.emit(CounterMessage::Reset);
+            self.emit(CounterMessage::Add(self.add));
+        }
+    }
+
+    impl Render for Counter {
+        fn render(&self) -> Markup<Self> {
+            html!()
+        }
+    }
+
+    #[component]
+    struct MainApp {
+        #[state(default = 10)]
+        count: i32,
+    }
+
+    impl Reducer for MainApp {
+        type Message = CounterMessage;
+
+        fn reduce(state: &mut MainAppState, message: CounterMessage) {
+            match message {
+                CounterMessage::Add(value) => state.count += value,
+                CounterMessage::Reset => state.count = 0,
+            }
+        }
+    }
+
+    impl Lifecycle for MainApp {}
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                { self.count }
+                <Counter add={3} @emit={Self::dispatch}></Counter>
+                // The handler is optional.
+                <Counter add={4}></Counter>
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "10");
+
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "3");
+}
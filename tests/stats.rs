@@ -0,0 +1,198 @@
+#![cfg(feature = "stats")]
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::{
+    prelude::*,
+    stats::{self, FlushStats},
+};
+
+#[test]
+fn should_count_the_mutations_of_the_last_flush() {
+    #[component]
+    struct MainApp {
+        #[state]
+        reversed: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| state.reversed = true);
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            if self.reversed {
+                html! {
+                    <p key={3}>"Third"</p>
+                    <p key={1}>"First!"</p>
+                }
+            } else {
+                html! {
+                    <p key={1}>"First"</p>
+                    <p key={2}>"Second"</p>
+                    <p key={3}>"Third"</p>
+                }
+            }
+        }
+    }
+
+    stats::reset();
+    let (root, mut app) = common::mount::<MainApp>();
+    // Along with the end marker of the app.
+    assert_eq!(
+        stats::last_flush(),
+        FlushStats {
+            elements_created: 3,
+            texts_created: 4,
+            nodes_inserted: 7,
+            ..FlushStats::default()
+        }
+    );
+
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "Third\nFirst!");
+    assert_eq!(
+        stats::last_flush(),
+        FlushStats {
+            nodes_moved: 2,
+            nodes_removed: 2,
+            text_updates: 1,
+            ..FlushStats::default()
+        }
+    );
+    assert_eq!(stats::total().nodes_inserted, 7);
+
+    // Nothing is rerendered, so the stats of the last flush are kept.
+    assert!(!app.flush().unwrap());
+    assert_eq!(stats::last_flush().nodes_moved, 2);
+}
+
+#[test]
+fn should_not_recreate_the_unkeyed_items_after_the_one_inserted() {
+    #[component]
+    struct MainApp {
+        #[state]
+        has_title: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| state.has_title = true);
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            let mut items = vec![html! { <p>"Body"</p> }, html! { <footer>"Footer"</footer> }];
+            if self.has_title {
+                items.insert(0, html! { <h1>"Title"</h1> });
+            }
+            html! { <div>{ items }</div> }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "Title\nBody\nFooter");
+    assert_eq!(
+        stats::last_flush(),
+        FlushStats {
+            elements_created: 1,
+            texts_created: 1,
+            nodes_inserted: 2,
+            ..FlushStats::default()
+        }
+    );
+}
+
+#[test]
+fn should_clone_the_static_subtrees_from_a_template() {
+    #[component]
+    struct MainApp {
+        #[state]
+        twice: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| state.twice = true);
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            let another = if self.twice {
+                html! {
+                    <ul class={"menu"}><li>"Home"</li><li>"About"</li></ul>
+                }
+            } else {
+                html!()
+            };
+            html! {
+                <div>
+                    <ul class={"menu"}><li>"Home"</li><li>"About"</li></ul>
+                    { another }
+                </div>
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(stats::last_flush().elements_created, 4);
+    assert_eq!(stats::last_flush().templates_cloned, 0);
+
+    assert!(app.flush().unwrap());
+    let last_flush = stats::last_flush();
+    assert_eq!(last_flush.templates_cloned, 1);
+    assert_eq!(last_flush.elements_created + last_flush.texts_created, 0);
+    assert_eq!(last_flush.nodes_inserted, 1);
+    let menus = root.children()[0].children();
+    assert_eq!(menus.len(), 2);
+    assert_eq!(menus[1].attribute("class"), Some("menu".to_string()));
+    assert_eq!(menus[1].text_content(), "HomeAbout");
+}
+
+#[test]
+fn should_not_patch_the_hoisted_static_markup() {
+    #[component]
+    struct MainApp {
+        #[state]
+        count: i32,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| state.count += 1);
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <div>
+                    <ul class={"links"}>
+                        <li>"Home"</li>
+                        <li>"About"</li>
+                    </ul>
+                    <span>{ self.count }</span>
+                </div>
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+
+    assert!(app.flush().unwrap());
+    assert_eq!(
+        stats::last_flush(),
+        FlushStats {
+            text_updates: 1,
+            ..FlushStats::default()
+        }
+    );
+    assert_eq!(root.text_content(), "HomeAbout1");
+}
@@ -0,0 +1,105 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::{prelude::*, store::Store};
+
+#[derive(Default)]
+struct Cart {
+    items: Vec<&'static str>,
+}
+
+#[test]
+fn should_rerender_the_subscribed_components_on_dispatch() {
+    #[component]
+    struct Badge {
+        label: &'static str,
+    }
+
+    impl Lifecycle for Badge {
+        fn created(&self) {
+            Store::<Cart>::global().subscribe(self);
+        }
+    }
+
+    impl Render for Badge {
+        fn render(&self) -> Markup<Self> {
+            let count = Store::<Cart>::global().state().items.len();
+            html! {
+                <p>{ self.label }": "{ count }</p>
+            }
+        }
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <Badge label={"Items"}></Badge>
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "Items: 0");
+    assert!(!app.flush().unwrap());
+
+    Store::<Cart>::global().dispatch(|cart| cart.items.push("Apple"));
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "Items: 1");
+}
+
+#[test]
+fn should_rerender_only_when_the_computed_value_changes() {
+    use std::cell::Cell;
+
+    thread_local! {
+        static RENDERS: Cell<i32> = Cell::new(0);
+    }
+
+    #[component]
+    struct Badge {
+        label: &'static str,
+    }
+
+    impl Lifecycle for Badge {
+        fn created(&self) {
+            Store::<Cart>::global()
+                .computed(|cart| cart.items.is_empty())
+                .subscribe(self);
+        }
+    }
+
+    impl Render for Badge {
+        fn render(&self) -> Markup<Self> {
+            RENDERS.with(|renders| renders.set(renders.get() + 1));
+            html!({ self.label })
+        }
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <Badge label={"Cart"}></Badge>
+            }
+        }
+    }
+
+    let (_, mut app) = common::mount::<MainApp>();
+    assert_eq!(RENDERS.with(|renders| renders.get()), 1);
+
+    Store::<Cart>::global().dispatch(|cart| cart.items.push("Apple"));
+    assert!(app.flush().unwrap());
+    assert_eq!(RENDERS.with(|renders| renders.get()), 2);
+
+    Store::<Cart>::global().dispatch(|cart| cart.items.push("Banana"));
+    assert!(!app.flush().unwrap());
+    assert_eq!(RENDERS.with(|renders| renders.get()), 2);
+}
@@ -0,0 +1,161 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use futures_core::Stream;
+use ruukh::prelude::*;
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+/// A stream of the items sent onto it, which ends once closed.
+#[derive(Clone, Default)]
+struct Channel(Rc<RefCell<ChannelInner>>);
+
+#[derive(Default)]
+struct ChannelInner {
+    items: VecDeque<&'static str>,
+    closed: bool,
+    waker: Option<Waker>,
+    polls: usize,
+}
+
+impl Channel {
+    fn send(&self, item: &'static str) {
+        self.0.borrow_mut().items.push_back(item);
+        self.wake();
+    }
+
+    fn close(&self) {
+        self.0.borrow_mut().closed = true;
+        self.wake();
+    }
+
+    fn wake(&self) {
+        if let Some(waker) = self.0.borrow_mut().waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn polls(&self) -> usize {
+        self.0.borrow().polls
+    }
+}
+
+impl Stream for Channel {
+    type Item = &'static str;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<&'static str>> {
+        let mut inner = self.0.borrow_mut();
+        inner.polls += 1;
+        match inner.items.pop_front() {
+            Some(item) => Poll::Ready(Some(item)),
+            None if inner.closed => Poll::Ready(None),
+            None => {
+                inner.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+thread_local! {
+    static CHANNEL: Channel = Channel::default();
+}
+
+#[component]
+struct Messages {
+    #[state]
+    items: Vec<&'static str>,
+}
+
+impl Lifecycle for Messages {
+    fn created(&self) {
+        let channel = CHANNEL.with(Channel::clone);
+        self.subscribe(channel, |state, item| state.items.push(item));
+    }
+}
+
+impl Render for Messages {
+    fn render(&self) -> Markup<Self> {
+        html! {
+            <p>{ self.items.join(", ") }</p>
+        }
+    }
+}
+
+#[test]
+fn should_feed_the_items_of_a_stream_into_the_state_on_flush() {
+    let channel = CHANNEL.with(Channel::clone);
+    channel.send("Hello");
+
+    let (root, mut app) = common::mount::<Messages>();
+    assert_eq!(root.to_string(), "");
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "Hello");
+
+    channel.send("World");
+    channel.send("!");
+    // Nothing is polled until the flush.
+    assert_eq!(root.to_string(), "Hello");
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "Hello, World, !");
+
+    channel.close();
+    app.flush().unwrap();
+    let polls = channel.polls();
+    channel.send("Ignored");
+    app.flush().unwrap();
+    assert_eq!(channel.polls(), polls);
+    assert_eq!(root.to_string(), "Hello, World, !");
+}
+
+#[test]
+fn should_stop_polling_the_stream_once_the_component_is_destroyed() {
+    thread_local! {
+        static HIDE: RefCell<Option<Box<dyn Fn()>>> = RefCell::new(None);
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        hidden: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            let setter = self.state_setter();
+            HIDE.with(|hide| {
+                *hide.borrow_mut() = Some(Box::new(move || {
+                    setter.set_state(|state| state.hidden = true);
+                }))
+            });
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            if self.hidden {
+                html!()
+            } else {
+                html! {
+                    <Messages></Messages>
+                }
+            }
+        }
+    }
+
+    let channel = CHANNEL.with(Channel::clone);
+    let (_, mut app) = common::mount::<MainApp>();
+    app.flush().unwrap();
+    assert_eq!(Rc::strong_count(&channel.0), 3);
+
+    HIDE.with(|hide| hide.borrow().as_ref().unwrap()());
+    app.flush().unwrap();
+    // Only the thread local & this clone are left.
+    assert_eq!(Rc::strong_count(&channel.0), 2);
+}
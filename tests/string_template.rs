@@ -0,0 +1,78 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+use ruukh::{
+    prelude::*,
+    vdom::string_template::{Slots, StringTemplate},
+};
+use std::rc::Rc;
+
+#[component]
+#[derive(Lifecycle)]
+struct MainApp;
+
+impl Render for MainApp {
+    fn render(&self) -> Markup<Self> {
+        html!()
+    }
+}
+
+#[test]
+fn should_fill_the_slots_of_the_template() {
+    let template = StringTemplate::parse(
+        r#"<p class="banner {kind}">Hello, {name}! {{literal}} {action}</p><custom-tag>{missing}</custom-tag>"#,
+    );
+    assert_eq!(template.slots(), vec!["kind", "name", "action", "missing"]);
+
+    let rendered: Markup<MainApp> = template.render(
+        Slots::new()
+            .text("kind", "info")
+            .text("name", "<Ruukh>")
+            .node("action", html!(<button>"Open"</button>)),
+    );
+    assert_eq!(
+        rendered.to_string(),
+        r#"<p class="banner info">Hello, &lt;Ruukh&gt;! {literal} <button>Open</button></p><custom-tag></custom-tag>"#
+    );
+}
+
+#[test]
+fn should_cache_the_parsed_templates() {
+    let template = "<b>{count}</b>";
+    assert!(Rc::ptr_eq(
+        &StringTemplate::cached(template),
+        &StringTemplate::cached(template)
+    ));
+
+    let rendered: Markup<MainApp> =
+        StringTemplate::cached(template).render(Slots::new().text("count", 3));
+    assert_eq!(rendered.to_string(), "<b>3</b>");
+}
+
+#[test]
+fn should_leave_out_the_urls_of_the_schemes_not_allowed() {
+    let template = StringTemplate::parse(
+        r#"<a href="{url}" title="{url}">Profile</a><a href="javascript:void(0)">Menu</a>"#,
+    );
+
+    let rendered: Markup<MainApp> =
+        template.render(Slots::new().text("url", "javascript:alert(1)"));
+    assert_eq!(
+        rendered.to_string(),
+        r#"<a title="javascript:alert(1)">Profile</a><a href="javascript:void(0)">Menu</a>"#
+    );
+
+    let rendered: Markup<MainApp> = template.render(Slots::new().text("url", "/users/1"));
+    assert_eq!(
+        rendered.to_string(),
+        r#"<a href="/users/1" title="/users/1">Profile</a><a href="javascript:void(0)">Menu</a>"#
+    );
+}
+
+#[test]
+fn should_evict_the_least_recently_used_templates() {
+    let first = StringTemplate::cached("<i>first</i>");
+    for i in 0..100 {
+        StringTemplate::cached(&format!("<i>{}</i>", i));
+    }
+    assert!(!Rc::ptr_eq(&first, &StringTemplate::cached("<i>first</i>")));
+}
@@ -0,0 +1,161 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::{
+    prelude::*,
+    vdom::{vcomponent::VComponent, VNode},
+};
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+/// A future which resolves once opened.
+#[derive(Clone, Default)]
+struct Gate(Rc<RefCell<(bool, Option<Waker>)>>);
+
+impl Gate {
+    fn open(&self) {
+        let mut gate = self.0.borrow_mut();
+        gate.0 = true;
+        if let Some(waker) = gate.1.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Future for Gate {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut gate = self.0.borrow_mut();
+        if gate.0 {
+            Poll::Ready(())
+        } else {
+            gate.1 = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+thread_local! {
+    static GATE: Gate = Gate::default();
+}
+
+#[component]
+struct Profile {
+    #[state]
+    name: Option<&'static str>,
+}
+
+impl Lifecycle for Profile {
+    fn created(&self) {
+        let setter = self.state_setter();
+        let gate = GATE.with(Gate::clone);
+        self.suspend(async move {
+            gate.await;
+            setter.set_state(|state| state.name = Some("Ruukh"));
+        });
+    }
+}
+
+impl Render for Profile {
+    fn render(&self) -> Markup<Self> {
+        html! {
+            <p>"Hello, "{ self.name.unwrap_or("nobody") }</p>
+        }
+    }
+}
+
+#[test]
+fn should_show_the_fallback_until_the_suspended_futures_resolve() {
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <h1>"Profile"</h1>
+                {
+                    Suspense::new(html!(<Profile></Profile>))
+                        .fallback(html!(<p>"Loading"</p>))
+                }
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "Profile\nLoading");
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "Profile\nLoading");
+
+    GATE.with(Gate::open);
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "Profile\nHello, Ruukh");
+}
+
+#[test]
+fn should_show_the_content_which_is_not_suspended_right_away() {
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                {
+                    Suspense::new(html!(<p>"Loaded"</p>))
+                        .fallback(html!(<p>"Loading"</p>))
+                }
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "Loaded");
+    assert!(!app.flush().unwrap());
+    assert_eq!(
+        app.dump_tree(),
+        "<MainApp>\n  <Suspense>\n    p\n      \"Loaded\"\n"
+    );
+}
+
+#[test]
+fn should_show_the_fallback_until_the_lazy_component_is_loaded() {
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            let lazy = VComponent::lazy(|| async {
+                GATE.with(Gate::clone).await;
+                html!(<p>"Settings"</p>)
+            });
+            html! {
+                {
+                    Suspense::new(VNode::from(lazy))
+                        .fallback(html!(<p>"Loading"</p>))
+                }
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "Loading");
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "Loading");
+
+    GATE.with(Gate::open);
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "Settings");
+    assert_eq!(
+        app.dump_tree(),
+        "<MainApp>\n  <Suspense>\n    <Lazy>\n      p\n        \"Settings\"\n"
+    );
+}
@@ -0,0 +1,61 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+use ruukh::{
+    backend::TuiNode,
+    prelude::*,
+    theme::{self, ColorScheme, Theme},
+};
+
+#[test]
+fn should_rerender_the_subscribers_once_the_scheme_is_picked() {
+    #[component]
+    struct Label {
+        // A state, so that it can be rerendered.
+        #[state]
+        renders: u32,
+    }
+
+    impl Lifecycle for Label {
+        fn created(&self) {
+            theme::subscribe(self);
+        }
+    }
+
+    impl Render for Label {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                { theme::current().as_str() }
+            }
+        }
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <Label></Label>
+            }
+        }
+    }
+
+    let root = TuiNode::root();
+    let mut app = App::<MainApp>::new()
+        .theme(Theme::new())
+        .mount_headless(root.clone().into())
+        .unwrap();
+    // The system is not known outside of a browser.
+    assert_eq!(root.to_string(), "light");
+    assert_eq!(theme::preference(), None);
+
+    theme::set_preference(Some(ColorScheme::Dark));
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "dark");
+    assert_eq!(theme::preference(), Some(ColorScheme::Dark));
+
+    theme::set_preference(None);
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "light");
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "time-travel")]
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::{prelude::*, time_travel};
+use serde_json::Value;
+use std::cell::RefCell;
+
+thread_local! {
+    static COUNTER: RefCell<Option<Box<dyn Fn()>>> = RefCell::new(None);
+}
+
+#[test]
+fn should_restore_the_state_of_a_snapshot() {
+    #[component]
+    struct Counter {
+        #[state]
+        count: i64,
+    }
+
+    impl Lifecycle for Counter {
+        fn mounted(&self) {
+            let setter = self.state_setter();
+            COUNTER.with(|counter| {
+                *counter.borrow_mut() = Some(Box::new(move || {
+                    setter.set_state(|state| state.count += 1);
+                }))
+            });
+        }
+    }
+
+    impl Render for Counter {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <p>{ self.count }</p>
+            }
+        }
+
+        fn dehydrate(&self) -> Option<Value> {
+            Some(Value::from(self.count))
+        }
+
+        fn rehydrate(&self, state: Value) {
+            if let Some(count) = state.as_i64() {
+                self.set_state(|state| state.count = count);
+            }
+        }
+    }
+
+    let increment = || COUNTER.with(|counter| counter.borrow().as_ref().unwrap()());
+
+    let (root, mut app) = common::mount::<Counter>();
+    increment();
+    app.flush().unwrap();
+    increment();
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "2");
+    assert_eq!(time_travel::len(), 3);
+    let first = time_travel::snapshot(0).unwrap();
+    assert_eq!(first.states.len(), 1);
+    assert_eq!(first.states[0].name, "Counter");
+    assert_eq!(first.states[0].state, Value::from(0));
+
+    assert!(time_travel::restore(1));
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "1");
+    // Rerendering the restored snapshot is not recorded again.
+    assert_eq!(time_travel::len(), 3);
+
+    increment();
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "2");
+    assert_eq!(time_travel::len(), 4);
+    assert!(!time_travel::restore(4));
+}
@@ -0,0 +1,138 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+use ruukh::{
+    backend::{Backend, Node, TuiBackend, TuiNode},
+    prelude::*,
+    web_api::debounce,
+};
+use std::{cell::RefCell, thread, time::Duration};
+use wasm_bindgen::{prelude::JsValue, JsCast};
+use web_sys::Event;
+
+thread_local! {
+    static LISTENERS: RefCell<Vec<(String, Box<dyn Fn(Event)>)>> = RefCell::new(vec![]);
+}
+
+/// Lays out like the `TuiBackend`, but keeps the listeners so that the events
+/// can be dispatched.
+#[derive(Clone)]
+struct ListeningBackend;
+
+impl ListeningBackend {
+    fn dispatch(type_: &str) {
+        LISTENERS.with(|listeners| {
+            for (_, listener) in listeners.borrow().iter().filter(|(ty, _)| ty == type_) {
+                listener(JsValue::NULL.unchecked_into());
+            }
+        });
+    }
+}
+
+impl Backend for ListeningBackend {
+    type Node = TuiNode;
+    type Listener = ();
+
+    fn create_element(&self, tag: &str) -> Result<TuiNode, JsValue> {
+        TuiBackend.create_element(tag)
+    }
+
+    fn create_text_node(&self, content: &str) -> Result<TuiNode, JsValue> {
+        TuiBackend.create_text_node(content)
+    }
+
+    fn create_comment(&self, content: &str) -> Result<TuiNode, JsValue> {
+        TuiBackend.create_comment(content)
+    }
+
+    fn set_text_content(&self, node: &TuiNode, content: &str) -> Result<(), JsValue> {
+        TuiBackend.set_text_content(node, content)
+    }
+
+    fn set_attribute(&self, node: &TuiNode, key: &str, value: &str) -> Result<(), JsValue> {
+        TuiBackend.set_attribute(node, key, value)
+    }
+
+    fn remove_attribute(&self, node: &TuiNode, key: &str) -> Result<(), JsValue> {
+        TuiBackend.remove_attribute(node, key)
+    }
+
+    fn insert_before(
+        &self,
+        parent: &TuiNode,
+        child: &TuiNode,
+        next: Option<&TuiNode>,
+    ) -> Result<(), JsValue> {
+        TuiBackend.insert_before(parent, child, next)
+    }
+
+    fn remove_child(&self, parent: &TuiNode, child: &TuiNode) -> Result<(), JsValue> {
+        TuiBackend.remove_child(parent, child)
+    }
+
+    fn add_event_listener(
+        &self,
+        _: &TuiNode,
+        type_: &str,
+        listener: Box<dyn Fn(Event)>,
+    ) -> Result<(), JsValue> {
+        LISTENERS.with(|listeners| listeners.borrow_mut().push((type_.to_string(), listener)));
+        Ok(())
+    }
+
+    fn remove_event_listener(&self, _: &TuiNode, _: &str, _: &()) -> Result<(), JsValue> {
+        Ok(())
+    }
+}
+
+#[test]
+fn should_keep_the_debounced_invocation_across_the_rerenders() {
+    thread_local! {
+        static SEARCHES: RefCell<u32> = RefCell::new(0);
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct Search {
+        #[state]
+        clicks: u32,
+    }
+
+    impl Render for Search {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <input @input={debounce(100, Self::on_search)}>
+                <button @click={Self::on_click}>{ self.clicks }</button>
+            }
+        }
+    }
+
+    impl Search {
+        fn on_search(&self, _: Event) {
+            SEARCHES.with(|searches| *searches.borrow_mut() += 1);
+        }
+
+        fn on_click(&self, _: Event) {
+            self.set_state(|state| state.clicks += 1);
+        }
+    }
+
+    let root = TuiNode::root();
+    let mut app = App::<Search>::new()
+        .mount_headless(Node::new(ListeningBackend, root.clone()))
+        .unwrap();
+
+    // Type twice with a rerender in between, and once more rerender before
+    // the debounced search is due.
+    ListeningBackend::dispatch("input");
+    ListeningBackend::dispatch("click");
+    assert!(app.flush().unwrap());
+    ListeningBackend::dispatch("input");
+    ListeningBackend::dispatch("click");
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "2");
+    assert_eq!(SEARCHES.with(|searches| *searches.borrow()), 0);
+
+    thread::sleep(Duration::from_millis(150));
+    app.flush().unwrap();
+    assert_eq!(SEARCHES.with(|searches| *searches.borrow()), 1);
+}
@@ -0,0 +1,122 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::prelude::*;
+
+#[test]
+fn should_insert_and_remove_the_transitioned_elements_right_away() {
+    use ruukh::transition::Transition;
+
+    #[component]
+    struct MainApp {
+        #[state]
+        hidden: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| state.hidden = true);
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            let fade = Transition::new("fade");
+            if self.hidden {
+                html!()
+            } else {
+                fade.wrap(html! {
+                    <p>"First"</p>
+                    <p>"Second"</p>
+                })
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "First\nSecond");
+
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "");
+}
+
+#[test]
+fn should_reorder_the_items_of_a_transition_group() {
+    use ruukh::transition::TransitionGroup;
+
+    #[component]
+    struct MainApp {
+        #[state]
+        reversed: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| state.reversed = true);
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            let group = TransitionGroup::new("list");
+            let items = if self.reversed {
+                html! {
+                    <p key={3}>"Third"</p>
+                    <p key={1}>"First"</p>
+                }
+            } else {
+                html! {
+                    <p key={1}>"First"</p>
+                    <p key={2}>"Second"</p>
+                    <p key={3}>"Third"</p>
+                }
+            };
+            group.wrap(items)
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "First\nSecond\nThird");
+
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "Third\nFirst");
+}
+
+#[test]
+fn should_reorder_the_items_of_a_flipped_list() {
+    use ruukh::transition::flip;
+
+    #[component]
+    struct MainApp {
+        #[state]
+        swapped: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| state.swapped = true);
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            let items = if self.swapped {
+                html! {
+                    <p key={2}>"Second"</p>
+                    <p key={1}>"First"</p>
+                }
+            } else {
+                html! {
+                    <p key={1}>"First"</p>
+                    <p key={2}>"Second"</p>
+                }
+            };
+            flip("transform 300ms", items)
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "Second\nFirst");
+}
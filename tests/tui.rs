@@ -0,0 +1,87 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::prelude::*;
+
+#[test]
+fn should_layout_blocks_and_inline_elements() {
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <div>"Hello "<span>"World!"</span></div>
+                <p>"How are you?"</p>
+            }
+        }
+    }
+
+    let (root, _) = common::mount::<MainApp>();
+
+    assert_eq!(root.to_string(), "Hello World!\nHow are you?");
+}
+
+#[test]
+fn should_layout_nested_lists() {
+    #[component]
+    #[derive(Lifecycle)]
+    struct MainApp;
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <ol>
+                    <li>"First"</li>
+                    <li>
+                        "Second"
+                        <ul>
+                            <li>"Nested"</li>
+                        </ul>
+                    </li>
+                </ol>
+            }
+        }
+    }
+
+    let (root, _) = common::mount::<MainApp>();
+
+    assert_eq!(root.to_string(), "1. First\n2. Second\n   - Nested");
+}
+
+#[test]
+fn should_rerender_on_flush_after_state_change() {
+    #[component]
+    struct MainApp {
+        #[state]
+        count: i32,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| {
+                state.count += 1;
+            });
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <div>"Count: "{ self.count }</div>
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "Count: 0");
+
+    assert!(app.flush().unwrap());
+    assert_eq!(root.to_string(), "Count: 1");
+    // The div along with the end marker of the app.
+    assert_eq!(root.children().len(), 2);
+
+    assert!(!app.flush().unwrap());
+}
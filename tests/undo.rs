@@ -0,0 +1,104 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::{prelude::*, undo::UndoableState};
+use std::cell::RefCell;
+
+#[test]
+fn should_rerender_the_undone_and_redone_changes() {
+    enum Edit {
+        Append(&'static str),
+        Undo,
+        Redo,
+    }
+
+    thread_local! {
+        static EDIT: RefCell<Option<Box<dyn Fn(Edit)>>> = RefCell::new(None);
+    }
+
+    #[component]
+    struct Editor {
+        #[state]
+        text: UndoableState<String>,
+    }
+
+    impl Lifecycle for Editor {
+        fn mounted(&self) {
+            let setter = self.state_setter();
+            EDIT.with(|edit| {
+                *edit.borrow_mut() = Some(Box::new(move |edit| {
+                    setter.set_state(|state| match edit {
+                        Edit::Append(word) => state.text.set(|text| text.push_str(word)),
+                        Edit::Undo => {
+                            state.text.undo();
+                        }
+                        Edit::Redo => {
+                            state.text.redo();
+                        }
+                    });
+                }))
+            });
+        }
+    }
+
+    impl Render for Editor {
+        fn render(&self) -> Markup<Self> {
+            let undo = if self.text.can_undo() { "+" } else { "-" };
+            let redo = if self.text.can_redo() { "+" } else { "-" };
+            html! {
+                <p>{ self.text.get() }" "{ undo }{ redo }</p>
+            }
+        }
+    }
+
+    let edit = |e| EDIT.with(|edit| edit.borrow().as_ref().unwrap()(e));
+
+    let (root, mut app) = common::mount::<Editor>();
+    assert_eq!(root.to_string(), " --");
+
+    edit(Edit::Append("Hello"));
+    edit(Edit::Append(" World"));
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "Hello World +-");
+
+    edit(Edit::Undo);
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "Hello ++");
+
+    edit(Edit::Undo);
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), " -+");
+
+    // Nothing is left to undo.
+    edit(Edit::Undo);
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), " -+");
+
+    edit(Edit::Redo);
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "Hello ++");
+
+    // A new change drops the undone ones.
+    edit(Edit::Append("!"));
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "Hello! +-");
+}
+
+#[test]
+fn should_keep_the_last_changes_within_the_limit() {
+    let mut count = UndoableState::new(0).with_limit(2);
+    for _ in 0..3 {
+        count.set(|count| *count += 1);
+    }
+    assert!(count.undo());
+    assert!(count.undo());
+    assert!(!count.undo());
+    assert_eq!(*count, 1);
+
+    assert!(count.redo());
+    assert_eq!(*count.get(), 2);
+    count.clear_history();
+    assert!(!count.can_undo());
+    assert!(!count.can_redo());
+}
@@ -0,0 +1,255 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::{backend::TuiBackend, prelude::*};
+
+#[test]
+fn should_set_the_node_ref_of_an_element() {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static TAG: RefCell<Option<String>> = RefCell::new(None);
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        input: NodeRef,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            let node = self.input.get().unwrap();
+            let tag = node.backend_node::<TuiBackend>().unwrap().tag();
+            TAG.with(|t| *t.borrow_mut() = tag);
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <div>
+                    <input ref={&self.input}>
+                </div>
+            }
+        }
+    }
+
+    common::mount::<MainApp>();
+    assert_eq!(TAG.with(|t| t.borrow().clone()), Some("input".to_string()));
+}
+
+#[test]
+fn should_remove_an_optional_attribute_once_it_is_none() {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static SET_TITLE: RefCell<Option<Box<dyn Fn(Option<&'static str>)>>> = RefCell::new(None);
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        title: Option<&'static str>,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            let setter = self.state_setter();
+            SET_TITLE.with(|set_title| {
+                *set_title.borrow_mut() = Some(Box::new(move |title| {
+                    setter.set_state(|state| state.title = title);
+                }))
+            });
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <p title={self.title}>"Saved"</p>
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    let p = || root.children()[0].clone();
+    assert_eq!(p().attribute("title"), None);
+
+    let set_title = |title| SET_TITLE.with(|set_title| set_title.borrow().as_ref().unwrap()(title));
+    set_title(Some("Saved at noon"));
+    app.flush().unwrap();
+    assert_eq!(p().attribute("title"), Some("Saved at noon".to_string()));
+
+    set_title(None);
+    app.flush().unwrap();
+    assert_eq!(p().attribute("title"), None);
+}
+
+#[test]
+fn should_keep_the_edits_of_an_editable_element_until_its_content_changes() {
+    use ruukh::backend::Backend;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static SET_TEXT: RefCell<Option<Box<dyn Fn(&'static str)>>> = RefCell::new(None);
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        text: &'static str,
+        #[state]
+        saves: u32,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            let setter = self.state_setter();
+            SET_TEXT.with(|set_text| {
+                *set_text.borrow_mut() = Some(Box::new(move |text| {
+                    setter.set_state(|state| {
+                        state.text = text;
+                        state.saves += 1;
+                    });
+                }))
+            });
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <p contenteditable={true}>"Draft: "{ self.text }</p>
+                <span>"Saves: "{ self.saves }</span>
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    let set_text = |text| SET_TEXT.with(|set_text| set_text.borrow().as_ref().unwrap()(text));
+    set_text("Hello");
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "Draft: Hello\nSaves: 1");
+
+    // The user edits the paragraph in place.
+    let p = root.children()[0].clone();
+    TuiBackend.set_text_content(&p, "Draft: Hello, World").unwrap();
+
+    set_text("Hello");
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "Draft: Hello, World\nSaves: 2");
+
+    set_text("Bye");
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "Draft: Bye\nSaves: 3");
+}
+
+#[test]
+fn should_destroy_the_components_left_out_of_an_edited_element() {
+    use ruukh::backend::Backend;
+    use std::cell::Cell;
+
+    thread_local! {
+        static DESTROYED: Cell<bool> = Cell::new(false);
+    }
+
+    #[component]
+    #[derive(Lifecycle)]
+    struct Mention;
+
+    impl Render for Mention {
+        fn render(&self) -> Markup<Self> {
+            html!("@ruukh")
+        }
+    }
+
+    impl Drop for Mention {
+        fn drop(&mut self) {
+            DESTROYED.with(|destroyed| destroyed.set(true));
+        }
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        hide_mention: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| state.hide_mention = true);
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            if self.hide_mention {
+                html!(<p contenteditable={true}>"Hi"</p>)
+            } else {
+                html!(<p contenteditable={true}>"Hi "<Mention></Mention></p>)
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "Hi @ruukh");
+
+    // The user edits the paragraph in place.
+    let p = root.children()[0].clone();
+    TuiBackend.set_text_content(&p, "Hi @ruukh!").unwrap();
+
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "Hi");
+    assert!(DESTROYED.with(|destroyed| destroyed.get()));
+}
+
+#[test]
+fn should_focus_the_autofocused_element_once_it_is_created() {
+    use ruukh::vdom::{velement::VElement, VNode};
+    use std::cell::RefCell;
+
+    thread_local! {
+        static SET_OPEN: RefCell<Option<Box<dyn Fn(bool)>>> = RefCell::new(None);
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        open: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            let setter = self.state_setter();
+            SET_OPEN.with(|set_open| {
+                *set_open.borrow_mut() = Some(Box::new(move |open| {
+                    setter.set_state(|state| state.open = open);
+                }))
+            });
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            let button = VElement::childless("button", vec![], vec![]);
+            if self.open {
+                let input = VElement::childless("input", vec![], vec![]).autofocus();
+                vec![VNode::Element(button), VNode::Element(input)].into()
+            } else {
+                VNode::Element(button)
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert!(root.children().iter().all(|node| !node.is_focused()));
+
+    let set_open = |open| SET_OPEN.with(|set_open| set_open.borrow().as_ref().unwrap()(open));
+    set_open(true);
+    app.flush().unwrap();
+    let input = root.children()[1].clone();
+    assert_eq!(input.tag(), Some("input".to_string()));
+    assert!(input.is_focused());
+}
@@ -0,0 +1,192 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+mod common;
+
+use ruukh::{
+    prelude::*,
+    vdom::{velement::VElement, vlist::VList},
+};
+
+#[component]
+#[derive(Lifecycle)]
+struct MainApp;
+
+impl Render for MainApp {
+    fn render(&self) -> Markup<Self> {
+        html!()
+    }
+}
+
+#[test]
+fn should_build_a_list_of_the_heterogeneous_items() {
+    let todos = vec!["Write", "Test"];
+    let list: VList<MainApp> = vlist![
+        html!(<h2>"Todos"</h2>),
+        ..todos.iter().map(|todo| html!(<li>{ *todo }</li>)),
+        3,
+        "footer" => "Done",
+    ];
+
+    assert_eq!(list.len(), 5);
+    let keys: Vec<_> = list.items().map(|(key, _)| key.to_string()).collect();
+    assert_eq!(keys, vec!["0", "1", "2", "3", "footer"]);
+    assert_eq!(
+        Markup::from(list).to_string(),
+        "<h2>Todos</h2><li>Write</li><li>Test</li>3Done"
+    );
+}
+
+#[test]
+fn should_build_an_empty_list() {
+    let list: VList<MainApp> = vlist![];
+    assert!(list.is_empty());
+}
+
+#[test]
+fn should_build_an_element_out_of_the_optional_and_nested_children() {
+    let todos = vec!["Write", "Test"];
+    let children = vec![
+        vec![html!(<h2>"Todos"</h2>)],
+        todos.iter().map(|todo| html!(<li>{ *todo }</li>)).collect(),
+    ];
+    let list: Markup<MainApp> = VElement::new("ul", vec![], vec![], children).into();
+    assert_eq!(
+        list.to_string(),
+        "<ul><h2>Todos</h2><li>Write</li><li>Test</li></ul>"
+    );
+
+    let badge: Markup<MainApp> =
+        VElement::new("p", vec![], vec![], vec![Some("New"), None, Some("!")]).into();
+    assert_eq!(badge.to_string(), "<p>New!</p>");
+}
+
+#[test]
+fn should_build_an_element_out_of_the_keyed_children() {
+    let todos = vec!["Write", "Test"];
+    let list: Markup<MainApp> = VElement::new(
+        "ul",
+        vec![],
+        vec![],
+        todos.iter().map(|todo| (*todo, html!(<li>{ *todo }</li>))),
+    )
+    .into();
+    assert_eq!(list.to_string(), "<ul><li>Write</li><li>Test</li></ul>");
+    let items = list.as_element().unwrap().child().as_list().unwrap();
+    let keys: Vec<_> = items.items().map(|(key, _)| key.to_string()).collect();
+    assert_eq!(keys, vec!["Write", "Test"]);
+
+    let empty: Markup<MainApp> = VElement::new("ul", vec![], vec![], None::<&str>).into();
+    assert_eq!(empty.to_string(), "<ul></ul>");
+}
+
+#[test]
+fn should_read_the_key_and_the_node_of_a_child() {
+    use ruukh::vdom::{vlist::KeyedVNodes, Key};
+
+    let mut child: KeyedVNodes<MainApp> = ("footer", "Done").into();
+    assert!(child.key() == Some(&Key::new("footer")));
+    assert_eq!(child.vnode().as_text().unwrap().content(), "Done");
+
+    *child.vnode_mut() = html!(<p>"Done"</p>);
+    let (key, node) = child.into_parts();
+    assert!(key == Some(Key::new("footer")));
+    assert_eq!(node.to_string(), "<p>Done</p>");
+
+    let child: KeyedVNodes<MainApp> = KeyedVNodes::unkeyed("Done");
+    assert!(child.key().is_none());
+}
+
+#[test]
+fn should_move_the_keyed_components_along_with_their_nodes() {
+    #[component]
+    #[derive(Lifecycle)]
+    struct Item {
+        name: &'static str,
+    }
+
+    impl Render for Item {
+        fn render(&self) -> Markup<Self> {
+            html! {
+                <p>{ self.name }</p>
+                <p>{ self.name }"!"</p>
+            }
+        }
+    }
+
+    #[component]
+    struct MainApp {
+        #[state]
+        reversed: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| state.reversed = true);
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            let mut names = vec!["First", "Second", "Third"];
+            if self.reversed {
+                names.reverse();
+            }
+            let items: Vec<_> = names
+                .into_iter()
+                .map(|name| html! { <Item key={name} name={name}></Item> })
+                .collect();
+            html! {
+                { items }
+                <p>"Last"</p>
+            }
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(
+        root.to_string(),
+        "First\nFirst!\nSecond\nSecond!\nThird\nThird!\nLast"
+    );
+
+    app.flush().unwrap();
+    assert_eq!(
+        root.to_string(),
+        "Third\nThird!\nSecond\nSecond!\nFirst\nFirst!\nLast"
+    );
+}
+
+#[test]
+fn should_move_the_keyed_nested_lists_along_with_their_items() {
+    use ruukh::vdom::vlist::VList;
+
+    #[component]
+    struct MainApp {
+        #[state]
+        reversed: bool,
+    }
+
+    impl Lifecycle for MainApp {
+        fn mounted(&self) {
+            self.set_state(|state| state.reversed = true);
+        }
+    }
+
+    impl Render for MainApp {
+        fn render(&self) -> Markup<Self> {
+            let first: VList<Self> = vlist![html!(<p>"A1"</p>), html!(<p>"A2"</p>)];
+            let second: VList<Self> = vlist![html!(<p>"B1"</p>)];
+            let list: VList<Self> = if self.reversed {
+                vlist!["b" => second, "a" => first]
+            } else {
+                vlist!["a" => first, "b" => second]
+            };
+            list.into()
+        }
+    }
+
+    let (root, mut app) = common::mount::<MainApp>();
+    assert_eq!(root.to_string(), "A1\nA2\nB1");
+
+    app.flush().unwrap();
+    assert_eq!(root.to_string(), "B1\nA1\nA2");
+}
@@ -0,0 +1,62 @@
+#![feature(proc_macro_hygiene, decl_macro)]
+
+use ruukh::{prelude::*, vdom::vraw::Sanitizer};
+
+#[component]
+#[derive(Lifecycle)]
+struct MainApp;
+
+impl Render for MainApp {
+    fn render(&self) -> Markup<Self> {
+        html!()
+    }
+}
+
+fn sanitize(html: &str, sanitizer: &Sanitizer) -> String {
+    Markup::<MainApp>::from(VRaw::sanitized_with(html, sanitizer)).to_string()
+}
+
+#[test]
+fn should_keep_the_allowed_markup() {
+    assert_eq!(
+        sanitize(
+            r#"<p title="Hi">Hello <b>World</b> &amp; <a href="https://ruukh.rs">more</a></p><br><img src="/a.png" alt="A">"#,
+            &Sanitizer::new()
+        ),
+        r#"<p title="Hi">Hello <b>World</b> &amp; <a href="https://ruukh.rs">more</a></p><br><img alt="A" src="/a.png">"#
+    );
+}
+
+#[test]
+fn should_strip_the_dangerous_markup() {
+    assert_eq!(
+        sanitize(
+            r#"<p onclick="steal()" id="x">Hi<script>alert(1)</script><blink>!</blink></p><style>p {}</style><a href=" JaVa&#x09;script:alert(1)">link</a><img src=x onerror=alert(1)><!-- <b>hidden</b> -->"#,
+            &Sanitizer::new()
+        ),
+        r#"<p>Hi!</p><a>link</a><img src="x">"#
+    );
+}
+
+#[test]
+fn should_sanitize_with_the_custom_allow_list() {
+    let sanitizer = Sanitizer::new()
+        .allow_attributes("*", &["class"])
+        .allow_schemes(&["tel"]);
+    assert_eq!(
+        sanitize(
+            r#"<span class="note"><a href="tel:123">Call</a><a href="ftp://x">FTP</a>"#,
+            &sanitizer
+        ),
+        r#"<span class="note"><a href="tel:123">Call</a><a>FTP</a></span>"#
+    );
+}
+
+#[test]
+fn should_flatten_the_markup_nested_too_deep() {
+    let depth = 100_000;
+    let html = format!("{}Deep{}", "<div>".repeat(depth), "</div>".repeat(depth));
+    let sanitized = sanitize(&html, &Sanitizer::new());
+    assert_eq!(sanitized.matches("<div>").count(), 128);
+    assert!(sanitized.contains("<div>Deep</div>"));
+}